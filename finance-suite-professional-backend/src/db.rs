@@ -1,7 +1,19 @@
+use mongodb::bson::{doc, Bson, Decimal128, Document};
 use mongodb::{Client, Collection, Database};
 use std::env;
+use std::str::FromStr;
 
-use crate::models::{Customer, Organisation, Invoice, Expense};
+use crate::models::catalog_item::CatalogItem;
+use crate::models::credit_debit_note::CreditDebitNote;
+use crate::models::estimate::Estimate;
+use crate::models::purchase_order::PurchaseOrder;
+use crate::models::ledger::{Account, JournalEntry};
+use crate::models::recurring_invoice::RecurringInvoice;
+use crate::models::tds::TdsChallan;
+use crate::models::user::{RefreshToken, User};
+use crate::models::vendor::Vendor;
+use crate::models::vendor_bill::{BillPayment, VendorBill};
+use crate::models::{Customer, Organisation, Invoice, Expense, Payment};
 
 #[derive(Clone)]
 pub struct MongoDbClient {
@@ -43,4 +55,395 @@ impl MongoDbClient {
     pub fn get_expense_collection(&self) -> Collection<Expense> {
         self.database.collection::<Expense>("expenses")
     }
-}
\ No newline at end of file
+
+    pub fn get_payment_collection(&self) -> Collection<Payment> {
+        self.database.collection::<Payment>("payments")
+    }
+
+    pub fn get_credit_debit_note_collection(&self) -> Collection<CreditDebitNote> {
+        self.database.collection::<CreditDebitNote>("credit_debit_notes")
+    }
+
+    pub fn get_recurring_invoice_collection(&self) -> Collection<RecurringInvoice> {
+        self.database.collection::<RecurringInvoice>("recurring_invoices")
+    }
+
+    pub fn get_estimate_collection(&self) -> Collection<Estimate> {
+        self.database.collection::<Estimate>("estimates")
+    }
+
+    pub fn get_purchase_order_collection(&self) -> Collection<PurchaseOrder> {
+        self.database.collection::<PurchaseOrder>("purchase_orders")
+    }
+
+    pub fn get_vendor_collection(&self) -> Collection<Vendor> {
+        self.database.collection::<Vendor>("vendors")
+    }
+
+    pub fn get_vendor_bill_collection(&self) -> Collection<VendorBill> {
+        self.database.collection::<VendorBill>("vendor_bills")
+    }
+
+    pub fn get_bill_payment_collection(&self) -> Collection<BillPayment> {
+        self.database.collection::<BillPayment>("bill_payments")
+    }
+
+    pub fn get_user_collection(&self) -> Collection<User> {
+        self.database.collection::<User>("users")
+    }
+
+    pub fn get_refresh_token_collection(&self) -> Collection<RefreshToken> {
+        self.database.collection::<RefreshToken>("refresh_tokens")
+    }
+
+    pub fn get_tds_challan_collection(&self) -> Collection<TdsChallan> {
+        self.database.collection::<TdsChallan>("tds_challans")
+    }
+
+    pub fn get_catalog_item_collection(&self) -> Collection<CatalogItem> {
+        self.database.collection::<CatalogItem>("catalog_items")
+    }
+
+    pub fn get_account_collection(&self) -> Collection<Account> {
+        self.database.collection::<Account>("accounts")
+    }
+
+    pub fn get_journal_collection(&self) -> Collection<JournalEntry> {
+        self.database.collection::<JournalEntry>("journal_entries")
+    }
+
+    /// Ids of documents matching `filter` with a money field that does not
+    /// read as a number, logged so they can be fixed by hand. The migration
+    /// leaves them alone rather than guessing an amount.
+    async fn unparsable_money(
+        &self,
+        collection: &str,
+        filter: &Document,
+        fields: &[&str],
+        item_fields: &[&str],
+    ) -> Result<Vec<Bson>, mongodb::error::Error> {
+        let item_checks: Vec<Document> = item_fields
+            .iter()
+            .map(|field| not_a_number(format!("$$item.{}", field)))
+            .collect();
+        let mut checks: Vec<Document> = fields
+            .iter()
+            .map(|field| not_a_number(format!("${}", field)))
+            .collect();
+        if !item_checks.is_empty() {
+            checks.push(doc! {
+                "$anyElementTrue": [{
+                    "$map": {
+                        "input": { "$ifNull": ["$items", []] },
+                        "as": "item",
+                        "in": { "$or": item_checks }
+                    }
+                }]
+            });
+        }
+
+        let mut unparsable = filter.clone();
+        unparsable.insert("$expr", doc! { "$or": checks });
+        let ids = self
+            .database
+            .collection::<Document>(collection)
+            .distinct("_id", unparsable, None)
+            .await?;
+        if !ids.is_empty() {
+            log::warn!(
+                "⚠️ Left {} {} unconverted; these money fields are not numbers: {:?}",
+                ids.len(),
+                collection,
+                ids
+            );
+        }
+        Ok(ids)
+    }
+
+    /// Convert legacy string/f64 money fields on invoices and expenses to
+    /// Decimal128. Only documents whose total is not yet Decimal128 are
+    /// touched, so this is safe to run on every start-up.
+    pub async fn migrate_money_fields(&self) -> Result<(), mongodb::error::Error> {
+        let invoices = self.database.collection::<Document>("invoices");
+        let tax_block = |block: &str, amount: &str| {
+            let path = format!("$$item.{}", block);
+            let amount_path = format!("$$item.{}.{}", block, amount);
+            doc! {
+                "$mergeObjects": [
+                    { "$ifNull": [path, {}] },
+                    { amount: to_decimal(amount_path, zero_decimal()) }
+                ]
+            }
+        };
+        let invoice_pipeline = vec![doc! {
+            "$set": {
+                "currency": { "$ifNull": ["$currency", crate::models::money::DEFAULT_CURRENCY] },
+                "subTotal": to_decimal("$subTotal", zero_decimal()),
+                "totalcgst": to_decimal("$totalcgst", zero_decimal()),
+                "totalsgst": to_decimal("$totalsgst", zero_decimal()),
+                "totaligst": to_decimal("$totaligst", zero_decimal()),
+                "total": to_decimal("$total", zero_decimal()),
+                "items": {
+                    "$map": {
+                        "input": { "$ifNull": ["$items", []] },
+                        "as": "item",
+                        "in": {
+                            "$mergeObjects": [
+                                "$$item",
+                                {
+                                    "rate": to_decimal("$$item.rate", zero_decimal()),
+                                    "itemTotal": to_decimal("$$item.itemTotal", zero_decimal()),
+                                    "cgst": tax_block("cgst", "cgstAmount"),
+                                    "sgst": tax_block("sgst", "sgstAmount"),
+                                    "igst": tax_block("igst", "igstAmount"),
+                                }
+                            ]
+                        }
+                    }
+                }
+            }
+        }];
+        let mut filter = doc! { "total": { "$not": { "$type": "decimal" } } };
+        let skipped = self
+            .unparsable_money(
+                "invoices",
+                &filter,
+                &["subTotal", "totalcgst", "totalsgst", "totaligst", "total"],
+                &[
+                    "rate",
+                    "itemTotal",
+                    "cgst.cgstAmount",
+                    "sgst.sgstAmount",
+                    "igst.igstAmount",
+                ],
+            )
+            .await?;
+        filter.insert("_id", doc! { "$nin": skipped });
+        let result = invoices.update_many(filter, invoice_pipeline, None).await?;
+        if result.modified_count > 0 {
+            log::info!("💱 Migrated money fields on {} invoices", result.modified_count);
+        }
+
+        // Invoices saved before payments existed have no balance due and read
+        // back as fully paid; derive it from the total and what was settled
+        let mut filter = doc! {
+            "balanceDue": { "$not": { "$type": "decimal" } },
+            "total": { "$type": "decimal" },
+        };
+        let skipped = self
+            .unparsable_money(
+                "invoices",
+                &filter,
+                &["amountPaid", "creditedAmount", "debitedAmount"],
+                &[],
+            )
+            .await?;
+        filter.insert("_id", doc! { "$nin": skipped });
+        let result = invoices
+            .update_many(
+                filter,
+                vec![
+                    doc! { "$set": {
+                        "amountPaid": to_decimal("$amountPaid", zero_decimal()),
+                        "creditedAmount": to_decimal("$creditedAmount", zero_decimal()),
+                        "debitedAmount": to_decimal("$debitedAmount", zero_decimal()),
+                    } },
+                    doc! { "$set": { "balanceDue": { "$subtract": [
+                        { "$add": ["$total", "$debitedAmount"] },
+                        { "$add": ["$creditedAmount", "$amountPaid"] },
+                    ] } } },
+                ],
+                None,
+            )
+            .await?;
+        if result.modified_count > 0 {
+            log::info!("💱 Initialised balance due on {} invoices", result.modified_count);
+        }
+
+        let expenses = self.database.collection::<Document>("expenses");
+        let expense_pipeline = vec![doc! {
+            "$set": {
+                "total_amount": to_decimal("$total_amount", zero_decimal()),
+                "total_tax": to_decimal("$total_tax", zero_decimal()),
+                "items": {
+                    "$map": {
+                        "input": { "$ifNull": ["$items", []] },
+                        "as": "item",
+                        "in": {
+                            "$mergeObjects": [
+                                "$$item",
+                                {
+                                    "amount": to_decimal("$$item.amount", zero_decimal()),
+                                    "tax_amount": to_decimal("$$item.tax_amount", Bson::Null),
+                                }
+                            ]
+                        }
+                    }
+                }
+            }
+        }];
+        let mut filter = doc! { "total_amount": { "$not": { "$type": "decimal" } } };
+        let skipped = self
+            .unparsable_money(
+                "expenses",
+                &filter,
+                &["total_amount", "total_tax"],
+                &["amount", "tax_amount"],
+            )
+            .await?;
+        filter.insert("_id", doc! { "$nin": skipped });
+        let result = expenses.update_many(filter, expense_pipeline, None).await?;
+        if result.modified_count > 0 {
+            log::info!("💱 Migrated money fields on {} expenses", result.modified_count);
+        }
+
+        Ok(())
+    }
+
+    /// Give records saved before they carried an organisation one, so the
+    /// per-organisation filters find them. Invoices take their company email;
+    /// the rest can only be attributed while there is a single organisation.
+    pub async fn assign_organisations(&self) -> Result<(), mongodb::error::Error> {
+        let unassigned = doc! { "org_email": { "$in": [Bson::Null, ""] } };
+
+        let mut filter = unassigned.clone();
+        filter.insert("company_email", doc! { "$nin": [Bson::Null, ""] });
+        let result = self
+            .database
+            .collection::<Document>("invoices")
+            .update_many(
+                filter,
+                vec![doc! { "$set": { "org_email": "$company_email" } }],
+                None,
+            )
+            .await?;
+        if result.modified_count > 0 {
+            log::info!(
+                "🏢 Assigned {} invoices to their organisation",
+                result.modified_count
+            );
+        }
+
+        let organisations = self
+            .database
+            .collection::<Document>("organisations")
+            .distinct("email", None, None)
+            .await?;
+        let org_email = match organisations.as_slice() {
+            [Bson::String(email)] => email.clone(),
+            [] => return Ok(()),
+            _ => {
+                log::warn!(
+                    "⚠️ Records without an organisation are hidden until org_email is set on them"
+                );
+                return Ok(());
+            }
+        };
+
+        for name in [
+            "invoices",
+            "expenses",
+            "estimates",
+            "purchase_orders",
+            "payments",
+            "credit_debit_notes",
+            "vendor_bills",
+            "bill_payments",
+            "recurring_invoices",
+            "tds_challans",
+            "customers",
+            "vendors",
+            "catalog_items",
+        ] {
+            let result = self
+                .database
+                .collection::<Document>(name)
+                .update_many(
+                    unassigned.clone(),
+                    doc! { "$set": { "org_email": &org_email } },
+                    None,
+                )
+                .await?;
+            if result.modified_count > 0 {
+                log::info!(
+                    "🏢 Assigned {} {} to {}",
+                    result.modified_count,
+                    name,
+                    org_email
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn zero_decimal() -> Bson {
+    Bson::Decimal128(Decimal128::from_str("0").expect("valid Decimal128"))
+}
+
+/// Legacy amount with thousands separators, currency symbols and
+/// surrounding spaces removed; blank strings become null
+fn cleaned_amount(input: Bson) -> Document {
+    let symbols = [
+        Bson::from(","),
+        Bson::from("₹"),
+        Bson::from("Rs."),
+        Bson::from("INR"),
+        Bson::from(doc! { "$literal": "$" }),
+    ];
+    let mut stripped = Bson::from("$$raw");
+    for symbol in symbols {
+        stripped = Bson::from(doc! {
+            "$replaceAll": { "input": stripped, "find": symbol, "replacement": "" }
+        });
+    }
+    doc! {
+        "$let": {
+            "vars": { "raw": input },
+            "in": {
+                "$cond": [
+                    { "$eq": [{ "$type": "$$raw" }, "string"] },
+                    {
+                        "$let": {
+                            "vars": { "text": { "$trim": { "input": stripped } } },
+                            "in": { "$cond": [{ "$eq": ["$$text", ""] }, Bson::Null, "$$text"] }
+                        }
+                    },
+                    "$$raw"
+                ]
+            }
+        }
+    }
+}
+
+/// `$convert` to decimal after cleaning the amount up; anything that still
+/// does not parse is left as it was
+fn to_decimal(input: impl Into<Bson>, on_null: Bson) -> Document {
+    let input = input.into();
+    doc! {
+        "$convert": {
+            "input": cleaned_amount(input.clone()),
+            "to": "decimal",
+            "onError": input,
+            "onNull": on_null,
+        }
+    }
+}
+
+/// Whether the amount at `input` is present but does not read as a number
+fn not_a_number(input: impl Into<Bson>) -> Document {
+    doc! {
+        "$eq": [
+            {
+                "$convert": {
+                    "input": cleaned_amount(input.into()),
+                    "to": "decimal",
+                    "onError": Bson::Null,
+                    "onNull": zero_decimal(),
+                }
+            },
+            Bson::Null
+        ]
+    }
+}
@@ -2,6 +2,9 @@ use actix_web::{error::ResponseError, http::StatusCode, HttpResponse};
 use serde::Serialize;
 use std::fmt;
 
+use crate::models::organisation::{Action, Resource};
+use crate::models::user::Role;
+
 #[derive(Debug)]
 pub enum ApiError {
     DatabaseError(String),
@@ -9,12 +12,31 @@ pub enum ApiError {
     NotFound(String),
     InternalServerError(String),
     BadRequest(String),  // Added this variant
+    Conflict(String),
+    Unauthorized(String),
+    Forbidden(String),
+    /// The caller's role may not perform `action` on `resource`
+    PermissionDenied {
+        role: Role,
+        resource: Resource,
+        action: Action,
+    },
 }
 
 #[derive(Serialize)]
 struct ErrorResponse {
     error: String,
     message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    details: Option<ErrorDetails>,
+}
+
+/// What was refused, so clients can explain it or hide the control
+#[derive(Serialize)]
+struct ErrorDetails {
+    role: Role,
+    resource: Resource,
+    action: Action,
 }
 
 impl fmt::Display for ApiError {
@@ -25,10 +47,24 @@ impl fmt::Display for ApiError {
             ApiError::NotFound(msg) => write!(f, "Not found: {}", msg),
             ApiError::InternalServerError(msg) => write!(f, "Internal server error: {}", msg),
             ApiError::BadRequest(msg) => write!(f, "Bad request: {}", msg),  // Added this
+            ApiError::Conflict(msg) => write!(f, "Conflict: {}", msg),
+            ApiError::Unauthorized(msg) => write!(f, "Unauthorized: {}", msg),
+            ApiError::Forbidden(msg) => write!(f, "Forbidden: {}", msg),
+            ApiError::PermissionDenied {
+                role,
+                resource,
+                action,
+            } => write!(
+                f,
+                "Forbidden: the {:?} role is not allowed to {} {}",
+                role, action, resource
+            ),
         }
     }
 }
 
+impl std::error::Error for ApiError {}
+
 impl ResponseError for ApiError {
     fn status_code(&self) -> StatusCode {
         match self {
@@ -37,6 +73,10 @@ impl ResponseError for ApiError {
             ApiError::NotFound(_) => StatusCode::NOT_FOUND,
             ApiError::InternalServerError(_) => StatusCode::INTERNAL_SERVER_ERROR,
             ApiError::BadRequest(_) => StatusCode::BAD_REQUEST,  // Added this
+            ApiError::Conflict(_) => StatusCode::CONFLICT,
+            ApiError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            ApiError::Forbidden(_) => StatusCode::FORBIDDEN,
+            ApiError::PermissionDenied { .. } => StatusCode::FORBIDDEN,
         }
     }
 
@@ -49,8 +89,24 @@ impl ResponseError for ApiError {
                 ApiError::NotFound(_) => "NOT_FOUND".to_string(),
                 ApiError::InternalServerError(_) => "INTERNAL_SERVER_ERROR".to_string(),
                 ApiError::BadRequest(_) => "BAD_REQUEST".to_string(),  // Added this
+                ApiError::Conflict(_) => "CONFLICT".to_string(),
+                ApiError::Unauthorized(_) => "UNAUTHORIZED".to_string(),
+                ApiError::Forbidden(_) => "FORBIDDEN".to_string(),
+                ApiError::PermissionDenied { .. } => "PERMISSION_DENIED".to_string(),
             },
             message: self.to_string(),
+            details: match self {
+                ApiError::PermissionDenied {
+                    role,
+                    resource,
+                    action,
+                } => Some(ErrorDetails {
+                    role: *role,
+                    resource: *resource,
+                    action: *action,
+                }),
+                _ => None,
+            },
         };
 
         HttpResponse::build(status_code).json(error_response)
@@ -63,6 +119,22 @@ impl From<mongodb::error::Error> for ApiError {
     }
 }
 
+/// Services that speak anyhow keep the `ApiError` they raised (e.g. a 400
+/// for validation); driver errors stay database errors and anything else is
+/// a 500
+impl From<anyhow::Error> for ApiError {
+    fn from(err: anyhow::Error) -> Self {
+        let err = match err.downcast::<ApiError>() {
+            Ok(api_err) => return api_err,
+            Err(err) => err,
+        };
+        match err.downcast::<mongodb::error::Error>() {
+            Ok(db_err) => db_err.into(),
+            Err(other) => ApiError::InternalServerError(other.to_string()),
+        }
+    }
+}
+
 impl From<validator::ValidationErrors> for ApiError {
     fn from(err: validator::ValidationErrors) -> Self {
         ApiError::ValidationError(err.to_string())
@@ -0,0 +1,89 @@
+use actix_web::{get, post, put, web, HttpResponse, Responder};
+
+use crate::error::ApiError;
+use crate::middleware::{CurrentOrganisation, CurrentUser};
+use crate::models::user::{
+    ChangePasswordRequest, LoginRequest, RefreshRequest, RegisterRequest, UserProfile,
+};
+use crate::services::AuthService;
+
+/// POST /api/v1/auth/register
+///
+/// Signs up the first user of an organisation as its administrator
+#[post("/auth/register")]
+pub async fn register(
+    service: web::Data<AuthService>,
+    req: web::Json<RegisterRequest>,
+) -> Result<impl Responder, ApiError> {
+    let tokens = service.register(req.into_inner()).await?;
+    Ok(HttpResponse::Created().json(tokens))
+}
+
+/// POST /api/v1/auth/login
+#[post("/auth/login")]
+pub async fn login(
+    service: web::Data<AuthService>,
+    req: web::Json<LoginRequest>,
+) -> Result<impl Responder, ApiError> {
+    let tokens = service.login(req.into_inner()).await?;
+    Ok(HttpResponse::Ok().json(tokens))
+}
+
+/// POST /api/v1/auth/refresh
+///
+/// Exchanges a refresh token for a new access and refresh token
+#[post("/auth/refresh")]
+pub async fn refresh(
+    service: web::Data<AuthService>,
+    req: web::Json<RefreshRequest>,
+) -> Result<impl Responder, ApiError> {
+    let tokens = service.refresh(&req.refresh_token).await?;
+    Ok(HttpResponse::Ok().json(tokens))
+}
+
+/// POST /api/v1/auth/logout
+#[post("/auth/logout")]
+pub async fn logout(
+    service: web::Data<AuthService>,
+    req: web::Json<RefreshRequest>,
+) -> Result<impl Responder, ApiError> {
+    service.logout(&req.refresh_token).await?;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+/// GET /api/v1/auth/me
+///
+/// The signed-in user, with their organisation when it has been set up
+#[get("/auth/me")]
+pub async fn me(
+    user: CurrentUser,
+    organisation: Option<CurrentOrganisation>,
+) -> Result<impl Responder, ApiError> {
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "user": UserProfile::from(&user.0),
+        "organisation": organisation.map(|org| org.0),
+    })))
+}
+
+/// PUT /api/v1/auth/password
+///
+/// Changes the caller's password and signs out their other sessions
+#[put("/auth/password")]
+pub async fn change_password(
+    service: web::Data<AuthService>,
+    user: CurrentUser,
+    req: web::Json<ChangePasswordRequest>,
+) -> Result<impl Responder, ApiError> {
+    service.change_password(&user, req.into_inner()).await?;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+/// Register authentication routes under /api/v1
+pub fn configure_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(register)
+        .service(login)
+        .service(refresh)
+        .service(logout)
+        .service(me)
+        .service(change_password);
+}
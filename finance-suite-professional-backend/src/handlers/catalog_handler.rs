@@ -0,0 +1,95 @@
+use actix_web::{delete, get, post, put, web, HttpResponse, Responder};
+use serde::Deserialize;
+
+use crate::error::ApiError;
+use crate::middleware::CurrentUser;
+use crate::models::catalog_item::CatalogItemRequest;
+use crate::services::CatalogService;
+
+#[derive(Deserialize)]
+pub struct SearchQuery {
+    q: String,
+}
+
+/// POST /api/v1/catalog-items
+#[post("/catalog-items")]
+pub async fn create_catalog_item(
+    user: CurrentUser,
+    service: web::Data<CatalogService>,
+    req: web::Json<CatalogItemRequest>,
+) -> Result<impl Responder, ApiError> {
+    let item = service
+        .create_item(req.into_inner(), &user.org_email)
+        .await?;
+    Ok(HttpResponse::Created().json(item))
+}
+
+/// GET /api/v1/catalog-items
+#[get("/catalog-items")]
+pub async fn get_all_catalog_items(
+    user: CurrentUser,
+    service: web::Data<CatalogService>,
+) -> Result<impl Responder, ApiError> {
+    let items = service.get_all_items(&user.org_email).await?;
+    Ok(HttpResponse::Ok().json(items))
+}
+
+/// GET /api/v1/catalog-items/search?q=
+#[get("/catalog-items/search")]
+pub async fn search_catalog_items(
+    user: CurrentUser,
+    service: web::Data<CatalogService>,
+    query: web::Query<SearchQuery>,
+) -> Result<impl Responder, ApiError> {
+    let items = service.search_items(&query.q, &user.org_email).await?;
+    Ok(HttpResponse::Ok().json(items))
+}
+
+/// GET /api/v1/catalog-items/{id}
+#[get("/catalog-items/{id}")]
+pub async fn get_catalog_item_by_id(
+    user: CurrentUser,
+    service: web::Data<CatalogService>,
+    id: web::Path<String>,
+) -> Result<impl Responder, ApiError> {
+    let item = service.get_item_by_id(&id, &user.org_email).await?;
+    Ok(HttpResponse::Ok().json(item))
+}
+
+/// PUT /api/v1/catalog-items/{id}
+#[put("/catalog-items/{id}")]
+pub async fn update_catalog_item(
+    user: CurrentUser,
+    service: web::Data<CatalogService>,
+    id: web::Path<String>,
+    req: web::Json<CatalogItemRequest>,
+) -> Result<impl Responder, ApiError> {
+    let item = service
+        .update_item(&id, req.into_inner(), &user.org_email)
+        .await?;
+    Ok(HttpResponse::Ok().json(item))
+}
+
+/// DELETE /api/v1/catalog-items/{id}
+#[delete("/catalog-items/{id}")]
+pub async fn delete_catalog_item(
+    user: CurrentUser,
+    service: web::Data<CatalogService>,
+    id: web::Path<String>,
+) -> Result<impl Responder, ApiError> {
+    if service.delete_item(&id, &user.org_email).await? {
+        Ok(HttpResponse::NoContent().finish())
+    } else {
+        Err(ApiError::NotFound("Catalogue item not found".to_string()))
+    }
+}
+
+/// Register catalogue routes under /api/v1
+pub fn configure_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(create_catalog_item)
+        .service(get_all_catalog_items)
+        .service(search_catalog_items)
+        .service(get_catalog_item_by_id)
+        .service(update_catalog_item)
+        .service(delete_catalog_item);
+}
@@ -0,0 +1,164 @@
+use actix_web::{get, post, web, HttpResponse, Responder};
+use serde::Deserialize;
+
+use crate::error::ApiError;
+use crate::middleware::CurrentUser;
+use crate::models::credit_debit_note::{CancelNoteRequest, CreateNoteRequest, NoteType};
+use crate::services::CreditDebitNoteService;
+
+#[derive(Deserialize)]
+pub struct ListNotesQuery {
+    invoice_id: Option<String>,
+}
+
+async fn create_note(
+    service: &CreditDebitNoteService,
+    note_type: NoteType,
+    req: CreateNoteRequest,
+    org_email: &str,
+) -> Result<HttpResponse, ApiError> {
+    let note = service.create_note(note_type, req, org_email).await?;
+    Ok(HttpResponse::Created().json(note))
+}
+
+async fn list_notes(
+    service: &CreditDebitNoteService,
+    note_type: NoteType,
+    invoice_id: Option<&str>,
+    org_email: &str,
+) -> Result<HttpResponse, ApiError> {
+    let notes = service.get_notes(note_type, invoice_id, org_email).await?;
+    Ok(HttpResponse::Ok().json(notes))
+}
+
+async fn cancel_note(
+    service: &CreditDebitNoteService,
+    note_type: NoteType,
+    id: &str,
+    req: Option<web::Json<CancelNoteRequest>>,
+    org_email: &str,
+) -> Result<HttpResponse, ApiError> {
+    let reason = req.and_then(|r| r.into_inner().reason);
+    let note = service
+        .cancel_note(note_type, id, reason, org_email)
+        .await?;
+    Ok(HttpResponse::Ok().json(note))
+}
+
+/// POST /api/v1/credit-notes
+#[post("/credit-notes")]
+pub async fn create_credit_note(
+    user: CurrentUser,
+    service: web::Data<CreditDebitNoteService>,
+    req: web::Json<CreateNoteRequest>,
+) -> Result<impl Responder, ApiError> {
+    create_note(
+        &service,
+        NoteType::Credit,
+        req.into_inner(),
+        &user.org_email,
+    )
+    .await
+}
+
+/// GET /api/v1/credit-notes
+#[get("/credit-notes")]
+pub async fn get_credit_notes(
+    user: CurrentUser,
+    service: web::Data<CreditDebitNoteService>,
+    query: web::Query<ListNotesQuery>,
+) -> Result<impl Responder, ApiError> {
+    list_notes(
+        &service,
+        NoteType::Credit,
+        query.invoice_id.as_deref(),
+        &user.org_email,
+    )
+    .await
+}
+
+/// GET /api/v1/credit-notes/{id}
+#[get("/credit-notes/{id}")]
+pub async fn get_credit_note_by_id(
+    user: CurrentUser,
+    service: web::Data<CreditDebitNoteService>,
+    id: web::Path<String>,
+) -> Result<impl Responder, ApiError> {
+    let note = service
+        .get_note_by_id(NoteType::Credit, &id, &user.org_email)
+        .await?;
+    Ok(HttpResponse::Ok().json(note))
+}
+
+/// POST /api/v1/credit-notes/{id}/cancel
+#[post("/credit-notes/{id}/cancel")]
+pub async fn cancel_credit_note(
+    user: CurrentUser,
+    service: web::Data<CreditDebitNoteService>,
+    id: web::Path<String>,
+    req: Option<web::Json<CancelNoteRequest>>,
+) -> Result<impl Responder, ApiError> {
+    cancel_note(&service, NoteType::Credit, &id, req, &user.org_email).await
+}
+
+/// POST /api/v1/debit-notes
+#[post("/debit-notes")]
+pub async fn create_debit_note(
+    user: CurrentUser,
+    service: web::Data<CreditDebitNoteService>,
+    req: web::Json<CreateNoteRequest>,
+) -> Result<impl Responder, ApiError> {
+    create_note(&service, NoteType::Debit, req.into_inner(), &user.org_email).await
+}
+
+/// GET /api/v1/debit-notes
+#[get("/debit-notes")]
+pub async fn get_debit_notes(
+    user: CurrentUser,
+    service: web::Data<CreditDebitNoteService>,
+    query: web::Query<ListNotesQuery>,
+) -> Result<impl Responder, ApiError> {
+    list_notes(
+        &service,
+        NoteType::Debit,
+        query.invoice_id.as_deref(),
+        &user.org_email,
+    )
+    .await
+}
+
+/// GET /api/v1/debit-notes/{id}
+#[get("/debit-notes/{id}")]
+pub async fn get_debit_note_by_id(
+    user: CurrentUser,
+    service: web::Data<CreditDebitNoteService>,
+    id: web::Path<String>,
+) -> Result<impl Responder, ApiError> {
+    let note = service
+        .get_note_by_id(NoteType::Debit, &id, &user.org_email)
+        .await?;
+    Ok(HttpResponse::Ok().json(note))
+}
+
+/// POST /api/v1/debit-notes/{id}/cancel
+#[post("/debit-notes/{id}/cancel")]
+pub async fn cancel_debit_note(
+    user: CurrentUser,
+    service: web::Data<CreditDebitNoteService>,
+    id: web::Path<String>,
+    req: Option<web::Json<CancelNoteRequest>>,
+) -> Result<impl Responder, ApiError> {
+    cancel_note(&service, NoteType::Debit, &id, req, &user.org_email).await
+}
+
+/// Register credit/debit note routes under /api/v1
+pub fn configure_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(create_credit_note)
+        .service(get_credit_notes)
+        .service(get_credit_note_by_id)
+        .service(cancel_credit_note)
+        .service(create_debit_note)
+        .service(get_debit_notes)
+        .service(get_debit_note_by_id)
+        .service(cancel_debit_note);
+}
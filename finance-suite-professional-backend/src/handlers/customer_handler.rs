@@ -2,6 +2,8 @@ use actix_web::{delete, get, post, put, web, HttpResponse, Responder};
 use serde::Deserialize;
 
 use crate::error::ApiError;
+use crate::middleware::Access;
+use crate::models::organisation::{Action, Resource};
 use crate::models::{CreateCustomerRequest, UpdateCustomerRequest};
 use crate::services::CustomerService;
 
@@ -18,10 +20,15 @@ pub struct DeleteQuery {
 
 #[post("/customers")]
 pub async fn create_customer(
+    access: Access,
     service: web::Data<CustomerService>,
     req: web::Json<CreateCustomerRequest>,
 ) -> Result<impl Responder, ApiError> {
-    let customer = service.create_customer(req.into_inner()).await?;
+    access.require(Resource::Customer, Action::Edit)?;
+
+    let customer = service
+        .create_customer(req.into_inner(), &access.org_email)
+        .await?;
     Ok(HttpResponse::Ok().json(serde_json::json!({
         "message": "Customer created successfully"
     })))
@@ -29,28 +36,39 @@ pub async fn create_customer(
 
 #[get("/customers")]
 pub async fn get_all_customers(
+    access: Access,
     service: web::Data<CustomerService>,
 ) -> Result<impl Responder, ApiError> {
-    let customers = service.get_all_customers().await?;
+    access.require(Resource::Customer, Action::View)?;
+
+    let customers = service.get_all_customers(&access.org_email).await?;
     Ok(HttpResponse::Ok().json(customers))
 }
 
 #[get("/customers/{id}")]
 pub async fn get_customer_by_id(
+    access: Access,
     service: web::Data<CustomerService>,
     id: web::Path<String>,
 ) -> Result<impl Responder, ApiError> {
-    let customer = service.get_customer_by_id(&id).await?;
+    access.require(Resource::Customer, Action::View)?;
+
+    let customer = service.get_customer_by_id(&id, &access.org_email).await?;
     Ok(HttpResponse::Ok().json(customer))
 }
 
 #[put("/customer/{id}")]
 pub async fn update_customer(
+    access: Access,
     service: web::Data<CustomerService>,
     id: web::Path<String>,
     req: web::Json<UpdateCustomerRequest>,
 ) -> Result<impl Responder, ApiError> {
-    let customer = service.update_customer(&id, req.into_inner()).await?;
+    access.require(Resource::Customer, Action::Edit)?;
+
+    let customer = service
+        .update_customer(&id, req.into_inner(), &access.org_email)
+        .await?;
     Ok(HttpResponse::Ok().json(serde_json::json!({
         "message": "Customer updated successfully"
     })))
@@ -58,10 +76,13 @@ pub async fn update_customer(
 
 #[delete("/customer/{id}")]
 pub async fn delete_customer_by_id(
+    access: Access,
     service: web::Data<CustomerService>,
     id: web::Path<String>,
 ) -> Result<impl Responder, ApiError> {
-    let deleted = service.delete_customer(&id).await?;
+    access.require(Resource::Customer, Action::Delete)?;
+
+    let deleted = service.delete_customer(&id, &access.org_email).await?;
     if deleted {
         Ok(HttpResponse::Ok().json(serde_json::json!({
             "message": "Customer deleted successfully"
@@ -73,11 +94,16 @@ pub async fn delete_customer_by_id(
 
 #[delete("/customers")]
 pub async fn delete_customer_by_query(
+    access: Access,
     service: web::Data<CustomerService>,
     query: web::Query<DeleteQuery>,
 ) -> Result<impl Responder, ApiError> {
+    access.require(Resource::Customer, Action::Delete)?;
+
     if let Some(gstin) = &query.gstin {
-        let deleted = service.delete_customer_by_gstin(gstin).await?;
+        let deleted = service
+            .delete_customer_by_gstin(gstin, &access.org_email)
+            .await?;
         if deleted {
             Ok(HttpResponse::Ok().json(serde_json::json!({
                 "message": "Customer deleted successfully",
@@ -90,7 +116,9 @@ pub async fn delete_customer_by_query(
             )))
         }
     } else if let Some(email) = &query.email {
-        let deleted = service.delete_customer_by_email(email).await?;
+        let deleted = service
+            .delete_customer_by_email(email, &access.org_email)
+            .await?;
         if deleted {
             Ok(HttpResponse::Ok().json(serde_json::json!({
                 "message": "Customer deleted successfully",
@@ -111,10 +139,15 @@ pub async fn delete_customer_by_query(
 
 #[get("/customers/search")]
 pub async fn search_customers(
+    access: Access,
     service: web::Data<CustomerService>,
     query: web::Query<SearchQuery>,
 ) -> Result<impl Responder, ApiError> {
-    let customers = service.search_customers(&query.q).await?;
+    access.require(Resource::Customer, Action::View)?;
+
+    let customers = service
+        .search_customers(&query.q, &access.org_email)
+        .await?;
     Ok(HttpResponse::Ok().json(customers))
 }
 
@@ -0,0 +1,78 @@
+use actix_web::{get, post, web, HttpResponse, Responder};
+use serde::Deserialize;
+
+use crate::error::ApiError;
+use crate::middleware::CurrentUser;
+use crate::models::e_invoice::IrpResponse;
+use crate::services::EInvoiceService;
+
+#[derive(Deserialize)]
+pub struct EInvoiceQuery {
+    /// Return only the NIC JSON as a file download
+    #[serde(default)]
+    download: bool,
+}
+
+/// GET /api/v1/invoices/{id}/e-invoice
+///
+/// The NIC v1.1 payload with local validation errors, or with
+/// `download=true` just the JSON for uploading to the IRP
+#[get("/invoices/{id}/e-invoice")]
+pub async fn get_e_invoice(
+    user: CurrentUser,
+    service: web::Data<EInvoiceService>,
+    id: web::Path<String>,
+    query: web::Query<EInvoiceQuery>,
+) -> Result<impl Responder, ApiError> {
+    let preview = service.preview(&id, &user.org_email).await?;
+
+    if query.download {
+        let filename = format!(
+            "EINV_{}.json",
+            preview.payload.doc_dtls.no.replace(['/', '\\', '"'], "-")
+        );
+        return Ok(HttpResponse::Ok()
+            .insert_header((
+                "Content-Disposition",
+                format!("attachment; filename=\"{}\"", filename),
+            ))
+            .json(vec![&preview.payload]));
+    }
+    Ok(HttpResponse::Ok().json(preview))
+}
+
+/// POST /api/v1/invoices/{id}/e-invoice
+///
+/// Register the invoice with the IRP and lock it
+#[post("/invoices/{id}/e-invoice")]
+pub async fn generate_irn(
+    user: CurrentUser,
+    service: web::Data<EInvoiceService>,
+    id: web::Path<String>,
+) -> Result<impl Responder, ApiError> {
+    let invoice = service.generate_irn(&id, &user.org_email).await?;
+    Ok(HttpResponse::Ok().json(invoice))
+}
+
+/// POST /api/v1/invoices/{id}/e-invoice/irn
+///
+/// Body is the IRP response for an invoice registered outside the system
+#[post("/invoices/{id}/e-invoice/irn")]
+pub async fn record_irn(
+    user: CurrentUser,
+    service: web::Data<EInvoiceService>,
+    id: web::Path<String>,
+    req: web::Json<IrpResponse>,
+) -> Result<impl Responder, ApiError> {
+    let invoice = service
+        .record_irn(&id, req.into_inner(), &user.org_email)
+        .await?;
+    Ok(HttpResponse::Ok().json(invoice))
+}
+
+/// Register e-invoice routes under /api/v1
+pub fn configure_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(get_e_invoice)
+        .service(generate_irn)
+        .service(record_irn);
+}
@@ -0,0 +1,69 @@
+use actix_web::{post, web, HttpResponse, Responder};
+use serde::Deserialize;
+
+use crate::error::ApiError;
+use crate::middleware::CurrentUser;
+use crate::models::e_way_bill::{RecordEWayBillRequest, TransportDetails};
+use crate::services::EWayBillService;
+
+#[derive(Deserialize)]
+pub struct EWayBillQuery {
+    /// Return only the bulk-upload JSON as a file download
+    #[serde(default)]
+    download: bool,
+}
+
+/// POST /api/v1/invoices/{id}/e-way-bill
+///
+/// Body is the transport details. Returns the bulk-upload JSON with the
+/// validation result, or with `download=true` just the JSON file
+#[post("/invoices/{id}/e-way-bill")]
+pub async fn prepare_e_way_bill(
+    user: CurrentUser,
+    service: web::Data<EWayBillService>,
+    id: web::Path<String>,
+    query: web::Query<EWayBillQuery>,
+    req: web::Json<TransportDetails>,
+) -> Result<impl Responder, ApiError> {
+    let preview = service.prepare(&id, &req, &user.org_email).await?;
+
+    if query.download {
+        if !preview.errors.is_empty() {
+            return Err(ApiError::ValidationError(preview.errors.join("; ")));
+        }
+        let doc_no = preview
+            .file
+            .bill_lists
+            .first()
+            .map(|entry| entry.doc_no.replace(['/', '\\', '"'], "-"))
+            .unwrap_or_default();
+        return Ok(HttpResponse::Ok()
+            .insert_header((
+                "Content-Disposition",
+                format!("attachment; filename=\"EWB_{}.json\"", doc_no),
+            ))
+            .json(&preview.file));
+    }
+    Ok(HttpResponse::Ok().json(preview))
+}
+
+/// POST /api/v1/invoices/{id}/e-way-bill/number
+///
+/// Store the e-way bill number and validity returned by the portal
+#[post("/invoices/{id}/e-way-bill/number")]
+pub async fn record_e_way_bill(
+    user: CurrentUser,
+    service: web::Data<EWayBillService>,
+    id: web::Path<String>,
+    req: web::Json<RecordEWayBillRequest>,
+) -> Result<impl Responder, ApiError> {
+    let invoice = service
+        .record(&id, req.into_inner(), &user.org_email)
+        .await?;
+    Ok(HttpResponse::Ok().json(invoice))
+}
+
+/// Register e-way bill routes under /api/v1
+pub fn configure_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(prepare_e_way_bill).service(record_e_way_bill);
+}
@@ -0,0 +1,128 @@
+use actix_web::{delete, get, post, put, web, HttpResponse, Responder};
+
+use crate::error::ApiError;
+use crate::middleware::CurrentUser;
+use crate::models::estimate::EstimateRequest;
+use crate::services::EstimateService;
+
+/// POST /api/v1/estimates
+#[post("/estimates")]
+pub async fn create_estimate(
+    user: CurrentUser,
+    service: web::Data<EstimateService>,
+    req: web::Json<EstimateRequest>,
+) -> Result<impl Responder, ApiError> {
+    let estimate = service
+        .create_estimate(req.into_inner(), &user.org_email)
+        .await?;
+    Ok(HttpResponse::Created().json(estimate))
+}
+
+/// GET /api/v1/estimates
+#[get("/estimates")]
+pub async fn get_estimates(
+    user: CurrentUser,
+    service: web::Data<EstimateService>,
+) -> Result<impl Responder, ApiError> {
+    let estimates = service.get_estimates(&user.org_email).await?;
+    Ok(HttpResponse::Ok().json(estimates))
+}
+
+/// GET /api/v1/estimates/{id}
+#[get("/estimates/{id}")]
+pub async fn get_estimate_by_id(
+    user: CurrentUser,
+    service: web::Data<EstimateService>,
+    id: web::Path<String>,
+) -> Result<impl Responder, ApiError> {
+    let estimate = service.get_estimate_by_id(&id, &user.org_email).await?;
+    Ok(HttpResponse::Ok().json(estimate))
+}
+
+/// PUT /api/v1/estimates/{id}
+#[put("/estimates/{id}")]
+pub async fn update_estimate(
+    user: CurrentUser,
+    service: web::Data<EstimateService>,
+    id: web::Path<String>,
+    req: web::Json<EstimateRequest>,
+) -> Result<impl Responder, ApiError> {
+    let estimate = service
+        .update_estimate(&id, req.into_inner(), &user.org_email)
+        .await?;
+    Ok(HttpResponse::Ok().json(estimate))
+}
+
+/// POST /api/v1/estimates/{id}/send
+#[post("/estimates/{id}/send")]
+pub async fn send_estimate(
+    user: CurrentUser,
+    service: web::Data<EstimateService>,
+    id: web::Path<String>,
+) -> Result<impl Responder, ApiError> {
+    let estimate = service.send_estimate(&id, &user.org_email).await?;
+    Ok(HttpResponse::Ok().json(estimate))
+}
+
+/// POST /api/v1/estimates/{id}/accept
+#[post("/estimates/{id}/accept")]
+pub async fn accept_estimate(
+    user: CurrentUser,
+    service: web::Data<EstimateService>,
+    id: web::Path<String>,
+) -> Result<impl Responder, ApiError> {
+    let estimate = service.accept_estimate(&id, &user.org_email).await?;
+    Ok(HttpResponse::Ok().json(estimate))
+}
+
+/// POST /api/v1/estimates/{id}/decline
+#[post("/estimates/{id}/decline")]
+pub async fn decline_estimate(
+    user: CurrentUser,
+    service: web::Data<EstimateService>,
+    id: web::Path<String>,
+) -> Result<impl Responder, ApiError> {
+    let estimate = service.decline_estimate(&id, &user.org_email).await?;
+    Ok(HttpResponse::Ok().json(estimate))
+}
+
+/// POST /api/v1/estimates/{id}/convert
+#[post("/estimates/{id}/convert")]
+pub async fn convert_estimate(
+    user: CurrentUser,
+    service: web::Data<EstimateService>,
+    id: web::Path<String>,
+) -> Result<impl Responder, ApiError> {
+    let (estimate, invoice) = service.convert_to_invoice(&id, &user.org_email).await?;
+    Ok(HttpResponse::Created().json(serde_json::json!({
+        "estimate": estimate,
+        "invoice": invoice
+    })))
+}
+
+/// DELETE /api/v1/estimates/{id}
+#[delete("/estimates/{id}")]
+pub async fn delete_estimate(
+    user: CurrentUser,
+    service: web::Data<EstimateService>,
+    id: web::Path<String>,
+) -> Result<impl Responder, ApiError> {
+    if service.delete_estimate(&id, &user.org_email).await? {
+        Ok(HttpResponse::NoContent().finish())
+    } else {
+        Err(ApiError::NotFound("Estimate not found".to_string()))
+    }
+}
+
+/// Register estimate routes under /api/v1
+pub fn configure_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(create_estimate)
+        .service(get_estimates)
+        .service(get_estimate_by_id)
+        .service(update_estimate)
+        .service(send_estimate)
+        .service(accept_estimate)
+        .service(decline_estimate)
+        .service(convert_estimate)
+        .service(delete_estimate);
+}
@@ -14,13 +14,25 @@ use uuid::Uuid;
 use mongodb::bson::DateTime;
 
 use crate::{
-    models::expense::{Expense, ExpenseItem, ExpenseStatus},
+    middleware::Access,
+    models::expense::{
+        Expense, ExpenseItem, ExpenseStatus, ReviewExpenseRequest, SubmitExpenseRequest,
+    },
+    models::money::Money,
+    models::organisation::{Action, Resource},
     services::expense_service::ExpenseService,
 };
 
 /// Directory to store uploaded receipts
 const EXPENSE_UPLOAD_DIR: &str = "./uploads/expenses";
 
+/// Read an amount sent either as a JSON number or a numeric string
+fn json_amount(value: Option<&serde_json::Value>) -> Option<Money> {
+    value
+        .filter(|v| !v.is_null())
+        .and_then(|v| serde_json::from_value::<Money>(v.clone()).ok())
+}
+
 /// Query parameters for listing expenses
 #[derive(Debug, Deserialize)]
 pub struct ExpenseQuery {
@@ -54,12 +66,15 @@ pub struct ExpenseListResponse {
 ///   - receipt_0, receipt_1, etc. (files for each item)
 #[post("")]
 pub async fn create_expense(
+    access: Access,
     service: Data<ExpenseService>,
     mut payload: Multipart,
 ) -> actix_web::Result<impl Responder> {
     use std::fs;
     use std::path::Path;
 
+    access.require(Resource::Expense, Action::Edit)?;
+
     let mut fields: HashMap<String, String> = HashMap::new();
     let mut receipt_files: HashMap<String, (String, String)> = HashMap::new(); // index -> (stored_name, original_name)
 
@@ -142,27 +157,32 @@ pub async fn create_expense(
                     .get("currency")
                     .cloned()
                     .unwrap_or_else(|| "INR".to_string()),
-                amount: item["amount"]
-                    .as_f64()
-                    .or_else(|| item["amount"].as_str().and_then(|s| s.parse::<f64>().ok()))
-                    .unwrap_or(0.0),
+                amount: json_amount(item.get("amount")).unwrap_or_default(),
                 expense_date: fields.get("expenseDate").cloned().unwrap_or_default(),
                 comment: item["comment"].as_str().unwrap_or("").to_string(),
                 receipt_file: receipt_info.as_ref().map(|(stored, _)| stored.clone()),
                 original_filename: receipt_info.as_ref().map(|(_, orig)| orig.clone()),
                 payment_method: item.get("paymentMethod").and_then(|v| v.as_str()).map(|s| s.to_string()),
                 vendor: item.get("vendor").and_then(|v| v.as_str()).map(|s| s.to_string()),
+                vendor_gstin: item
+                    .get("vendorGstin")
+                    .and_then(|v| v.as_str())
+                    .map(|s| s.trim().to_uppercase())
+                    .filter(|s| !s.is_empty()),
+                invoice_number: item
+                    .get("invoiceNumber")
+                    .and_then(|v| v.as_str())
+                    .map(|s| s.trim().to_string())
+                    .filter(|s| !s.is_empty()),
                 billable: item.get("billable").and_then(|v| v.as_bool()).unwrap_or(false),
-                tax_amount: item.get("taxAmount")
-                    .and_then(|v| v.as_f64())
-                    .or_else(|| item.get("taxAmount").and_then(|v| v.as_str()).and_then(|s| s.parse::<f64>().ok())),
+                tax_amount: json_amount(item.get("taxAmount")),
             }
         })
         .collect();
 
     // Calculate totals
-    let total_amount: f64 = expense_items.iter().map(|item| item.amount).sum();
-    let total_tax: f64 = expense_items
+    let total_amount: Money = expense_items.iter().map(|item| item.amount).sum();
+    let total_tax: Money = expense_items
         .iter()
         .filter_map(|item| item.tax_amount)
         .sum();
@@ -188,6 +208,7 @@ pub async fn create_expense(
         reimbursed_at: None,
         notes: fields.get("notes").cloned(),
         department: fields.get("department").cloned(),
+        org_email: access.org_email.clone(),
         created_at: Some(now),
         updated_at: Some(now),
     };
@@ -203,13 +224,16 @@ pub async fn create_expense(
 /// GET /expenses
 #[get("")]
 pub async fn list_expenses(
+    access: Access,
     service: Data<ExpenseService>,
     query: Query<ExpenseQuery>,
 ) -> actix_web::Result<impl Responder> {
+    access.require(Resource::Expense, Action::View)?;
+
     // Handle search
     if let Some(search_term) = &query.search {
         let expenses = service
-            .search_expenses(search_term)
+            .search_expenses(search_term, &access.org_email)
             .await
             .map_err(actix_web::error::ErrorInternalServerError)?;
 
@@ -224,12 +248,12 @@ pub async fn list_expenses(
     // Handle filter by project
     let expenses = if let Some(project) = &query.project_cost_center {
         service
-            .get_expenses_by_project(project, query.page, query.limit)
+            .get_expenses_by_project(project, query.page, query.limit, &access.org_email)
             .await
             .map_err(actix_web::error::ErrorInternalServerError)?
     } else {
         service
-            .get_all_expenses(query.page, query.limit)
+            .get_all_expenses(query.page, query.limit, &access.org_email)
             .await
             .map_err(actix_web::error::ErrorInternalServerError)?
     };
@@ -237,12 +261,15 @@ pub async fn list_expenses(
     // Get total count for pagination
     let total = if query.project_cost_center.is_some() {
         service
-            .count_expenses_by_project(query.project_cost_center.as_ref().unwrap())
+            .count_expenses_by_project(
+                query.project_cost_center.as_ref().unwrap(),
+                &access.org_email,
+            )
             .await
             .map_err(actix_web::error::ErrorInternalServerError)?
     } else {
         service
-            .count_expenses()
+            .count_expenses(&access.org_email)
             .await
             .map_err(actix_web::error::ErrorInternalServerError)?
     };
@@ -258,13 +285,16 @@ pub async fn list_expenses(
 /// GET /expenses/{id}
 #[get("/{id}")]
 pub async fn get_expense(
+    access: Access,
     service: Data<ExpenseService>,
     id: Path<String>,
 ) -> actix_web::Result<impl Responder> {
+    access.require(Resource::Expense, Action::View)?;
+
     let id = id.into_inner();
 
     let maybe_expense = service
-        .get_expense_by_id(&id)
+        .get_expense_by_id(&id, &access.org_email)
         .await
         .map_err(actix_web::error::ErrorInternalServerError)?;
 
@@ -280,6 +310,7 @@ pub async fn get_expense(
 /// PUT /expenses/{id}
 #[put("/{id}")]
 pub async fn update_expense(
+    access: Access,
     service: Data<ExpenseService>,
     id: Path<String>,
     mut payload: Multipart,
@@ -287,11 +318,13 @@ pub async fn update_expense(
     use std::fs;
     use std::path::Path;
 
+    access.require(Resource::Expense, Action::Edit)?;
+
     let id = id.into_inner();
 
     // Verify expense exists
     let existing = service
-        .get_expense_by_id(&id)
+        .get_expense_by_id(&id, &access.org_email)
         .await
         .map_err(actix_web::error::ErrorInternalServerError)?;
 
@@ -387,18 +420,25 @@ pub async fn update_expense(
                         .get("currency")
                         .cloned()
                         .unwrap_or_else(|| "INR".to_string()),
-                    amount: item["amount"]
-                        .as_f64()
-                        .or_else(|| item["amount"].as_str().and_then(|s| s.parse::<f64>().ok()))
-                        .unwrap_or(0.0),
+                    amount: json_amount(item.get("amount")).unwrap_or_default(),
                     expense_date: fields.get("expenseDate").cloned().unwrap_or_default(),
                     comment: item["comment"].as_str().unwrap_or("").to_string(),
                     receipt_file,
                     original_filename,
                     payment_method: item.get("paymentMethod").and_then(|v| v.as_str()).map(|s| s.to_string()),
                     vendor: item.get("vendor").and_then(|v| v.as_str()).map(|s| s.to_string()),
+                    vendor_gstin: item
+                        .get("vendorGstin")
+                        .and_then(|v| v.as_str())
+                        .map(|s| s.trim().to_uppercase())
+                        .filter(|s| !s.is_empty()),
+                    invoice_number: item
+                        .get("invoiceNumber")
+                        .and_then(|v| v.as_str())
+                        .map(|s| s.trim().to_string())
+                        .filter(|s| !s.is_empty()),
                     billable: item.get("billable").and_then(|v| v.as_bool()).unwrap_or(false),
-                    tax_amount: item.get("taxAmount").and_then(|v| v.as_f64()),
+                    tax_amount: json_amount(item.get("taxAmount")),
                 }
             })
             .collect()
@@ -407,8 +447,8 @@ pub async fn update_expense(
     };
 
     // Calculate totals
-    let total_amount: f64 = expense_items.iter().map(|item| item.amount).sum();
-    let total_tax: f64 = expense_items
+    let total_amount: Money = expense_items.iter().map(|item| item.amount).sum();
+    let total_tax: Money = expense_items
         .iter()
         .filter_map(|item| item.tax_amount)
         .sum();
@@ -433,12 +473,13 @@ pub async fn update_expense(
         reimbursed_at: existing_expense.reimbursed_at,
         notes: fields.get("notes").cloned().or(existing_expense.notes),
         department: fields.get("department").cloned().or(existing_expense.department),
+        org_email: existing_expense.org_email,
         created_at: existing_expense.created_at,
         updated_at: Some(DateTime::now()),
     };
 
     let updated = service
-        .update_expense(&id, expense)
+        .update_expense(&id, expense, &access.org_email)
         .await
         .map_err(actix_web::error::ErrorInternalServerError)?;
 
@@ -454,16 +495,19 @@ pub async fn update_expense(
 /// DELETE /expenses/{id}
 #[delete("/{id}")]
 pub async fn delete_expense(
+    access: Access,
     service: Data<ExpenseService>,
     id: Path<String>,
 ) -> actix_web::Result<impl Responder> {
     use std::path::Path;
 
+    access.require(Resource::Expense, Action::Delete)?;
+
     let id = id.into_inner();
 
     // Get expense first to retrieve receipt filenames
     if let Some(expense) = service
-        .get_expense_by_id(&id)
+        .get_expense_by_id(&id, &access.org_email)
         .await
         .map_err(actix_web::error::ErrorInternalServerError)?
     {
@@ -477,7 +521,7 @@ pub async fn delete_expense(
     }
 
     let deleted = service
-        .delete_expense(&id)
+        .delete_expense(&id, &access.org_email)
         .await
         .map_err(actix_web::error::ErrorInternalServerError)?;
 
@@ -493,10 +537,23 @@ pub async fn delete_expense(
 /// GET /expenses/receipt/{filename}
 /// Serves the stored receipt file so UI can preview/download
 #[get("/receipt/{filename}")]
-pub async fn get_expense_receipt(path: Path<String>) -> actix_web::Result<NamedFile> {
+pub async fn get_expense_receipt(
+    access: Access,
+    service: Data<ExpenseService>,
+    path: Path<String>,
+) -> actix_web::Result<NamedFile> {
     use std::path::Path;
 
+    access.require(Resource::Expense, Action::View)?;
+
     let filename = path.into_inner();
+    let own = service
+        .has_receipt(&filename, &access.org_email)
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+    if !own {
+        return Err(actix_web::error::ErrorNotFound("File not found"));
+    }
     let filepath = Path::new(EXPENSE_UPLOAD_DIR).join(&filename);
 
     if !filepath.exists() {
@@ -510,10 +567,13 @@ pub async fn get_expense_receipt(path: Path<String>) -> actix_web::Result<NamedF
 /// Get overall expense statistics
 #[get("/stats/summary")]
 pub async fn get_expense_summary(
+    access: Access,
     service: Data<ExpenseService>,
 ) -> actix_web::Result<impl Responder> {
+    access.require(Resource::Expense, Action::View)?;
+
     let summary = service
-        .get_expense_summary()
+        .get_expense_summary(&access.org_email)
         .await
         .map_err(actix_web::error::ErrorInternalServerError)?;
 
@@ -524,13 +584,16 @@ pub async fn get_expense_summary(
 /// Get statistics for a specific project
 #[get("/stats/project/{project}")]
 pub async fn get_project_statistics(
+    access: Access,
     service: Data<ExpenseService>,
     project: Path<String>,
 ) -> actix_web::Result<impl Responder> {
+    access.require(Resource::Expense, Action::View)?;
+
     let project = project.into_inner();
 
     let stats = service
-        .get_project_statistics(&project)
+        .get_project_statistics(&project, &access.org_email)
         .await
         .map_err(actix_web::error::ErrorInternalServerError)?;
 
@@ -540,15 +603,71 @@ pub async fn get_project_statistics(
 /// GET /expenses/projects
 /// Get all unique project names
 #[get("/projects")]
-pub async fn get_all_projects(service: Data<ExpenseService>) -> actix_web::Result<impl Responder> {
+pub async fn get_all_projects(
+    access: Access,
+    service: Data<ExpenseService>,
+) -> actix_web::Result<impl Responder> {
+    access.require(Resource::Expense, Action::View)?;
+
     let projects = service
-        .get_all_projects()
+        .get_all_projects(&access.org_email)
         .await
         .map_err(actix_web::error::ErrorInternalServerError)?;
 
    Ok(HttpResponse::Ok().json(projects)) 
 }
 
+/// POST /expenses/{id}/submit
+/// Submit a draft expense for approval
+#[post("/{id}/submit")]
+pub async fn submit_expense(
+    access: Access,
+    service: Data<ExpenseService>,
+    id: Path<String>,
+    req: Option<web::Json<SubmitExpenseRequest>>,
+) -> actix_web::Result<impl Responder> {
+    access.require(Resource::Expense, Action::Edit)?;
+
+    let req = req.map(|r| r.into_inner()).unwrap_or_default();
+    let expense = service
+        .submit_expense(&id.into_inner(), req, &access.org_email)
+        .await?;
+    Ok(HttpResponse::Ok().json(expense))
+}
+
+/// POST /expenses/{id}/review
+/// Approve (posting to the ledger) or reject a submitted expense
+#[post("/{id}/review")]
+pub async fn review_expense(
+    access: Access,
+    service: Data<ExpenseService>,
+    id: Path<String>,
+    req: web::Json<ReviewExpenseRequest>,
+) -> actix_web::Result<impl Responder> {
+    access.require(Resource::Expense, Action::Edit)?;
+
+    let expense = service
+        .review_expense(&id.into_inner(), req.into_inner(), &access.org_email)
+        .await?;
+    Ok(HttpResponse::Ok().json(expense))
+}
+
+/// POST /expenses/{id}/reimburse
+/// Mark an approved expense as reimbursed (posting the payout)
+#[post("/{id}/reimburse")]
+pub async fn reimburse_expense(
+    access: Access,
+    service: Data<ExpenseService>,
+    id: Path<String>,
+) -> actix_web::Result<impl Responder> {
+    access.require(Resource::Expense, Action::Edit)?;
+
+    let expense = service
+        .reimburse_expense(&id.into_inner(), &access.org_email)
+        .await?;
+    Ok(HttpResponse::Ok().json(expense))
+}
+
 pub fn configure_routes(cfg: &mut web::ServiceConfig) {
     cfg.service(
         web::scope("/expenses")
@@ -560,6 +679,9 @@ pub fn configure_routes(cfg: &mut web::ServiceConfig) {
             .service(get_all_projects)
             .service(get_expense)
             .service(update_expense)
+            .service(submit_expense)
+            .service(review_expense)
+            .service(reimburse_expense)
             .service(delete_expense),
     );
 }
\ No newline at end of file
@@ -0,0 +1,93 @@
+use actix_web::{get, web, HttpResponse, Responder};
+use serde::Deserialize;
+
+use crate::error::ApiError;
+use crate::middleware::CurrentUser;
+use crate::services::{Gstr1Service, Gstr2bService, Gstr3bService};
+
+/// GSTR-2B downloads run to several megabytes for busy months
+const GSTR2B_MAX_BYTES: usize = 20 * 1024 * 1024;
+
+#[derive(Deserialize)]
+pub struct GstReturnQuery {
+    /// Tax period as MMYYYY, e.g. "042025"
+    period: String,
+    /// Return only the portal JSON as a file download
+    #[serde(default)]
+    download: bool,
+}
+
+/// GET /api/v1/gst/gstr1
+///
+/// The return with its validation report, or with `download=true` just
+/// the JSON file for the GST offline tool
+#[get("/gst/gstr1")]
+pub async fn gstr1(
+    user: CurrentUser,
+    service: web::Data<Gstr1Service>,
+    query: web::Query<GstReturnQuery>,
+) -> Result<impl Responder, ApiError> {
+    let report = service.generate(&user.org_email, &query.period).await?;
+
+    if query.download {
+        let filename = format!("GSTR1_{}_{}.json", report.gstr1.gstin, report.gstr1.fp);
+        return Ok(HttpResponse::Ok()
+            .insert_header((
+                "Content-Disposition",
+                format!("attachment; filename=\"{}\"", filename),
+            ))
+            .json(&report.gstr1));
+    }
+    Ok(HttpResponse::Ok().json(report))
+}
+
+/// GET /api/v1/gst/gstr3b
+///
+/// The table-wise breakdown with the portal JSON, or with `download=true`
+/// just the JSON
+#[get("/gst/gstr3b")]
+pub async fn gstr3b(
+    user: CurrentUser,
+    service: web::Data<Gstr3bService>,
+    query: web::Query<GstReturnQuery>,
+) -> Result<impl Responder, ApiError> {
+    let report = service.generate(&user.org_email, &query.period).await?;
+
+    if query.download {
+        let filename = format!(
+            "GSTR3B_{}_{}.json",
+            report.gstr3b.gstin, report.gstr3b.ret_period
+        );
+        return Ok(HttpResponse::Ok()
+            .insert_header((
+                "Content-Disposition",
+                format!("attachment; filename=\"{}\"", filename),
+            ))
+            .json(&report.gstr3b));
+    }
+    Ok(HttpResponse::Ok().json(report))
+}
+
+/// POST /api/v1/gst/gstr2b/reconcile
+///
+/// Body is the GSTR-2B JSON file downloaded from the portal
+pub async fn reconcile_gstr2b(
+    user: CurrentUser,
+    service: web::Data<Gstr2bService>,
+    body: web::Bytes,
+) -> Result<impl Responder, ApiError> {
+    if body.is_empty() {
+        return Err(ApiError::BadRequest("GSTR-2B JSON body is required".to_string()));
+    }
+    let reconciliation = service.reconcile(&user.org_email, &body).await?;
+    Ok(HttpResponse::Ok().json(reconciliation))
+}
+
+/// Register GST return routes under /api/v1
+pub fn configure_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(gstr1).service(gstr3b).service(
+        web::resource("/gst/gstr2b/reconcile")
+            .app_data(web::PayloadConfig::new(GSTR2B_MAX_BYTES))
+            .route(web::post().to(reconcile_gstr2b)),
+    );
+}
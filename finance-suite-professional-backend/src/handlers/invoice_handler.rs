@@ -1,32 +1,33 @@
 use actix_web::{
     delete, get, post, put,
-    web::{self, Json, Path, Query},
+    web::{self, Json, Path},
     HttpResponse, Responder,
 };
 use serde_json::json;
-use serde::Deserialize;
 
 use crate::{
-    models::invoice::{CreateInvoiceRequest, UpdateInvoiceRequest},
+    error::ApiError,
+    middleware::Access,
+    models::invoice::{
+        CreateInvoiceRequest, Invoice, InvoiceTransitionRequest, ShippingBillRequest,
+        UpdateInvoiceRequest,
+    },
+    models::organisation::{Action, Resource},
     services::InvoiceService,
 };
 
-#[derive(Deserialize)]
-struct OrgEmailQuery {
-    org_email: String,
-}
-
 /// POST /api/v1/invoices
 #[post("/invoices")]
 pub async fn create_invoice(
+    access: Access,
     service: web::Data<InvoiceService>,
     req: Json<CreateInvoiceRequest>,
-    query: Query<OrgEmailQuery>,
-) -> actix_web::Result<impl Responder> {
+) -> Result<impl Responder, ApiError> {
+    access.require(Resource::Invoice, Action::Edit)?;
+
     let invoice = service
-        .create_invoice(req.into_inner(), &query.org_email)
-        .await
-        .map_err(actix_web::error::ErrorInternalServerError)?;
+        .create_invoice(req.into_inner(), &access.org_email)
+        .await?;
 
     Ok(HttpResponse::Created().json(invoice))
 }
@@ -34,13 +35,14 @@ pub async fn create_invoice(
 /// GET /api/v1/invoices/next-number
 #[get("/invoices/next-number")]
 pub async fn get_next_invoice_number(
+    access: Access,
     service: web::Data<InvoiceService>,
-    query: Query<OrgEmailQuery>,
-) -> actix_web::Result<impl Responder> {
+) -> Result<impl Responder, ApiError> {
+    access.require(Resource::Invoice, Action::View)?;
+
     let invoice_number = service
-        .peek_next_invoice_number(&query.org_email)
-        .await
-        .map_err(actix_web::error::ErrorInternalServerError)?;
+        .peek_next_invoice_number(&access.org_email)
+        .await?;
 
     Ok(HttpResponse::Ok().json(json!({
         "invoice_number": invoice_number
@@ -50,12 +52,14 @@ pub async fn get_next_invoice_number(
 /// GET /api/v1/invoices
 #[get("/invoices")]
 pub async fn list_invoices(
+    access: Access,
     service: web::Data<InvoiceService>,
-) -> actix_web::Result<impl Responder> {
+) -> Result<impl Responder, ApiError> {
+    access.require(Resource::Invoice, Action::View)?;
+
     let invoices = service
-        .get_all_invoices()
-        .await
-        .map_err(actix_web::error::ErrorInternalServerError)?;
+        .get_all_invoices(&access.org_email)
+        .await?;
 
     Ok(HttpResponse::Ok().json(invoices))
 }
@@ -63,15 +67,17 @@ pub async fn list_invoices(
 /// GET /api/v1/invoices/{id}
 #[get("/invoices/{id}")]
 pub async fn get_invoice(
+    access: Access,
     service: web::Data<InvoiceService>,
     id: Path<String>,
-) -> actix_web::Result<impl Responder> {
+) -> Result<impl Responder, ApiError> {
+    access.require(Resource::Invoice, Action::View)?;
+
     let id = id.into_inner();
 
     let maybe_invoice = service
-        .get_invoice_by_id(&id)
-        .await
-        .map_err(actix_web::error::ErrorInternalServerError)?;
+        .get_invoice_by_id(&id, &access.org_email)
+        .await?;
 
     if let Some(invoice) = maybe_invoice {
         Ok(HttpResponse::Ok().json(invoice))
@@ -82,19 +88,56 @@ pub async fn get_invoice(
     }
 }
 
+/// GET /api/v1/invoices/{id}/pdf
+#[get("/invoices/{id}/pdf")]
+pub async fn get_invoice_pdf(
+    access: Access,
+    service: web::Data<InvoiceService>,
+    id: Path<String>,
+) -> Result<impl Responder, ApiError> {
+    access.require(Resource::Invoice, Action::Export)?;
+
+    let id = id.into_inner();
+
+    let maybe_pdf = service
+        .render_invoice_pdf(&id, &access.org_email)
+        .await?;
+
+    if let Some((invoice, pdf)) = maybe_pdf {
+        let filename = if invoice.invoice_number.is_empty() {
+            format!("invoice-{}.pdf", id)
+        } else {
+            format!("{}.pdf", invoice.invoice_number.replace(['/', '\\', '"'], "-"))
+        };
+        Ok(HttpResponse::Ok()
+            .content_type("application/pdf")
+            .insert_header((
+                "Content-Disposition",
+                format!("inline; filename=\"{}\"", filename),
+            ))
+            .body(pdf))
+    } else {
+        Ok(HttpResponse::NotFound().json(json!({
+            "message": "Invoice not found"
+        })))
+    }
+}
+
 /// PUT /api/v1/invoices/{id}
 #[put("/invoices/{id}")]
 pub async fn update_invoice(
+    access: Access,
     service: web::Data<InvoiceService>,
     id: Path<String>,
     req: Json<UpdateInvoiceRequest>,
-) -> actix_web::Result<impl Responder> {
+) -> Result<impl Responder, ApiError> {
+    access.require(Resource::Invoice, Action::Edit)?;
+
     let id = id.into_inner();
 
     let maybe_updated = service
-        .update_invoice(&id, req.into_inner())
-        .await
-        .map_err(actix_web::error::ErrorInternalServerError)?;
+        .update_invoice(&id, req.into_inner(), &access.org_email)
+        .await?;
 
     if let Some(updated) = maybe_updated {
         Ok(HttpResponse::Ok().json(updated))
@@ -105,18 +148,37 @@ pub async fn update_invoice(
     }
 }
 
+/// PUT /api/v1/invoices/{id}/shipping-bill
+#[put("/invoices/{id}/shipping-bill")]
+pub async fn record_shipping_bill(
+    access: Access,
+    service: web::Data<InvoiceService>,
+    id: Path<String>,
+    req: Json<ShippingBillRequest>,
+) -> Result<impl Responder, ApiError> {
+    access.require(Resource::Invoice, Action::Edit)?;
+
+    let maybe_invoice = service
+        .record_shipping_bill(&id.into_inner(), req.into_inner(), &access.org_email)
+        .await?;
+
+    transition_response(maybe_invoice)
+}
+
 /// DELETE /api/v1/invoices/{id}
 #[delete("/invoices/{id}")]
 pub async fn delete_invoice(
+    access: Access,
     service: web::Data<InvoiceService>,
     id: Path<String>,
-) -> actix_web::Result<impl Responder> {
+) -> Result<impl Responder, ApiError> {
+    access.require(Resource::Invoice, Action::Delete)?;
+
     let id = id.into_inner();
 
     let deleted = service
-        .delete_invoice(&id)
-        .await
-        .map_err(actix_web::error::ErrorInternalServerError)?;
+        .delete_invoice(&id, &access.org_email)
+        .await?;
 
     if deleted {
         Ok(HttpResponse::NoContent().finish())
@@ -127,12 +189,81 @@ pub async fn delete_invoice(
     }
 }
 
+/// POST /api/v1/invoices/{id}/issue
+#[post("/invoices/{id}/issue")]
+pub async fn issue_invoice(
+    access: Access,
+    service: web::Data<InvoiceService>,
+    id: Path<String>,
+) -> Result<impl Responder, ApiError> {
+    access.require(Resource::Invoice, Action::Edit)?;
+
+    let maybe_invoice = service
+        .issue_invoice(&id.into_inner(), &access.org_email)
+        .await?;
+
+    transition_response(maybe_invoice)
+}
+
+/// POST /api/v1/invoices/{id}/cancel
+#[post("/invoices/{id}/cancel")]
+pub async fn cancel_invoice(
+    access: Access,
+    service: web::Data<InvoiceService>,
+    id: Path<String>,
+    req: Option<Json<InvoiceTransitionRequest>>,
+) -> Result<impl Responder, ApiError> {
+    access.require(Resource::Invoice, Action::Edit)?;
+
+    let reason = req.and_then(|r| r.into_inner().reason);
+    let maybe_invoice = service
+        .cancel_invoice(&id.into_inner(), reason, &access.org_email)
+        .await?;
+
+    transition_response(maybe_invoice)
+}
+
+/// POST /api/v1/invoices/{id}/void
+#[post("/invoices/{id}/void")]
+pub async fn void_invoice(
+    access: Access,
+    service: web::Data<InvoiceService>,
+    id: Path<String>,
+    req: Option<Json<InvoiceTransitionRequest>>,
+) -> Result<impl Responder, ApiError> {
+    access.require(Resource::Invoice, Action::Edit)?;
+
+    let reason = req.and_then(|r| r.into_inner().reason);
+    let maybe_invoice = service
+        .void_invoice(&id.into_inner(), reason, &access.org_email)
+        .await?;
+
+    transition_response(maybe_invoice)
+}
+
+fn transition_response(
+    maybe_invoice: Option<Invoice>,
+) -> Result<HttpResponse, ApiError> {
+    if let Some(invoice) = maybe_invoice {
+        Ok(HttpResponse::Ok().json(invoice))
+    } else {
+        Ok(HttpResponse::NotFound().json(json!({
+            "message": "Invoice not found"
+        })))
+    }
+}
+
 /// Register invoice routes under /api/v1
 pub fn configure_routes(cfg: &mut web::ServiceConfig) {
     cfg.service(get_next_invoice_number)
         .service(create_invoice)
         .service(list_invoices)
         .service(get_invoice)
+        .service(get_invoice_pdf)
         .service(update_invoice)
+        .service(record_shipping_bill)
+        .service(issue_invoice)
+        .service(cancel_invoice)
+        .service(void_invoice)
         .service(delete_invoice);
 }
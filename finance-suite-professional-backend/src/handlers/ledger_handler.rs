@@ -0,0 +1,91 @@
+use actix_web::{get, post, web, HttpResponse, Responder};
+use serde::Deserialize;
+
+use crate::error::ApiError;
+use crate::middleware::CurrentUser;
+use crate::models::ledger::CreateAccountRequest;
+use crate::services::LedgerService;
+
+/// Date range in YYYY-MM-DD; both bounds inclusive and optional
+#[derive(Deserialize)]
+pub struct LedgerRangeQuery {
+    from: Option<String>,
+    to: Option<String>,
+}
+
+/// GET /api/v1/ledger/accounts
+#[get("/ledger/accounts")]
+pub async fn get_accounts(
+    user: CurrentUser,
+    service: web::Data<LedgerService>,
+) -> Result<impl Responder, ApiError> {
+    let accounts = service.get_accounts(&user.org_email).await?;
+    Ok(HttpResponse::Ok().json(accounts))
+}
+
+/// POST /api/v1/ledger/accounts
+#[post("/ledger/accounts")]
+pub async fn create_account(
+    user: CurrentUser,
+    service: web::Data<LedgerService>,
+    req: web::Json<CreateAccountRequest>,
+) -> Result<impl Responder, ApiError> {
+    let account = service
+        .create_account(&user.org_email, req.into_inner())
+        .await?;
+    Ok(HttpResponse::Created().json(account))
+}
+
+/// GET /api/v1/ledger/accounts/{code}?from=&to=
+#[get("/ledger/accounts/{code}")]
+pub async fn get_account_ledger(
+    user: CurrentUser,
+    service: web::Data<LedgerService>,
+    code: web::Path<String>,
+    query: web::Query<LedgerRangeQuery>,
+) -> Result<impl Responder, ApiError> {
+    let ledgers = service
+        .get_account_ledger(
+            &user.org_email,
+            &code,
+            query.from.as_deref(),
+            query.to.as_deref(),
+        )
+        .await?;
+    Ok(HttpResponse::Ok().json(ledgers))
+}
+
+/// GET /api/v1/ledger/balances?from=&to=
+#[get("/ledger/balances")]
+pub async fn get_balances(
+    user: CurrentUser,
+    service: web::Data<LedgerService>,
+    query: web::Query<LedgerRangeQuery>,
+) -> Result<impl Responder, ApiError> {
+    let balances = service
+        .get_balances(&user.org_email, query.from.as_deref(), query.to.as_deref())
+        .await?;
+    Ok(HttpResponse::Ok().json(balances))
+}
+
+/// GET /api/v1/ledger/journal?from=&to=
+#[get("/ledger/journal")]
+pub async fn get_journal(
+    user: CurrentUser,
+    service: web::Data<LedgerService>,
+    query: web::Query<LedgerRangeQuery>,
+) -> Result<impl Responder, ApiError> {
+    let entries = service
+        .get_journal(&user.org_email, query.from.as_deref(), query.to.as_deref())
+        .await?;
+    Ok(HttpResponse::Ok().json(entries))
+}
+
+/// Register ledger routes under /api/v1
+pub fn configure_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(get_accounts)
+        .service(create_account)
+        .service(get_account_ledger)
+        .service(get_balances)
+        .service(get_journal);
+}
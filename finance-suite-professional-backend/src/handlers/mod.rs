@@ -2,8 +2,40 @@ pub mod customer_handler;
 pub mod organisation_handler;
 pub mod invoice_handler;
 pub mod expense_handler;     // 👈 NEW
+pub mod payment_handler;
+pub mod credit_debit_note_handler;
+pub mod recurring_invoice_handler;
+pub mod estimate_handler;
+pub mod purchase_order_handler;
+pub mod vendor_handler;
+pub mod vendor_bill_handler;
+pub mod ledger_handler;
+pub mod report_handler;
+pub mod gst_return_handler;
+pub mod catalog_handler;
+pub mod e_invoice_handler;
+pub mod e_way_bill_handler;
+pub mod tds_handler;
+pub mod auth_handler;
+pub mod user_handler;
 
 pub use customer_handler::configure_routes as configure_customer_routes;
 pub use organisation_handler::configure_routes as configure_organisation_routes;
 pub use invoice_handler::configure_routes as configure_invoice_routes;
 pub use expense_handler::configure_routes as configure_expense_routes;   // 👈 NEW
+pub use payment_handler::configure_routes as configure_payment_routes;
+pub use credit_debit_note_handler::configure_routes as configure_credit_debit_note_routes;
+pub use recurring_invoice_handler::configure_routes as configure_recurring_invoice_routes;
+pub use estimate_handler::configure_routes as configure_estimate_routes;
+pub use purchase_order_handler::configure_routes as configure_purchase_order_routes;
+pub use vendor_handler::configure_routes as configure_vendor_routes;
+pub use vendor_bill_handler::configure_routes as configure_vendor_bill_routes;
+pub use ledger_handler::configure_routes as configure_ledger_routes;
+pub use report_handler::configure_routes as configure_report_routes;
+pub use gst_return_handler::configure_routes as configure_gst_return_routes;
+pub use catalog_handler::configure_routes as configure_catalog_routes;
+pub use e_invoice_handler::configure_routes as configure_e_invoice_routes;
+pub use e_way_bill_handler::configure_routes as configure_e_way_bill_routes;
+pub use tds_handler::configure_routes as configure_tds_routes;
+pub use auth_handler::configure_routes as configure_auth_routes;
+pub use user_handler::configure_routes as configure_user_routes;
@@ -1,13 +1,52 @@
 use actix_web::{delete, get, post, put, web, HttpResponse, Responder};
 use crate::error::ApiError;
-use crate::models::{CreateOrganisationRequest, UpdateOrganizationRequest};
+use crate::middleware::{Access, CurrentUser};
+use crate::models::organisation::{Action, Resource};
+use crate::models::user::{Role, User};
+use crate::models::{CreateOrganisationRequest, Organisation, UpdateOrganizationRequest};
 use crate::services::OrganisationService;
 
+fn is_own(user: &User, email: &str) -> bool {
+    email.trim().eq_ignore_ascii_case(&user.org_email)
+}
+
+/// Load an organisation by id; other organisations are reported as not found
+async fn find_own(
+    service: &OrganisationService,
+    access: &Access,
+    id: &str,
+) -> Result<Organisation, ApiError> {
+    let organisation = service.get_organisation_by_id(id).await?;
+    if !is_own(access, &organisation.email) {
+        return Err(ApiError::NotFound(format!(
+            "Organisation with id {} not found",
+            id
+        )));
+    }
+    Ok(organisation)
+}
+
+/// Save the caller's organisation. There is no permission matrix until it
+/// exists, so only the administrator who signed it up may create it.
 #[post("/organisation")]
 pub async fn create_organisation(
+    user: CurrentUser,
     service: web::Data<OrganisationService>,
     req: web::Json<CreateOrganisationRequest>,
 ) -> Result<impl Responder, ApiError> {
+    if user.role != Role::Admin {
+        return Err(ApiError::PermissionDenied {
+            role: user.role,
+            resource: Resource::Organisation,
+            action: Action::Edit,
+        });
+    }
+    if !is_own(&user, &req.email) {
+        return Err(ApiError::Forbidden(
+            "The organisation email must match the one you signed up with".to_string(),
+        ));
+    }
+
     let organisation = service.create_organisation(req.into_inner()).await?;
     Ok(HttpResponse::Ok().json(serde_json::json!({
         "message": "Organisation created successfully",
@@ -17,18 +56,35 @@ pub async fn create_organisation(
 
 #[get("/organisation")]
 pub async fn get_all_organisation(
+    access: Access,
     service: web::Data<OrganisationService>,
 ) -> Result<impl Responder, ApiError> {
-    let organisations = service.get_all_organisation().await?;
+    access.require(Resource::Organisation, Action::View)?;
+
+    let organisations: Vec<Organisation> = service
+        .get_all_organisation()
+        .await?
+        .into_iter()
+        .filter(|organisation| is_own(&access, &organisation.email))
+        .collect();
     Ok(HttpResponse::Ok().json(organisations))
 }
 
 #[get("/organisation/by-email/{email}")]
 pub async fn get_organisation_by_email(
+    access: Access,
     service: web::Data<OrganisationService>,
     email: web::Path<String>,
 ) -> Result<HttpResponse, ApiError> {
+    access.require(Resource::Organisation, Action::View)?;
+
     let email = email.into_inner();
+    if !is_own(&access, &email) {
+        return Err(ApiError::NotFound(format!(
+            "Organisation with email '{}' not found",
+            email
+        )));
+    }
     log::info!("📧 Looking up organisation by email: {}", email);
     let organisation = service.get_organisation_by_email(&email).await?;
     Ok(HttpResponse::Ok().json(organisation))
@@ -36,19 +92,32 @@ pub async fn get_organisation_by_email(
 
 #[get("/organisation/{id}")]
 pub async fn get_organisation_by_id(
+    access: Access,
     service: web::Data<OrganisationService>,
     id: web::Path<String>,
 ) -> Result<impl Responder, ApiError> {
-    let organisation = service.get_organisation_by_id(&id).await?;
+    access.require(Resource::Organisation, Action::View)?;
+
+    let organisation = find_own(&service, &access, &id).await?;
     Ok(HttpResponse::Ok().json(organisation))
 }
 
 #[put("/organisationsUpdate/{id}")]
 pub async fn update_organisation(
+    access: Access,
     service: web::Data<OrganisationService>,
     id: web::Path<String>,
     req: web::Json<UpdateOrganizationRequest>,
 ) -> Result<impl Responder, ApiError> {
+    access.require(Resource::Organisation, Action::Edit)?;
+
+    find_own(&service, &access, &id).await?;
+    // Users belong to the organisation through its email
+    if matches!(&req.email, Some(email) if !is_own(&access, email)) {
+        return Err(ApiError::BadRequest(
+            "The organisation email cannot be changed".to_string(),
+        ));
+    }
     service.update_organisation(&id, req.into_inner()).await?;
     Ok(HttpResponse::Ok().json(serde_json::json!({
         "message": "Organisation details updated successfully"
@@ -57,9 +126,13 @@ pub async fn update_organisation(
 
 #[delete("/organisation/{id}")]
 pub async fn delete_organisation_by_id(
+    access: Access,
     service: web::Data<OrganisationService>,
     id: web::Path<String>,
 ) -> Result<impl Responder, ApiError> {
+    access.require(Resource::Organisation, Action::Delete)?;
+
+    find_own(&service, &access, &id).await?;
     service.delete_organisation(&id).await?;
     Ok(HttpResponse::Ok().json(serde_json::json!({
         "message": "Organisation deleted successfully"
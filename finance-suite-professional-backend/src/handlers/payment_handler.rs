@@ -0,0 +1,72 @@
+use actix_web::{delete, get, post, web, HttpResponse, Responder};
+use serde::Deserialize;
+
+use crate::error::ApiError;
+use crate::middleware::CurrentUser;
+use crate::models::CreatePaymentRequest;
+use crate::services::PaymentService;
+
+#[derive(Deserialize)]
+pub struct ListPaymentsQuery {
+    invoice_id: Option<String>,
+}
+
+/// POST /api/v1/payments
+#[post("/payments")]
+pub async fn record_payment(
+    user: CurrentUser,
+    service: web::Data<PaymentService>,
+    req: web::Json<CreatePaymentRequest>,
+) -> Result<impl Responder, ApiError> {
+    let payment = service
+        .record_payment(req.into_inner(), &user.org_email)
+        .await?;
+    Ok(HttpResponse::Created().json(payment))
+}
+
+/// GET /api/v1/payments
+#[get("/payments")]
+pub async fn get_payments(
+    user: CurrentUser,
+    service: web::Data<PaymentService>,
+    query: web::Query<ListPaymentsQuery>,
+) -> Result<impl Responder, ApiError> {
+    let payments = service
+        .get_payments(query.invoice_id.as_deref(), &user.org_email)
+        .await?;
+    Ok(HttpResponse::Ok().json(payments))
+}
+
+/// GET /api/v1/payments/{id}
+#[get("/payments/{id}")]
+pub async fn get_payment_by_id(
+    user: CurrentUser,
+    service: web::Data<PaymentService>,
+    id: web::Path<String>,
+) -> Result<impl Responder, ApiError> {
+    let payment = service.get_payment_by_id(&id, &user.org_email).await?;
+    Ok(HttpResponse::Ok().json(payment))
+}
+
+/// DELETE /api/v1/payments/{id}
+#[delete("/payments/{id}")]
+pub async fn delete_payment(
+    user: CurrentUser,
+    service: web::Data<PaymentService>,
+    id: web::Path<String>,
+) -> Result<impl Responder, ApiError> {
+    let deleted = service.delete_payment(&id, &user.org_email).await?;
+    if deleted {
+        Ok(HttpResponse::NoContent().finish())
+    } else {
+        Err(ApiError::NotFound("Payment not found".to_string()))
+    }
+}
+
+/// Register payment routes under /api/v1
+pub fn configure_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(record_payment)
+        .service(get_payments)
+        .service(get_payment_by_id)
+        .service(delete_payment);
+}
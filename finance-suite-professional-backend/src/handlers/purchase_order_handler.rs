@@ -0,0 +1,124 @@
+use actix_web::{delete, get, post, put, web, HttpResponse, Responder};
+use serde::Deserialize;
+
+use crate::error::ApiError;
+use crate::middleware::CurrentUser;
+use crate::models::purchase_order::{PurchaseOrderKind, PurchaseOrderRequest};
+use crate::services::PurchaseOrderService;
+
+#[derive(Deserialize)]
+pub struct ListPurchaseOrdersQuery {
+    kind: Option<PurchaseOrderKind>,
+}
+
+/// POST /api/v1/purchase-orders
+#[post("/purchase-orders")]
+pub async fn create_purchase_order(
+    user: CurrentUser,
+    service: web::Data<PurchaseOrderService>,
+    req: web::Json<PurchaseOrderRequest>,
+) -> Result<impl Responder, ApiError> {
+    let po = service
+        .create_purchase_order(req.into_inner(), &user.org_email)
+        .await?;
+    Ok(HttpResponse::Created().json(po))
+}
+
+/// GET /api/v1/purchase-orders?kind=Customer|Vendor
+#[get("/purchase-orders")]
+pub async fn get_purchase_orders(
+    user: CurrentUser,
+    service: web::Data<PurchaseOrderService>,
+    query: web::Query<ListPurchaseOrdersQuery>,
+) -> Result<impl Responder, ApiError> {
+    let orders = service
+        .get_purchase_orders(query.kind, &user.org_email)
+        .await?;
+    Ok(HttpResponse::Ok().json(orders))
+}
+
+/// GET /api/v1/purchase-orders/{id}
+#[get("/purchase-orders/{id}")]
+pub async fn get_purchase_order_by_id(
+    user: CurrentUser,
+    service: web::Data<PurchaseOrderService>,
+    id: web::Path<String>,
+) -> Result<impl Responder, ApiError> {
+    let po = service
+        .get_purchase_order_by_id(&id, &user.org_email)
+        .await?;
+    Ok(HttpResponse::Ok().json(po))
+}
+
+/// PUT /api/v1/purchase-orders/{id}
+#[put("/purchase-orders/{id}")]
+pub async fn update_purchase_order(
+    user: CurrentUser,
+    service: web::Data<PurchaseOrderService>,
+    id: web::Path<String>,
+    req: web::Json<PurchaseOrderRequest>,
+) -> Result<impl Responder, ApiError> {
+    let po = service
+        .update_purchase_order(&id, req.into_inner(), &user.org_email)
+        .await?;
+    Ok(HttpResponse::Ok().json(po))
+}
+
+/// POST /api/v1/purchase-orders/{id}/open
+#[post("/purchase-orders/{id}/open")]
+pub async fn open_purchase_order(
+    user: CurrentUser,
+    service: web::Data<PurchaseOrderService>,
+    id: web::Path<String>,
+) -> Result<impl Responder, ApiError> {
+    let po = service.open_purchase_order(&id, &user.org_email).await?;
+    Ok(HttpResponse::Ok().json(po))
+}
+
+/// POST /api/v1/purchase-orders/{id}/close
+#[post("/purchase-orders/{id}/close")]
+pub async fn close_purchase_order(
+    user: CurrentUser,
+    service: web::Data<PurchaseOrderService>,
+    id: web::Path<String>,
+) -> Result<impl Responder, ApiError> {
+    let po = service.close_purchase_order(&id, &user.org_email).await?;
+    Ok(HttpResponse::Ok().json(po))
+}
+
+/// POST /api/v1/purchase-orders/{id}/cancel
+#[post("/purchase-orders/{id}/cancel")]
+pub async fn cancel_purchase_order(
+    user: CurrentUser,
+    service: web::Data<PurchaseOrderService>,
+    id: web::Path<String>,
+) -> Result<impl Responder, ApiError> {
+    let po = service.cancel_purchase_order(&id, &user.org_email).await?;
+    Ok(HttpResponse::Ok().json(po))
+}
+
+/// DELETE /api/v1/purchase-orders/{id}
+#[delete("/purchase-orders/{id}")]
+pub async fn delete_purchase_order(
+    user: CurrentUser,
+    service: web::Data<PurchaseOrderService>,
+    id: web::Path<String>,
+) -> Result<impl Responder, ApiError> {
+    if service.delete_purchase_order(&id, &user.org_email).await? {
+        Ok(HttpResponse::NoContent().finish())
+    } else {
+        Err(ApiError::NotFound("Purchase order not found".to_string()))
+    }
+}
+
+/// Register purchase order routes under /api/v1
+pub fn configure_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(create_purchase_order)
+        .service(get_purchase_orders)
+        .service(get_purchase_order_by_id)
+        .service(update_purchase_order)
+        .service(open_purchase_order)
+        .service(close_purchase_order)
+        .service(cancel_purchase_order)
+        .service(delete_purchase_order);
+}
@@ -0,0 +1,115 @@
+use actix_web::{delete, get, post, put, web, HttpResponse, Responder};
+use serde::Deserialize;
+
+use crate::error::ApiError;
+use crate::middleware::CurrentUser;
+use crate::models::recurring_invoice::RecurringInvoiceRequest;
+use crate::services::recurring_invoice_service::as_of_date;
+use crate::services::RecurringInvoiceService;
+
+#[derive(Deserialize)]
+pub struct RunQuery {
+    as_of: Option<String>,
+}
+
+/// POST /api/v1/recurring-invoices
+#[post("/recurring-invoices")]
+pub async fn create_recurring_invoice(
+    user: CurrentUser,
+    service: web::Data<RecurringInvoiceService>,
+    req: web::Json<RecurringInvoiceRequest>,
+) -> Result<impl Responder, ApiError> {
+    let schedule = service
+        .create_schedule(req.into_inner(), &user.org_email)
+        .await?;
+    Ok(HttpResponse::Created().json(schedule))
+}
+
+/// GET /api/v1/recurring-invoices
+#[get("/recurring-invoices")]
+pub async fn get_recurring_invoices(
+    user: CurrentUser,
+    service: web::Data<RecurringInvoiceService>,
+) -> Result<impl Responder, ApiError> {
+    let schedules = service.get_schedules(&user.org_email).await?;
+    Ok(HttpResponse::Ok().json(schedules))
+}
+
+/// GET /api/v1/recurring-invoices/preview?as_of=YYYY-MM-DD
+///
+/// Dry run: the invoices the scheduler would generate, without saving anything.
+#[get("/recurring-invoices/preview")]
+pub async fn preview_recurring_invoices(
+    user: CurrentUser,
+    service: web::Data<RecurringInvoiceService>,
+    query: web::Query<RunQuery>,
+) -> Result<impl Responder, ApiError> {
+    let as_of = as_of_date(query.as_of.as_deref())?;
+    let results = service.run_due(as_of, true, Some(&user.org_email)).await?;
+    Ok(HttpResponse::Ok().json(results))
+}
+
+/// POST /api/v1/recurring-invoices/run?as_of=YYYY-MM-DD
+///
+/// Generate due invoices now instead of waiting for the background task.
+/// `as_of` may not be later than today.
+#[post("/recurring-invoices/run")]
+pub async fn run_recurring_invoices(
+    user: CurrentUser,
+    service: web::Data<RecurringInvoiceService>,
+    query: web::Query<RunQuery>,
+) -> Result<impl Responder, ApiError> {
+    let as_of = as_of_date(query.as_of.as_deref())?;
+    let results = service.run_due(as_of, false, Some(&user.org_email)).await?;
+    Ok(HttpResponse::Ok().json(results))
+}
+
+/// GET /api/v1/recurring-invoices/{id}
+#[get("/recurring-invoices/{id}")]
+pub async fn get_recurring_invoice_by_id(
+    user: CurrentUser,
+    service: web::Data<RecurringInvoiceService>,
+    id: web::Path<String>,
+) -> Result<impl Responder, ApiError> {
+    let schedule = service.get_schedule_by_id(&id, &user.org_email).await?;
+    Ok(HttpResponse::Ok().json(schedule))
+}
+
+/// PUT /api/v1/recurring-invoices/{id}
+#[put("/recurring-invoices/{id}")]
+pub async fn update_recurring_invoice(
+    user: CurrentUser,
+    service: web::Data<RecurringInvoiceService>,
+    id: web::Path<String>,
+    req: web::Json<RecurringInvoiceRequest>,
+) -> Result<impl Responder, ApiError> {
+    let schedule = service
+        .update_schedule(&id, req.into_inner(), &user.org_email)
+        .await?;
+    Ok(HttpResponse::Ok().json(schedule))
+}
+
+/// DELETE /api/v1/recurring-invoices/{id}
+#[delete("/recurring-invoices/{id}")]
+pub async fn delete_recurring_invoice(
+    user: CurrentUser,
+    service: web::Data<RecurringInvoiceService>,
+    id: web::Path<String>,
+) -> Result<impl Responder, ApiError> {
+    if service.delete_schedule(&id, &user.org_email).await? {
+        Ok(HttpResponse::NoContent().finish())
+    } else {
+        Err(ApiError::NotFound("Recurring invoice not found".to_string()))
+    }
+}
+
+/// Register recurring invoice routes under /api/v1
+pub fn configure_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(preview_recurring_invoices)
+        .service(run_recurring_invoices)
+        .service(create_recurring_invoice)
+        .service(get_recurring_invoices)
+        .service(get_recurring_invoice_by_id)
+        .service(update_recurring_invoice)
+        .service(delete_recurring_invoice);
+}
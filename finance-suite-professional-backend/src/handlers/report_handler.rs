@@ -0,0 +1,123 @@
+use actix_web::{get, web, HttpResponse, Responder};
+use serde::{Deserialize, Serialize};
+
+use crate::error::ApiError;
+use crate::middleware::CurrentUser;
+use crate::services::ReportService;
+
+/// Period is `fy` (e.g. "2024-25") or `from` + `to` (YYYY-MM-DD); the
+/// current financial year when omitted
+#[derive(Deserialize)]
+pub struct ReportQuery {
+    fy: Option<String>,
+    from: Option<String>,
+    to: Option<String>,
+    /// Defaults to INR
+    currency: Option<String>,
+    /// "json" (default) or "csv"
+    format: Option<String>,
+}
+
+impl ReportQuery {
+    fn wants_csv(&self) -> Result<bool, ApiError> {
+        match self.format.as_deref().map(str::to_ascii_lowercase).as_deref() {
+            None | Some("json") => Ok(false),
+            Some("csv") => Ok(true),
+            Some(other) => Err(ApiError::BadRequest(format!(
+                "Unsupported format '{}'; use json or csv",
+                other
+            ))),
+        }
+    }
+}
+
+/// JSON body, or a CSV attachment named after the report and period
+fn report_response<T: Serialize>(
+    report: &T,
+    csv: Option<(String, &str, &str)>,
+) -> HttpResponse {
+    match csv {
+        Some((body, name, period)) => HttpResponse::Ok()
+            .content_type("text/csv; charset=utf-8")
+            .insert_header((
+                "Content-Disposition",
+                format!(
+                    "attachment; filename=\"{}-{}.csv\"",
+                    name,
+                    period.replace(' ', "_")
+                ),
+            ))
+            .body(body),
+        None => HttpResponse::Ok().json(report),
+    }
+}
+
+/// GET /api/v1/reports/trial-balance
+#[get("/reports/trial-balance")]
+pub async fn trial_balance(
+    user: CurrentUser,
+    service: web::Data<ReportService>,
+    query: web::Query<ReportQuery>,
+) -> Result<impl Responder, ApiError> {
+    let csv = query.wants_csv()?;
+    let period = ReportService::resolve_period(
+        query.fy.as_deref(),
+        query.from.as_deref(),
+        query.to.as_deref(),
+    )?;
+    let report = service
+        .trial_balance(&user.org_email, period, query.currency.as_deref())
+        .await?;
+
+    let export = csv.then(|| (report.to_csv(), "trial-balance", report.period.label.as_str()));
+    Ok(report_response(&report, export))
+}
+
+/// GET /api/v1/reports/profit-and-loss
+#[get("/reports/profit-and-loss")]
+pub async fn profit_and_loss(
+    user: CurrentUser,
+    service: web::Data<ReportService>,
+    query: web::Query<ReportQuery>,
+) -> Result<impl Responder, ApiError> {
+    let csv = query.wants_csv()?;
+    let period = ReportService::resolve_period(
+        query.fy.as_deref(),
+        query.from.as_deref(),
+        query.to.as_deref(),
+    )?;
+    let report = service
+        .profit_and_loss(&user.org_email, period, query.currency.as_deref())
+        .await?;
+
+    let export = csv.then(|| (report.to_csv(), "profit-and-loss", report.period.label.as_str()));
+    Ok(report_response(&report, export))
+}
+
+/// GET /api/v1/reports/balance-sheet
+#[get("/reports/balance-sheet")]
+pub async fn balance_sheet(
+    user: CurrentUser,
+    service: web::Data<ReportService>,
+    query: web::Query<ReportQuery>,
+) -> Result<impl Responder, ApiError> {
+    let csv = query.wants_csv()?;
+    let period = ReportService::resolve_period(
+        query.fy.as_deref(),
+        query.from.as_deref(),
+        query.to.as_deref(),
+    )?;
+    let report = service
+        .balance_sheet(&user.org_email, period, query.currency.as_deref())
+        .await?;
+
+    let export = csv.then(|| (report.to_csv(), "balance-sheet", report.period.label.as_str()));
+    Ok(report_response(&report, export))
+}
+
+/// Register report routes under /api/v1
+pub fn configure_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(trial_balance)
+        .service(profit_and_loss)
+        .service(balance_sheet);
+}
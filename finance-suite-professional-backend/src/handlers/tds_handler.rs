@@ -0,0 +1,159 @@
+use actix_web::{delete, get, post, web, HttpResponse, Responder};
+use serde::Deserialize;
+
+use crate::error::ApiError;
+use crate::middleware::CurrentUser;
+use crate::models::tds::{Form26QRequest, TdsChallanRequest};
+use crate::services::TdsService;
+
+/// Form 26AS text exports of busy deductees run to a few megabytes
+const FORM_26AS_MAX_BYTES: usize = 10 * 1024 * 1024;
+
+#[derive(Deserialize)]
+pub struct TdsSummaryQuery {
+    /// e.g. "2025-26"
+    financial_year: String,
+    /// "Q1" to "Q4"
+    quarter: String,
+}
+
+/// GET /api/v1/tds/sections
+#[get("/tds/sections")]
+pub async fn get_sections(
+    user: CurrentUser,
+    service: web::Data<TdsService>,
+) -> Result<impl Responder, ApiError> {
+    let sections = service.sections(&user.org_email).await?;
+    Ok(HttpResponse::Ok().json(sections))
+}
+
+/// GET /api/v1/tds/summary?financial_year=&quarter=
+///
+/// TDS deducted per deductee PAN and section, and TDS customers deducted
+/// from us, for one quarter
+#[get("/tds/summary")]
+pub async fn get_summary(
+    user: CurrentUser,
+    service: web::Data<TdsService>,
+    query: web::Query<TdsSummaryQuery>,
+) -> Result<impl Responder, ApiError> {
+    let summary = service
+        .quarterly_summary(&user.org_email, &query.financial_year, &query.quarter)
+        .await?;
+    Ok(HttpResponse::Ok().json(summary))
+}
+
+/// POST /api/v1/tds/challans
+#[post("/tds/challans")]
+pub async fn create_challan(
+    user: CurrentUser,
+    service: web::Data<TdsService>,
+    req: web::Json<TdsChallanRequest>,
+) -> Result<impl Responder, ApiError> {
+    let challan = service
+        .create_challan(&user.org_email, req.into_inner())
+        .await?;
+    Ok(HttpResponse::Created().json(challan))
+}
+
+/// GET /api/v1/tds/challans?financial_year=&quarter=
+#[get("/tds/challans")]
+pub async fn get_challans(
+    user: CurrentUser,
+    service: web::Data<TdsService>,
+    query: web::Query<TdsSummaryQuery>,
+) -> Result<impl Responder, ApiError> {
+    let challans = service
+        .get_challans(&user.org_email, &query.financial_year, &query.quarter)
+        .await?;
+    Ok(HttpResponse::Ok().json(challans))
+}
+
+/// DELETE /api/v1/tds/challans/{id}
+#[delete("/tds/challans/{id}")]
+pub async fn delete_challan(
+    user: CurrentUser,
+    service: web::Data<TdsService>,
+    path: web::Path<String>,
+) -> Result<impl Responder, ApiError> {
+    service
+        .delete_challan(&user.org_email, &path.into_inner())
+        .await?;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+#[derive(Deserialize)]
+pub struct Form26QQuery {
+    /// Return only the FVU text file; refused while there are errors
+    #[serde(default)]
+    download: bool,
+}
+
+/// POST /api/v1/tds/26q
+///
+/// The Form 26Q statement with its pre-flight errors and warnings, or with
+/// `download=true` just the text file for the FVU
+#[post("/tds/26q")]
+pub async fn form_26q(
+    user: CurrentUser,
+    service: web::Data<TdsService>,
+    query: web::Query<Form26QQuery>,
+    req: web::Json<Form26QRequest>,
+) -> Result<impl Responder, ApiError> {
+    let statement = service.form_26q(&user.org_email, &req).await?;
+
+    if query.download {
+        if !statement.errors.is_empty() {
+            return Err(ApiError::ValidationError(statement.errors.join("; ")));
+        }
+        return Ok(HttpResponse::Ok()
+            .content_type("text/plain; charset=utf-8")
+            .insert_header((
+                "Content-Disposition",
+                format!("attachment; filename=\"{}\"", statement.file_name),
+            ))
+            .body(statement.text));
+    }
+    Ok(HttpResponse::Ok().json(statement))
+}
+
+#[derive(Deserialize)]
+pub struct TdsCreditQuery {
+    /// e.g. "2025-26"
+    financial_year: String,
+}
+
+/// POST /api/v1/tds/26as/reconcile?financial_year=
+///
+/// Body is the Form 26AS text file from TRACES or the AIS JSON export
+pub async fn reconcile_26as(
+    user: CurrentUser,
+    service: web::Data<TdsService>,
+    query: web::Query<TdsCreditQuery>,
+    body: web::Bytes,
+) -> Result<impl Responder, ApiError> {
+    if body.is_empty() {
+        return Err(ApiError::BadRequest(
+            "Form 26AS text or AIS JSON body is required".to_string(),
+        ));
+    }
+    let reconciliation = service
+        .reconcile_credits(&user.org_email, &query.financial_year, &body)
+        .await?;
+    Ok(HttpResponse::Ok().json(reconciliation))
+}
+
+/// Register TDS routes under /api/v1
+pub fn configure_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(get_sections)
+        .service(get_summary)
+        .service(create_challan)
+        .service(get_challans)
+        .service(delete_challan)
+        .service(form_26q)
+        .service(
+            web::resource("/tds/26as/reconcile")
+                .app_data(web::PayloadConfig::new(FORM_26AS_MAX_BYTES))
+                .route(web::post().to(reconcile_26as)),
+        );
+}
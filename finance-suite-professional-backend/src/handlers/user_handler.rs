@@ -0,0 +1,60 @@
+use actix_web::{delete, get, post, put, web, HttpResponse, Responder};
+
+use crate::error::ApiError;
+use crate::middleware::CurrentUser;
+use crate::models::user::{CreateUserRequest, UpdateUserRequest};
+use crate::services::UserService;
+
+/// GET /api/v1/users
+///
+/// Users of the caller's organisation
+#[get("/users")]
+pub async fn get_users(
+    service: web::Data<UserService>,
+    user: CurrentUser,
+) -> Result<impl Responder, ApiError> {
+    let users = service.list_users(&user).await?;
+    Ok(HttpResponse::Ok().json(users))
+}
+
+/// POST /api/v1/users
+#[post("/users")]
+pub async fn create_user(
+    service: web::Data<UserService>,
+    user: CurrentUser,
+    req: web::Json<CreateUserRequest>,
+) -> Result<impl Responder, ApiError> {
+    let created = service.create_user(&user, req.into_inner()).await?;
+    Ok(HttpResponse::Created().json(created))
+}
+
+/// PUT /api/v1/users/{id}
+#[put("/users/{id}")]
+pub async fn update_user(
+    service: web::Data<UserService>,
+    user: CurrentUser,
+    id: web::Path<String>,
+    req: web::Json<UpdateUserRequest>,
+) -> Result<impl Responder, ApiError> {
+    let updated = service.update_user(&user, &id, req.into_inner()).await?;
+    Ok(HttpResponse::Ok().json(updated))
+}
+
+/// DELETE /api/v1/users/{id}
+#[delete("/users/{id}")]
+pub async fn delete_user(
+    service: web::Data<UserService>,
+    user: CurrentUser,
+    id: web::Path<String>,
+) -> Result<impl Responder, ApiError> {
+    service.delete_user(&user, &id).await?;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+/// Register user management routes under /api/v1
+pub fn configure_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(get_users)
+        .service(create_user)
+        .service(update_user)
+        .service(delete_user);
+}
@@ -0,0 +1,146 @@
+use actix_web::{delete, get, post, put, web, HttpResponse, Responder};
+use serde::Deserialize;
+
+use crate::error::ApiError;
+use crate::middleware::CurrentUser;
+use crate::models::vendor_bill::{CreateBillPaymentRequest, VendorBillRequest};
+use crate::services::VendorBillService;
+
+#[derive(Deserialize)]
+pub struct ListBillsQuery {
+    vendor_id: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct ListBillPaymentsQuery {
+    bill_id: Option<String>,
+}
+
+/// POST /api/v1/vendor-bills
+#[post("/vendor-bills")]
+pub async fn create_bill(
+    user: CurrentUser,
+    service: web::Data<VendorBillService>,
+    req: web::Json<VendorBillRequest>,
+) -> Result<impl Responder, ApiError> {
+    let bill = service
+        .create_bill(req.into_inner(), &user.org_email)
+        .await?;
+    Ok(HttpResponse::Created().json(bill))
+}
+
+/// GET /api/v1/vendor-bills?vendor_id=
+#[get("/vendor-bills")]
+pub async fn get_bills(
+    user: CurrentUser,
+    service: web::Data<VendorBillService>,
+    query: web::Query<ListBillsQuery>,
+) -> Result<impl Responder, ApiError> {
+    let bills = service
+        .get_bills(query.vendor_id.as_deref(), &user.org_email)
+        .await?;
+    Ok(HttpResponse::Ok().json(bills))
+}
+
+/// GET /api/v1/vendor-bills/{id}
+#[get("/vendor-bills/{id}")]
+pub async fn get_bill_by_id(
+    user: CurrentUser,
+    service: web::Data<VendorBillService>,
+    id: web::Path<String>,
+) -> Result<impl Responder, ApiError> {
+    let bill = service.get_bill_by_id(&id, &user.org_email).await?;
+    Ok(HttpResponse::Ok().json(bill))
+}
+
+/// PUT /api/v1/vendor-bills/{id}
+#[put("/vendor-bills/{id}")]
+pub async fn update_bill(
+    user: CurrentUser,
+    service: web::Data<VendorBillService>,
+    id: web::Path<String>,
+    req: web::Json<VendorBillRequest>,
+) -> Result<impl Responder, ApiError> {
+    let bill = service
+        .update_bill(&id, req.into_inner(), &user.org_email)
+        .await?;
+    Ok(HttpResponse::Ok().json(bill))
+}
+
+/// DELETE /api/v1/vendor-bills/{id}
+#[delete("/vendor-bills/{id}")]
+pub async fn delete_bill(
+    user: CurrentUser,
+    service: web::Data<VendorBillService>,
+    id: web::Path<String>,
+) -> Result<impl Responder, ApiError> {
+    if service.delete_bill(&id, &user.org_email).await? {
+        Ok(HttpResponse::NoContent().finish())
+    } else {
+        Err(ApiError::NotFound("Vendor bill not found".to_string()))
+    }
+}
+
+/// POST /api/v1/bill-payments
+#[post("/bill-payments")]
+pub async fn record_bill_payment(
+    user: CurrentUser,
+    service: web::Data<VendorBillService>,
+    req: web::Json<CreateBillPaymentRequest>,
+) -> Result<impl Responder, ApiError> {
+    let payment = service
+        .record_bill_payment(req.into_inner(), &user.org_email)
+        .await?;
+    Ok(HttpResponse::Created().json(payment))
+}
+
+/// GET /api/v1/bill-payments?bill_id=
+#[get("/bill-payments")]
+pub async fn get_bill_payments(
+    user: CurrentUser,
+    service: web::Data<VendorBillService>,
+    query: web::Query<ListBillPaymentsQuery>,
+) -> Result<impl Responder, ApiError> {
+    let payments = service
+        .get_bill_payments(query.bill_id.as_deref(), &user.org_email)
+        .await?;
+    Ok(HttpResponse::Ok().json(payments))
+}
+
+/// GET /api/v1/bill-payments/{id}
+#[get("/bill-payments/{id}")]
+pub async fn get_bill_payment_by_id(
+    user: CurrentUser,
+    service: web::Data<VendorBillService>,
+    id: web::Path<String>,
+) -> Result<impl Responder, ApiError> {
+    let payment = service.get_bill_payment_by_id(&id, &user.org_email).await?;
+    Ok(HttpResponse::Ok().json(payment))
+}
+
+/// DELETE /api/v1/bill-payments/{id}
+#[delete("/bill-payments/{id}")]
+pub async fn delete_bill_payment(
+    user: CurrentUser,
+    service: web::Data<VendorBillService>,
+    id: web::Path<String>,
+) -> Result<impl Responder, ApiError> {
+    if service.delete_bill_payment(&id, &user.org_email).await? {
+        Ok(HttpResponse::NoContent().finish())
+    } else {
+        Err(ApiError::NotFound("Bill payment not found".to_string()))
+    }
+}
+
+/// Register vendor bill and bill payment routes under /api/v1
+pub fn configure_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(create_bill)
+        .service(get_bills)
+        .service(get_bill_by_id)
+        .service(update_bill)
+        .service(delete_bill)
+        .service(record_bill_payment)
+        .service(get_bill_payments)
+        .service(get_bill_payment_by_id)
+        .service(delete_bill_payment);
+}
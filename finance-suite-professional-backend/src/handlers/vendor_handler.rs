@@ -0,0 +1,95 @@
+use actix_web::{delete, get, post, put, web, HttpResponse, Responder};
+use serde::Deserialize;
+
+use crate::error::ApiError;
+use crate::middleware::CurrentUser;
+use crate::models::vendor::VendorRequest;
+use crate::services::VendorService;
+
+#[derive(Deserialize)]
+pub struct SearchQuery {
+    q: String,
+}
+
+/// POST /api/v1/vendors
+#[post("/vendors")]
+pub async fn create_vendor(
+    user: CurrentUser,
+    service: web::Data<VendorService>,
+    req: web::Json<VendorRequest>,
+) -> Result<impl Responder, ApiError> {
+    let vendor = service
+        .create_vendor(req.into_inner(), &user.org_email)
+        .await?;
+    Ok(HttpResponse::Created().json(vendor))
+}
+
+/// GET /api/v1/vendors
+#[get("/vendors")]
+pub async fn get_all_vendors(
+    user: CurrentUser,
+    service: web::Data<VendorService>,
+) -> Result<impl Responder, ApiError> {
+    let vendors = service.get_all_vendors(&user.org_email).await?;
+    Ok(HttpResponse::Ok().json(vendors))
+}
+
+/// GET /api/v1/vendors/search?q=
+#[get("/vendors/search")]
+pub async fn search_vendors(
+    user: CurrentUser,
+    service: web::Data<VendorService>,
+    query: web::Query<SearchQuery>,
+) -> Result<impl Responder, ApiError> {
+    let vendors = service.search_vendors(&query.q, &user.org_email).await?;
+    Ok(HttpResponse::Ok().json(vendors))
+}
+
+/// GET /api/v1/vendors/{id}
+#[get("/vendors/{id}")]
+pub async fn get_vendor_by_id(
+    user: CurrentUser,
+    service: web::Data<VendorService>,
+    id: web::Path<String>,
+) -> Result<impl Responder, ApiError> {
+    let vendor = service.get_vendor_by_id(&id, &user.org_email).await?;
+    Ok(HttpResponse::Ok().json(vendor))
+}
+
+/// PUT /api/v1/vendors/{id}
+#[put("/vendors/{id}")]
+pub async fn update_vendor(
+    user: CurrentUser,
+    service: web::Data<VendorService>,
+    id: web::Path<String>,
+    req: web::Json<VendorRequest>,
+) -> Result<impl Responder, ApiError> {
+    let vendor = service
+        .update_vendor(&id, req.into_inner(), &user.org_email)
+        .await?;
+    Ok(HttpResponse::Ok().json(vendor))
+}
+
+/// DELETE /api/v1/vendors/{id}
+#[delete("/vendors/{id}")]
+pub async fn delete_vendor(
+    user: CurrentUser,
+    service: web::Data<VendorService>,
+    id: web::Path<String>,
+) -> Result<impl Responder, ApiError> {
+    if service.delete_vendor(&id, &user.org_email).await? {
+        Ok(HttpResponse::NoContent().finish())
+    } else {
+        Err(ApiError::NotFound("Vendor not found".to_string()))
+    }
+}
+
+/// Register vendor routes under /api/v1
+pub fn configure_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(create_vendor)
+        .service(get_all_vendors)
+        .service(search_vendors)
+        .service(get_vendor_by_id)
+        .service(update_vendor)
+        .service(delete_vendor);
+}
@@ -1,6 +1,7 @@
 mod db;
 mod error;
 mod handlers;
+mod middleware;
 mod models;
 mod repository;
 mod services;
@@ -10,18 +11,48 @@ use actix_cors::Cors;
 use actix_web::{middleware::Logger, web, App, HttpResponse, HttpServer};
 use dotenv::dotenv;
 use std::env;
+use std::sync::Arc;
 
 use db::MongoDbClient;
 use handlers::{
+    configure_auth_routes,
+    configure_catalog_routes,
+    configure_credit_debit_note_routes,
     configure_customer_routes, 
+    configure_e_invoice_routes,
+    configure_e_way_bill_routes,
+    configure_estimate_routes,
     configure_expense_routes, 
+    configure_gst_return_routes,
     configure_invoice_routes,
+    configure_ledger_routes,
     configure_organisation_routes,
+    configure_payment_routes,
+    configure_purchase_order_routes,
+    configure_recurring_invoice_routes,
+    configure_report_routes,
+    configure_tds_routes,
+    configure_user_routes,
+    configure_vendor_bill_routes,
+    configure_vendor_routes,
 };
+use middleware::Authentication;
 use repository::{
-    CustomerRepository, ExpenseRepository, InvoiceRepository, OrganisationRepository,
+    AccountRepository, BillPaymentRepository, CatalogItemRepository, CreditDebitNoteRepository,
+    CustomerRepository, EstimateRepository, ExpenseRepository, InvoiceRepository,
+    JournalRepository, OrganisationRepository, PaymentRepository, PurchaseOrderRepository,
+    RecurringInvoiceRepository, RefreshTokenRepository, TdsChallanRepository, UserRepository,
+    VendorBillRepository, VendorRepository,
 };
-use services::{CustomerService, ExpenseService, InvoiceService, OrganisationService};
+use services::{
+    AuthService, CatalogService, CreditDebitNoteService, CustomerService, EInvoiceService,
+    EWayBillService, EstimateService, ExpenseService, Gstr1Service, Gstr2bService,
+    Gstr3bService, InvoiceService, LedgerService, OrganisationService, PaymentService,
+    PurchaseOrderService, RecurringInvoiceService, ReportService, TdsService, UserService,
+    VendorBillService, VendorService,
+};
+use services::auth_service::JwtConfig;
+use services::irp_client::{IrpClient, StubIrpClient};
 
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
@@ -37,31 +68,220 @@ async fn main() -> std::io::Result<()> {
     let cors_origin =
         env::var("CORS_ALLOWED_ORIGIN").unwrap_or_else(|_| "http://localhost:3000".to_string());
 
+    let jwt_config = JwtConfig {
+        secret: env::var("JWT_SECRET").expect("JWT_SECRET must be set"),
+        access_ttl: chrono::Duration::minutes(
+            env::var("JWT_ACCESS_TTL_MINUTES")
+                .unwrap_or_else(|_| "15".to_string())
+                .parse()
+                .expect("Invalid JWT_ACCESS_TTL_MINUTES"),
+        ),
+        refresh_ttl: chrono::Duration::days(
+            env::var("JWT_REFRESH_TTL_DAYS")
+                .unwrap_or_else(|_| "30".to_string())
+                .parse()
+                .expect("Invalid JWT_REFRESH_TTL_DAYS"),
+        ),
+    };
+
     let db_client = MongoDbClient::new()
         .await
         .expect("❌ Failed to connect to MongoDB");
 
     log::info!("✅ Connected to MongoDB successfully");
 
-    // 🔹 Customers
-    let customer_collection = db_client.get_customers_collection();
-    let customer_repository = CustomerRepository::new(customer_collection);
-    let customer_service = CustomerService::new(customer_repository);
+    db_client
+        .migrate_money_fields()
+        .await
+        .expect("❌ Failed to migrate money fields");
+    db_client
+        .assign_organisations()
+        .await
+        .expect("❌ Failed to assign records to organisations");
 
     // 🔹 Organisations
     let organisation_collection = db_client.get_organisation_collection();
     let organisation_repository = OrganisationRepository::new(organisation_collection);
     let organisation_service = OrganisationService::new(organisation_repository.clone());
 
+    // 🔹 Users and sign-in
+    let user_repository = UserRepository::new(db_client.get_user_collection());
+    let refresh_token_repository =
+        RefreshTokenRepository::new(db_client.get_refresh_token_collection());
+    let auth_service = AuthService::new(
+        user_repository.clone(),
+        refresh_token_repository.clone(),
+        organisation_repository.clone(),
+        jwt_config,
+    );
+    let user_service = UserService::new(user_repository, refresh_token_repository);
+
+    // 🔹 Customers
+    let customer_collection = db_client.get_customers_collection();
+    let customer_repository = CustomerRepository::new(customer_collection);
+    let customer_service = CustomerService::new(customer_repository.clone());
+
+    // 🔹 General ledger
+    let account_repository = AccountRepository::new(db_client.get_account_collection());
+    account_repository
+        .ensure_indexes()
+        .await
+        .expect("❌ Failed to create account indexes");
+    let journal_repository = JournalRepository::new(db_client.get_journal_collection());
+    journal_repository
+        .ensure_indexes()
+        .await
+        .expect("❌ Failed to create journal indexes");
+    let ledger_service = LedgerService::new(account_repository, journal_repository.clone());
+    let report_service = ReportService::new(journal_repository.clone(), ledger_service.clone());
+
+    // 🔹 Purchase orders
+    let purchase_order_collection = db_client.get_purchase_order_collection();
+    let purchase_order_repository = PurchaseOrderRepository::new(purchase_order_collection);
+    let purchase_order_service = PurchaseOrderService::new(
+        purchase_order_repository.clone(),
+        organisation_repository.clone(),
+    );
+
+    // 🔹 Products and services catalogue
+    let catalog_repository = CatalogItemRepository::new(db_client.get_catalog_item_collection());
+    let catalog_service = CatalogService::new(catalog_repository.clone());
+
     // 🔹 Invoices
     let invoice_collection = db_client.get_invoice_collection();
     let invoice_repository = InvoiceRepository::new(invoice_collection);
-    let invoice_service = InvoiceService::new(invoice_repository, organisation_repository);
+    invoice_repository
+        .ensure_indexes()
+        .await
+        .expect("❌ Failed to create invoice indexes");
+    let invoice_service = InvoiceService::new(
+        invoice_repository.clone(),
+        organisation_repository.clone(),
+        purchase_order_repository.clone(),
+        ledger_service.clone(),
+        catalog_service.clone(),
+    );
+    let overdue_interval = env::var("OVERDUE_CHECK_INTERVAL_SECS")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .unwrap_or(3600);
+    invoice_service
+        .clone()
+        .spawn_overdue_scheduler(std::time::Duration::from_secs(overdue_interval));
+
+    // 🔹 E-invoicing; replace the stub with a GSP-backed IrpClient for production
+    let irp_client: Arc<dyn IrpClient> = Arc::new(StubIrpClient);
+    let e_invoice_service = EInvoiceService::new(
+        invoice_repository.clone(),
+        organisation_repository.clone(),
+        customer_repository.clone(),
+        catalog_repository.clone(),
+        irp_client,
+    );
+    let e_way_bill_service = EWayBillService::new(
+        invoice_repository.clone(),
+        organisation_repository.clone(),
+        catalog_repository,
+    );
+
+    // 🔹 Payments
+    let payment_collection = db_client.get_payment_collection();
+    let payment_repository = PaymentRepository::new(payment_collection);
+    let payment_service = PaymentService::new(
+        payment_repository.clone(),
+        invoice_repository.clone(),
+        organisation_repository.clone(),
+    );
+
+    // 🔹 Credit / Debit notes
+    let note_collection = db_client.get_credit_debit_note_collection();
+    let note_repository = CreditDebitNoteRepository::new(note_collection);
+    let note_service = CreditDebitNoteService::new(
+        note_repository.clone(),
+        invoice_repository.clone(),
+        organisation_repository.clone(),
+    );
+
+    // 🔹 Vendors and bills
+    let vendor_repository = VendorRepository::new(db_client.get_vendor_collection());
+    let vendor_bill_repository = VendorBillRepository::new(db_client.get_vendor_bill_collection());
+    let bill_payment_repository =
+        BillPaymentRepository::new(db_client.get_bill_payment_collection());
+    let vendor_service =
+        VendorService::new(vendor_repository.clone(), vendor_bill_repository.clone());
+    let vendor_bill_service = VendorBillService::new(
+        vendor_bill_repository.clone(),
+        bill_payment_repository.clone(),
+        vendor_repository,
+        organisation_repository.clone(),
+        purchase_order_repository,
+    );
+    vendor_bill_service
+        .clone()
+        .spawn_overdue_scheduler(std::time::Duration::from_secs(overdue_interval));
+
+
+    // 🔹 Estimates
+    let estimate_collection = db_client.get_estimate_collection();
+    let estimate_repository = EstimateRepository::new(estimate_collection);
+    let estimate_service = EstimateService::new(
+        estimate_repository,
+        organisation_repository.clone(),
+        invoice_service.clone(),
+    );
+
+    // 🔹 Recurring invoices
+    let recurring_collection = db_client.get_recurring_invoice_collection();
+    let recurring_repository = RecurringInvoiceRepository::new(recurring_collection);
+    let recurring_service = RecurringInvoiceService::new(
+        recurring_repository,
+        invoice_repository.clone(),
+        invoice_service.clone(),
+    );
+    let recurring_interval = env::var("RECURRING_INVOICE_INTERVAL_SECS")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .unwrap_or(3600);
+    recurring_service
+        .clone()
+        .spawn_scheduler(std::time::Duration::from_secs(recurring_interval));
 
     // 🔹 Expenses
     let expense_collection = db_client.get_expense_collection();
     let expense_repository = ExpenseRepository::new(expense_collection);
-    let expense_service = ExpenseService::new(expense_repository);
+    let expense_service =
+        ExpenseService::new(expense_repository.clone(), ledger_service.clone());
+
+    // 🔹 GST returns
+    let gstr1_service = Gstr1Service::new(
+        invoice_repository.clone(),
+        note_repository.clone(),
+        organisation_repository.clone(),
+    );
+    let gstr3b_service = Gstr3bService::new(
+        invoice_repository,
+        note_repository,
+        organisation_repository.clone(),
+        journal_repository.clone(),
+        expense_repository.clone(),
+    );
+    let gstr2b_service = Gstr2bService::new(
+        organisation_repository.clone(),
+        vendor_bill_repository,
+        journal_repository,
+        expense_repository,
+    );
+
+    // 🔹 TDS
+    let tds_challan_repository =
+        TdsChallanRepository::new(db_client.get_tds_challan_collection());
+    let tds_service = TdsService::new(
+        organisation_repository,
+        payment_repository,
+        bill_payment_repository,
+        tds_challan_repository,
+        customer_repository,
+    );
 
     log::info!("🚀 Starting server at http://{}:{}", host, port);
 
@@ -80,19 +300,54 @@ async fn main() -> std::io::Result<()> {
             .wrap(cors)
             .wrap(Logger::default())
             // inject services
+            .app_data(web::Data::new(auth_service.clone()))
+            .app_data(web::Data::new(user_service.clone()))
             .app_data(web::Data::new(customer_service.clone()))
             .app_data(web::Data::new(organisation_service.clone()))
+            .app_data(web::Data::new(catalog_service.clone()))
             .app_data(web::Data::new(invoice_service.clone()))
+            .app_data(web::Data::new(e_invoice_service.clone()))
+            .app_data(web::Data::new(e_way_bill_service.clone()))
             .app_data(web::Data::new(expense_service.clone()))
+            .app_data(web::Data::new(payment_service.clone()))
+            .app_data(web::Data::new(note_service.clone()))
+            .app_data(web::Data::new(recurring_service.clone()))
+            .app_data(web::Data::new(estimate_service.clone()))
+            .app_data(web::Data::new(purchase_order_service.clone()))
+            .app_data(web::Data::new(vendor_service.clone()))
+            .app_data(web::Data::new(vendor_bill_service.clone()))
+            .app_data(web::Data::new(ledger_service.clone()))
+            .app_data(web::Data::new(report_service.clone()))
+            .app_data(web::Data::new(gstr1_service.clone()))
+            .app_data(web::Data::new(gstr3b_service.clone()))
+            .app_data(web::Data::new(gstr2b_service.clone()))
+            .app_data(web::Data::new(tds_service.clone()))
             // health
             .route("/health", web::get().to(health_check))
-            // all APIs under /api/v1
+            // all APIs under /api/v1; everything but sign-in needs a bearer token
             .service(
                 web::scope("/api/v1")
+                    .wrap(Authentication)
+                    .configure(configure_auth_routes)
+                    .configure(configure_user_routes)
                     .configure(configure_customer_routes)
                     .configure(configure_organisation_routes)
+                    .configure(configure_catalog_routes)
                     .configure(configure_invoice_routes)
-                    .configure(configure_expense_routes),
+                    .configure(configure_e_invoice_routes)
+                    .configure(configure_e_way_bill_routes)
+                    .configure(configure_expense_routes)
+                    .configure(configure_payment_routes)
+                    .configure(configure_credit_debit_note_routes)
+                    .configure(configure_recurring_invoice_routes)
+                    .configure(configure_estimate_routes)
+                    .configure(configure_purchase_order_routes)
+                    .configure(configure_vendor_routes)
+                    .configure(configure_vendor_bill_routes)
+                    .configure(configure_ledger_routes)
+                    .configure(configure_report_routes)
+                    .configure(configure_gst_return_routes)
+                    .configure(configure_tds_routes),
             )
     })
     .bind((host, port))?
@@ -0,0 +1,169 @@
+//! Bearer-token authentication for the `/api/v1` scope. Requests without a
+//! valid access token are rejected with 401; otherwise the signed-in user is
+//! stored on the request for the `CurrentUser` and `CurrentOrganisation`
+//! extractors.
+
+use std::future::{ready, Ready};
+use std::ops::Deref;
+use std::rc::Rc;
+
+use actix_web::body::EitherBody;
+use actix_web::dev::{forward_ready, Payload, Service, ServiceRequest, ServiceResponse, Transform};
+use actix_web::http::{header, Method};
+use actix_web::{web, Error, FromRequest, HttpMessage, HttpRequest, ResponseError};
+use futures::future::LocalBoxFuture;
+
+use crate::error::ApiError;
+use crate::models::user::User;
+use crate::models::Organisation;
+use crate::services::{AuthService, OrganisationService};
+
+/// Routes reachable without an access token
+const PUBLIC_PATHS: &[&str] = &[
+    "/api/v1/auth/register",
+    "/api/v1/auth/login",
+    "/api/v1/auth/refresh",
+    "/api/v1/auth/logout",
+];
+
+fn bearer_token(req: &ServiceRequest) -> Option<String> {
+    let value = req.headers().get(header::AUTHORIZATION)?.to_str().ok()?;
+    let (scheme, token) = value.trim().split_once(' ')?;
+    scheme
+        .eq_ignore_ascii_case("Bearer")
+        .then(|| token.trim().to_string())
+        .filter(|token| !token.is_empty())
+}
+
+/// Middleware factory; wrap the API scope with `.wrap(Authentication)`
+pub struct Authentication;
+
+impl<S, B> Transform<S, ServiceRequest> for Authentication
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Transform = AuthenticationMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(AuthenticationMiddleware {
+            service: Rc::new(service),
+        }))
+    }
+}
+
+pub struct AuthenticationMiddleware<S> {
+    service: Rc<S>,
+}
+
+impl<S, B> Service<ServiceRequest> for AuthenticationMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let service = Rc::clone(&self.service);
+
+        Box::pin(async move {
+            if req.method() == Method::OPTIONS || PUBLIC_PATHS.contains(&req.path()) {
+                return service.call(req).await.map(ServiceResponse::map_into_left_body);
+            }
+
+            let result = match (req.app_data::<web::Data<AuthService>>(), bearer_token(&req)) {
+                (None, _) => Err(ApiError::InternalServerError(
+                    "Authentication is not configured".to_string(),
+                )),
+                (Some(_), None) => Err(ApiError::Unauthorized(
+                    "Missing bearer access token".to_string(),
+                )),
+                (Some(auth), Some(token)) => auth.authenticate(&token).await,
+            };
+
+            match result {
+                Ok(user) => {
+                    req.extensions_mut().insert(CurrentUser(user));
+                    service.call(req).await.map(ServiceResponse::map_into_left_body)
+                }
+                Err(e) => {
+                    let response = e.error_response();
+                    Ok(req.into_response(response).map_into_right_body())
+                }
+            }
+        })
+    }
+}
+
+/// The signed-in user, put on the request by `Authentication`
+#[derive(Debug, Clone)]
+pub struct CurrentUser(pub User);
+
+impl Deref for CurrentUser {
+    type Target = User;
+
+    fn deref(&self) -> &User {
+        &self.0
+    }
+}
+
+impl FromRequest for CurrentUser {
+    type Error = ApiError;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        ready(
+            req.extensions()
+                .get::<CurrentUser>()
+                .cloned()
+                .ok_or_else(|| ApiError::Unauthorized("Not signed in".to_string())),
+        )
+    }
+}
+
+/// Organisation of the signed-in user, loaded when a handler asks for it
+#[derive(Debug, Clone)]
+pub struct CurrentOrganisation(pub Organisation);
+
+impl Deref for CurrentOrganisation {
+    type Target = Organisation;
+
+    fn deref(&self) -> &Organisation {
+        &self.0
+    }
+}
+
+impl FromRequest for CurrentOrganisation {
+    type Error = ApiError;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        let user = req.extensions().get::<CurrentUser>().cloned();
+        let service = req.app_data::<web::Data<OrganisationService>>().cloned();
+
+        Box::pin(async move {
+            let user = user.ok_or_else(|| ApiError::Unauthorized("Not signed in".to_string()))?;
+            let service = service.ok_or_else(|| {
+                ApiError::InternalServerError("Organisation service is not configured".to_string())
+            })?;
+            let organisation = service
+                .get_organisation_by_email(&user.org_email)
+                .await
+                .map_err(|_| {
+                    ApiError::NotFound(format!(
+                        "Organisation {} has not been set up yet",
+                        user.org_email
+                    ))
+                })?;
+            Ok(CurrentOrganisation(organisation))
+        })
+    }
+}
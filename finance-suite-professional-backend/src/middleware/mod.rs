@@ -0,0 +1,5 @@
+pub mod auth;
+pub mod permissions;
+
+pub use auth::{Authentication, CurrentOrganisation, CurrentUser};
+pub use permissions::Access;
@@ -0,0 +1,76 @@
+//! Role-based authorisation against the organisation's `Permissions` matrix.
+//! Handlers take an `Access` and call `require` with the resource and action
+//! they touch before doing any work.
+
+use std::ops::Deref;
+
+use actix_web::dev::Payload;
+use actix_web::{web, FromRequest, HttpMessage, HttpRequest};
+use futures::future::LocalBoxFuture;
+
+use crate::error::ApiError;
+use crate::models::organisation::{Action, Permissions, Resource};
+use crate::models::user::User;
+use crate::services::OrganisationService;
+
+use super::CurrentUser;
+
+/// The signed-in user with the permission matrix of their organisation
+#[derive(Debug, Clone)]
+pub struct Access {
+    user: User,
+    permissions: Permissions,
+}
+
+impl Access {
+    /// Refuse with a 403 `PERMISSION_DENIED` unless the caller's role may
+    /// perform `action` on `resource`
+    pub fn require(&self, resource: Resource, action: Action) -> Result<(), ApiError> {
+        if self.permissions.allows(self.user.role, resource, action) {
+            Ok(())
+        } else {
+            Err(ApiError::PermissionDenied {
+                role: self.user.role,
+                resource,
+                action,
+            })
+        }
+    }
+}
+
+impl Deref for Access {
+    type Target = User;
+
+    fn deref(&self) -> &User {
+        &self.user
+    }
+}
+
+impl FromRequest for Access {
+    type Error = ApiError;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        let user = req.extensions().get::<CurrentUser>().cloned();
+        let service = req.app_data::<web::Data<OrganisationService>>().cloned();
+
+        Box::pin(async move {
+            let CurrentUser(user) =
+                user.ok_or_else(|| ApiError::Unauthorized("Not signed in".to_string()))?;
+            let service = service.ok_or_else(|| {
+                ApiError::InternalServerError("Organisation service is not configured".to_string())
+            })?;
+            let permissions = match service.get_organisation_by_email(&user.org_email).await {
+                Ok(organisation) => organisation.permissions,
+                Err(ApiError::NotFound(_)) => {
+                    return Err(ApiError::Forbidden(format!(
+                        "Organisation {} has not been set up yet",
+                        user.org_email
+                    )))
+                }
+                Err(e) => return Err(e),
+            };
+            Ok(Access { user, permissions })
+        })
+    }
+}
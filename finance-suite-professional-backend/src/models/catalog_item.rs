@@ -0,0 +1,65 @@
+use mongodb::bson::{oid::ObjectId, DateTime};
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+use super::money::Money;
+
+/// Whether a catalogue entry is classified under HSN (goods) or SAC (services)
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CatalogItemKind {
+    Goods,
+    #[default]
+    Services,
+}
+
+/// Product or service that invoice lines can reference by id
+#[derive(Debug, Serialize, Deserialize, Clone, Validate)]
+pub struct CatalogItem {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+
+    #[validate(length(min = 1, message = "Item name is required"))]
+    pub name: String,
+
+    #[serde(default)]
+    pub kind: CatalogItemKind,
+
+    /// HSN (goods) or SAC (services) code, 4, 6 or 8 digits
+    #[serde(rename = "hsnSac", default)]
+    pub hsn_sac: String,
+
+    /// Default price per unit
+    #[serde(default)]
+    pub rate: Money,
+
+    /// Unit quantity code, e.g. "NOS", "HRS", "KGS"
+    #[serde(default)]
+    pub unit: String,
+
+    /// Default GST rate in percent (CGST + SGST, or IGST)
+    #[serde(rename = "gstRate", default)]
+    pub gst_rate: String,
+
+    #[serde(default)]
+    pub description: String,
+
+    #[serde(rename = "isActive", default = "default_active")]
+    pub is_active: bool,
+
+    /// Organisation whose catalogue holds the item; set by the server
+    #[serde(default)]
+    pub org_email: String,
+
+    #[serde(rename = "createdAt", default)]
+    pub created_at: Option<DateTime>,
+
+    #[serde(rename = "updatedAt", default)]
+    pub updated_at: Option<DateTime>,
+}
+
+fn default_active() -> bool {
+    true
+}
+
+/// Create / Update Catalogue Item Request DTO
+pub type CatalogItemRequest = CatalogItem;
@@ -0,0 +1,178 @@
+use mongodb::bson::{oid::ObjectId, DateTime};
+use serde::{Deserialize, Serialize};
+
+use super::invoice::InvoiceItem;
+use super::money::{default_currency, Money};
+
+/// Whether the note reduces (credit) or increases (debit) the original invoice
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum NoteType {
+    Credit,
+    Debit,
+}
+
+impl NoteType {
+    /// Stored value of `note_type`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NoteType::Credit => "Credit",
+            NoteType::Debit => "Debit",
+        }
+    }
+
+    /// Note type code used in GSTR-1 CDNR/CDNUR tables
+    pub fn gst_code(&self) -> &'static str {
+        match self {
+            NoteType::Credit => "C",
+            NoteType::Debit => "D",
+        }
+    }
+
+    /// Organisation counter field for this note series
+    pub fn sequence_field(&self) -> &'static str {
+        match self {
+            NoteType::Credit => "lastCreditNoteSequence",
+            NoteType::Debit => "lastDebitNoteSequence",
+        }
+    }
+
+    /// Prefix used when the organisation has not configured one
+    pub fn default_prefix(&self) -> &'static str {
+        match self {
+            NoteType::Credit => "CN",
+            NoteType::Debit => "DN",
+        }
+    }
+}
+
+/// Lifecycle of a note. Notes are issued as soon as they are saved, so an
+/// issued note is cancelled rather than deleted to keep the series intact
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NoteStatus {
+    #[default]
+    Issued,
+    Cancelled,
+}
+
+/// Credit or debit note issued against an invoice (section 34 of the CGST Act)
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CreditDebitNote {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+
+    /// Set from the route, not the request body
+    #[serde(default = "default_note_type")]
+    pub note_type: NoteType,
+
+    /// Generated from the organisation's note series
+    #[serde(default)]
+    pub note_number: String,
+
+    /// Set by the service; notes saved before cancellation existed are issued
+    #[serde(default)]
+    pub status: NoteStatus,
+
+    pub note_date: String,
+
+    /// e.g. Sales Return, Post Sale Discount, Deficiency in services,
+    /// Correction in Invoice, Change in POS, Others
+    #[serde(default)]
+    pub reason: String,
+
+    // Original invoice
+    pub original_invoice_id: String,
+
+    #[serde(default)]
+    pub original_invoice_number: String,
+
+    #[serde(default)]
+    pub original_invoice_date: String,
+
+    // Copied from the original invoice for the CDNR/CDNUR sections
+    #[serde(default)]
+    pub billcustomer_name: String,
+
+    #[serde(default)]
+    pub billcustomer_gstin: String,
+
+    #[serde(default)]
+    pub place_of_supply: String,
+
+    #[serde(default)]
+    pub invoice_type: String,
+
+    #[serde(default)]
+    pub reverse_charge: bool,
+
+    #[serde(default = "default_currency")]
+    pub currency: String,
+
+    #[serde(default)]
+    pub items: Vec<InvoiceItem>,
+
+    #[serde(rename = "subTotal", default)]
+    pub sub_total: Money,
+
+    #[serde(default)]
+    pub totalcgst: Money,
+
+    #[serde(default)]
+    pub totalsgst: Money,
+
+    #[serde(default)]
+    pub totaligst: Money,
+
+    #[serde(default)]
+    pub total: Money,
+
+    #[serde(default)]
+    pub notes: String,
+
+    /// Organisation that raised the note
+    #[serde(default)]
+    pub org_email: String,
+
+    #[serde(default)]
+    pub created_at: Option<DateTime>,
+
+    #[serde(default)]
+    pub cancelled_at: Option<DateTime>,
+
+    #[serde(default)]
+    pub cancel_reason: Option<String>,
+}
+
+fn default_note_type() -> NoteType {
+    NoteType::Credit
+}
+
+impl CreditDebitNote {
+    /// Registered recipients go to CDNR, everyone else to CDNUR
+    pub fn is_registered_recipient(&self) -> bool {
+        !self.billcustomer_gstin.trim().is_empty()
+    }
+
+    /// Validate the request before taxes are computed
+    pub fn validate(&self) -> Result<(), String> {
+        if self.note_date.trim().is_empty() {
+            return Err("Note date is required".to_string());
+        }
+        if self.original_invoice_id.trim().is_empty() {
+            return Err("Original invoice is required".to_string());
+        }
+        if self.items.is_empty() {
+            return Err("At least one item is required".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Create Credit/Debit Note Request DTO
+pub type CreateNoteRequest = CreditDebitNote;
+
+/// Request body for cancelling a note
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct CancelNoteRequest {
+    #[serde(default)]
+    pub reason: Option<String>,
+}
@@ -41,6 +41,14 @@ pub struct Customer {
 
     #[validate(email(message = "Invalid email format"))]
     pub email: String,
+
+    /// TAN the customer deducts TDS under; matches their Form 26AS entries
+    #[serde(default)]
+    pub tan: String,
+
+    /// Organisation the customer belongs to; set by the server
+    #[serde(default)]
+    pub org_email: String,
     // #[serde(rename = "createdAt")]
     // pub created_at: Option<DateTime<Utc>>,
 
@@ -64,6 +72,8 @@ pub struct CreateCustomerRequest {
     pub country_code: String,
     #[serde(rename = "isActive")]
     pub is_active: String,
+    #[serde(default)]
+    pub tan: String,
 }
 
 #[derive(Debug, Deserialize, Validate)]
@@ -82,10 +92,11 @@ pub struct UpdateCustomerRequest {
     pub country_code: Option<String>,
     #[serde(rename = "isActive")]
     pub is_active: Option<String>,
+    pub tan: Option<String>,
 }
 
 impl Customer {
-    pub fn new(req: CreateCustomerRequest) -> Self {
+    pub fn new(req: CreateCustomerRequest, org_email: &str) -> Self {
         Self {
             id: None,
             customer_name: req.customer_name,
@@ -97,6 +108,8 @@ impl Customer {
             email: req.email,
             country_code: req.country_code,
             is_active: req.is_active,
+            tan: req.tan,
+            org_email: org_email.to_string(),
             // created_at: Some(Utc::now()),
             // updated_at: Some(Utc::now()),
         }
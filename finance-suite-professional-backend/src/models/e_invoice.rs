@@ -0,0 +1,224 @@
+//! E-invoice in the NIC schema v1.1 JSON layout, and what the Invoice
+//! Registration Portal (IRP) returns for it. Field names follow the NIC
+//! schema; amounts are plain numbers in INR.
+
+use mongodb::bson::DateTime;
+use serde::{Deserialize, Deserializer, Serialize};
+
+/// NIC e-invoice schema version the payload is written for
+pub const EINVOICE_SCHEMA_VERSION: &str = "1.1";
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "PascalCase")]
+pub struct EInvoicePayload {
+    pub version: String,
+    pub tran_dtls: TranDtls,
+    pub doc_dtls: DocDtls,
+    pub seller_dtls: PartyDtls,
+    pub buyer_dtls: BuyerDtls,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ship_dtls: Option<PartyDtls>,
+    pub item_list: Vec<EInvoiceItem>,
+    pub val_dtls: ValDtls,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exp_dtls: Option<ExpDtls>,
+}
+
+/// Transaction details
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "PascalCase")]
+pub struct TranDtls {
+    /// Always "GST"
+    pub tax_sch: String,
+    /// "B2B", "EXPWP" (export with IGST) or "EXPWOP" (export under LUT)
+    pub sup_typ: String,
+    /// Reverse charge, "Y" or "N"
+    pub reg_rev: String,
+    /// IGST charged on an intra-state supply, "Y" or "N"
+    pub igst_on_intra: String,
+}
+
+/// Document details
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "PascalCase")]
+pub struct DocDtls {
+    /// "INV", "CRN" or "DBN"
+    pub typ: String,
+    pub no: String,
+    /// dd/mm/yyyy
+    pub dt: String,
+}
+
+/// Seller or ship-to party
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "PascalCase")]
+pub struct PartyDtls {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gstin: Option<String>,
+    pub lgl_nm: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trd_nm: Option<String>,
+    pub addr1: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub addr2: Option<String>,
+    pub loc: String,
+    pub pin: u32,
+    /// Two-digit state code
+    pub stcd: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ph: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub em: Option<String>,
+}
+
+/// Buyer: a party with a place of supply
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "PascalCase")]
+pub struct BuyerDtls {
+    /// "URP" for overseas buyers
+    pub gstin: String,
+    pub lgl_nm: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trd_nm: Option<String>,
+    /// Place of supply state code; "96" for exports
+    pub pos: String,
+    pub addr1: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub addr2: Option<String>,
+    pub loc: String,
+    /// 999999 for exports
+    pub pin: u32,
+    pub stcd: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ph: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub em: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "PascalCase")]
+pub struct EInvoiceItem {
+    pub sl_no: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prd_desc: Option<String>,
+    /// "Y" for services (SAC codes), "N" for goods
+    pub is_servc: String,
+    pub hsn_cd: String,
+    pub qty: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unit: Option<String>,
+    pub unit_price: f64,
+    pub tot_amt: f64,
+    pub discount: f64,
+    pub ass_amt: f64,
+    pub gst_rt: f64,
+    pub igst_amt: f64,
+    pub cgst_amt: f64,
+    pub sgst_amt: f64,
+    pub ces_rt: f64,
+    pub ces_amt: f64,
+    pub oth_chrg: f64,
+    pub tot_item_val: f64,
+}
+
+/// Document totals
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "PascalCase")]
+pub struct ValDtls {
+    pub ass_val: f64,
+    pub cgst_val: f64,
+    pub sgst_val: f64,
+    pub igst_val: f64,
+    pub ces_val: f64,
+    pub st_ces_val: f64,
+    pub discount: f64,
+    pub oth_chrg: f64,
+    pub rnd_off_amt: f64,
+    pub tot_inv_val: f64,
+}
+
+/// Export details
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "PascalCase")]
+pub struct ExpDtls {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ship_b_no: Option<String>,
+    /// dd/mm/yyyy
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ship_b_dt: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub port: Option<String>,
+    /// Refund claimed, "Y" or "N"
+    pub ref_clm: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub for_cur: Option<String>,
+    /// ISO 3166 alpha-2 country of the buyer
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cnt_code: Option<String>,
+}
+
+/// Payload with the problems that would make the IRP reject it
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct EInvoicePreview {
+    pub invoice_id: String,
+    pub payload: EInvoicePayload,
+    /// Empty when the payload passes local schema validation
+    pub errors: Vec<String>,
+}
+
+/// Successful IRP response to a generate-IRN request, as the IRP sends it
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "PascalCase")]
+pub struct IrpResponse {
+    #[serde(deserialize_with = "string_or_number")]
+    pub ack_no: String,
+    /// yyyy-MM-dd HH:mm:ss
+    pub ack_dt: String,
+    pub irn: String,
+    #[serde(default)]
+    pub signed_invoice: String,
+    #[serde(rename = "SignedQRCode")]
+    pub signed_qr_code: String,
+}
+
+/// The IRP sends the acknowledgement number as a JSON number
+fn string_or_number<'de, D>(deserializer: D) -> Result<String, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match serde_json::Value::deserialize(deserializer)? {
+        serde_json::Value::String(value) => Ok(value),
+        serde_json::Value::Number(value) => Ok(value.to_string()),
+        other => Err(serde::de::Error::custom(format!(
+            "expected a string or number, got {}",
+            other
+        ))),
+    }
+}
+
+/// Registration stored on the invoice once the IRP has accepted it
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct EInvoiceDetails {
+    /// 64-character invoice reference number
+    pub irn: String,
+
+    #[serde(rename = "ackNo")]
+    pub ack_no: String,
+
+    #[serde(rename = "ackDate")]
+    pub ack_date: String,
+
+    /// JWT signed by the IRP carrying the QR code contents
+    #[serde(rename = "signedQrCode")]
+    pub signed_qr_code: String,
+
+    #[serde(rename = "signedInvoice", default)]
+    pub signed_invoice: String,
+
+    /// IRP client that registered it, e.g. "stub"; "manual" when recorded by hand
+    #[serde(default)]
+    pub source: String,
+
+    #[serde(rename = "registeredAt")]
+    pub registered_at: DateTime,
+}
@@ -0,0 +1,200 @@
+//! E-way bill in the NIC bulk-generation JSON layout, and the transport
+//! details and portal response that go with it. Field names follow the
+//! NIC schema; amounts are plain numbers in INR.
+
+use mongodb::bson::DateTime;
+use serde::{Deserialize, Serialize};
+
+/// Bulk-generation tool version the file is written for
+pub const EWB_BULK_VERSION: &str = "1.0.0621";
+
+/// Mode of transport; the NIC file codes them 1 to 4
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum TransportMode {
+    Road,
+    Rail,
+    Air,
+    Ship,
+}
+
+impl TransportMode {
+    pub fn code(self) -> u8 {
+        match self {
+            TransportMode::Road => 1,
+            TransportMode::Rail => 2,
+            TransportMode::Air => 3,
+            TransportMode::Ship => 4,
+        }
+    }
+}
+
+/// Transport details supplied when preparing the e-way bill
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TransportDetails {
+    /// GSTIN or TRANSIN of the transporter
+    #[serde(rename = "transporterId", default)]
+    pub transporter_id: String,
+
+    #[serde(rename = "transporterName", default)]
+    pub transporter_name: String,
+
+    pub mode: TransportMode,
+
+    /// Approximate distance in km; 0 lets the portal work it out from the PIN codes
+    #[serde(rename = "distanceKm", default)]
+    pub distance_km: u32,
+
+    #[serde(rename = "vehicleNumber", default)]
+    pub vehicle_number: String,
+
+    /// Over-dimensional cargo
+    #[serde(rename = "overDimensional", default)]
+    pub over_dimensional: bool,
+
+    /// RR, airway bill or bill of lading number for rail, air and ship
+    #[serde(rename = "transportDocNumber", default)]
+    pub transport_doc_number: String,
+
+    /// YYYY-MM-DD
+    #[serde(rename = "transportDocDate", default)]
+    pub transport_doc_date: String,
+
+    /// State code of the port or airport goods are exported from
+    #[serde(rename = "portStateCode", default)]
+    pub port_state_code: String,
+}
+
+/// `{ "version": ..., "billLists": [...] }` as uploaded to the portal
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct EWayBillFile {
+    pub version: String,
+    pub bill_lists: Vec<EWayBillEntry>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct EWayBillEntry {
+    pub user_gstin: String,
+    /// "O" outward
+    pub supply_type: String,
+    /// 1 supply, 3 export
+    pub sub_supply_type: u8,
+    pub sub_supply_desc: String,
+    pub doc_type: String,
+    pub doc_no: String,
+    /// dd/mm/yyyy
+    pub doc_date: String,
+    /// 1 regular, 2 bill-to ship-to
+    pub trans_type: u8,
+
+    pub from_gstin: String,
+    pub from_trd_name: String,
+    pub from_addr1: String,
+    pub from_addr2: String,
+    pub from_place: String,
+    pub from_pincode: u32,
+    pub from_state_code: u8,
+    pub actual_from_state_code: u8,
+
+    /// "URP" for unregistered or overseas recipients
+    pub to_gstin: String,
+    pub to_trd_name: String,
+    pub to_addr1: String,
+    pub to_addr2: String,
+    pub to_place: String,
+    pub to_pincode: u32,
+    /// 99 for exports
+    pub to_state_code: u8,
+    pub actual_to_state_code: u8,
+
+    pub total_value: f64,
+    pub cgst_value: f64,
+    pub sgst_value: f64,
+    pub igst_value: f64,
+    pub cess_value: f64,
+    #[serde(rename = "TotNonAdvolVal")]
+    pub tot_non_advol_val: f64,
+    pub oth_value: f64,
+    pub tot_inv_value: f64,
+
+    pub trans_mode: u8,
+    pub trans_distance: u32,
+    pub transporter_name: String,
+    pub transporter_id: String,
+    pub trans_doc_no: String,
+    /// dd/mm/yyyy
+    pub trans_doc_date: String,
+    pub vehicle_no: String,
+    /// "R" regular, "O" over-dimensional cargo
+    pub vehicle_type: String,
+    pub main_hsn_code: u32,
+    pub item_list: Vec<EWayBillItem>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct EWayBillItem {
+    pub item_no: u32,
+    pub product_name: String,
+    pub product_desc: String,
+    pub hsn_code: u32,
+    pub quantity: f64,
+    pub qty_unit: String,
+    pub taxable_amount: f64,
+    pub sgst_rate: f64,
+    pub cgst_rate: f64,
+    pub igst_rate: f64,
+    pub cess_rate: f64,
+    pub cess_non_advol: f64,
+}
+
+/// Bulk-upload file with the problems that would make the portal reject it
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct EWayBillPreview {
+    pub invoice_id: String,
+    pub file: EWayBillFile,
+    /// Empty when every mandatory field is present and well formed
+    pub errors: Vec<String>,
+    /// Points worth checking that do not block generation
+    pub warnings: Vec<String>,
+}
+
+/// E-way bill number and validity returned by the portal
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RecordEWayBillRequest {
+    #[serde(rename = "ewbNo")]
+    pub ewb_no: String,
+
+    /// As shown by the portal, e.g. "05/06/2025 11:30:00 AM"
+    #[serde(rename = "ewbDate")]
+    pub ewb_date: String,
+
+    #[serde(rename = "validUpto")]
+    pub valid_upto: String,
+
+    /// Transport the bill was generated with, kept for reference
+    #[serde(default)]
+    pub transport: Option<TransportDetails>,
+}
+
+/// E-way bill stored on the invoice
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct EWayBillDetails {
+    /// 12-digit e-way bill number
+    #[serde(rename = "ewbNo")]
+    pub ewb_no: String,
+
+    #[serde(rename = "ewbDate")]
+    pub ewb_date: String,
+
+    #[serde(rename = "validUpto")]
+    pub valid_upto: String,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transport: Option<TransportDetails>,
+
+    #[serde(rename = "recordedAt")]
+    pub recorded_at: DateTime,
+}
@@ -0,0 +1,190 @@
+use mongodb::bson::{oid::ObjectId, DateTime};
+use serde::{Deserialize, Serialize};
+
+use super::invoice::{Invoice, InvoiceItem};
+use super::money::{default_currency, Money};
+
+/// Estimate (quotation) status
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EstimateStatus {
+    /// Being prepared
+    #[default]
+    Draft,
+    /// Sent to the customer, awaiting a decision
+    Sent,
+    Accepted,
+    Declined,
+    /// Validity date passed without a decision
+    Expired,
+    /// Converted into an invoice
+    Invoiced,
+}
+
+/// A quotation sent before invoicing
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Estimate {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+
+    /// Generated from the organisation's estimate series
+    #[serde(default)]
+    pub estimate_number: String,
+
+    pub estimate_date: String,
+
+    /// Last date the quote can be accepted, YYYY-MM-DD
+    #[serde(default)]
+    pub valid_until: String,
+
+    // "domestic" or "international", as on invoices
+    #[serde(default)]
+    pub invoice_type: String,
+
+    #[serde(default)]
+    pub place_of_supply: String,
+
+    #[serde(default = "default_currency")]
+    pub currency: String,
+
+    // Bill To
+    #[serde(default)]
+    pub billcustomer_name: String,
+
+    #[serde(default)]
+    pub billcustomer_address: String,
+
+    #[serde(default)]
+    pub billcustomer_gstin: String,
+
+    // Ship To
+    #[serde(default)]
+    pub shipcustomer_name: String,
+
+    #[serde(default)]
+    pub shipcustomer_address: String,
+
+    #[serde(default)]
+    pub shipcustomer_gstin: String,
+
+    #[serde(default)]
+    pub subject: String,
+
+    #[serde(default)]
+    pub items: Vec<InvoiceItem>,
+
+    #[serde(rename = "subTotal", default)]
+    pub sub_total: Money,
+
+    #[serde(default)]
+    pub totalcgst: Money,
+
+    #[serde(default)]
+    pub totalsgst: Money,
+
+    #[serde(default)]
+    pub totaligst: Money,
+
+    #[serde(default)]
+    pub total: Money,
+
+    #[serde(default)]
+    pub notes: String,
+
+    #[serde(default)]
+    pub status: EstimateStatus,
+
+    /// Invoice created from this estimate
+    #[serde(default)]
+    pub invoice_id: Option<String>,
+
+    /// Organisation that quoted; set by the server
+    #[serde(default)]
+    pub org_email: String,
+
+    #[serde(default)]
+    pub created_at: Option<DateTime>,
+
+    #[serde(default)]
+    pub updated_at: Option<DateTime>,
+}
+
+/// Create / Update Estimate Request DTO
+pub type EstimateRequest = Estimate;
+
+impl Estimate {
+    /// Check if estimate can be edited or deleted
+    pub fn is_editable(&self) -> bool {
+        matches!(self.status, EstimateStatus::Draft | EstimateStatus::Sent)
+    }
+
+    /// Check if estimate can be sent to the customer
+    pub fn can_send(&self) -> bool {
+        self.status == EstimateStatus::Draft && !self.items.is_empty()
+    }
+
+    /// Check if the customer can accept or decline it
+    pub fn can_decide(&self) -> bool {
+        self.status == EstimateStatus::Sent
+    }
+
+    /// Check if estimate can be converted into an invoice
+    pub fn can_convert(&self) -> bool {
+        matches!(self.status, EstimateStatus::Sent | EstimateStatus::Accepted)
+            && self.invoice_id.is_none()
+    }
+
+    pub fn send(&mut self) -> Result<(), String> {
+        if !self.can_send() {
+            return Err("Estimate cannot be sent in current status".to_string());
+        }
+        self.status = EstimateStatus::Sent;
+        self.updated_at = Some(DateTime::now());
+        Ok(())
+    }
+
+    pub fn accept(&mut self) -> Result<(), String> {
+        if !self.can_decide() {
+            return Err("Estimate cannot be accepted in current status".to_string());
+        }
+        self.status = EstimateStatus::Accepted;
+        self.updated_at = Some(DateTime::now());
+        Ok(())
+    }
+
+    pub fn decline(&mut self) -> Result<(), String> {
+        if !self.can_decide() {
+            return Err("Estimate cannot be declined in current status".to_string());
+        }
+        self.status = EstimateStatus::Declined;
+        self.updated_at = Some(DateTime::now());
+        Ok(())
+    }
+
+    /// Mark converted and link the invoice
+    pub fn mark_invoiced(&mut self, invoice_id: String) {
+        self.status = EstimateStatus::Invoiced;
+        self.invoice_id = Some(invoice_id);
+        self.updated_at = Some(DateTime::now());
+    }
+
+    /// Draft invoice pre-filled from this estimate. Seller details, dates and
+    /// number are filled in by the invoice flow.
+    pub fn to_invoice(&self) -> Invoice {
+        Invoice {
+            invoice_type: self.invoice_type.clone(),
+            place_of_supply: self.place_of_supply.clone(),
+            currency: self.currency.clone(),
+            billcustomer_name: self.billcustomer_name.clone(),
+            billcustomer_address: self.billcustomer_address.clone(),
+            billcustomer_gstin: self.billcustomer_gstin.clone(),
+            shipcustomer_name: self.shipcustomer_name.clone(),
+            shipcustomer_address: self.shipcustomer_address.clone(),
+            shipcustomer_gstin: self.shipcustomer_gstin.clone(),
+            subject: self.subject.clone(),
+            items: self.items.clone(),
+            notes: self.notes.clone(),
+            estimate_id: self.id.map(|id| id.to_hex()),
+            ..Default::default()
+        }
+    }
+}
@@ -1,6 +1,8 @@
 use mongodb::bson::{oid::ObjectId, DateTime};
 use serde::{Deserialize, Serialize};
 
+use super::money::Money;
+
 /// A single expense sub-item
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ExpenseItem {
@@ -11,7 +13,7 @@ pub struct ExpenseItem {
     pub currency: String,
     
     /// Amount for this expense item
-    pub amount: f64,
+    pub amount: Money,
     
     /// Date when the expense occurred (ISO format or any string format)
     pub expense_date: String,
@@ -35,6 +37,14 @@ pub struct ExpenseItem {
     /// Vendor or merchant name
     #[serde(default)]
     pub vendor: Option<String>,
+
+    /// Vendor GSTIN from the tax invoice; decides IGST vs CGST/SGST for ITC
+    #[serde(default)]
+    pub vendor_gstin: Option<String>,
+
+    /// Number of the vendor's tax invoice, for matching against GSTR-2B
+    #[serde(default)]
+    pub invoice_number: Option<String>,
     
     /// Whether this item is billable to client
     #[serde(default)]
@@ -42,7 +52,7 @@ pub struct ExpenseItem {
     
     /// Tax amount if applicable
     #[serde(default)]
-    pub tax_amount: Option<f64>,
+    pub tax_amount: Option<Money>,
 }
 
 impl ExpenseItem {
@@ -56,7 +66,7 @@ impl ExpenseItem {
             return Err("Currency is required".to_string());
         }
         
-        if self.amount < 0.0 {
+        if self.amount.is_negative() {
             return Err("Amount cannot be negative".to_string());
         }
         
@@ -65,7 +75,7 @@ impl ExpenseItem {
         }
         
         if let Some(tax) = self.tax_amount {
-            if tax < 0.0 {
+            if tax.is_negative() {
                 return Err("Tax amount cannot be negative".to_string());
             }
         }
@@ -74,8 +84,8 @@ impl ExpenseItem {
     }
     
     /// Get total amount including tax
-    pub fn total_with_tax(&self) -> f64 {
-        self.amount + self.tax_amount.unwrap_or(0.0)
+    pub fn total_with_tax(&self) -> Money {
+        self.amount + self.tax_amount.unwrap_or_default()
     }
 }
 
@@ -120,11 +130,11 @@ pub struct Expense {
 
     /// Total amount (calculated from items)
     #[serde(default)]
-    pub total_amount: f64,
+    pub total_amount: Money,
     
     /// Total tax amount across all items
     #[serde(default)]
-    pub total_tax: f64,
+    pub total_tax: Money,
     
     /// Status of the expense report
     #[serde(default)]
@@ -162,6 +172,11 @@ pub struct Expense {
     #[serde(default)]
     pub department: Option<String>,
 
+    /// Organisation the expense is booked to; its ledger receives the
+    /// postings. Empty on expenses recorded before this was kept.
+    #[serde(default)]
+    pub org_email: String,
+
     /// When the expense was created
     #[serde(default)]
     pub created_at: Option<DateTime>,
@@ -180,8 +195,8 @@ impl Expense {
             expense_title: title,
             project_cost_center,
             items: Vec::new(),
-            total_amount: 0.0,
-            total_tax: 0.0,
+            total_amount: Money::ZERO,
+            total_tax: Money::ZERO,
             status: ExpenseStatus::Draft,
             submitted_by: None,
             approved_by: None,
@@ -191,6 +206,7 @@ impl Expense {
             reimbursed_at: None,
             notes: None,
             department: None,
+            org_email: String::new(),
             created_at: Some(now),
             updated_at: Some(now),
         }
@@ -203,7 +219,7 @@ impl Expense {
     }
     
     /// Get grand total including tax
-    pub fn grand_total(&self) -> f64 {
+    pub fn grand_total(&self) -> Money {
         self.total_amount + self.total_tax
     }
     
@@ -228,7 +244,7 @@ impl Expense {
             }
         }
         
-        if self.total_amount < 0.0 {
+        if self.total_amount.is_negative() {
             return Err("Total amount cannot be negative".to_string());
         }
         
@@ -329,10 +345,10 @@ impl Expense {
     }
     
     /// Get total amount by currency
-    pub fn total_by_currency(&self) -> std::collections::HashMap<String, f64> {
+    pub fn total_by_currency(&self) -> std::collections::HashMap<String, Money> {
         let mut map = std::collections::HashMap::new();
         for item in &self.items {
-            *map.entry(item.currency.clone()).or_insert(0.0) += item.amount;
+            *map.entry(item.currency.clone()).or_insert(Money::ZERO) += item.amount;
         }
         map
     }
@@ -359,8 +375,8 @@ impl From<CreateExpenseRequest> for Expense {
             expense_title: req.expense_title,
             project_cost_center: req.project_cost_center,
             items: req.items,
-            total_amount: 0.0,
-            total_tax: 0.0,
+            total_amount: Money::ZERO,
+            total_tax: Money::ZERO,
             status: ExpenseStatus::Draft,
             submitted_by: None,
             approved_by: None,
@@ -370,6 +386,7 @@ impl From<CreateExpenseRequest> for Expense {
             reimbursed_at: None,
             notes: req.notes,
             department: req.department,
+            org_email: String::new(),
             created_at: Some(now),
             updated_at: Some(now),
         };
@@ -393,12 +410,23 @@ pub struct UpdateExpenseRequest {
     pub department: Option<String>,
 }
 
+/// Request to submit an expense for approval
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct SubmitExpenseRequest {
+    /// Defaults to the user who created the expense
+    #[serde(default)]
+    pub submitted_by: Option<String>,
+}
+
 /// Request to approve/reject an expense
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ReviewExpenseRequest {
     pub action: ReviewAction,
     #[serde(default)]
     pub reason: Option<String>,
+    /// Manager/Approver ID
+    #[serde(default)]
+    pub reviewer_id: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
@@ -412,7 +440,7 @@ pub enum ReviewAction {
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ExpenseStats {
     pub total_count: usize,
-    pub total_amount: f64,
+    pub total_amount: Money,
     pub by_status: std::collections::HashMap<String, usize>,
-    pub by_category: std::collections::HashMap<String, f64>,
+    pub by_category: std::collections::HashMap<String, Money>,
 }
\ No newline at end of file
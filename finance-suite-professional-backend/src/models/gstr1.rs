@@ -0,0 +1,253 @@
+//! GSTR-1 return in the GSTN offline-tool JSON layout. Field names follow
+//! the GSTN schema; amounts are plain numbers in INR.
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+/// Tax period of a monthly return, written "MMYYYY" by GSTN
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TaxPeriod {
+    pub month: u32,
+    pub year: i32,
+}
+
+impl TaxPeriod {
+    pub fn parse(fp: &str) -> Result<Self, String> {
+        let fp = fp.trim();
+        let invalid = || format!("Invalid tax period '{}'; expected MMYYYY", fp);
+        if fp.len() != 6 || !fp.chars().all(|c| c.is_ascii_digit()) {
+            return Err(invalid());
+        }
+        let month: u32 = fp[0..2].parse().map_err(|_| invalid())?;
+        let year: i32 = fp[2..6].parse().map_err(|_| invalid())?;
+        if !(1..=12).contains(&month) {
+            return Err(invalid());
+        }
+        Ok(Self { month, year })
+    }
+
+    pub fn fp(&self) -> String {
+        format!("{:02}{}", self.month, self.year)
+    }
+
+    pub fn first_day(&self) -> NaiveDate {
+        NaiveDate::from_ymd_opt(self.year, self.month, 1).expect("validated month")
+    }
+
+    pub fn last_day(&self) -> NaiveDate {
+        let (year, month) = if self.month == 12 {
+            (self.year + 1, 1)
+        } else {
+            (self.year, self.month + 1)
+        };
+        NaiveDate::from_ymd_opt(year, month, 1).expect("validated month") - chrono::Duration::days(1)
+    }
+}
+
+/// Tax amounts of one rate slab
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct ItemDetail {
+    pub txval: f64,
+    pub rt: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub iamt: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub camt: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub samt: Option<f64>,
+    pub csamt: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct NumberedItem {
+    pub num: u32,
+    pub itm_det: ItemDetail,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct B2bInvoice {
+    pub inum: String,
+    /// dd-mm-yyyy
+    pub idt: String,
+    pub val: f64,
+    pub pos: String,
+    pub rchrg: String,
+    pub inv_typ: String,
+    pub itms: Vec<NumberedItem>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct B2bParty {
+    pub ctin: String,
+    pub inv: Vec<B2bInvoice>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct B2clInvoice {
+    pub inum: String,
+    pub idt: String,
+    pub val: f64,
+    pub itms: Vec<NumberedItem>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct B2clState {
+    pub pos: String,
+    pub inv: Vec<B2clInvoice>,
+}
+
+/// B2C small supplies, aggregated per supply type, state and rate
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct B2csEntry {
+    pub sply_ty: String,
+    pub rt: f64,
+    pub typ: String,
+    pub pos: String,
+    pub txval: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub iamt: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub camt: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub samt: Option<f64>,
+    pub csamt: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ExpItem {
+    pub txval: f64,
+    pub rt: f64,
+    pub iamt: f64,
+    pub csamt: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ExpInvoice {
+    pub inum: String,
+    pub idt: String,
+    pub val: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sbpcode: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sbnum: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sbdt: Option<String>,
+    pub itms: Vec<ExpItem>,
+}
+
+/// "WPAY" (IGST paid) or "WOPAY" (under LUT/bond)
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ExpGroup {
+    pub exp_typ: String,
+    pub inv: Vec<ExpInvoice>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CdnrNote {
+    /// "C" or "D"
+    pub ntty: String,
+    pub nt_num: String,
+    pub nt_dt: String,
+    pub val: f64,
+    pub pos: String,
+    pub rchrg: String,
+    pub inv_typ: String,
+    pub itms: Vec<NumberedItem>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CdnrParty {
+    pub ctin: String,
+    pub nt: Vec<CdnrNote>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct HsnEntry {
+    pub num: u32,
+    pub hsn_sc: String,
+    pub desc: String,
+    pub uqc: String,
+    pub qty: f64,
+    pub rt: f64,
+    pub txval: f64,
+    pub iamt: f64,
+    pub camt: f64,
+    pub samt: f64,
+    pub csamt: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct HsnSummary {
+    pub data: Vec<HsnEntry>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DocRange {
+    pub num: u32,
+    pub from: String,
+    pub to: String,
+    pub totnum: u32,
+    pub cancel: u32,
+    pub net_issue: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DocDetail {
+    /// 1 = outward invoices, 4 = debit notes, 5 = credit notes
+    pub doc_num: u32,
+    pub docs: Vec<DocRange>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct DocIssue {
+    pub doc_det: Vec<DocDetail>,
+}
+
+/// The return file uploaded to the GST portal
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Gstr1 {
+    pub gstin: String,
+    pub fp: String,
+    pub version: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub b2b: Vec<B2bParty>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub b2cl: Vec<B2clState>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub b2cs: Vec<B2csEntry>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub exp: Vec<ExpGroup>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub cdnr: Vec<CdnrParty>,
+    pub hsn: HsnSummary,
+    pub doc_issue: DocIssue,
+}
+
+/// A document left out of (or only partly reflected in) a GST return
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ReturnIssue {
+    /// "invoice", "credit_note" or "debit_note"
+    pub document_type: String,
+    pub document_id: String,
+    pub document_number: String,
+    pub message: String,
+}
+
+/// Number of documents placed in each section
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct Gstr1Counts {
+    pub b2b: usize,
+    pub b2cl: usize,
+    pub b2cs: usize,
+    pub exp: usize,
+    pub cdnr: usize,
+    pub skipped: usize,
+}
+
+/// GSTR-1 return plus the validation report
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Gstr1Report {
+    pub gstr1: Gstr1,
+    pub counts: Gstr1Counts,
+    pub issues: Vec<ReturnIssue>,
+}
@@ -0,0 +1,150 @@
+//! GSTR-2B statement as downloaded from the GST portal, and the result of
+//! reconciling it against the purchases we have booked.
+
+use serde::{Deserialize, Serialize};
+
+use super::gstr3b::TaxHeads;
+use super::money::Money;
+
+/// Portal download: `{ "data": { ... } }`
+#[derive(Debug, Deserialize, Clone)]
+pub struct Gstr2bFile {
+    pub data: Gstr2bData,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct Gstr2bData {
+    #[serde(default)]
+    pub gstin: String,
+    /// Return period, MMYYYY
+    #[serde(default)]
+    pub rtnprd: String,
+    #[serde(default)]
+    pub docdata: Gstr2bDocData,
+}
+
+/// Only B2B invoices are reconciled; other sections are ignored
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct Gstr2bDocData {
+    #[serde(default)]
+    pub b2b: Vec<Gstr2bSupplier>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct Gstr2bSupplier {
+    pub ctin: String,
+    /// Trade name of the supplier
+    #[serde(default)]
+    pub trdnm: String,
+    #[serde(default)]
+    pub inv: Vec<Gstr2bInvoice>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct Gstr2bInvoice {
+    pub inum: String,
+    /// dd-mm-yyyy
+    pub dt: String,
+    #[serde(default)]
+    pub val: f64,
+    /// Reverse charge, "Y" or "N"
+    #[serde(default)]
+    pub rev: String,
+    /// ITC availability, "Y" or "N"
+    #[serde(default)]
+    pub itcavl: String,
+    #[serde(default)]
+    pub txval: Option<f64>,
+    #[serde(default)]
+    pub igst: Option<f64>,
+    #[serde(default)]
+    pub cgst: Option<f64>,
+    #[serde(default)]
+    pub sgst: Option<f64>,
+    #[serde(default)]
+    pub cess: Option<f64>,
+    #[serde(default)]
+    pub items: Vec<Gstr2bItem>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct Gstr2bItem {
+    #[serde(default)]
+    pub txval: f64,
+    #[serde(default)]
+    pub igst: f64,
+    #[serde(default)]
+    pub cgst: f64,
+    #[serde(default)]
+    pub sgst: f64,
+    #[serde(default)]
+    pub cess: f64,
+}
+
+/// A supplier invoice as reported in GSTR-2B
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PortalDocument {
+    pub supplier_gstin: String,
+    pub supplier_name: String,
+    pub invoice_number: String,
+    /// YYYY-MM-DD
+    pub invoice_date: String,
+    pub invoice_value: Money,
+    pub taxable_value: Money,
+    pub tax: TaxHeads,
+    pub itc_available: bool,
+    pub reverse_charge: bool,
+}
+
+/// A purchase we have booked: a vendor bill, or one line of an expense
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BookedPurchase {
+    /// "vendor_bill" or "expense"
+    pub source: String,
+    pub source_id: String,
+    /// 1-based expense line; absent for vendor bills
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line: Option<usize>,
+    pub supplier_gstin: String,
+    pub supplier_name: String,
+    pub invoice_number: String,
+    /// YYYY-MM-DD
+    pub invoice_date: String,
+    pub taxable_value: Money,
+    pub tax: TaxHeads,
+}
+
+/// A portal document paired with the booked purchase it was matched to
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ReconciledPair {
+    pub portal: PortalDocument,
+    pub books: BookedPurchase,
+    /// What differs between the two; empty for a clean match
+    pub differences: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ReconciliationTotals {
+    pub portal_tax: TaxHeads,
+    pub books_tax: TaxHeads,
+    pub matched: usize,
+    pub mismatched: usize,
+    pub missing_in_books: usize,
+    pub missing_in_2b: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Gstr2bReconciliation {
+    pub org_email: String,
+    pub gstin: String,
+    /// Return period of the statement, MMYYYY
+    pub period: String,
+    pub matched: Vec<ReconciledPair>,
+    /// Same supplier and invoice but differing date or tax
+    pub mismatched: Vec<ReconciledPair>,
+    /// Reported by suppliers but not booked by us
+    pub missing_in_books: Vec<PortalDocument>,
+    /// Booked in the period but not reported by the supplier
+    pub missing_in_2b: Vec<BookedPurchase>,
+    pub totals: ReconciliationTotals,
+}
@@ -0,0 +1,190 @@
+//! GSTR-3B monthly summary: a readable breakdown of tables 3.1, 4, 5 and
+//! 6.1, and the same figures in the GSTN JSON layout.
+
+use serde::{Deserialize, Serialize};
+
+use super::gstr1::ReturnIssue;
+use super::money::Money;
+
+/// Tax split by head
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TaxHeads {
+    pub igst: Money,
+    pub cgst: Money,
+    pub sgst: Money,
+    pub cess: Money,
+}
+
+impl std::ops::AddAssign for TaxHeads {
+    fn add_assign(&mut self, other: Self) {
+        self.igst += other.igst;
+        self.cgst += other.cgst;
+        self.sgst += other.sgst;
+        self.cess += other.cess;
+    }
+}
+
+impl std::ops::Sub for TaxHeads {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self {
+            igst: self.igst - other.igst,
+            cgst: self.cgst - other.cgst,
+            sgst: self.sgst - other.sgst,
+            cess: self.cess - other.cess,
+        }
+    }
+}
+
+/// One line of table 3.1 or 4
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Gstr3bRow {
+    /// Table reference as printed on the form, e.g. "3.1(a)"
+    pub table: String,
+    pub description: String,
+    pub taxable_value: Money,
+    pub tax: TaxHeads,
+}
+
+/// Table 5: exempt, nil-rated and non-GST inward supplies
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct InwardSupplyRow {
+    pub description: String,
+    pub inter_state: Money,
+    pub intra_state: Money,
+}
+
+/// Table 6.1: how the liability under one head is discharged
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TaxPaymentRow {
+    /// "IGST", "CGST", "SGST" or "Cess"
+    pub head: String,
+    pub payable: Money,
+    pub paid_through_igst: Money,
+    pub paid_through_cgst: Money,
+    pub paid_through_sgst: Money,
+    pub paid_through_cess: Money,
+    pub paid_in_cash: Money,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Gstr3bSummary {
+    pub org_email: String,
+    pub gstin: String,
+    /// MMYYYY
+    pub period: String,
+    /// Whether the organisation claims input tax credit
+    pub input_tax_credit_claimed: bool,
+    pub outward_supplies: Vec<Gstr3bRow>,
+    pub input_tax_credit: Vec<Gstr3bRow>,
+    pub inward_exempt_supplies: Vec<InwardSupplyRow>,
+    pub tax_payment: Vec<TaxPaymentRow>,
+    pub issues: Vec<ReturnIssue>,
+}
+
+// -------- GSTN JSON --------
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct SupplyValue {
+    pub txval: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub iamt: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub camt: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub samt: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub csamt: Option<f64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct SupDetails {
+    pub osup_det: SupplyValue,
+    pub osup_zero: SupplyValue,
+    pub osup_nil_exmp: SupplyValue,
+    pub isup_rev: SupplyValue,
+    pub osup_nongst: SupplyValue,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct TaxAmounts {
+    pub iamt: f64,
+    pub camt: f64,
+    pub samt: f64,
+    pub csamt: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ItcLine {
+    /// IMPG, IMPS, ISRC, ISD, OTH (available); RUL, OTH (reversed/ineligible)
+    pub ty: String,
+    #[serde(flatten)]
+    pub amounts: TaxAmounts,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ItcEligibility {
+    pub itc_avl: Vec<ItcLine>,
+    pub itc_rev: Vec<ItcLine>,
+    pub itc_net: TaxAmounts,
+    pub itc_inelg: Vec<ItcLine>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct InwardLine {
+    /// "GST" (exempt/nil/composition) or "NONGST"
+    pub ty: String,
+    pub inter: f64,
+    pub intra: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct InwardSupplies {
+    pub isup_details: Vec<InwardLine>,
+}
+
+/// Liability set off through credit, head paid (first letter) through head
+/// used (suffix)
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ItcPaid {
+    pub i_pdi: f64,
+    pub i_pdc: f64,
+    pub i_pds: f64,
+    pub c_pdi: f64,
+    pub c_pdc: f64,
+    pub s_pdi: f64,
+    pub s_pds: f64,
+    pub cs_pdcs: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct CashPaid {
+    pub ipd: f64,
+    pub cpd: f64,
+    pub spd: f64,
+    pub cspd: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct TaxPayment {
+    pub pditc: ItcPaid,
+    pub pdcash: Vec<CashPaid>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Gstr3b {
+    pub gstin: String,
+    pub ret_period: String,
+    pub sup_details: SupDetails,
+    pub itc_elg: ItcEligibility,
+    pub inward_sup: InwardSupplies,
+    pub tx_pmt: TaxPayment,
+}
+
+/// Readable breakdown plus the portal JSON
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Gstr3bReport {
+    pub summary: Gstr3bSummary,
+    pub gstr3b: Gstr3b,
+}
@@ -1,5 +1,11 @@
-use mongodb::bson::oid::ObjectId;
+use mongodb::bson::{oid::ObjectId, DateTime};
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+use super::e_invoice::EInvoiceDetails;
+use super::e_way_bill::EWayBillDetails;
+use super::money::{default_currency, Money, DEFAULT_CURRENCY};
 
 /// CGST Tax block for a line item
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
@@ -8,7 +14,7 @@ pub struct CGST {
     pub cgst_percent: String,
 
     #[serde(rename = "cgstAmount", default)]
-    pub cgst_amount: String,
+    pub cgst_amount: Money,
 }
 
 /// SGST Tax block for a line item
@@ -18,7 +24,7 @@ pub struct SGST {
     pub sgst_percent: String,
 
     #[serde(rename = "sgstAmount", default)]
-    pub sgst_amount: String,
+    pub sgst_amount: Money,
 }
 
 /// IGST Tax block for a line item
@@ -28,20 +34,28 @@ pub struct IGST {
     pub igst_percent: String,
 
     #[serde(rename = "igstAmount", default)]
-    pub igst_amount: String,
+    pub igst_amount: Money,
 }
 
 /// A single line item in the invoice
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
 pub struct InvoiceItem {
+    /// Catalogue item the line was raised from; blank fields are filled from it
+    #[serde(rename = "catalogItemId", default, skip_serializing_if = "Option::is_none")]
+    pub catalog_item_id: Option<String>,
+
     #[serde(default)]
     pub description: String,
 
+    /// HSN (goods) or SAC (services) code; feeds the GSTR-1 HSN summary
+    #[serde(rename = "hsnSac", default)]
+    pub hsn_sac: String,
+
     #[serde(default)]
     pub hours: String,
 
     #[serde(default)]
-    pub rate: String,
+    pub rate: Money,
 
     #[serde(default)]
     pub cgst: CGST,
@@ -53,11 +67,53 @@ pub struct InvoiceItem {
     pub igst: IGST,
 
     #[serde(rename = "itemTotal", default)]
-    pub item_total: String,
+    pub item_total: Money,
+}
+
+/// Lifecycle status of an invoice
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum InvoiceStatus {
+    /// Being prepared, freely editable
+    #[default]
+    #[serde(alias = "")]
+    Draft,
+    /// Sent to the customer, awaiting payment
+    #[serde(alias = "Pending")]
+    Issued,
+    /// Some payment received
+    PartiallyPaid,
+    /// Fully paid
+    Paid,
+    /// Past the due date without full payment
+    Overdue,
+    /// Cancelled before any payment
+    Cancelled,
+    /// Issued in error and nullified; the number stays used
+    Void,
+}
+
+/// How an export is made under GST; reported as the GSTR-1 EXP type
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum ExportType {
+    /// With payment of IGST, refunded later
+    #[serde(rename = "WPAY")]
+    WithPayment,
+    /// Without payment of IGST, under a letter of undertaking (LUT)
+    #[serde(rename = "WOPAY")]
+    WithoutPayment,
+}
+
+impl ExportType {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ExportType::WithPayment => "WPAY",
+            ExportType::WithoutPayment => "WOPAY",
+        }
+    }
 }
 
 /// The Invoice document stored in MongoDB
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
 pub struct Invoice {
     /// MongoDB document _id
     #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
@@ -90,6 +146,30 @@ pub struct Invoice {
     #[serde(default)]
     pub iec_no: String,
 
+    // ISO 4217 code all amounts on this invoice are in
+    #[serde(default = "default_currency")]
+    pub currency: String,
+
+    // INR per unit of `currency` on the invoice date; required for foreign currencies
+    #[serde(rename = "exchangeRate", default)]
+    pub exchange_rate: String,
+
+    // Export details (International invoices); the export type defaults to
+    // WOPAY when an LUT is given, else WPAY
+    #[serde(rename = "exportType", default, skip_serializing_if = "Option::is_none")]
+    pub export_type: Option<ExportType>,
+
+    #[serde(rename = "shippingBillNo", default)]
+    pub shipping_bill_no: String,
+
+    // YYYY-MM-DD
+    #[serde(rename = "shippingBillDate", default)]
+    pub shipping_bill_date: String,
+
+    // Six-character customs port code, e.g. INMAA1
+    #[serde(rename = "portCode", default)]
+    pub port_code: String,
+
     // Invoice details
     #[serde(default)]
     pub invoice_number: String,
@@ -142,26 +222,340 @@ pub struct Invoice {
 
     // Totals
     #[serde(rename = "subTotal", default)]
-    pub sub_total: String,
+    pub sub_total: Money,
 
     #[serde(default)]
-    pub totalcgst: String,
+    pub totalcgst: Money,
 
     #[serde(default)]
-    pub totalsgst: String,
+    pub totalsgst: Money,
 
     #[serde(default)]
-    pub totaligst: String,
+    pub totaligst: Money,
 
     #[serde(default)]
-    pub total: String,
+    pub total: Money,
+
+    // Payments allocated against this invoice, including TDS and bank charges
+    #[serde(rename = "amountPaid", default)]
+    pub amount_paid: Money,
+
+    // Credit notes issued against this invoice
+    #[serde(rename = "creditedAmount", default)]
+    pub credited_amount: Money,
+
+    // Debit notes issued against this invoice
+    #[serde(rename = "debitedAmount", default)]
+    pub debited_amount: Money,
+
+    // total + debited - credited - amountPaid; maintained by the server
+    #[serde(rename = "balanceDue", default)]
+    pub balance_due: Money,
 
     // Notes / Terms & Conditions
     #[serde(default)]
     pub notes: String,
 
     #[serde(default)]
-    pub status: String,
+    pub status: InvoiceStatus,
+
+    /// Date when the invoice was issued
+    #[serde(default)]
+    pub issued_at: Option<DateTime>,
+
+    /// Date when the invoice was cancelled or voided
+    #[serde(default)]
+    pub closed_at: Option<DateTime>,
+
+    /// Reason given when cancelling or voiding
+    #[serde(default)]
+    pub closed_reason: Option<String>,
+
+    /// Recurring schedule this invoice was generated from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub recurring_id: Option<String>,
+
+    /// Scheduled run date (YYYY-MM-DD) of the recurring schedule that produced it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub recurring_run_date: Option<String>,
+
+    /// Estimate this invoice was converted from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub estimate_id: Option<String>,
+
+    /// Customer purchase order this invoice bills against; resolved from
+    /// `po_number` when the PO is on file
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub po_id: Option<String>,
+
+    /// Organisation that raised the invoice; its ledger receives the postings
+    #[serde(default)]
+    pub org_email: String,
+
+    /// IRP registration; once present the invoice is locked
+    #[serde(rename = "eInvoice", default, skip_serializing_if = "Option::is_none")]
+    pub e_invoice: Option<EInvoiceDetails>,
+
+    /// E-way bill generated for the goods on this invoice
+    #[serde(rename = "eWayBill", default, skip_serializing_if = "Option::is_none")]
+    pub e_way_bill: Option<EWayBillDetails>,
+}
+
+impl Invoice {
+    /// INR value of one unit of the invoice currency; 1 for INR invoices
+    pub fn inr_rate(&self) -> Result<Decimal, String> {
+        if self.currency == DEFAULT_CURRENCY {
+            return Ok(Decimal::ONE);
+        }
+        match Decimal::from_str(self.exchange_rate.trim()) {
+            Ok(rate) if rate > Decimal::ZERO => Ok(rate),
+            _ => Err(format!(
+                "Invoice is in {} and has no exchange rate to INR",
+                self.currency
+            )),
+        }
+    }
+
+    /// Export type, inferred from the IGST charged for invoices saved
+    /// before it was recorded
+    pub fn effective_export_type(&self) -> ExportType {
+        self.export_type.unwrap_or(if self.totaligst.is_zero() {
+            ExportType::WithoutPayment
+        } else {
+            ExportType::WithPayment
+        })
+    }
+
+    /// Copy of the invoice with every amount converted to INR at its
+    /// exchange rate, for returns and portal files that only take INR
+    pub fn in_inr(&self) -> Result<Invoice, String> {
+        let rate = self.inr_rate()?;
+        let mut converted = self.clone();
+        if self.currency == DEFAULT_CURRENCY {
+            return Ok(converted);
+        }
+
+        let convert = |amount: Money| (amount * rate).round_paise();
+        for item in converted.items.iter_mut() {
+            item.rate = convert(item.rate);
+            item.item_total = convert(item.item_total);
+            item.cgst.cgst_amount = convert(item.cgst.cgst_amount);
+            item.sgst.sgst_amount = convert(item.sgst.sgst_amount);
+            item.igst.igst_amount = convert(item.igst.igst_amount);
+        }
+        converted.sub_total = convert(self.sub_total);
+        converted.totalcgst = convert(self.totalcgst);
+        converted.totalsgst = convert(self.totalsgst);
+        converted.totaligst = convert(self.totaligst);
+        converted.total = convert(self.total);
+        converted.amount_paid = convert(self.amount_paid);
+        converted.credited_amount = convert(self.credited_amount);
+        converted.debited_amount = convert(self.debited_amount);
+        converted.balance_due = convert(self.balance_due);
+        converted.currency = DEFAULT_CURRENCY.to_string();
+        converted.exchange_rate = "1".to_string();
+        Ok(converted)
+    }
+
+    /// Check if invoice can be edited or deleted (only if in Draft status)
+    pub fn is_editable(&self) -> bool {
+        self.status == InvoiceStatus::Draft && !self.has_irn()
+    }
+
+    /// Whether the invoice is registered with the IRP
+    pub fn has_irn(&self) -> bool {
+        self.e_invoice.is_some()
+    }
+
+    /// Check if invoice can be issued
+    pub fn can_issue(&self) -> bool {
+        self.status == InvoiceStatus::Draft && !self.items.is_empty()
+    }
+
+    /// Check if invoice can be cancelled
+    pub fn can_cancel(&self) -> bool {
+        matches!(
+            self.status,
+            InvoiceStatus::Draft | InvoiceStatus::Issued | InvoiceStatus::Overdue
+        ) && self.amount_paid.is_zero()
+    }
+
+    /// Check if invoice can be voided
+    pub fn can_void(&self) -> bool {
+        matches!(self.status, InvoiceStatus::Issued | InvoiceStatus::Overdue)
+            && self.amount_paid.is_zero()
+    }
+
+    /// Check if payments can be allocated to this invoice
+    pub fn can_receive_payment(&self) -> bool {
+        matches!(
+            self.status,
+            InvoiceStatus::Issued | InvoiceStatus::PartiallyPaid | InvoiceStatus::Overdue
+        )
+    }
+
+    /// Check if credit or debit notes can be raised against this invoice
+    pub fn can_adjust(&self) -> bool {
+        matches!(
+            self.status,
+            InvoiceStatus::Issued
+                | InvoiceStatus::PartiallyPaid
+                | InvoiceStatus::Paid
+                | InvoiceStatus::Overdue
+        )
+    }
+
+    /// Invoice value after credit and debit notes
+    pub fn adjusted_total(&self) -> Money {
+        self.total + self.debited_amount - self.credited_amount
+    }
+
+    /// Recompute the balance due from the adjusted total and the amount paid
+    pub fn refresh_balance(&mut self) {
+        self.balance_due = self.adjusted_total() - self.amount_paid;
+    }
+
+    /// Move between Issued, PartiallyPaid and Paid after the balance changed.
+    /// An overdue invoice stays overdue until it is paid in full.
+    fn settle_status(&mut self) {
+        self.refresh_balance();
+        self.status = if self.balance_due.is_zero() || self.balance_due.is_negative() {
+            InvoiceStatus::Paid
+        } else if self.status == InvoiceStatus::Overdue {
+            InvoiceStatus::Overdue
+        } else if !self.amount_paid.is_zero() {
+            InvoiceStatus::PartiallyPaid
+        } else {
+            InvoiceStatus::Issued
+        };
+    }
+
+    /// Reduce the receivable by a credit note
+    pub fn apply_credit_note(&mut self, amount: Money) -> Result<(), String> {
+        if !self.can_adjust() {
+            return Err(format!(
+                "Credit notes cannot be raised against invoice {} in current status",
+                self.invoice_number
+            ));
+        }
+        if amount > self.adjusted_total() {
+            return Err(format!(
+                "Credit note amount {} exceeds invoice value {} of invoice {}",
+                amount,
+                self.adjusted_total(),
+                self.invoice_number
+            ));
+        }
+
+        self.credited_amount += amount;
+        self.settle_status();
+        Ok(())
+    }
+
+    /// Increase the receivable by a debit note
+    pub fn apply_debit_note(&mut self, amount: Money) -> Result<(), String> {
+        if !self.can_adjust() {
+            return Err(format!(
+                "Debit notes cannot be raised against invoice {} in current status",
+                self.invoice_number
+            ));
+        }
+
+        self.debited_amount += amount;
+        self.settle_status();
+        Ok(())
+    }
+
+    /// Allocate part of a payment to this invoice
+    pub fn apply_payment(&mut self, amount: Money) -> Result<(), String> {
+        if !self.can_receive_payment() {
+            return Err(format!(
+                "Invoice {} cannot receive payments in current status",
+                self.invoice_number
+            ));
+        }
+        if amount.is_negative() || amount.is_zero() {
+            return Err("Allocated amount must be greater than zero".to_string());
+        }
+
+        self.refresh_balance();
+        if amount > self.balance_due {
+            return Err(format!(
+                "Allocated amount {} exceeds balance due {} on invoice {}",
+                amount, self.balance_due, self.invoice_number
+            ));
+        }
+
+        self.amount_paid += amount;
+        self.settle_status();
+
+        Ok(())
+    }
+
+    /// Issue the invoice to the customer
+    pub fn issue(&mut self) -> Result<(), String> {
+        if !self.can_issue() {
+            return Err("Invoice cannot be issued in current status".to_string());
+        }
+
+        self.status = InvoiceStatus::Issued;
+        self.issued_at = Some(DateTime::now());
+
+        Ok(())
+    }
+
+    /// Cancel the invoice
+    pub fn cancel(&mut self, reason: Option<String>) -> Result<(), String> {
+        if self.has_irn() {
+            return Err("Invoice has an IRN; cancel the e-invoice on the IRP first".to_string());
+        }
+        if !self.can_cancel() {
+            return Err("Invoice cannot be cancelled in current status".to_string());
+        }
+
+        self.status = InvoiceStatus::Cancelled;
+        self.closed_at = Some(DateTime::now());
+        self.closed_reason = reason;
+
+        Ok(())
+    }
+
+    /// Void an issued invoice
+    pub fn void(&mut self, reason: Option<String>) -> Result<(), String> {
+        if self.has_irn() {
+            return Err("Invoice has an IRN; cancel the e-invoice on the IRP first".to_string());
+        }
+        if !self.can_void() {
+            return Err("Invoice cannot be voided in current status".to_string());
+        }
+
+        self.status = InvoiceStatus::Void;
+        self.closed_at = Some(DateTime::now());
+        self.closed_reason = reason;
+
+        Ok(())
+    }
+}
+
+/// Shipping bill details, usually known only after the invoice is issued
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ShippingBillRequest {
+    #[serde(rename = "shippingBillNo")]
+    pub shipping_bill_no: String,
+
+    /// YYYY-MM-DD
+    #[serde(rename = "shippingBillDate")]
+    pub shipping_bill_date: String,
+
+    #[serde(rename = "portCode")]
+    pub port_code: String,
+}
+
+/// Request body for cancel/void transitions
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct InvoiceTransitionRequest {
+    #[serde(default)]
+    pub reason: Option<String>,
 }
 
 /// For creation (POST /invoices)
@@ -169,3 +563,132 @@ pub type CreateInvoiceRequest = Invoice;
 
 /// For updates (PUT /invoices/{id})
 pub type UpdateInvoiceRequest = Invoice;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn money(value: &str) -> Money {
+        value.parse().unwrap()
+    }
+
+    fn invoice(status: InvoiceStatus, total: &str) -> Invoice {
+        Invoice {
+            invoice_number: "INV-0001".to_string(),
+            status,
+            items: vec![InvoiceItem::default()],
+            total: money(total),
+            balance_due: money(total),
+            ..Default::default()
+        }
+    }
+
+    fn e_invoice() -> EInvoiceDetails {
+        EInvoiceDetails {
+            irn: "a".repeat(64),
+            ack_no: "112010000000001".to_string(),
+            ack_date: "2024-08-10 10:00:00".to_string(),
+            signed_qr_code: String::new(),
+            signed_invoice: String::new(),
+            source: "manual".to_string(),
+            registered_at: DateTime::now(),
+        }
+    }
+
+    #[test]
+    fn only_unregistered_drafts_are_editable() {
+        assert!(invoice(InvoiceStatus::Draft, "100").is_editable());
+        assert!(!invoice(InvoiceStatus::Issued, "100").is_editable());
+
+        let registered = Invoice {
+            e_invoice: Some(e_invoice()),
+            ..invoice(InvoiceStatus::Draft, "100")
+        };
+        assert!(!registered.is_editable());
+    }
+
+    #[test]
+    fn issue_needs_a_draft_with_lines() {
+        let mut draft = invoice(InvoiceStatus::Draft, "100");
+        draft.issue().unwrap();
+        assert_eq!(draft.status, InvoiceStatus::Issued);
+        assert!(draft.issued_at.is_some());
+        assert!(draft.issue().is_err());
+
+        let mut empty = Invoice {
+            items: Vec::new(),
+            ..invoice(InvoiceStatus::Draft, "100")
+        };
+        assert!(empty.issue().is_err());
+    }
+
+    #[test]
+    fn cancel_and_void_refuse_paid_or_registered_invoices() {
+        let mut draft = invoice(InvoiceStatus::Draft, "100");
+        draft.cancel(Some("Duplicate".to_string())).unwrap();
+        assert_eq!(draft.status, InvoiceStatus::Cancelled);
+        assert!(draft.void(None).is_err());
+
+        let mut issued = invoice(InvoiceStatus::Issued, "100");
+        issued.void(None).unwrap();
+        assert_eq!(issued.status, InvoiceStatus::Void);
+
+        let mut part_paid = invoice(InvoiceStatus::Issued, "100");
+        part_paid.apply_payment(money("10")).unwrap();
+        assert!(part_paid.cancel(None).is_err());
+        assert!(part_paid.void(None).is_err());
+
+        let mut registered = Invoice {
+            e_invoice: Some(e_invoice()),
+            ..invoice(InvoiceStatus::Issued, "100")
+        };
+        assert!(registered.cancel(None).is_err());
+        assert!(registered.void(None).is_err());
+        assert_eq!(registered.status, InvoiceStatus::Issued);
+    }
+
+    #[test]
+    fn payments_and_notes_settle_the_status() {
+        let mut invoice = invoice(InvoiceStatus::Issued, "100");
+        invoice.apply_payment(money("40")).unwrap();
+        assert_eq!(invoice.status, InvoiceStatus::PartiallyPaid);
+        assert_eq!(invoice.balance_due, money("60"));
+
+        assert!(invoice.apply_payment(money("70")).is_err());
+        invoice.apply_credit_note(money("60")).unwrap();
+        assert_eq!(invoice.status, InvoiceStatus::Paid);
+        assert!(invoice.balance_due.is_zero());
+
+        invoice.apply_debit_note(money("25")).unwrap();
+        assert_eq!(invoice.status, InvoiceStatus::PartiallyPaid);
+        assert_eq!(invoice.balance_due, money("25"));
+    }
+
+    #[test]
+    fn payments_refused_outside_open_statuses() {
+        for status in [
+            InvoiceStatus::Draft,
+            InvoiceStatus::Cancelled,
+            InvoiceStatus::Void,
+        ] {
+            let mut closed = invoice(status, "100");
+            assert!(closed.apply_payment(money("10")).is_err());
+            assert!(closed.apply_credit_note(money("10")).is_err());
+        }
+        assert!(invoice(InvoiceStatus::Issued, "100")
+            .apply_payment(Money::ZERO)
+            .is_err());
+    }
+
+    #[test]
+    fn overdue_stays_overdue_until_paid() {
+        let mut invoice = invoice(InvoiceStatus::Overdue, "100");
+        invoice.apply_payment(money("30")).unwrap();
+        assert_eq!(invoice.status, InvoiceStatus::Overdue);
+        invoice.apply_debit_note(money("10")).unwrap();
+        assert_eq!(invoice.status, InvoiceStatus::Overdue);
+
+        invoice.apply_payment(money("80")).unwrap();
+        assert_eq!(invoice.status, InvoiceStatus::Paid);
+    }
+}
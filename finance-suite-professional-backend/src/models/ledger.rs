@@ -0,0 +1,274 @@
+use mongodb::bson::{oid::ObjectId, DateTime};
+use serde::{Deserialize, Serialize};
+
+use super::money::Money;
+
+/// Top-level classification of a ledger account
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum AccountType {
+    Asset,
+    Liability,
+    Equity,
+    Income,
+    Expense,
+}
+
+impl AccountType {
+    /// Assets and expenses grow with debits; everything else with credits
+    pub fn is_debit_normal(&self) -> bool {
+        matches!(self, AccountType::Asset | AccountType::Expense)
+    }
+}
+
+// System account codes the posting rules rely on
+pub const BANK: &str = "1000";
+pub const ACCOUNTS_RECEIVABLE: &str = "1100";
+pub const INPUT_TAX: &str = "1300";
+pub const ACCOUNTS_PAYABLE: &str = "2000";
+pub const REIMBURSEMENTS_PAYABLE: &str = "2100";
+pub const OUTPUT_CGST: &str = "2200";
+pub const OUTPUT_SGST: &str = "2210";
+pub const OUTPUT_IGST: &str = "2220";
+pub const OWNERS_EQUITY: &str = "3000";
+pub const SALES: &str = "4000";
+pub const ROUNDING: &str = "4900";
+pub const GENERAL_EXPENSES: &str = "5000";
+
+/// Chart of accounts every organisation starts with
+pub const DEFAULT_ACCOUNTS: &[(&str, &str, AccountType)] = &[
+    (BANK, "Bank", AccountType::Asset),
+    (ACCOUNTS_RECEIVABLE, "Accounts Receivable", AccountType::Asset),
+    (INPUT_TAX, "Input Tax Credit", AccountType::Asset),
+    (ACCOUNTS_PAYABLE, "Accounts Payable", AccountType::Liability),
+    (REIMBURSEMENTS_PAYABLE, "Employee Reimbursements Payable", AccountType::Liability),
+    (OUTPUT_CGST, "Output CGST", AccountType::Liability),
+    (OUTPUT_SGST, "Output SGST", AccountType::Liability),
+    (OUTPUT_IGST, "Output IGST", AccountType::Liability),
+    (OWNERS_EQUITY, "Owner's Equity", AccountType::Equity),
+    (SALES, "Sales", AccountType::Income),
+    (ROUNDING, "Rounding Off", AccountType::Income),
+    (GENERAL_EXPENSES, "General Expenses", AccountType::Expense),
+];
+
+/// Account in an organisation's chart of accounts
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Account {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+
+    #[serde(default)]
+    pub org_email: String,
+
+    /// Unique within the organisation, e.g. "4000"
+    pub code: String,
+
+    pub name: String,
+
+    pub account_type: AccountType,
+
+    /// Seeded account used by automatic postings; cannot be removed
+    #[serde(default)]
+    pub system: bool,
+
+    #[serde(default)]
+    pub created_at: Option<DateTime>,
+}
+
+/// Create Account Request DTO
+pub type CreateAccountRequest = Account;
+
+impl Account {
+    pub fn new(org_email: &str, code: &str, name: &str, account_type: AccountType) -> Self {
+        Self {
+            id: None,
+            org_email: org_email.to_string(),
+            code: code.to_string(),
+            name: name.to_string(),
+            account_type,
+            system: true,
+            created_at: Some(DateTime::now()),
+        }
+    }
+}
+
+/// Business event that produced a journal entry
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum JournalSource {
+    InvoiceIssued,
+    /// Invoice cancelled or voided after it was issued
+    InvoiceReversed,
+    ExpenseApproved,
+    ExpenseReimbursed,
+}
+
+impl JournalSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            JournalSource::InvoiceIssued => "InvoiceIssued",
+            JournalSource::InvoiceReversed => "InvoiceReversed",
+            JournalSource::ExpenseApproved => "ExpenseApproved",
+            JournalSource::ExpenseReimbursed => "ExpenseReimbursed",
+        }
+    }
+}
+
+/// One debit or credit line of a journal entry
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct JournalLine {
+    pub account_code: String,
+
+    #[serde(default)]
+    pub account_name: String,
+
+    #[serde(default)]
+    pub debit: Money,
+
+    #[serde(default)]
+    pub credit: Money,
+}
+
+impl JournalLine {
+    pub fn debit(account_code: &str, amount: Money) -> Self {
+        Self {
+            account_code: account_code.to_string(),
+            account_name: String::new(),
+            debit: amount,
+            credit: Money::ZERO,
+        }
+    }
+
+    pub fn credit(account_code: &str, amount: Money) -> Self {
+        Self {
+            account_code: account_code.to_string(),
+            account_name: String::new(),
+            debit: Money::ZERO,
+            credit: amount,
+        }
+    }
+}
+
+/// Document amount of an entry converted from a foreign currency; kept as
+/// a memo, the lines carry the converted amounts
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ForeignAmount {
+    pub currency: String,
+    pub amount: Money,
+    /// Units of the entry currency per unit of `currency`
+    pub exchange_rate: String,
+}
+
+/// Balanced journal entry. Entries are never edited or deleted; mistakes
+/// are corrected by posting a reversing entry.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct JournalEntry {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+
+    pub org_email: String,
+
+    /// Accounting date (YYYY-MM-DD)
+    pub entry_date: String,
+
+    pub source: JournalSource,
+
+    /// Id of the invoice or expense that was posted
+    pub source_id: String,
+
+    /// Invoice number or expense title, for display
+    #[serde(default)]
+    pub reference: String,
+
+    #[serde(default)]
+    pub narration: String,
+
+    /// All lines of an entry share one currency
+    pub currency: String,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub foreign_amount: Option<ForeignAmount>,
+
+    pub lines: Vec<JournalLine>,
+
+    #[serde(default)]
+    pub created_at: Option<DateTime>,
+}
+
+impl JournalEntry {
+    pub fn total_debit(&self) -> Money {
+        self.lines.iter().map(|l| l.debit).sum()
+    }
+
+    pub fn total_credit(&self) -> Money {
+        self.lines.iter().map(|l| l.credit).sum()
+    }
+
+    /// Drop empty lines and check the entry balances
+    pub fn validate(&mut self) -> Result<(), String> {
+        self.lines.retain(|l| !(l.debit.is_zero() && l.credit.is_zero()));
+
+        if self.lines.len() < 2 {
+            return Err("A journal entry needs at least two lines".to_string());
+        }
+        for line in &self.lines {
+            if line.debit.is_negative() || line.credit.is_negative() {
+                return Err(format!(
+                    "Account {} has a negative amount",
+                    line.account_code
+                ));
+            }
+            if !line.debit.is_zero() && !line.credit.is_zero() {
+                return Err(format!(
+                    "Account {} is both debited and credited on one line",
+                    line.account_code
+                ));
+            }
+        }
+        if self.total_debit() != self.total_credit() {
+            return Err(format!(
+                "Journal entry does not balance: debits {} vs credits {}",
+                self.total_debit(),
+                self.total_credit()
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Debit/credit totals of one account in one currency
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AccountBalance {
+    pub account_code: String,
+    pub account_name: String,
+    pub account_type: AccountType,
+    pub currency: String,
+    pub debit: Money,
+    pub credit: Money,
+    /// Signed by the account's normal side: positive means a normal balance
+    pub balance: Money,
+}
+
+/// One posting in an account ledger, with the balance after it
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct LedgerLine {
+    pub entry_id: Option<ObjectId>,
+    pub entry_date: String,
+    pub source: JournalSource,
+    pub source_id: String,
+    pub reference: String,
+    pub narration: String,
+    pub debit: Money,
+    pub credit: Money,
+    pub balance: Money,
+}
+
+/// Postings to one account in one currency over a date range
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AccountLedger {
+    pub account_code: String,
+    pub account_name: String,
+    pub account_type: AccountType,
+    pub currency: String,
+    pub opening_balance: Money,
+    pub lines: Vec<LedgerLine>,
+    pub closing_balance: Money,
+}
@@ -2,7 +2,25 @@ pub mod address;
 pub mod customer;
 pub mod organisation;
 pub mod invoice;
+pub mod money;
 pub mod expense; // ✅ added
+pub mod payment;
+pub mod credit_debit_note;
+pub mod recurring_invoice;
+pub mod estimate;
+pub mod purchase_order;
+pub mod vendor;
+pub mod vendor_bill;
+pub mod ledger;
+pub mod report;
+pub mod gstr1;
+pub mod gstr3b;
+pub mod gstr2b;
+pub mod catalog_item;
+pub mod e_invoice;
+pub mod e_way_bill;
+pub mod tds;
+pub mod user;
 
 // Existing exports
 pub use customer::{CreateCustomerRequest, Customer, UpdateCustomerRequest};
@@ -11,3 +29,4 @@ pub use invoice::{CreateInvoiceRequest, Invoice, UpdateInvoiceRequest};
 
 // ✅ Export Expense models
 pub use expense::{CreateExpenseRequest, Expense, UpdateExpenseRequest};
+pub use payment::{CreatePaymentRequest, Payment};
@@ -0,0 +1,329 @@
+use mongodb::bson::{self, Bson, Decimal128};
+use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
+use rust_decimal::{Decimal, RoundingStrategy};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Div, Mul, Neg, Sub, SubAssign};
+use std::str::FromStr;
+
+use super::organisation::RoundingRule;
+
+/// Default ISO 4217 currency for documents created before currencies were stored
+pub const DEFAULT_CURRENCY: &str = "INR";
+
+pub fn default_currency() -> String {
+    DEFAULT_CURRENCY.to_string()
+}
+
+/// Check that a currency is a three-letter uppercase ISO 4217 code
+pub fn is_valid_currency_code(code: &str) -> bool {
+    code.len() == 3 && code.chars().all(|c| c.is_ascii_uppercase())
+}
+
+/// Exact decimal money amount shared by invoices and expenses.
+///
+/// Stored in MongoDB as Decimal128 so amounts can be summed in aggregations,
+/// and sent over the JSON API as a string such as `"1234.50"`. The currency
+/// code lives on the owning document (`Invoice.currency`, `ExpenseItem.currency`).
+///
+/// Reading accepts Decimal128, strings, doubles and integers, so documents
+/// written before the switch still load.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Money(Decimal);
+
+impl Money {
+    pub const ZERO: Money = Money(Decimal::ZERO);
+
+    pub fn new(amount: Decimal) -> Self {
+        Self(amount)
+    }
+
+    pub fn amount(&self) -> Decimal {
+        self.0
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.0.is_zero()
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.0.is_sign_negative() && !self.0.is_zero()
+    }
+
+    /// Round to two decimal places (paise), half away from zero
+    pub fn round_paise(self) -> Self {
+        Self(self.0.round_dp_with_strategy(2, RoundingStrategy::MidpointAwayFromZero))
+    }
+
+    /// Apply the organisation rounding rule (whole rupees unless `None`)
+    pub fn round(self, rule: RoundingRule) -> Self {
+        match rule {
+            RoundingRule::Nearest => Self(
+                self.0
+                    .round_dp_with_strategy(0, RoundingStrategy::MidpointAwayFromZero),
+            ),
+            RoundingRule::Up => {
+                Self(self.0.round_dp_with_strategy(0, RoundingStrategy::AwayFromZero))
+            }
+            RoundingRule::Down => Self(self.0.round_dp_with_strategy(0, RoundingStrategy::ToZero)),
+            RoundingRule::None => self.round_paise(),
+        }
+    }
+
+    /// `percent`% of this amount, rounded to paise
+    pub fn percent(self, percent: Decimal) -> Self {
+        Self(self.0 * percent / Decimal::ONE_HUNDRED).round_paise()
+    }
+
+    /// Lossy conversion for legacy `f64` values
+    pub fn from_f64(value: f64) -> Option<Self> {
+        Decimal::from_f64(value).map(Self)
+    }
+
+    pub fn to_f64(self) -> f64 {
+        self.0.to_f64().unwrap_or(0.0)
+    }
+
+    pub fn to_decimal128(self) -> Decimal128 {
+        Decimal128::from_str(&self.0.to_string()).expect("rust_decimal is a valid Decimal128")
+    }
+
+    /// Convert any numeric or numeric-string BSON value; blank strings are zero
+    pub fn from_bson(value: &Bson) -> Option<Self> {
+        match value {
+            Bson::Decimal128(d) => parse_decimal(&d.to_string()).map(Self),
+            Bson::Double(f) => Self::from_f64(*f),
+            Bson::Int32(i) => Some(Self(Decimal::from(*i))),
+            Bson::Int64(i) => Some(Self(Decimal::from(*i))),
+            Bson::String(s) if s.trim().is_empty() => Some(Self::ZERO),
+            Bson::String(s) => parse_decimal(s.trim()).map(Self),
+            _ => None,
+        }
+    }
+}
+
+fn parse_decimal(value: &str) -> Option<Decimal> {
+    Decimal::from_str(value)
+        .or_else(|_| Decimal::from_scientific(value))
+        .ok()
+}
+
+impl fmt::Display for Money {
+    /// Always shows at least two decimal places
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.0.scale() < 2 {
+            let mut padded = self.0;
+            padded.rescale(2);
+            write!(f, "{}", padded)
+        } else {
+            write!(f, "{}", self.0)
+        }
+    }
+}
+
+impl FromStr for Money {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.is_empty() {
+            return Ok(Self::ZERO);
+        }
+        parse_decimal(s)
+            .map(Self)
+            .ok_or_else(|| format!("'{}' is not a valid amount", s))
+    }
+}
+
+impl From<Decimal> for Money {
+    fn from(amount: Decimal) -> Self {
+        Self(amount)
+    }
+}
+
+impl From<Money> for Bson {
+    fn from(money: Money) -> Self {
+        Bson::Decimal128(money.to_decimal128())
+    }
+}
+
+impl Serialize for Money {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.serialize_str(&self.to_string())
+        } else {
+            self.to_decimal128().serialize(serializer)
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Money {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = Bson::deserialize(deserializer)?;
+        Money::from_bson(&value)
+            .ok_or_else(|| serde::de::Error::custom(format!("invalid amount: {}", value)))
+    }
+}
+
+/// `bson::to_bson` presents itself as human readable, which would store
+/// `Money` as a string. Use this when building update documents by hand;
+/// it goes through the driver's raw (binary) serializer instead.
+pub fn to_bson<T: Serialize + ?Sized>(value: &T) -> mongodb::error::Result<Bson> {
+    #[derive(Serialize)]
+    struct Wrapper<'a, T: ?Sized> {
+        value: &'a T,
+    }
+
+    let bytes = bson::to_vec(&Wrapper { value })?;
+    let doc: bson::Document = bson::from_slice(&bytes)?;
+    Ok(doc.get("value").cloned().unwrap_or(Bson::Null))
+}
+
+impl Add for Money {
+    type Output = Money;
+
+    fn add(self, rhs: Money) -> Money {
+        Money(self.0 + rhs.0)
+    }
+}
+
+impl AddAssign for Money {
+    fn add_assign(&mut self, rhs: Money) {
+        self.0 += rhs.0;
+    }
+}
+
+impl Sub for Money {
+    type Output = Money;
+
+    fn sub(self, rhs: Money) -> Money {
+        Money(self.0 - rhs.0)
+    }
+}
+
+impl SubAssign for Money {
+    fn sub_assign(&mut self, rhs: Money) {
+        self.0 -= rhs.0;
+    }
+}
+
+impl Neg for Money {
+    type Output = Money;
+
+    fn neg(self) -> Money {
+        Money(-self.0)
+    }
+}
+
+impl Mul<Decimal> for Money {
+    type Output = Money;
+
+    fn mul(self, rhs: Decimal) -> Money {
+        Money(self.0 * rhs)
+    }
+}
+
+impl Div<Decimal> for Money {
+    type Output = Money;
+
+    fn div(self, rhs: Decimal) -> Money {
+        Money(self.0 / rhs)
+    }
+}
+
+impl Sum for Money {
+    fn sum<I: Iterator<Item = Money>>(iter: I) -> Money {
+        iter.fold(Money::ZERO, Add::add)
+    }
+}
+
+impl<'a> Sum<&'a Money> for Money {
+    fn sum<I: Iterator<Item = &'a Money>>(iter: I) -> Money {
+        iter.copied().sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
+    struct Doc {
+        amount: Money,
+    }
+
+    fn money(value: &str) -> Money {
+        value.parse().unwrap()
+    }
+
+    #[test]
+    fn decimal128_round_trip_keeps_the_exact_amount() {
+        for value in [
+            "0",
+            "0.10",
+            "1234.50",
+            "-42.75",
+            "99999999999.99",
+            "0.333333",
+        ] {
+            let doc = Doc {
+                amount: money(value),
+            };
+            let bytes = bson::to_vec(&doc).unwrap();
+            let stored: bson::Document = bson::from_slice(&bytes).unwrap();
+            assert!(
+                matches!(stored.get("amount"), Some(Bson::Decimal128(_))),
+                "{} was not stored as Decimal128",
+                value
+            );
+
+            let read: Doc = bson::from_slice(&bytes).unwrap();
+            assert_eq!(read, doc);
+            assert_eq!(read.amount.amount().scale(), doc.amount.amount().scale());
+        }
+    }
+
+    #[test]
+    fn to_bson_writes_decimal128() {
+        let value = to_bson(&money("10.05")).unwrap();
+        assert_eq!(value, Bson::Decimal128(money("10.05").to_decimal128()));
+        assert_eq!(Money::from_bson(&value), Some(money("10.05")));
+    }
+
+    #[test]
+    fn legacy_values_still_load() {
+        assert_eq!(Money::from_bson(&Bson::Double(12.5)), Some(money("12.5")));
+        assert_eq!(Money::from_bson(&Bson::Int32(7)), Some(money("7")));
+        assert_eq!(Money::from_bson(&Bson::Int64(-3)), Some(money("-3")));
+        assert_eq!(
+            Money::from_bson(&Bson::String(" 15.20 ".to_string())),
+            Some(money("15.20"))
+        );
+        assert_eq!(
+            Money::from_bson(&Bson::String(String::new())),
+            Some(Money::ZERO)
+        );
+        assert_eq!(Money::from_bson(&Bson::Null), None);
+    }
+
+    #[test]
+    fn json_uses_strings_with_two_decimals() {
+        let json = serde_json::to_string(&Doc { amount: money("5") }).unwrap();
+        assert_eq!(json, r#"{"amount":"5.00"}"#);
+
+        let read: Doc = serde_json::from_str(r#"{"amount":12.34}"#).unwrap();
+        assert_eq!(read.amount, money("12.34"));
+    }
+
+    #[test]
+    fn rounding_rules() {
+        let amount = money("10.505");
+        assert_eq!(amount.round_paise(), money("10.51"));
+        assert_eq!(amount.round(RoundingRule::Nearest), money("11"));
+        assert_eq!(money("10.4").round(RoundingRule::Up), money("11"));
+        assert_eq!(money("10.9").round(RoundingRule::Down), money("10"));
+        assert_eq!(amount.round(RoundingRule::None), money("10.51"));
+        assert_eq!(money("200").percent(Decimal::from(18)), money("36"));
+    }
+}
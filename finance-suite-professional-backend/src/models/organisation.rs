@@ -1,8 +1,12 @@
+use std::fmt;
+
 use mongodb::bson::oid::ObjectId;
 use serde::{Deserialize, Serialize};
 use validator::Validate;
 
 use super::address::Address;
+use super::tds::{default_tds_sections, TdsSection};
+use super::user::Role;
 
 //
 // ================= ORGANISATION =================
@@ -44,6 +48,18 @@ pub struct Organisation {
     #[serde(rename = "startingInvoiceNo", default)]
     pub starting_invoice_no: String,
 
+    #[serde(rename = "creditNotePrefix", default)]
+    pub credit_note_prefix: String,
+
+    #[serde(rename = "debitNotePrefix", default)]
+    pub debit_note_prefix: String,
+
+    #[serde(rename = "estimatePrefix", default)]
+    pub estimate_prefix: String,
+
+    #[serde(rename = "purchaseOrderPrefix", default)]
+    pub purchase_order_prefix: String,
+
     #[serde(rename = "dateFormat", default)]
     pub date_format: String,
 
@@ -105,13 +121,28 @@ pub struct Organisation {
     #[serde(rename = "requireHSN", default)]
     pub require_hsn: String,
 
+    /// Aggregate turnover band of the previous financial year, "upto_5cr" or
+    /// "above_5cr"; decides how many HSN/SAC digits invoices must carry
+    #[serde(rename = "turnoverBand", default)]
+    pub turnover_band: String,
+
     #[serde(rename = "roundingRule", default)]
     pub rounding_rule: String,
 
     #[serde(rename = "taxNotes", default)]
     pub tax_notes: String,
 
+    /// Tax deduction account number, quoted on TDS returns
+    #[serde(default)]
+    pub tan: String,
+
+    /// TDS sections the organisation deducts under; the standard sections
+    /// apply when empty
+    #[serde(rename = "tdsSections", default)]
+    pub tds_sections: Vec<TdsSection>,
+
     // -------- Users & Roles --------
+    // Draft of the settings page's invite form; accounts live in `users`
     #[serde(rename = "newUserName", default)]
     pub new_user_name: String,
 
@@ -167,6 +198,19 @@ pub struct Organisation {
     // Invoice sequence tracking
     #[serde(rename = "lastInvoiceSequence", default)]
     pub last_invoice_sequence: i32,
+
+    // Credit / debit note sequences, separate from invoices
+    #[serde(rename = "lastCreditNoteSequence", default)]
+    pub last_credit_note_sequence: i32,
+
+    #[serde(rename = "lastDebitNoteSequence", default)]
+    pub last_debit_note_sequence: i32,
+
+    #[serde(rename = "lastEstimateSequence", default)]
+    pub last_estimate_sequence: i32,
+
+    #[serde(rename = "lastPurchaseOrderSequence", default)]
+    pub last_purchase_order_sequence: i32,
 }
 
 //
@@ -198,6 +242,14 @@ pub struct CreateOrganisationRequest {
     // #[serde(rename = "startingInvoiceNo")]
 
     pub starting_invoice_no: String,
+    #[serde(default)]
+    pub credit_note_prefix: String,
+    #[serde(default)]
+    pub debit_note_prefix: String,
+    #[serde(default)]
+    pub estimate_prefix: String,
+    #[serde(default)]
+    pub purchase_order_prefix: String,
     pub date_format: String,
     pub currency: String,
     pub payment_terms: String,
@@ -220,8 +272,14 @@ pub struct CreateOrganisationRequest {
     pub igst: String,
     pub input_tax_credit: String,
     pub require_hsn: String,
+    #[serde(default)]
+    pub turnover_band: String,
     pub rounding_rule: String,
     pub tax_notes: String,
+    #[serde(default)]
+    pub tan: String,
+    #[serde(rename = "tdsSections", default)]
+    pub tds_sections: Vec<TdsSection>,
 
     // Users
     pub new_user_name: String,
@@ -272,6 +330,10 @@ pub struct UpdateOrganizationRequest {
 
     pub invoice_prefix: Option<String>,
     pub starting_invoice_no: Option<String>,
+    pub credit_note_prefix: Option<String>,
+    pub debit_note_prefix: Option<String>,
+    pub estimate_prefix: Option<String>,
+    pub purchase_order_prefix: Option<String>,
     pub date_format: Option<String>,
     pub currency: Option<String>,
     pub payment_terms: Option<String>,
@@ -293,8 +355,12 @@ pub struct UpdateOrganizationRequest {
     pub igst: Option<String>,
     pub input_tax_credit: Option<String>,
     pub require_hsn: Option<String>,
+    pub turnover_band: Option<String>,
     pub rounding_rule: Option<String>,
     pub tax_notes: Option<String>,
+    pub tan: Option<String>,
+    #[serde(rename = "tdsSections")]
+    pub tds_sections: Option<Vec<TdsSection>>,
 
     pub new_user_name: Option<String>,
     pub new_user_email: Option<String>,
@@ -338,6 +404,130 @@ pub struct Permissions {
     pub Custom: PermissionSet,
 }
 
+/// Area of the app a permission applies to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Resource {
+    Customer,
+    Invoice,
+    Expense,
+    Organisation,
+}
+
+/// Column of a `PermissionSet`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Action {
+    View,
+    Edit,
+    Delete,
+    Export,
+}
+
+impl fmt::Display for Resource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Resource::Customer => "customers",
+            Resource::Invoice => "invoices",
+            Resource::Expense => "expenses",
+            Resource::Organisation => "the organisation",
+        };
+        f.write_str(name)
+    }
+}
+
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Action::View => "view",
+            Action::Edit => "edit",
+            Action::Delete => "delete",
+            Action::Export => "export",
+        };
+        f.write_str(name)
+    }
+}
+
+impl PermissionSet {
+    const ALL: Self = Self {
+        view: true,
+        edit: true,
+        delete: true,
+        export: true,
+    };
+
+    fn allows(&self, action: Action) -> bool {
+        match action {
+            Action::View => self.view,
+            Action::Edit => self.edit,
+            Action::Delete => self.delete,
+            Action::Export => self.export,
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        !(self.view || self.edit || self.delete || self.export)
+    }
+}
+
+impl Permissions {
+    /// Matrix used until the organisation saves its own: managers may do
+    /// everything, accountants everything but delete, the others only view
+    fn defaults_for(role: Role) -> PermissionSet {
+        match role {
+            Role::Admin | Role::Manager => PermissionSet::ALL,
+            Role::Accountant => PermissionSet {
+                delete: false,
+                ..PermissionSet::ALL
+            },
+            Role::Viewer | Role::Custom => PermissionSet {
+                view: true,
+                ..PermissionSet::default()
+            },
+        }
+    }
+
+    fn for_role(&self, role: Role) -> &PermissionSet {
+        match role {
+            Role::Admin => &self.Admin,
+            Role::Manager => &self.Manager,
+            Role::Accountant => &self.Accountant,
+            Role::Viewer => &self.Viewer,
+            Role::Custom => &self.Custom,
+        }
+    }
+
+    fn is_configured(&self) -> bool {
+        [
+            &self.Admin,
+            &self.Manager,
+            &self.Accountant,
+            &self.Viewer,
+            &self.Custom,
+        ]
+        .iter()
+        .any(|set| !set.is_empty())
+    }
+
+    /// Whether `role` may perform `action` on `resource`. Administrators can
+    /// do everything, so an organisation cannot lock itself out, and only
+    /// they may change or delete the organisation itself. A matrix with no
+    /// box ticked has never been saved and falls back to the defaults.
+    pub fn allows(&self, role: Role, resource: Resource, action: Action) -> bool {
+        if role == Role::Admin {
+            return true;
+        }
+        if resource == Resource::Organisation && action != Action::View {
+            return false;
+        }
+        if self.is_configured() {
+            self.for_role(role).allows(action)
+        } else {
+            Self::defaults_for(role).allows(action)
+        }
+    }
+}
+
 //
 // ================= PAYMENT METHODS =================
 //
@@ -350,6 +540,68 @@ pub struct EnabledMethods {
     pub paypal: bool,
     pub cash: bool,
 }
+//
+// ================= ROUNDING =================
+//
+
+/// Tax rounding rule configured under Tax Settings (`roundingRule`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RoundingRule {
+    /// Round to the nearest rupee
+    #[default]
+    Nearest,
+    /// Always round up to the next rupee
+    Up,
+    /// Always round down to the rupee
+    Down,
+    /// Keep paise, no rounding
+    None,
+}
+
+impl RoundingRule {
+    /// Parse the setting stored on the organisation, defaulting to `Nearest`
+    /// just like the settings page does for an empty value.
+    pub fn from_setting(value: &str) -> Self {
+        match value.trim().to_lowercase().as_str() {
+            "up" => RoundingRule::Up,
+            "down" => RoundingRule::Down,
+            "none" => RoundingRule::None,
+            _ => RoundingRule::Nearest,
+        }
+    }
+}
+
+//
+// ================= HSN / SAC =================
+//
+
+/// HSN/SAC requirement configured under Tax Settings (`requireHSN`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HsnRequirement {
+    /// Codes are optional
+    Off,
+    /// Digits follow the turnover band (Notification 78/2020-CT): up to
+    /// ₹5 crore needs 4 digits on B2B invoices, above ₹5 crore needs 6
+    /// digits on every invoice
+    ByTurnover,
+    /// A fixed minimum number of digits on every invoice
+    Digits(usize),
+}
+
+impl HsnRequirement {
+    /// Parse the setting stored on the organisation; any "yes"-like value
+    /// means the turnover rule applies
+    pub fn from_setting(value: &str) -> Self {
+        match value.trim().to_lowercase().as_str() {
+            "" | "no" | "n" | "false" | "0" | "off" | "none" | "optional" => HsnRequirement::Off,
+            "4" => HsnRequirement::Digits(4),
+            "6" => HsnRequirement::Digits(6),
+            "8" => HsnRequirement::Digits(8),
+            _ => HsnRequirement::ByTurnover,
+        }
+    }
+}
+
 impl Organisation {
     pub fn new(req: CreateOrganisationRequest) -> Self {
         Self {
@@ -367,6 +619,10 @@ impl Organisation {
             // Invoice
             invoice_prefix: req.invoice_prefix,
             starting_invoice_no: req.starting_invoice_no,
+            credit_note_prefix: req.credit_note_prefix,
+            debit_note_prefix: req.debit_note_prefix,
+            estimate_prefix: req.estimate_prefix,
+            purchase_order_prefix: req.purchase_order_prefix,
             date_format: req.date_format,
             currency: req.currency,
             payment_terms: req.payment_terms,
@@ -389,8 +645,11 @@ impl Organisation {
             igst: req.igst,
             input_tax_credit: req.input_tax_credit,
             require_hsn: req.require_hsn,
+            turnover_band: req.turnover_band,
             rounding_rule: req.rounding_rule,
             tax_notes: req.tax_notes,
+            tan: req.tan,
+            tds_sections: req.tds_sections,
 
             // Users
             new_user_name: req.new_user_name,
@@ -413,6 +672,67 @@ impl Organisation {
             cash_instructions: req.cash_instructions,
             custom_payment_name: req.custom_payment_name,
             last_invoice_sequence: 0,
+            last_credit_note_sequence: 0,
+            last_debit_note_sequence: 0,
+            last_estimate_sequence: 0,
+            last_purchase_order_sequence: 0,
+        }
+    }
+
+    /// Tax rounding rule for this organisation
+    pub fn rounding(&self) -> RoundingRule {
+        RoundingRule::from_setting(&self.rounding_rule)
+    }
+
+    /// Two-digit GST state code taken from the organisation GSTIN
+    pub fn state_code(&self) -> Option<&str> {
+        self.gst_in.get(0..2).filter(|c| c.chars().all(|ch| ch.is_ascii_digit()))
+    }
+
+    /// Whether the previous year's turnover was above ₹5 crore
+    pub fn turnover_above_five_crore(&self) -> bool {
+        matches!(
+            self.turnover_band.trim().to_ascii_lowercase().as_str(),
+            "above_5cr" | "above5cr" | ">5cr" | "above"
+        )
+    }
+
+    /// Minimum HSN/SAC digits each invoice line must carry, `None` when codes
+    /// are optional. `b2b` is true for supplies to registered customers and
+    /// exports.
+    pub fn hsn_digits_required(&self, b2b: bool) -> Option<usize> {
+        match HsnRequirement::from_setting(&self.require_hsn) {
+            HsnRequirement::Off => None,
+            HsnRequirement::Digits(digits) => Some(digits),
+            HsnRequirement::ByTurnover if self.turnover_above_five_crore() => Some(6),
+            HsnRequirement::ByTurnover if b2b => Some(4),
+            HsnRequirement::ByTurnover => None,
+        }
+    }
+
+    /// TDS sections in use: the organisation's own, or the standard ones
+    pub fn effective_tds_sections(&self) -> Vec<TdsSection> {
+        if self.tds_sections.is_empty() {
+            default_tds_sections()
+        } else {
+            self.tds_sections.clone()
         }
     }
+
+    /// Look up a TDS section by its code, e.g. "194J"
+    pub fn tds_section(&self, code: &str) -> Option<TdsSection> {
+        let code = code.trim();
+        self.effective_tds_sections()
+            .into_iter()
+            .find(|s| s.section.trim().eq_ignore_ascii_case(code))
+    }
+
+    /// Whether the `inputTaxCredit` setting is switched on ("yes", "true",
+    /// "enabled", ...); blank means no credit is claimed
+    pub fn claims_input_tax_credit(&self) -> bool {
+        matches!(
+            self.input_tax_credit.trim().to_ascii_lowercase().as_str(),
+            "yes" | "y" | "true" | "1" | "on" | "enabled" | "eligible" | "claim"
+        )
+    }
 }
@@ -0,0 +1,171 @@
+use mongodb::bson::{oid::ObjectId, DateTime};
+use serde::{Deserialize, Serialize};
+
+use super::money::{default_currency, Money};
+use super::organisation::EnabledMethods;
+
+/// How the money was received; matches the keys of `Organisation.enabledMethods`
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum PaymentMethod {
+    BankTransfer,
+    Upi,
+    Card,
+    Paypal,
+    Cash,
+}
+
+impl PaymentMethod {
+    /// Whether the organisation accepts this method
+    pub fn is_enabled(&self, methods: &EnabledMethods) -> bool {
+        match self {
+            PaymentMethod::BankTransfer => methods.bankTransfer,
+            PaymentMethod::Upi => methods.upi,
+            PaymentMethod::Card => methods.card,
+            PaymentMethod::Paypal => methods.paypal,
+            PaymentMethod::Cash => methods.cash,
+        }
+    }
+}
+
+/// Portion of a payment settled against one invoice
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PaymentAllocation {
+    pub invoice_id: String,
+
+    /// Filled in by the server from the invoice
+    #[serde(default)]
+    pub invoice_number: String,
+
+    pub amount: Money,
+}
+
+/// A receipt of money from a customer
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Payment {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+
+    #[serde(default)]
+    pub customer_name: String,
+
+    pub method: PaymentMethod,
+
+    /// UTR, UPI transaction ID, cheque number, etc.
+    #[serde(default)]
+    pub reference: String,
+
+    pub payment_date: String,
+
+    #[serde(default = "default_currency")]
+    pub currency: String,
+
+    /// Amount actually credited to the bank account / received in cash
+    pub amount: Money,
+
+    /// Charges deducted by the bank or gateway
+    #[serde(default)]
+    pub bank_charges: Money,
+
+    /// TDS deducted by the customer under the Income Tax Act. When zero
+    /// and a section is given, whatever the allocations exceed the money
+    /// received by is taken as TDS.
+    #[serde(default)]
+    pub tds_deducted: Money,
+
+    /// Section the customer deducted under, e.g. "194J"
+    #[serde(default)]
+    pub tds_section: String,
+
+    #[serde(default)]
+    pub allocations: Vec<PaymentAllocation>,
+
+    #[serde(default)]
+    pub notes: String,
+
+    /// Organisation the payment was received by; taken from the invoices
+    #[serde(default)]
+    pub org_email: String,
+
+    #[serde(default)]
+    pub created_at: Option<DateTime>,
+}
+
+impl Payment {
+    /// Amount settled against invoices: money received plus bank charges and TDS
+    pub fn gross_amount(&self) -> Money {
+        self.amount + self.bank_charges + self.tds_deducted
+    }
+
+    pub fn allocated_amount(&self) -> Money {
+        self.allocations.iter().map(|a| a.amount).sum()
+    }
+
+    /// Treat the part of the allocations not covered by the money received
+    /// and bank charges as TDS, when a section is given and no TDS was entered
+    pub fn take_shortfall_as_tds(&mut self) {
+        self.tds_section = self.tds_section.trim().to_uppercase();
+        if !self.tds_deducted.is_zero() || self.tds_section.is_empty() {
+            return;
+        }
+        let shortfall = self.allocated_amount() - self.amount - self.bank_charges;
+        if !shortfall.is_negative() && !shortfall.is_zero() {
+            self.tds_deducted = shortfall;
+        }
+    }
+
+    /// Part of the payment not yet allocated (customer advance)
+    pub fn unallocated_amount(&self) -> Money {
+        self.gross_amount() - self.allocated_amount()
+    }
+
+    /// Validate amounts and allocations
+    pub fn validate(&self) -> Result<(), String> {
+        if self.payment_date.trim().is_empty() {
+            return Err("Payment date is required".to_string());
+        }
+        if self.amount.is_negative() || self.amount.is_zero() {
+            return Err("Amount must be greater than zero".to_string());
+        }
+        if self.bank_charges.is_negative() {
+            return Err("Bank charges cannot be negative".to_string());
+        }
+        if self.tds_deducted.is_negative() {
+            return Err("TDS deducted cannot be negative".to_string());
+        }
+        if !self.tds_deducted.is_zero() && self.tds_section.trim().is_empty() {
+            return Err("TDS section is required when TDS is deducted".to_string());
+        }
+
+        for (idx, allocation) in self.allocations.iter().enumerate() {
+            if allocation.amount.is_negative() || allocation.amount.is_zero() {
+                return Err(format!(
+                    "Allocation {} amount must be greater than zero",
+                    idx + 1
+                ));
+            }
+            if self.allocations[..idx]
+                .iter()
+                .any(|a| a.invoice_id == allocation.invoice_id)
+            {
+                return Err(format!(
+                    "Invoice {} is allocated more than once",
+                    allocation.invoice_id
+                ));
+            }
+        }
+
+        if self.unallocated_amount().is_negative() {
+            return Err(format!(
+                "Allocations total {} exceeds payment amount {} (including bank charges and TDS)",
+                self.allocated_amount(),
+                self.gross_amount()
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+/// Create Payment Request DTO
+pub type CreatePaymentRequest = Payment;
@@ -0,0 +1,174 @@
+use mongodb::bson::{oid::ObjectId, DateTime};
+use serde::{Deserialize, Serialize};
+
+use super::money::{default_currency, Money};
+
+/// Who raised the purchase order
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum PurchaseOrderKind {
+    /// PO received from a customer; our invoices bill against it
+    Customer,
+    /// PO we issued to a vendor; their bills are matched against it
+    Vendor,
+}
+
+impl PurchaseOrderKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PurchaseOrderKind::Customer => "Customer",
+            PurchaseOrderKind::Vendor => "Vendor",
+        }
+    }
+}
+
+/// Purchase order status
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PurchaseOrderStatus {
+    /// Being prepared, freely editable
+    #[default]
+    Draft,
+    /// Confirmed, nothing billed yet
+    Open,
+    /// Some of the value has been billed
+    PartiallyBilled,
+    /// Entire value billed
+    Billed,
+    /// Closed manually; no further billing
+    Closed,
+    Cancelled,
+}
+
+/// A single purchase order line
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct PurchaseOrderItem {
+    #[serde(default)]
+    pub description: String,
+
+    /// Decimal quantity as entered, e.g. "10" or "2.5"
+    #[serde(default)]
+    pub quantity: String,
+
+    #[serde(default)]
+    pub unit: String,
+
+    #[serde(default)]
+    pub rate: Money,
+
+    /// quantity × rate; computed by the server
+    #[serde(default)]
+    pub amount: Money,
+}
+
+/// Purchase order. Values are before GST; billing is tracked against `sub_total`.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PurchaseOrder {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+
+    pub kind: PurchaseOrderKind,
+
+    /// Customer's PO number for customer POs; generated for vendor POs
+    #[serde(default)]
+    pub po_number: String,
+
+    pub po_date: String,
+
+    #[serde(default)]
+    pub delivery_date: String,
+
+    // Customer or vendor
+    pub party_name: String,
+
+    #[serde(default)]
+    pub party_gstin: String,
+
+    #[serde(default)]
+    pub party_address: String,
+
+    #[serde(default = "default_currency")]
+    pub currency: String,
+
+    #[serde(default)]
+    pub items: Vec<PurchaseOrderItem>,
+
+    #[serde(rename = "subTotal", default)]
+    pub sub_total: Money,
+
+    /// Value already billed against this PO; maintained by the server
+    #[serde(rename = "billedAmount", default)]
+    pub billed_amount: Money,
+
+    #[serde(default)]
+    pub status: PurchaseOrderStatus,
+
+    #[serde(default)]
+    pub notes: String,
+
+    /// Organisation the PO belongs to; set by the server
+    #[serde(default)]
+    pub org_email: String,
+
+    #[serde(default)]
+    pub created_at: Option<DateTime>,
+
+    #[serde(default)]
+    pub updated_at: Option<DateTime>,
+}
+
+/// Create / Update Purchase Order Request DTO
+pub type PurchaseOrderRequest = PurchaseOrder;
+
+impl PurchaseOrder {
+    /// Value still available for billing
+    pub fn remaining_amount(&self) -> Money {
+        self.sub_total - self.billed_amount
+    }
+
+    /// Check if the PO can be edited
+    pub fn is_editable(&self) -> bool {
+        matches!(
+            self.status,
+            PurchaseOrderStatus::Draft | PurchaseOrderStatus::Open | PurchaseOrderStatus::PartiallyBilled
+        )
+    }
+
+    /// Check if the PO accepts new bills or invoices
+    pub fn can_bill(&self) -> bool {
+        matches!(
+            self.status,
+            PurchaseOrderStatus::Open | PurchaseOrderStatus::PartiallyBilled
+        )
+    }
+
+    pub fn open(&mut self) -> Result<(), String> {
+        if self.status != PurchaseOrderStatus::Draft || self.items.is_empty() {
+            return Err("Purchase order cannot be opened in current status".to_string());
+        }
+        self.status = PurchaseOrderStatus::Open;
+        self.updated_at = Some(DateTime::now());
+        Ok(())
+    }
+
+    pub fn close(&mut self) -> Result<(), String> {
+        if !matches!(
+            self.status,
+            PurchaseOrderStatus::Open | PurchaseOrderStatus::PartiallyBilled | PurchaseOrderStatus::Billed
+        ) {
+            return Err("Purchase order cannot be closed in current status".to_string());
+        }
+        self.status = PurchaseOrderStatus::Closed;
+        self.updated_at = Some(DateTime::now());
+        Ok(())
+    }
+
+    pub fn cancel(&mut self) -> Result<(), String> {
+        if !matches!(self.status, PurchaseOrderStatus::Draft | PurchaseOrderStatus::Open)
+            || !self.billed_amount.is_zero()
+        {
+            return Err("Only unbilled purchase orders can be cancelled".to_string());
+        }
+        self.status = PurchaseOrderStatus::Cancelled;
+        self.updated_at = Some(DateTime::now());
+        Ok(())
+    }
+}
@@ -0,0 +1,155 @@
+use chrono::{Days, Months, NaiveDate};
+use mongodb::bson::{oid::ObjectId, DateTime};
+use serde::{Deserialize, Serialize};
+
+use super::invoice::Invoice;
+use crate::utils::date::{parse_date, DATE_FORMAT};
+
+/// How often a recurring invoice is raised
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum RecurrenceFrequency {
+    Weekly,
+    Monthly,
+    Quarterly,
+    HalfYearly,
+    Yearly,
+}
+
+/// Template and schedule for invoices raised on a fixed cycle
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RecurringInvoice {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+
+    pub name: String,
+
+    /// Organisation whose invoice series numbers the generated invoices
+    pub org_email: String,
+
+    pub frequency: RecurrenceFrequency,
+
+    /// First run date, YYYY-MM-DD
+    pub start_date: String,
+
+    /// Last date a run may fall on, YYYY-MM-DD (inclusive)
+    #[serde(default)]
+    pub end_date: Option<String>,
+
+    /// Days between invoice date and due date
+    #[serde(default)]
+    pub due_in_days: u32,
+
+    /// Issue generated invoices straight away instead of leaving them as drafts
+    #[serde(default)]
+    pub auto_issue: bool,
+
+    #[serde(default = "default_active")]
+    pub active: bool,
+
+    /// Customer, items, notes, etc. copied into every generated invoice
+    pub template: Invoice,
+
+    /// Maintained by the server
+    #[serde(default)]
+    pub runs_completed: u32,
+
+    /// Maintained by the server; `None` once the schedule has ended
+    #[serde(default)]
+    pub next_run_date: Option<String>,
+
+    #[serde(default)]
+    pub last_run_date: Option<String>,
+
+    #[serde(default)]
+    pub last_invoice_id: Option<String>,
+
+    #[serde(default)]
+    pub created_at: Option<DateTime>,
+}
+
+fn default_active() -> bool {
+    true
+}
+
+/// Create / Update Recurring Invoice Request DTO
+pub type RecurringInvoiceRequest = RecurringInvoice;
+
+impl RecurringInvoice {
+    /// Date of the `run`-th occurrence (0 = start date). Always computed from
+    /// the start date so month-end schedules do not drift (31 Jan -> 28 Feb -> 31 Mar).
+    pub fn run_date(&self, run: u32) -> Result<NaiveDate, String> {
+        let start = parse_date("Start date", &self.start_date)?;
+        let date = match self.frequency {
+            RecurrenceFrequency::Weekly => start.checked_add_days(Days::new(7 * run as u64)),
+            RecurrenceFrequency::Monthly => start.checked_add_months(Months::new(run)),
+            RecurrenceFrequency::Quarterly => start.checked_add_months(Months::new(3 * run)),
+            RecurrenceFrequency::HalfYearly => start.checked_add_months(Months::new(6 * run)),
+            RecurrenceFrequency::Yearly => start.checked_add_months(Months::new(12 * run)),
+        };
+        date.ok_or_else(|| "Schedule date out of range".to_string())
+    }
+
+    /// Next run after `runs_completed`, or `None` when past the end date or inactive
+    pub fn compute_next_run(&self) -> Result<Option<NaiveDate>, String> {
+        if !self.active {
+            return Ok(None);
+        }
+        let next = self.run_date(self.runs_completed)?;
+        if let Some(end) = self.end_date.as_deref().filter(|d| !d.trim().is_empty()) {
+            if next > parse_date("End date", end)? {
+                return Ok(None);
+            }
+        }
+        Ok(Some(next))
+    }
+
+    /// Validate the schedule; the template invoice is validated when generated
+    pub fn validate(&self) -> Result<(), String> {
+        if self.name.trim().is_empty() {
+            return Err("Name is required".to_string());
+        }
+        if self.org_email.trim().is_empty() {
+            return Err("Organisation email is required".to_string());
+        }
+        let start = parse_date("Start date", &self.start_date)?;
+        if let Some(end) = self.end_date.as_deref().filter(|d| !d.trim().is_empty()) {
+            if parse_date("End date", end)? < start {
+                return Err("End date cannot be before start date".to_string());
+            }
+        }
+        if self.template.items.is_empty() {
+            return Err("Template must have at least one item".to_string());
+        }
+        Ok(())
+    }
+
+    /// Build the invoice for a run dated `run_date`
+    pub fn materialise(&self, run_date: NaiveDate) -> Invoice {
+        let mut invoice = self.template.clone();
+        invoice.id = None;
+        invoice.invoice_number = String::new();
+        invoice.invoice_date = run_date.format(DATE_FORMAT).to_string();
+        invoice.invoice_due_date = run_date
+            .checked_add_days(Days::new(self.due_in_days as u64))
+            .unwrap_or(run_date)
+            .format(DATE_FORMAT)
+            .to_string();
+        invoice.recurring_id = self.id.map(|id| id.to_hex());
+        invoice.recurring_run_date = Some(run_date.format(DATE_FORMAT).to_string());
+        invoice.estimate_id = None;
+        invoice
+    }
+}
+
+/// One invoice a run would generate (dry run) or has generated
+#[derive(Debug, Serialize, Clone)]
+pub struct RecurringRunResult {
+    pub recurring_id: String,
+    pub name: String,
+    pub run_date: String,
+    /// Generated invoice; for a dry run this is unsaved and unnumbered
+    pub invoice: Option<Invoice>,
+    /// True when an invoice for this run already existed and was not re-created
+    pub already_generated: bool,
+    pub error: Option<String>,
+}
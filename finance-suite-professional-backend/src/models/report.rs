@@ -0,0 +1,274 @@
+use chrono::{Datelike, Duration, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use super::ledger::AccountType;
+use super::money::Money;
+use crate::utils::csv::CsvWriter;
+use crate::utils::date::{parse_date, DATE_FORMAT};
+
+/// Inclusive reporting period (YYYY-MM-DD)
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct ReportPeriod {
+    pub from: String,
+    pub to: String,
+    /// "FY 2024-25" or "2024-05-01 to 2024-05-31"
+    pub label: String,
+}
+
+impl ReportPeriod {
+    /// Indian financial year, 1 April to 31 March. Accepts "2024-25",
+    /// "2024-2025" or just the starting year "2024".
+    pub fn financial_year(fy: &str) -> Result<Self, String> {
+        let start: i32 = fy
+            .trim()
+            .split(['-', '/'])
+            .next()
+            .and_then(|y| y.parse().ok())
+            .filter(|y| (1900..=9999).contains(y))
+            .ok_or_else(|| format!("Invalid financial year '{}'", fy))?;
+        Ok(Self::financial_year_starting(start))
+    }
+
+    fn financial_year_starting(start: i32) -> Self {
+        Self {
+            from: format!("{}-04-01", start),
+            to: format!("{}-03-31", start + 1),
+            label: format!("FY {}-{:02}", start, (start + 1) % 100),
+        }
+    }
+
+    /// Financial year containing `date`
+    pub fn financial_year_of(date: NaiveDate) -> Self {
+        let start = if date.month() >= 4 {
+            date.year()
+        } else {
+            date.year() - 1
+        };
+        Self::financial_year_starting(start)
+    }
+
+    pub fn custom(from: &str, to: &str) -> Result<Self, String> {
+        let start = parse_date("from", from)?;
+        let end = parse_date("to", to)?;
+        if end < start {
+            return Err("'to' cannot be before 'from'".to_string());
+        }
+        Ok(Self::between(start, end))
+    }
+
+    fn between(start: NaiveDate, end: NaiveDate) -> Self {
+        let from = start.format(DATE_FORMAT).to_string();
+        let to = end.format(DATE_FORMAT).to_string();
+        Self {
+            label: format!("{} to {}", from, to),
+            from,
+            to,
+        }
+    }
+
+    /// Comparative period: the previous financial year for a financial
+    /// year, otherwise the same number of days immediately before
+    pub fn previous(&self) -> Self {
+        let start = parse_date("from", &self.from).expect("period dates are validated");
+        let end = parse_date("to", &self.to).expect("period dates are validated");
+
+        if self.label.starts_with("FY ") {
+            return Self::financial_year_starting(start.year() - 1);
+        }
+
+        let days = (end - start).num_days();
+        let prev_end = start - Duration::days(1);
+        Self::between(prev_end - Duration::days(days), prev_end)
+    }
+}
+
+/// A figure for the report period next to the comparative period
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Comparative {
+    pub current: Money,
+    pub previous: Money,
+}
+
+impl std::ops::AddAssign for Comparative {
+    fn add_assign(&mut self, other: Self) {
+        self.current += other.current;
+        self.previous += other.previous;
+    }
+}
+
+/// One account line of a statement
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ReportLine {
+    pub account_code: String,
+    pub account_name: String,
+    pub account_type: AccountType,
+    pub amount: Comparative,
+}
+
+/// Closing debit/credit balance of an account at each period end
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TrialBalanceLine {
+    pub account_code: String,
+    pub account_name: String,
+    pub account_type: AccountType,
+    pub debit: Comparative,
+    pub credit: Comparative,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TrialBalance {
+    pub org_email: String,
+    pub currency: String,
+    pub period: ReportPeriod,
+    pub previous_period: ReportPeriod,
+    pub lines: Vec<TrialBalanceLine>,
+    pub total_debit: Comparative,
+    pub total_credit: Comparative,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ProfitAndLoss {
+    pub org_email: String,
+    pub currency: String,
+    pub period: ReportPeriod,
+    pub previous_period: ReportPeriod,
+    pub income: Vec<ReportLine>,
+    pub total_income: Comparative,
+    pub expenses: Vec<ReportLine>,
+    pub total_expenses: Comparative,
+    /// Income less expenses; negative for a loss
+    pub net_profit: Comparative,
+}
+
+/// Position at the end of the period. Profit not yet closed to equity is
+/// shown as retained earnings so the two sides agree.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BalanceSheet {
+    pub org_email: String,
+    pub currency: String,
+    pub period: ReportPeriod,
+    pub previous_period: ReportPeriod,
+    pub assets: Vec<ReportLine>,
+    pub total_assets: Comparative,
+    pub liabilities: Vec<ReportLine>,
+    pub total_liabilities: Comparative,
+    pub equity: Vec<ReportLine>,
+    pub retained_earnings: Comparative,
+    pub total_equity: Comparative,
+    pub total_liabilities_and_equity: Comparative,
+}
+
+fn header(
+    csv: &mut CsvWriter,
+    title: &str,
+    currency: &str,
+    period: &ReportPeriod,
+    previous: &ReportPeriod,
+) {
+    csv.row(&[title, currency]);
+    csv.row(&["Period", period.label.as_str()]);
+    csv.row(&["Previous period", previous.label.as_str()]);
+    csv.blank();
+}
+
+fn section(csv: &mut CsvWriter, title: &str, lines: &[ReportLine], total: &Comparative) {
+    csv.row(&["Code", title, "Current", "Previous"]);
+    for line in lines {
+        csv.row(&[
+            line.account_code.clone(),
+            line.account_name.clone(),
+            line.amount.current.to_string(),
+            line.amount.previous.to_string(),
+        ]);
+    }
+    csv.row(&[
+        String::new(),
+        format!("Total {}", title.to_lowercase()),
+        total.current.to_string(),
+        total.previous.to_string(),
+    ]);
+    csv.blank();
+}
+
+impl TrialBalance {
+    pub fn to_csv(&self) -> String {
+        let mut csv = CsvWriter::new();
+        header(&mut csv, "Trial Balance", &self.currency, &self.period, &self.previous_period);
+        csv.row(&["Code", "Account", "Type", "Debit", "Credit", "Previous Debit", "Previous Credit"]);
+        for line in &self.lines {
+            csv.row(&[
+                line.account_code.clone(),
+                line.account_name.clone(),
+                format!("{:?}", line.account_type),
+                line.debit.current.to_string(),
+                line.credit.current.to_string(),
+                line.debit.previous.to_string(),
+                line.credit.previous.to_string(),
+            ]);
+        }
+        csv.row(&[
+            String::new(),
+            "Total".to_string(),
+            String::new(),
+            self.total_debit.current.to_string(),
+            self.total_credit.current.to_string(),
+            self.total_debit.previous.to_string(),
+            self.total_credit.previous.to_string(),
+        ]);
+        csv.finish()
+    }
+}
+
+impl ProfitAndLoss {
+    pub fn to_csv(&self) -> String {
+        let mut csv = CsvWriter::new();
+        header(&mut csv, "Profit and Loss", &self.currency, &self.period, &self.previous_period);
+        section(&mut csv, "Income", &self.income, &self.total_income);
+        section(&mut csv, "Expenses", &self.expenses, &self.total_expenses);
+        csv.row(&[
+            String::new(),
+            "Net profit".to_string(),
+            self.net_profit.current.to_string(),
+            self.net_profit.previous.to_string(),
+        ]);
+        csv.finish()
+    }
+}
+
+impl BalanceSheet {
+    pub fn to_csv(&self) -> String {
+        let mut csv = CsvWriter::new();
+        header(&mut csv, "Balance Sheet", &self.currency, &self.period, &self.previous_period);
+        section(&mut csv, "Assets", &self.assets, &self.total_assets);
+        section(&mut csv, "Liabilities", &self.liabilities, &self.total_liabilities);
+        csv.row(&["Code", "Equity", "Current", "Previous"]);
+        for line in &self.equity {
+            csv.row(&[
+                line.account_code.clone(),
+                line.account_name.clone(),
+                line.amount.current.to_string(),
+                line.amount.previous.to_string(),
+            ]);
+        }
+        csv.row(&[
+            String::new(),
+            "Retained earnings".to_string(),
+            self.retained_earnings.current.to_string(),
+            self.retained_earnings.previous.to_string(),
+        ]);
+        csv.row(&[
+            String::new(),
+            "Total equity".to_string(),
+            self.total_equity.current.to_string(),
+            self.total_equity.previous.to_string(),
+        ]);
+        csv.blank();
+        csv.row(&[
+            String::new(),
+            "Total liabilities and equity".to_string(),
+            self.total_liabilities_and_equity.current.to_string(),
+            self.total_liabilities_and_equity.previous.to_string(),
+        ]);
+        csv.finish()
+    }
+}
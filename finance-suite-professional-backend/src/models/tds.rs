@@ -0,0 +1,470 @@
+//! Tax deducted at source under the Income Tax Act: the sections an
+//! organisation deducts under, and the quarterly summary built from the
+//! payments it makes and receives.
+
+use chrono::{Datelike, NaiveDate};
+use mongodb::bson::{oid::ObjectId, DateTime};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+use super::money::Money;
+
+/// Rate under section 206AA when the deductee has not furnished a PAN
+pub const NO_PAN_RATE: Decimal = Decimal::from_parts(20, 0, 0, false, 0);
+
+/// A deduction section configured on the organisation (`tdsSections`)
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TdsSection {
+    /// e.g. "194C", "194J(b)"
+    pub section: String,
+
+    #[serde(default)]
+    pub description: String,
+
+    /// Percent deducted when the deductee has a PAN
+    pub rate: String,
+
+    /// Percent deducted without a PAN; 20 when blank
+    #[serde(rename = "rateNoPan", default)]
+    pub rate_no_pan: String,
+
+    /// Single payments above this are always subject to TDS; zero for none
+    #[serde(rename = "singleLimit", default)]
+    pub single_limit: Money,
+
+    /// TDS applies once the year's payments to a deductee exceed this; zero
+    /// for none
+    #[serde(rename = "annualLimit", default)]
+    pub annual_limit: Money,
+}
+
+impl TdsSection {
+    fn new(section: &str, description: &str, rate: &str, single: i64, annual: i64) -> Self {
+        Self {
+            section: section.to_string(),
+            description: description.to_string(),
+            rate: rate.to_string(),
+            rate_no_pan: String::new(),
+            single_limit: Money::new(Decimal::from(single)),
+            annual_limit: Money::new(Decimal::from(annual)),
+        }
+    }
+
+    /// Percent to deduct from a deductee with or without a PAN
+    pub fn deduction_rate(&self, has_pan: bool) -> Result<Decimal, String> {
+        let parse = |value: &str| {
+            Decimal::from_str(value.trim())
+                .ok()
+                .filter(|rate| !rate.is_sign_negative() && *rate <= Decimal::ONE_HUNDRED)
+                .ok_or_else(|| {
+                    format!("Section {} has an invalid TDS rate '{}'", self.section, value)
+                })
+        };
+        let rate = parse(&self.rate)?;
+        if has_pan {
+            return Ok(rate);
+        }
+        if self.rate_no_pan.trim().is_empty() {
+            Ok(rate.max(NO_PAN_RATE))
+        } else {
+            parse(&self.rate_no_pan)
+        }
+    }
+
+    /// Whether a payment of `amount` is subject to TDS when the deductee has
+    /// been paid `year_to_date` in the financial year, this payment included
+    pub fn applies(&self, amount: Money, year_to_date: Money) -> bool {
+        if self.single_limit.is_zero() && self.annual_limit.is_zero() {
+            return true;
+        }
+        (!self.single_limit.is_zero() && amount > self.single_limit)
+            || (!self.annual_limit.is_zero() && year_to_date > self.annual_limit)
+    }
+}
+
+/// Sections used when the organisation has not configured its own, with the
+/// rates and limits in force from 1 April 2025
+pub fn default_tds_sections() -> Vec<TdsSection> {
+    vec![
+        TdsSection::new("194C", "Payments to contractors", "2", 30_000, 100_000),
+        TdsSection::new("194H", "Commission or brokerage", "2", 0, 20_000),
+        TdsSection::new("194I(b)", "Rent of land, building or furniture", "10", 50_000, 0),
+        TdsSection::new("194J(a)", "Fees for technical services", "2", 0, 50_000),
+        TdsSection::new("194J(b)", "Fees for professional services", "10", 0, 50_000),
+    ]
+}
+
+/// Quarter of a financial year, the period TDS returns are filed for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TdsQuarter {
+    /// Calendar year the financial year starts in (2025 for 2025-26)
+    pub start_year: i32,
+    /// 1 (April to June) to 4 (January to March)
+    pub quarter: u32,
+}
+
+impl TdsQuarter {
+    /// Parse a financial year such as "2025-26" and a quarter "Q1".."Q4" or "1".."4"
+    pub fn parse(financial_year: &str, quarter: &str) -> Result<Self, String> {
+        let financial_year = financial_year.trim();
+        let invalid_year =
+            || format!("Invalid financial year '{}'; expected e.g. 2025-26", financial_year);
+        let (start, end) = financial_year.split_once('-').ok_or_else(invalid_year)?;
+        let start_year: i32 = start.parse().map_err(|_| invalid_year())?;
+        let end_year: i32 = end.parse().map_err(|_| invalid_year())?;
+        if start.len() != 4 || (end_year != (start_year + 1) % 100 && end_year != start_year + 1) {
+            return Err(invalid_year());
+        }
+
+        let quarter_value = quarter.trim().trim_start_matches(['Q', 'q']);
+        let quarter = quarter_value
+            .parse::<u32>()
+            .ok()
+            .filter(|q| (1..=4).contains(q))
+            .ok_or_else(|| format!("Invalid quarter '{}'; expected Q1 to Q4", quarter))?;
+        Ok(Self {
+            start_year,
+            quarter,
+        })
+    }
+
+    /// Quarter a date falls in
+    pub fn containing(date: NaiveDate) -> Self {
+        let (start_year, quarter) = match date.month() {
+            4..=6 => (date.year(), 1),
+            7..=9 => (date.year(), 2),
+            10..=12 => (date.year(), 3),
+            _ => (date.year() - 1, 4),
+        };
+        Self {
+            start_year,
+            quarter,
+        }
+    }
+
+    pub fn financial_year(&self) -> String {
+        format!("{}-{:02}", self.start_year, (self.start_year + 1) % 100)
+    }
+
+    pub fn label(&self) -> String {
+        format!("Q{}", self.quarter)
+    }
+
+    pub fn first_day(&self) -> NaiveDate {
+        let (year, month) = match self.quarter {
+            1 => (self.start_year, 4),
+            2 => (self.start_year, 7),
+            3 => (self.start_year, 10),
+            _ => (self.start_year + 1, 1),
+        };
+        NaiveDate::from_ymd_opt(year, month, 1).expect("valid quarter start")
+    }
+
+    pub fn last_day(&self) -> NaiveDate {
+        let next = if self.quarter == 4 {
+            NaiveDate::from_ymd_opt(self.start_year + 1, 4, 1).expect("valid year start")
+        } else {
+            Self {
+                start_year: self.start_year,
+                quarter: self.quarter + 1,
+            }
+            .first_day()
+        };
+        next - chrono::Duration::days(1)
+    }
+
+    /// First day of the financial year the quarter belongs to
+    pub fn year_start(&self) -> NaiveDate {
+        NaiveDate::from_ymd_opt(self.start_year, 4, 1).expect("valid year start")
+    }
+}
+
+/// TDS we deducted from one deductee under one section in the quarter
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TdsDeducteeSummary {
+    pub deductee_name: String,
+    /// Blank when the vendor has no PAN on file
+    pub pan: String,
+    pub section: String,
+    pub payments: usize,
+    /// Amount paid or credited that TDS was worked out on
+    pub amount_paid: Money,
+    pub tds_deducted: Money,
+}
+
+/// TDS a customer deducted from payments made to us under one section
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TdsDeductorSummary {
+    pub customer_name: String,
+    pub section: String,
+    pub payments: usize,
+    /// Invoice value settled, TDS included
+    pub amount_received: Money,
+    pub tds_deducted: Money,
+}
+
+/// Quarterly TDS position of an organisation
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TdsSummary {
+    pub org_email: String,
+    /// Deductor TAN; needed to file Form 26Q
+    pub tan: String,
+    pub financial_year: String,
+    pub quarter: String,
+    pub from: String,
+    pub to: String,
+    /// Payables: TDS we deducted, per deductee PAN and section
+    pub deducted: Vec<TdsDeducteeSummary>,
+    /// Receivables: TDS customers deducted from us, per customer and section
+    pub received: Vec<TdsDeductorSummary>,
+    pub total_deducted: Money,
+    pub total_received: Money,
+    /// Payments that will need attention when filing
+    pub issues: Vec<String>,
+}
+
+/// Challan (ITNS 281) a quarter's TDS was deposited with
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TdsChallan {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+
+    #[serde(default)]
+    pub org_email: String,
+
+    /// Quarter the deposited TDS belongs to, e.g. "2025-26" and "Q1"
+    pub financial_year: String,
+    pub quarter: String,
+
+    /// BSR code of the bank branch, 7 digits
+    pub bsr_code: String,
+
+    /// Challan serial number, up to 5 digits
+    pub challan_serial_no: String,
+
+    /// YYYY-MM-DD
+    pub deposit_date: String,
+
+    #[serde(default)]
+    pub income_tax: Money,
+
+    #[serde(default)]
+    pub surcharge: Money,
+
+    #[serde(default)]
+    pub cess: Money,
+
+    #[serde(default)]
+    pub interest: Money,
+
+    /// Late filing fee under section 234E
+    #[serde(default)]
+    pub fee: Money,
+
+    #[serde(default)]
+    pub others: Money,
+
+    /// "200" for TDS payable by the deductor, "400" on regular assessment
+    #[serde(default = "default_minor_head")]
+    pub minor_head: String,
+
+    /// Bill payments whose TDS this challan deposits
+    #[serde(default)]
+    pub payment_ids: Vec<String>,
+
+    #[serde(default)]
+    pub created_at: Option<DateTime>,
+}
+
+fn default_minor_head() -> String {
+    "200".to_string()
+}
+
+/// Create Challan Request DTO
+pub type TdsChallanRequest = TdsChallan;
+
+impl TdsChallan {
+    /// Tax deposited against deductions: income tax, surcharge and cess
+    pub fn tax_deposited(&self) -> Money {
+        self.income_tax + self.surcharge + self.cess
+    }
+
+    /// Everything paid with the challan
+    pub fn total_deposit(&self) -> Money {
+        self.tax_deposited() + self.interest + self.fee + self.others
+    }
+}
+
+/// Details Form 26Q needs that are not kept on the organisation
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Form26QRequest {
+    /// e.g. "2025-26"
+    pub financial_year: String,
+
+    /// "Q1" to "Q4"
+    pub quarter: String,
+
+    /// NSDL deductor category, e.g. "K" company, "F" firm; "K" when blank
+    #[serde(default)]
+    pub deductor_type: String,
+
+    /// Defaults to the PAN inside the organisation GSTIN
+    #[serde(default)]
+    pub deductor_pan: String,
+
+    #[serde(default)]
+    pub branch: String,
+
+    /// Person responsible for deducting tax
+    pub responsible_name: String,
+
+    pub responsible_designation: String,
+
+    #[serde(default)]
+    pub responsible_pan: String,
+
+    #[serde(default)]
+    pub responsible_mobile: String,
+
+    /// Defaults to the organisation email
+    #[serde(default)]
+    pub responsible_email: String,
+
+    /// Deductor or responsible person address changed since the last return
+    #[serde(default)]
+    pub address_changed: bool,
+}
+
+/// Form 26Q statement with the problems the FVU would reject it for
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Form26QReturn {
+    pub file_name: String,
+    /// FVU input file: one caret-separated record per line
+    pub text: String,
+    pub challans: usize,
+    pub deductees: usize,
+    pub total_deducted: Money,
+    pub total_deposited: Money,
+    /// Empty when the statement is ready to export
+    pub errors: Vec<String>,
+    /// Points worth checking that do not block the export
+    pub warnings: Vec<String>,
+}
+
+/// TDS a deductor reported against our PAN, read from Form 26AS or the AIS
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TdsCreditEntry {
+    pub deductor_name: String,
+    pub tan: String,
+    pub section: String,
+    /// Date the deductor paid or credited us, YYYY-MM-DD
+    pub transaction_date: String,
+    /// 26AS booking status: F final, U unmatched, P provisional, O overbooked;
+    /// blank when the source does not say
+    pub status: String,
+    pub amount_paid: Money,
+    pub tds_deducted: Money,
+    pub tds_deposited: Money,
+}
+
+/// One TDS row of the AIS JSON export. Amounts may be numbers or strings.
+#[derive(Debug, Deserialize, Clone)]
+pub struct AisTdsRow {
+    #[serde(alias = "TAN", alias = "tanOfDeductor", alias = "deductorTan")]
+    pub tan: String,
+
+    #[serde(
+        default,
+        alias = "deductorName",
+        alias = "nameOfDeductor",
+        alias = "informationSource"
+    )]
+    pub deductor_name: String,
+
+    /// "194J" or an AIS information code such as "TDS-194J"
+    #[serde(default, alias = "informationCode")]
+    pub section: String,
+
+    /// YYYY-MM-DD or DD/MM/YYYY
+    #[serde(alias = "transactionDate", alias = "dateOfPaymentCredit", alias = "date")]
+    pub transaction_date: String,
+
+    #[serde(default)]
+    pub status: String,
+
+    #[serde(default, alias = "amountPaid", alias = "amountPaidCredited")]
+    pub amount_paid: Money,
+
+    #[serde(alias = "tdsDeducted", alias = "taxDeducted")]
+    pub tds_deducted: Money,
+
+    /// Same as the TDS deducted when absent
+    #[serde(default, alias = "tdsDeposited", alias = "taxDeposited")]
+    pub tds_deposited: Option<Money>,
+}
+
+/// AIS JSON: a bare list of TDS rows or an object holding them
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum AisTdsFile {
+    Rows(Vec<AisTdsRow>),
+    Wrapped {
+        #[serde(alias = "TDS", alias = "tdsDetails")]
+        tds: Vec<AisTdsRow>,
+    },
+}
+
+/// Customer receipt we recorded TDS on
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BookedTdsCredit {
+    pub payment_id: String,
+    pub customer_name: String,
+    pub payment_date: String,
+    pub section: String,
+    /// Invoice value settled, TDS included
+    pub amount_received: Money,
+    pub tds_deducted: Money,
+}
+
+/// A 26AS credit paired with the receipt it belongs to
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TdsCreditMatch {
+    pub credit: TdsCreditEntry,
+    pub books: BookedTdsCredit,
+    /// Empty for an exact match
+    pub differences: Vec<String>,
+}
+
+/// 26AS credits and booked TDS of one customer (or one deductor TAN that is
+/// not on any customer)
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CustomerTdsCredits {
+    /// None when the TAN is not recorded on any customer
+    pub customer_id: Option<String>,
+    pub customer_name: String,
+    pub tan: String,
+    pub matched: Vec<TdsCreditMatch>,
+    /// Paired on customer and period, but the amounts or section differ
+    pub mismatched: Vec<TdsCreditMatch>,
+    /// Credits in 26AS with no receipt recorded against them
+    pub unclaimed: Vec<TdsCreditEntry>,
+    /// TDS we booked that the customer has not reported or deposited
+    pub not_in_26as: Vec<BookedTdsCredit>,
+    pub credit_in_26as: Money,
+    pub tds_in_books: Money,
+}
+
+/// Form 26AS / AIS credits matched against TDS on customer receipts
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TdsCreditReconciliation {
+    pub org_email: String,
+    pub financial_year: String,
+    /// "26AS text" or "AIS JSON"
+    pub source: String,
+    pub customers: Vec<CustomerTdsCredits>,
+    pub credit_in_26as: Money,
+    pub tds_in_books: Money,
+    /// Rows skipped or needing attention, e.g. other years or unbooked status
+    pub issues: Vec<String>,
+}
@@ -0,0 +1,207 @@
+//! User accounts, the role each holds in its organisation, and the tokens
+//! issued when they sign in.
+
+use mongodb::bson::{oid::ObjectId, DateTime};
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+/// Role a user holds in their organisation; one per column of the
+/// organisation's `Permissions` matrix
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum Role {
+    Admin,
+    Manager,
+    Accountant,
+    Viewer,
+    Custom,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct User {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+
+    pub name: String,
+
+    /// Sign-in name; stored lower-case
+    pub email: String,
+
+    /// Argon2 PHC string; clients get a `UserProfile` instead
+    #[serde(rename = "passwordHash")]
+    pub password_hash: String,
+
+    /// Email of the organisation the user belongs to
+    #[serde(rename = "orgEmail")]
+    pub org_email: String,
+
+    pub role: Role,
+
+    #[serde(rename = "isActive", default = "default_active")]
+    pub is_active: bool,
+
+    #[serde(rename = "lastLoginAt", default)]
+    pub last_login_at: Option<DateTime>,
+
+    #[serde(rename = "createdAt", default)]
+    pub created_at: Option<DateTime>,
+
+    #[serde(rename = "updatedAt", default)]
+    pub updated_at: Option<DateTime>,
+}
+
+fn default_active() -> bool {
+    true
+}
+
+/// User as sent to clients, without the password hash
+#[derive(Debug, Serialize, Clone)]
+pub struct UserProfile {
+    pub id: String,
+    pub name: String,
+    pub email: String,
+    #[serde(rename = "orgEmail")]
+    pub org_email: String,
+    pub role: Role,
+    #[serde(rename = "isActive")]
+    pub is_active: bool,
+    #[serde(rename = "lastLoginAt")]
+    pub last_login_at: Option<DateTime>,
+    #[serde(rename = "createdAt")]
+    pub created_at: Option<DateTime>,
+}
+
+impl From<&User> for UserProfile {
+    fn from(user: &User) -> Self {
+        Self {
+            id: user.id.map(|id| id.to_hex()).unwrap_or_default(),
+            name: user.name.clone(),
+            email: user.email.clone(),
+            org_email: user.org_email.clone(),
+            role: user.role,
+            is_active: user.is_active,
+            last_login_at: user.last_login_at,
+            created_at: user.created_at,
+        }
+    }
+}
+
+/// First user of an organisation signing up; becomes its administrator
+#[derive(Debug, Deserialize, Validate)]
+pub struct RegisterRequest {
+    #[validate(length(min = 1, message = "Name is required"))]
+    pub name: String,
+
+    #[validate(email(message = "Invalid email format"))]
+    pub email: String,
+
+    #[validate(length(min = 8, message = "Password must be at least 8 characters"))]
+    pub password: String,
+
+    #[serde(rename = "orgEmail")]
+    #[validate(email(message = "Invalid organisation email"))]
+    pub org_email: String,
+}
+
+/// User added to the organisation by an administrator
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateUserRequest {
+    #[validate(length(min = 1, message = "Name is required"))]
+    pub name: String,
+
+    #[validate(email(message = "Invalid email format"))]
+    pub email: String,
+
+    #[validate(length(min = 8, message = "Password must be at least 8 characters"))]
+    pub password: String,
+
+    pub role: Role,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateUserRequest {
+    #[validate(length(min = 1, message = "Name cannot be empty"))]
+    pub name: Option<String>,
+
+    pub role: Option<Role>,
+
+    #[serde(rename = "isActive")]
+    pub is_active: Option<bool>,
+
+    /// Reset the user's password
+    #[validate(length(min = 8, message = "Password must be at least 8 characters"))]
+    pub password: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LoginRequest {
+    pub email: String,
+    pub password: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RefreshRequest {
+    #[serde(rename = "refreshToken")]
+    pub refresh_token: String,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct ChangePasswordRequest {
+    #[serde(rename = "currentPassword")]
+    pub current_password: String,
+
+    #[serde(rename = "newPassword")]
+    #[validate(length(min = 8, message = "Password must be at least 8 characters"))]
+    pub new_password: String,
+}
+
+/// Issued on register, login and refresh
+#[derive(Debug, Serialize)]
+pub struct TokenResponse {
+    #[serde(rename = "accessToken")]
+    pub access_token: String,
+
+    #[serde(rename = "refreshToken")]
+    pub refresh_token: String,
+
+    #[serde(rename = "tokenType")]
+    pub token_type: &'static str,
+
+    /// Seconds until the access token expires
+    #[serde(rename = "expiresIn")]
+    pub expires_in: i64,
+
+    pub user: UserProfile,
+}
+
+/// Claims carried by an access token
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Claims {
+    /// User id
+    pub sub: String,
+    pub email: String,
+    pub org_email: String,
+    pub role: Role,
+    pub iat: i64,
+    pub exp: i64,
+}
+
+/// Server-side record of a refresh token. Only its SHA-256 is stored, so a
+/// leaked collection cannot be replayed.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RefreshToken {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+
+    pub user_id: String,
+
+    pub token_hash: String,
+
+    pub expires_at: DateTime,
+
+    /// Set on logout, and when the token is exchanged for a new one
+    #[serde(default)]
+    pub revoked_at: Option<DateTime>,
+
+    #[serde(default)]
+    pub created_at: Option<DateTime>,
+}
@@ -0,0 +1,94 @@
+use mongodb::bson::{oid::ObjectId, DateTime};
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+use super::address::Address;
+
+/// Supplier we buy from
+#[derive(Debug, Serialize, Deserialize, Clone, Validate)]
+pub struct Vendor {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+
+    #[validate(length(min = 1, message = "Vendor name is required"))]
+    #[serde(rename = "vendorName")]
+    pub vendor_name: String,
+
+    #[serde(rename = "companyName", default)]
+    pub company_name: String,
+
+    /// Blank for unregistered vendors
+    #[serde(rename = "gstIN", default)]
+    pub gst_in: String,
+
+    #[validate(regex(path = "crate::utils::validation::PAN_REGEX", message = "Invalid PAN"))]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pan: Option<String>,
+
+    #[serde(default)]
+    pub addresses: Vec<Address>,
+
+    #[serde(default)]
+    pub country: String,
+
+    #[serde(rename = "countryCode", default)]
+    pub country_code: String,
+
+    #[serde(default)]
+    pub phone: String,
+
+    #[validate(email(message = "Invalid email format"))]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+
+    /// Income-tax section TDS is deducted under, e.g. "194C", "194J"
+    #[serde(rename = "tdsSection", default)]
+    pub tds_section: String,
+
+    // -------- Bank details --------
+    #[serde(rename = "accountHolder", default)]
+    pub account_holder: String,
+
+    #[serde(rename = "bankName", default)]
+    pub bank_name: String,
+
+    #[serde(rename = "accountNumber", default)]
+    pub account_number: String,
+
+    #[serde(rename = "ifscCode", default)]
+    pub ifsc_code: String,
+
+    #[serde(rename = "upiId", default)]
+    pub upi_id: String,
+
+    /// Days from bill date to due date
+    #[serde(rename = "paymentTerms", default)]
+    pub payment_terms: u32,
+
+    #[serde(rename = "isActive", default = "default_active")]
+    pub is_active: bool,
+
+    /// Organisation the vendor supplies; set by the server
+    #[serde(default)]
+    pub org_email: String,
+
+    #[serde(rename = "createdAt", default)]
+    pub created_at: Option<DateTime>,
+
+    #[serde(rename = "updatedAt", default)]
+    pub updated_at: Option<DateTime>,
+}
+
+fn default_active() -> bool {
+    true
+}
+
+/// Create / Update Vendor Request DTO
+pub type VendorRequest = Vendor;
+
+impl Vendor {
+    /// Two-digit GST state code taken from the vendor GSTIN
+    pub fn state_code(&self) -> Option<&str> {
+        self.gst_in.get(0..2).filter(|c| c.chars().all(|ch| ch.is_ascii_digit()))
+    }
+}
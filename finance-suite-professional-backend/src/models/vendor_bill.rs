@@ -0,0 +1,293 @@
+use mongodb::bson::{oid::ObjectId, DateTime};
+use serde::{Deserialize, Serialize};
+
+use super::invoice::InvoiceItem;
+use super::money::{default_currency, Money};
+use super::payment::PaymentMethod;
+
+/// Payable status of a vendor bill
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BillStatus {
+    /// Recorded, nothing paid yet
+    #[default]
+    Open,
+    PartiallyPaid,
+    Paid,
+    /// Past the due date without full payment
+    Overdue,
+}
+
+/// Bill received from a vendor. Lines reuse `InvoiceItem`, so GST on the
+/// bill is input tax.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct VendorBill {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+
+    pub vendor_id: String,
+
+    // Copied from the vendor master when the bill is recorded
+    #[serde(default)]
+    pub vendor_name: String,
+
+    #[serde(default)]
+    pub vendor_gstin: String,
+
+    /// The vendor's own invoice number
+    pub bill_number: String,
+
+    pub bill_date: String,
+
+    /// Defaults to bill date + vendor payment terms
+    #[serde(default)]
+    pub due_date: String,
+
+    /// "domestic" or "import"
+    #[serde(default)]
+    pub bill_type: String,
+
+    /// Our state, normally; decides CGST/SGST vs IGST with the vendor's state
+    #[serde(default)]
+    pub place_of_supply: String,
+
+    /// GST payable by us under reverse charge
+    #[serde(default)]
+    pub reverse_charge: bool,
+
+    /// Whether input tax credit can be claimed on this bill
+    #[serde(default = "default_true")]
+    pub itc_eligible: bool,
+
+    #[serde(default = "default_currency")]
+    pub currency: String,
+
+    /// Vendor purchase order this bill is matched against
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub po_id: Option<String>,
+
+    #[serde(default)]
+    pub items: Vec<InvoiceItem>,
+
+    #[serde(rename = "subTotal", default)]
+    pub sub_total: Money,
+
+    #[serde(default)]
+    pub totalcgst: Money,
+
+    #[serde(default)]
+    pub totalsgst: Money,
+
+    #[serde(default)]
+    pub totaligst: Money,
+
+    #[serde(default)]
+    pub total: Money,
+
+    /// Bill payments allocated, including TDS deducted
+    #[serde(rename = "amountPaid", default)]
+    pub amount_paid: Money,
+
+    #[serde(rename = "balanceDue", default)]
+    pub balance_due: Money,
+
+    #[serde(default)]
+    pub status: BillStatus,
+
+    #[serde(default)]
+    pub notes: String,
+
+    /// Organisation that booked the bill; empty on bills recorded before
+    /// bills were tied to an organisation
+    #[serde(default)]
+    pub org_email: String,
+
+    #[serde(default)]
+    pub created_at: Option<DateTime>,
+
+    #[serde(default)]
+    pub updated_at: Option<DateTime>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// Create / Update Vendor Bill Request DTO
+pub type VendorBillRequest = VendorBill;
+
+impl VendorBill {
+    /// Check if bill can be edited or deleted (nothing paid yet)
+    pub fn is_editable(&self) -> bool {
+        self.amount_paid.is_zero()
+    }
+
+    pub fn refresh_balance(&mut self) {
+        self.balance_due = self.total - self.amount_paid;
+    }
+
+    fn settle_status(&mut self) {
+        self.refresh_balance();
+        self.status = if self.balance_due.is_zero() || self.balance_due.is_negative() {
+            BillStatus::Paid
+        } else if !self.amount_paid.is_zero() {
+            BillStatus::PartiallyPaid
+        } else {
+            BillStatus::Open
+        };
+    }
+
+    /// Allocate part of a bill payment to this bill
+    pub fn apply_payment(&mut self, amount: Money) -> Result<(), String> {
+        if self.status == BillStatus::Paid {
+            return Err(format!("Bill {} is already paid", self.bill_number));
+        }
+        if amount.is_negative() || amount.is_zero() {
+            return Err("Allocated amount must be greater than zero".to_string());
+        }
+
+        self.refresh_balance();
+        if amount > self.balance_due {
+            return Err(format!(
+                "Allocated amount {} exceeds balance due {} on bill {}",
+                amount, self.balance_due, self.bill_number
+            ));
+        }
+
+        self.amount_paid += amount;
+        self.settle_status();
+        Ok(())
+    }
+}
+
+/// Portion of a bill payment settled against one bill
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BillPaymentAllocation {
+    pub bill_id: String,
+
+    /// Filled in by the server from the bill
+    #[serde(default)]
+    pub bill_number: String,
+
+    pub amount: Money,
+}
+
+/// Money paid to a vendor
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BillPayment {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+
+    pub vendor_id: String,
+
+    #[serde(default)]
+    pub vendor_name: String,
+
+    pub method: PaymentMethod,
+
+    /// UTR, cheque number, etc.
+    #[serde(default)]
+    pub reference: String,
+
+    pub payment_date: String,
+
+    #[serde(default = "default_currency")]
+    pub currency: String,
+
+    /// Amount actually paid out
+    pub amount: Money,
+
+    /// TDS we withheld and owe to the government. When zero and the vendor
+    /// has a TDS section, the server works it out and takes it off `amount`,
+    /// which is then read as the gross amount settled.
+    #[serde(default)]
+    pub tds_deducted: Money,
+
+    /// Section TDS was deducted under; defaults to the vendor's section
+    #[serde(default)]
+    pub tds_section: String,
+
+    /// Percent applied, filled in by the server
+    #[serde(default)]
+    pub tds_rate: String,
+
+    /// Amount TDS was worked out on: the taxable value of the bills settled,
+    /// GST excluded
+    #[serde(default)]
+    pub tds_base: Money,
+
+    /// Vendor PAN at the time of payment; blank means the higher rate applied
+    #[serde(default)]
+    pub deductee_pan: String,
+
+    /// No TDS on this payment, e.g. under a nil deduction certificate
+    #[serde(default)]
+    pub skip_tds: bool,
+
+    #[serde(default)]
+    pub allocations: Vec<BillPaymentAllocation>,
+
+    #[serde(default)]
+    pub notes: String,
+
+    /// Organisation that made the payment; taken from the bills
+    #[serde(default)]
+    pub org_email: String,
+
+    #[serde(default)]
+    pub created_at: Option<DateTime>,
+}
+
+/// Create Bill Payment Request DTO
+pub type CreateBillPaymentRequest = BillPayment;
+
+impl BillPayment {
+    /// Amount settled against bills: money paid plus TDS withheld
+    pub fn gross_amount(&self) -> Money {
+        self.amount + self.tds_deducted
+    }
+
+    pub fn allocated_amount(&self) -> Money {
+        self.allocations.iter().map(|a| a.amount).sum()
+    }
+
+    /// Validate amounts and allocations
+    pub fn validate(&self) -> Result<(), String> {
+        if self.payment_date.trim().is_empty() {
+            return Err("Payment date is required".to_string());
+        }
+        if self.amount.is_negative() || self.amount.is_zero() {
+            return Err("Amount must be greater than zero".to_string());
+        }
+        if self.tds_deducted.is_negative() {
+            return Err("TDS deducted cannot be negative".to_string());
+        }
+
+        for (idx, allocation) in self.allocations.iter().enumerate() {
+            if allocation.amount.is_negative() || allocation.amount.is_zero() {
+                return Err(format!(
+                    "Allocation {} amount must be greater than zero",
+                    idx + 1
+                ));
+            }
+            if self.allocations[..idx]
+                .iter()
+                .any(|a| a.bill_id == allocation.bill_id)
+            {
+                return Err(format!(
+                    "Bill {} is allocated more than once",
+                    allocation.bill_id
+                ));
+            }
+        }
+
+        if self.allocated_amount() > self.gross_amount() {
+            return Err(format!(
+                "Allocations total {} exceeds payment amount {} (including TDS)",
+                self.allocated_amount(),
+                self.gross_amount()
+            ));
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,52 @@
+use mongodb::bson::doc;
+use mongodb::options::{FindOptions, IndexOptions};
+use mongodb::{Collection, IndexModel};
+
+use crate::error::ApiError;
+use crate::models::ledger::Account;
+
+#[derive(Clone)]
+pub struct AccountRepository {
+    collection: Collection<Account>,
+}
+
+impl AccountRepository {
+    pub fn new(collection: Collection<Account>) -> Self {
+        Self { collection }
+    }
+
+    /// Account codes are unique per organisation
+    pub async fn ensure_indexes(&self) -> Result<(), ApiError> {
+        let index = IndexModel::builder()
+            .keys(doc! { "org_email": 1, "code": 1 })
+            .options(
+                IndexOptions::builder()
+                    .name("account_code_unique".to_string())
+                    .unique(true)
+                    .build(),
+            )
+            .build();
+        self.collection.create_index(index, None).await?;
+        Ok(())
+    }
+
+    pub async fn create(&self, mut account: Account) -> Result<Account, ApiError> {
+        let result = self.collection.insert_one(&account, None).await?;
+        account.id = result.inserted_id.as_object_id();
+        Ok(account)
+    }
+
+    pub async fn find_all(&self, org_email: &str) -> Result<Vec<Account>, ApiError> {
+        let filter = doc! { "org_email": org_email };
+        let options = FindOptions::builder().sort(doc! { "code": 1 }).build();
+
+        let mut cursor = self.collection.find(filter, options).await?;
+        let mut accounts = Vec::new();
+
+        while cursor.advance().await? {
+            accounts.push(cursor.deserialize_current()?);
+        }
+
+        Ok(accounts)
+    }
+}
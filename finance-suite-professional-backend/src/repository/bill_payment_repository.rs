@@ -0,0 +1,98 @@
+use mongodb::bson::{doc, oid::ObjectId};
+use mongodb::options::FindOptions;
+use mongodb::Collection;
+
+use crate::error::ApiError;
+use crate::models::vendor_bill::BillPayment;
+
+#[derive(Clone)]
+pub struct BillPaymentRepository {
+    collection: Collection<BillPayment>,
+}
+
+impl BillPaymentRepository {
+    pub fn new(collection: Collection<BillPayment>) -> Self {
+        Self { collection }
+    }
+
+    pub async fn create(&self, mut payment: BillPayment) -> Result<BillPayment, ApiError> {
+        let result = self.collection.insert_one(&payment, None).await?;
+        payment.id = result.inserted_id.as_object_id();
+        Ok(payment)
+    }
+
+    /// The organisation's bill payments, newest first; optionally those
+    /// allocated to one bill
+    pub async fn find_all(
+        &self,
+        org_email: &str,
+        bill_id: Option<&str>,
+    ) -> Result<Vec<BillPayment>, ApiError> {
+        let mut filter = doc! { "org_email": org_email };
+        if let Some(id) = bill_id {
+            filter.insert("allocations.bill_id", id);
+        }
+        let options = FindOptions::builder()
+            .sort(doc! { "payment_date": -1, "_id": -1 })
+            .build();
+
+        let mut cursor = self.collection.find(filter, options).await?;
+        let mut payments = Vec::new();
+
+        while cursor.advance().await? {
+            payments.push(cursor.deserialize_current()?);
+        }
+
+        Ok(payments)
+    }
+
+    /// Bill payments made by the organisation dated between `from` and `to`
+    /// (YYYY-MM-DD), including payments recorded without an organisation
+    pub async fn find_in_range(
+        &self,
+        org_email: &str,
+        from: &str,
+        to: &str,
+    ) -> Result<Vec<BillPayment>, ApiError> {
+        let filter = doc! {
+            "payment_date": { "$gte": from, "$lte": to },
+            "org_email": { "$in": [org_email, "", null] },
+        };
+        let options = FindOptions::builder()
+            .sort(doc! { "payment_date": 1, "_id": 1 })
+            .build();
+
+        let mut cursor = self.collection.find(filter, options).await?;
+        let mut payments = Vec::new();
+
+        while cursor.advance().await? {
+            payments.push(cursor.deserialize_current()?);
+        }
+
+        Ok(payments)
+    }
+
+    pub async fn find_by_id(
+        &self,
+        org_email: &str,
+        id: &str,
+    ) -> Result<Option<BillPayment>, ApiError> {
+        let object_id = ObjectId::parse_str(id)
+            .map_err(|_| ApiError::ValidationError("Invalid ID format".to_string()))?;
+
+        let filter = doc! { "_id": object_id, "org_email": org_email };
+        let payment = self.collection.find_one(filter, None).await?;
+
+        Ok(payment)
+    }
+
+    pub async fn delete(&self, org_email: &str, id: &str) -> Result<bool, ApiError> {
+        let object_id = ObjectId::parse_str(id)
+            .map_err(|_| ApiError::ValidationError("Invalid ID format".to_string()))?;
+
+        let filter = doc! { "_id": object_id, "org_email": org_email };
+        let result = self.collection.delete_one(filter, None).await?;
+
+        Ok(result.deleted_count > 0)
+    }
+}
@@ -0,0 +1,99 @@
+use mongodb::bson::{doc, oid::ObjectId};
+use mongodb::Collection;
+
+use crate::error::ApiError;
+use crate::models::catalog_item::CatalogItem;
+
+#[derive(Clone)]
+pub struct CatalogItemRepository {
+    collection: Collection<CatalogItem>,
+}
+
+impl CatalogItemRepository {
+    pub fn new(collection: Collection<CatalogItem>) -> Self {
+        Self { collection }
+    }
+
+    pub async fn create(&self, mut item: CatalogItem) -> Result<CatalogItem, ApiError> {
+        let result = self.collection.insert_one(&item, None).await?;
+        item.id = result.inserted_id.as_object_id();
+        Ok(item)
+    }
+
+    pub async fn find_all(&self, org_email: &str) -> Result<Vec<CatalogItem>, ApiError> {
+        let filter = doc! { "org_email": org_email };
+        let mut cursor = self.collection.find(filter, None).await?;
+        let mut items = Vec::new();
+
+        while cursor.advance().await? {
+            items.push(cursor.deserialize_current()?);
+        }
+
+        Ok(items)
+    }
+
+    pub async fn find_by_id(
+        &self,
+        org_email: &str,
+        id: &str,
+    ) -> Result<Option<CatalogItem>, ApiError> {
+        let object_id = ObjectId::parse_str(id)
+            .map_err(|_| ApiError::ValidationError("Invalid ID format".to_string()))?;
+
+        let filter = doc! { "_id": object_id, "org_email": org_email };
+        let item = self.collection.find_one(filter, None).await?;
+
+        Ok(item)
+    }
+
+    pub async fn find_by_name(
+        &self,
+        org_email: &str,
+        name: &str,
+    ) -> Result<Option<CatalogItem>, ApiError> {
+        let filter = doc! { "org_email": org_email, "name": name };
+        let item = self.collection.find_one(filter, None).await?;
+        Ok(item)
+    }
+
+    pub async fn replace(&self, item: &CatalogItem) -> Result<bool, ApiError> {
+        let object_id = item
+            .id
+            .ok_or_else(|| ApiError::InternalServerError("Catalogue item has no id".to_string()))?;
+
+        let filter = doc! { "_id": object_id, "org_email": &item.org_email };
+        let result = self.collection.replace_one(filter, item, None).await?;
+
+        Ok(result.matched_count > 0)
+    }
+
+    pub async fn delete(&self, org_email: &str, id: &str) -> Result<bool, ApiError> {
+        let object_id = ObjectId::parse_str(id)
+            .map_err(|_| ApiError::ValidationError("Invalid ID format".to_string()))?;
+
+        let filter = doc! { "_id": object_id, "org_email": org_email };
+        let result = self.collection.delete_one(filter, None).await?;
+
+        Ok(result.deleted_count > 0)
+    }
+
+    pub async fn search(&self, org_email: &str, query: &str) -> Result<Vec<CatalogItem>, ApiError> {
+        let filter = doc! {
+            "org_email": org_email,
+            "$or": [
+                { "name": { "$regex": query, "$options": "i" } },
+                { "hsnSac": { "$regex": query, "$options": "i" } },
+                { "description": { "$regex": query, "$options": "i" } }
+            ]
+        };
+
+        let mut cursor = self.collection.find(filter, None).await?;
+        let mut items = Vec::new();
+
+        while cursor.advance().await? {
+            items.push(cursor.deserialize_current()?);
+        }
+
+        Ok(items)
+    }
+}
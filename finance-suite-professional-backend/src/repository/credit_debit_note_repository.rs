@@ -0,0 +1,143 @@
+use mongodb::bson::{doc, oid::ObjectId, DateTime};
+use mongodb::options::{FindOneAndUpdateOptions, FindOptions, ReturnDocument};
+use mongodb::Collection;
+
+use crate::error::ApiError;
+use crate::models::credit_debit_note::{CreditDebitNote, NoteType};
+
+#[derive(Clone)]
+pub struct CreditDebitNoteRepository {
+    collection: Collection<CreditDebitNote>,
+}
+
+impl CreditDebitNoteRepository {
+    pub fn new(collection: Collection<CreditDebitNote>) -> Self {
+        Self { collection }
+    }
+
+    pub async fn create(&self, mut note: CreditDebitNote) -> Result<CreditDebitNote, ApiError> {
+        let result = self.collection.insert_one(&note, None).await?;
+        note.id = result.inserted_id.as_object_id();
+        Ok(note)
+    }
+
+    /// The organisation's notes of one type, newest first; optionally only
+    /// those against one invoice
+    pub async fn find_all(
+        &self,
+        org_email: &str,
+        note_type: NoteType,
+        invoice_id: Option<&str>,
+    ) -> Result<Vec<CreditDebitNote>, ApiError> {
+        let mut filter = doc! { "org_email": org_email, "note_type": note_type.as_str() };
+        if let Some(invoice_id) = invoice_id {
+            filter.insert("original_invoice_id", invoice_id);
+        }
+        let options = FindOptions::builder()
+            .sort(doc! { "note_date": -1, "_id": -1 })
+            .build();
+
+        let mut cursor = self.collection.find(filter, options).await?;
+        let mut notes = Vec::new();
+
+        while cursor.advance().await? {
+            notes.push(cursor.deserialize_current()?);
+        }
+
+        Ok(notes)
+    }
+
+    /// Issued notes of both types dated within `from..=to` (YYYY-MM-DD),
+    /// including notes saved before they carried an organisation
+    pub async fn find_in_range(
+        &self,
+        org_email: &str,
+        from: &str,
+        to: &str,
+    ) -> Result<Vec<CreditDebitNote>, ApiError> {
+        let filter = doc! {
+            "note_date": { "$gte": from, "$lte": to },
+            "status": { "$ne": "Cancelled" },
+            "org_email": { "$in": [org_email, "", null] },
+        };
+        let options = FindOptions::builder()
+            .sort(doc! { "note_date": 1, "note_number": 1 })
+            .build();
+
+        let mut cursor = self.collection.find(filter, options).await?;
+        let mut notes = Vec::new();
+
+        while cursor.advance().await? {
+            notes.push(cursor.deserialize_current()?);
+        }
+
+        Ok(notes)
+    }
+
+    pub async fn find_by_id(
+        &self,
+        org_email: &str,
+        note_type: NoteType,
+        id: &str,
+    ) -> Result<Option<CreditDebitNote>, ApiError> {
+        let object_id = ObjectId::parse_str(id)
+            .map_err(|_| ApiError::ValidationError("Invalid ID format".to_string()))?;
+
+        let filter = doc! {
+            "_id": object_id,
+            "org_email": org_email,
+            "note_type": note_type.as_str(),
+        };
+        let note = self.collection.find_one(filter, None).await?;
+
+        Ok(note)
+    }
+
+    /// Mark an issued note cancelled; `None` if it was already cancelled
+    pub async fn cancel(
+        &self,
+        org_email: &str,
+        note_type: NoteType,
+        id: &str,
+        reason: Option<String>,
+    ) -> Result<Option<CreditDebitNote>, ApiError> {
+        let object_id = ObjectId::parse_str(id)
+            .map_err(|_| ApiError::ValidationError("Invalid ID format".to_string()))?;
+
+        let filter = doc! {
+            "_id": object_id,
+            "org_email": org_email,
+            "note_type": note_type.as_str(),
+            "status": { "$ne": "Cancelled" },
+        };
+        let update = doc! { "$set": {
+            "status": "Cancelled",
+            "cancelled_at": DateTime::now(),
+            "cancel_reason": reason,
+        } };
+        let options = FindOneAndUpdateOptions::builder()
+            .return_document(ReturnDocument::After)
+            .build();
+        let note = self
+            .collection
+            .find_one_and_update(filter, update, options)
+            .await?;
+
+        Ok(note)
+    }
+
+    /// Put a cancelled note back in force when reversing it failed
+    pub async fn restore(&self, id: &str) -> Result<(), ApiError> {
+        let object_id = ObjectId::parse_str(id)
+            .map_err(|_| ApiError::ValidationError("Invalid ID format".to_string()))?;
+
+        let filter = doc! { "_id": object_id };
+        let update = doc! {
+            "$set": { "status": "Issued" },
+            "$unset": { "cancelled_at": "", "cancel_reason": "" },
+        };
+        self.collection.update_one(filter, update, None).await?;
+
+        Ok(())
+    }
+}
@@ -14,8 +14,12 @@ impl CustomerRepository {
         Self { collection }
     }
 
-    pub async fn create(&self, req: CreateCustomerRequest) -> Result<Customer, ApiError> {
-        let mut customer = Customer::new(req);
+    pub async fn create(
+        &self,
+        org_email: &str,
+        req: CreateCustomerRequest,
+    ) -> Result<Customer, ApiError> {
+        let mut customer = Customer::new(req, org_email);
 
         let result = self.collection.insert_one(&customer, None).await?;
 
@@ -24,8 +28,12 @@ impl CustomerRepository {
         Ok(customer)
     }
 
-    pub async fn find_by_email(&self, email: &str) -> Result<Option<Customer>, ApiError> {
-        let filter = doc! { "email": email };
+    pub async fn find_by_email(
+        &self,
+        org_email: &str,
+        email: &str,
+    ) -> Result<Option<Customer>, ApiError> {
+        let filter = doc! { "org_email": org_email, "email": email };
         let result = self
             .collection
             .find_one(filter, None)
@@ -34,8 +42,12 @@ impl CustomerRepository {
         Ok(result)
     }
 
-    pub async fn find_by_gstin(&self, gstin: &str) -> Result<Option<Customer>, ApiError> {
-        let filter = doc! { "gstIN": gstin };
+    pub async fn find_by_gstin(
+        &self,
+        org_email: &str,
+        gstin: &str,
+    ) -> Result<Option<Customer>, ApiError> {
+        let filter = doc! { "org_email": org_email, "gstIN": gstin };
         let result = self
             .collection
             .find_one(filter, None)
@@ -43,8 +55,9 @@ impl CustomerRepository {
             .map_err(|e| ApiError::DatabaseError(e.to_string()))?;
         Ok(result)
     }
-    pub async fn find_all(&self) -> Result<Vec<Customer>, ApiError> {
-        let mut cursor = self.collection.find(None, None).await?;
+    pub async fn find_all(&self, org_email: &str) -> Result<Vec<Customer>, ApiError> {
+        let filter = doc! { "org_email": org_email };
+        let mut cursor = self.collection.find(filter, None).await?;
         let mut customers = Vec::new();
 
         while cursor.advance().await? {
@@ -54,21 +67,30 @@ impl CustomerRepository {
         Ok(customers)
     }
 
-    pub async fn find_by_id(&self, id: &str) -> Result<Option<Customer>, ApiError> {
+    pub async fn find_by_id(
+        &self,
+        org_email: &str,
+        id: &str,
+    ) -> Result<Option<Customer>, ApiError> {
         let object_id = ObjectId::parse_str(id)
             .map_err(|_| ApiError::ValidationError("Invalid ID format".to_string()))?;
 
-        let filter = doc! { "_id": object_id };
+        let filter = doc! { "_id": object_id, "org_email": org_email };
         let customer = self.collection.find_one(filter, None).await?;
 
         Ok(customer)
     }
 
-    pub async fn update(&self, id: &str, req: UpdateCustomerRequest) -> Result<Customer, ApiError> {
+    pub async fn update(
+        &self,
+        org_email: &str,
+        id: &str,
+        req: UpdateCustomerRequest,
+    ) -> Result<Customer, ApiError> {
         let object_id = ObjectId::parse_str(id)
             .map_err(|_| ApiError::ValidationError("Invalid ID format".to_string()))?;
 
-        let filter = doc! { "_id": object_id };
+        let filter = doc! { "_id": object_id, "org_email": org_email };
 
         let mut update_doc = doc! {
             "$set": {
@@ -133,6 +155,12 @@ impl CustomerRepository {
                 .unwrap()
                 .insert("email", email);
         }
+        if let Some(tan) = req.tan {
+            update_doc
+                .get_document_mut("$set")
+                .unwrap()
+                .insert("tan", tan);
+        }
 
         self.collection
             .update_one(filter.clone(), update_doc, None)
@@ -147,37 +175,38 @@ impl CustomerRepository {
         Ok(updated_customer)
     }
 
-    pub async fn delete(&self, id: &str) -> Result<bool, ApiError> {
+    pub async fn delete(&self, org_email: &str, id: &str) -> Result<bool, ApiError> {
         let object_id = ObjectId::parse_str(id)
             .map_err(|_| ApiError::ValidationError("Invalid ID format".to_string()))?;
         println!("🧩 Trying to delete ObjectId: {}", object_id);
 
-        let filter = doc! { "_id": object_id };
+        let filter = doc! { "_id": object_id, "org_email": org_email };
         let result = self.collection.delete_one(filter, None).await?;
 
         Ok(result.deleted_count > 0)
     }
 
-    pub async fn delete_by_gstin(&self, gstin: &str) -> Result<bool, ApiError> {
+    pub async fn delete_by_gstin(&self, org_email: &str, gstin: &str) -> Result<bool, ApiError> {
         println!("🧩 Trying to delete customer with GSTIN: {}", gstin);
 
-        let filter = doc! { "gstIN": gstin };
+        let filter = doc! { "org_email": org_email, "gstIN": gstin };
         let result = self.collection.delete_one(filter, None).await?;
 
         Ok(result.deleted_count > 0)
     }
 
-    pub async fn delete_by_email(&self, email: &str) -> Result<bool, ApiError> {
+    pub async fn delete_by_email(&self, org_email: &str, email: &str) -> Result<bool, ApiError> {
         println!("🧩 Trying to delete customer with email: {}", email);
 
-        let filter = doc! { "email": email };
+        let filter = doc! { "org_email": org_email, "email": email };
         let result = self.collection.delete_one(filter, None).await?;
 
         Ok(result.deleted_count > 0)
     }
 
-    pub async fn search(&self, query: &str) -> Result<Vec<Customer>, ApiError> {
+    pub async fn search(&self, org_email: &str, query: &str) -> Result<Vec<Customer>, ApiError> {
         let filter = doc! {
+            "org_email": org_email,
             "$or": [
                 { "customerName": { "$regex": query, "$options": "i" } },
                 { "companyName": { "$regex": query, "$options": "i" } },
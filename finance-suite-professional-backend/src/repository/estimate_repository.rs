@@ -0,0 +1,87 @@
+use mongodb::bson::{doc, oid::ObjectId};
+use mongodb::options::FindOptions;
+use mongodb::Collection;
+
+use crate::error::ApiError;
+use crate::models::estimate::Estimate;
+
+#[derive(Clone)]
+pub struct EstimateRepository {
+    collection: Collection<Estimate>,
+}
+
+impl EstimateRepository {
+    pub fn new(collection: Collection<Estimate>) -> Self {
+        Self { collection }
+    }
+
+    pub async fn create(&self, mut estimate: Estimate) -> Result<Estimate, ApiError> {
+        let result = self.collection.insert_one(&estimate, None).await?;
+        estimate.id = result.inserted_id.as_object_id();
+        Ok(estimate)
+    }
+
+    /// Estimates of an organisation, newest first
+    pub async fn find_all(&self, org_email: &str) -> Result<Vec<Estimate>, ApiError> {
+        let options = FindOptions::builder()
+            .sort(doc! { "estimate_date": -1, "_id": -1 })
+            .build();
+        let filter = doc! { "org_email": org_email };
+        let mut cursor = self.collection.find(filter, options).await?;
+        let mut estimates = Vec::new();
+
+        while cursor.advance().await? {
+            estimates.push(cursor.deserialize_current()?);
+        }
+
+        Ok(estimates)
+    }
+
+    /// An estimate of the organisation; `None` for other organisations' estimates
+    pub async fn find_by_id(
+        &self,
+        org_email: &str,
+        id: &str,
+    ) -> Result<Option<Estimate>, ApiError> {
+        let object_id = ObjectId::parse_str(id)
+            .map_err(|_| ApiError::ValidationError("Invalid ID format".to_string()))?;
+
+        let filter = doc! { "_id": object_id, "org_email": org_email };
+        let estimate = self.collection.find_one(filter, None).await?;
+
+        Ok(estimate)
+    }
+
+    pub async fn replace(&self, estimate: &Estimate) -> Result<bool, ApiError> {
+        let object_id = estimate
+            .id
+            .ok_or_else(|| ApiError::InternalServerError("Estimate has no id".to_string()))?;
+
+        let filter = doc! { "_id": object_id, "org_email": &estimate.org_email };
+        let result = self.collection.replace_one(filter, estimate, None).await?;
+
+        Ok(result.matched_count > 0)
+    }
+
+    /// Flag draft or sent estimates whose validity date (YYYY-MM-DD) is
+    /// before `today` as expired
+    pub async fn mark_expired(&self, today: &str) -> Result<u64, ApiError> {
+        let filter = doc! {
+            "status": { "$in": ["Draft", "Sent"] },
+            "valid_until": { "$gt": "", "$lt": today },
+        };
+        let update = doc! { "$set": { "status": "Expired" } };
+        let result = self.collection.update_many(filter, update, None).await?;
+        Ok(result.modified_count)
+    }
+
+    pub async fn delete(&self, org_email: &str, id: &str) -> Result<bool, ApiError> {
+        let object_id = ObjectId::parse_str(id)
+            .map_err(|_| ApiError::ValidationError("Invalid ID format".to_string()))?;
+
+        let filter = doc! { "_id": object_id, "org_email": org_email };
+        let result = self.collection.delete_one(filter, None).await?;
+
+        Ok(result.deleted_count > 0)
+    }
+}
@@ -1,4 +1,5 @@
-use crate::models::expense::Expense;
+use crate::models::expense::{Expense, ExpenseStatus};
+use crate::models::money::{self, Money};
 use futures::TryStreamExt;
 use mongodb::{
     bson::{doc, oid::ObjectId, DateTime},
@@ -29,9 +30,10 @@ impl ExpenseRepository {
         Ok(exp)
     }
 
-    /// Get all expenses with optional pagination
+    /// Get the organisation's expenses with optional pagination
     pub async fn get_all_expenses(
         &self,
+        org_email: &str,
         page: Option<u64>,
         limit: Option<i64>,
     ) -> mongodb::error::Result<Vec<Expense>> {
@@ -48,7 +50,8 @@ impl ExpenseRepository {
             Some(FindOptions::builder().sort(doc! { "created_at": -1 }).build())
         };
 
-        let mut cursor = self.collection.find(None, options).await?;
+        let filter = doc! { "org_email": org_email };
+        let mut cursor = self.collection.find(filter, options).await?;
         let mut list = Vec::new();
 
         while let Some(expense) = cursor.try_next().await? {
@@ -61,11 +64,12 @@ impl ExpenseRepository {
     /// Get expenses filtered by project/cost center
     pub async fn get_expenses_by_project(
         &self,
+        org_email: &str,
         project_cost_center: &str,
         page: Option<u64>,
         limit: Option<i64>,
     ) -> mongodb::error::Result<Vec<Expense>> {
-        let filter = doc! { "project_cost_center": project_cost_center };
+        let filter = doc! { "org_email": org_email, "project_cost_center": project_cost_center };
 
         let options = if let (Some(p), Some(l)) = (page, limit) {
             let skip = p.saturating_sub(1) * (l as u64);
@@ -93,10 +97,12 @@ impl ExpenseRepository {
     /// Get expenses within a date range
     pub async fn get_expenses_by_date_range(
         &self,
+        org_email: &str,
         start_date: DateTime,
         end_date: DateTime,
     ) -> mongodb::error::Result<Vec<Expense>> {
         let filter = doc! {
+            "org_email": org_email,
             "created_at": {
                 "$gte": start_date,
                 "$lte": end_date
@@ -118,13 +124,17 @@ impl ExpenseRepository {
     }
 
     /// Get a single expense by ID
-    pub async fn get_expense_by_id(&self, id: &str) -> mongodb::error::Result<Option<Expense>> {
+    pub async fn get_expense_by_id(
+        &self,
+        org_email: &str,
+        id: &str,
+    ) -> mongodb::error::Result<Option<Expense>> {
         let obj = match ObjectId::parse_str(id) {
             Ok(v) => v,
             Err(_) => return Ok(None),
         };
 
-        let filter = doc! { "_id": obj };
+        let filter = doc! { "_id": obj, "org_email": org_email };
         let data = self.collection.find_one(filter, None).await?;
 
         Ok(data)
@@ -133,6 +143,7 @@ impl ExpenseRepository {
     /// Update an existing expense
     pub async fn update_expense(
         &self,
+        org_email: &str,
         id: &str,
         expense: Expense,
     ) -> mongodb::error::Result<Option<Expense>> {
@@ -141,11 +152,10 @@ impl ExpenseRepository {
             Err(_) => return Ok(None),
         };
 
-        let filter = doc! { "_id": obj };
+        let filter = doc! { "_id": obj, "org_email": org_email };
 
         // Serialize items to BSON
-        let items_bson =
-            mongodb::bson::to_bson(&expense.items).map_err(|e| mongodb::error::Error::custom(e))?;
+        let items_bson = money::to_bson(&expense.items)?;
 
         let update = doc! {
             "$set": {
@@ -153,6 +163,7 @@ impl ExpenseRepository {
                 "project_cost_center": &expense.project_cost_center,
                 "items": items_bson,
                 "total_amount": expense.total_amount,
+                "total_tax": expense.total_tax,
                 "updated_at": DateTime::now(),
             }
         };
@@ -161,47 +172,85 @@ impl ExpenseRepository {
 
         if result.modified_count > 0 || result.matched_count > 0 {
             // Fetch and return the updated document
-            self.get_expense_by_id(id).await
+            self.get_expense_by_id(org_email, id).await
         } else {
             Ok(None)
         }
     }
 
+    /// Save a status change, provided the expense is still in `expected`
+    /// status. Returns false when another request moved it first.
+    pub async fn update_status(
+        &self,
+        expense: &Expense,
+        expected: &ExpenseStatus,
+    ) -> mongodb::error::Result<bool> {
+        let Some(id) = expense.id else {
+            return Ok(false);
+        };
+
+        let filter = doc! {
+            "_id": id,
+            "org_email": &expense.org_email,
+            "status": money::to_bson(expected)?,
+        };
+        let update = doc! {
+            "$set": {
+                "status": money::to_bson(&expense.status)?,
+                "submitted_by": expense.submitted_by.clone(),
+                "approved_by": expense.approved_by.clone(),
+                "submitted_at": expense.submitted_at,
+                "reviewed_at": expense.reviewed_at,
+                "rejection_reason": expense.rejection_reason.clone(),
+                "reimbursed_at": expense.reimbursed_at,
+                "updated_at": expense.updated_at,
+            }
+        };
+
+        let result = self.collection.update_one(filter, update, None).await?;
+        Ok(result.modified_count > 0)
+    }
+
     /// Delete an expense by ID
-    pub async fn delete_expense(&self, id: &str) -> mongodb::error::Result<bool> {
+    pub async fn delete_expense(&self, org_email: &str, id: &str) -> mongodb::error::Result<bool> {
         let obj = match ObjectId::parse_str(id) {
             Ok(v) => v,
             Err(_) => return Ok(false),
         };
 
-        let filter = doc! { "_id": obj };
+        let filter = doc! { "_id": obj, "org_email": org_email };
         let result = self.collection.delete_one(filter, None).await?;
 
         Ok(result.deleted_count > 0)
     }
 
     /// Get total count of expenses (useful for pagination)
-    pub async fn count_expenses(&self) -> mongodb::error::Result<u64> {
-        self.collection.count_documents(None, None).await
+    pub async fn count_expenses(&self, org_email: &str) -> mongodb::error::Result<u64> {
+        self.collection
+            .count_documents(doc! { "org_email": org_email }, None)
+            .await
     }
 
     /// Get count of expenses by project
     pub async fn count_expenses_by_project(
         &self,
+        org_email: &str,
         project_cost_center: &str,
     ) -> mongodb::error::Result<u64> {
-        let filter = doc! { "project_cost_center": project_cost_center };
+        let filter = doc! { "org_email": org_email, "project_cost_center": project_cost_center };
         self.collection.count_documents(filter, None).await
     }
 
     /// Get total expense amount by project
     pub async fn get_total_amount_by_project(
         &self,
+        org_email: &str,
         project_cost_center: &str,
-    ) -> mongodb::error::Result<f64> {
+    ) -> mongodb::error::Result<Money> {
         let pipeline = vec![
             doc! {
                 "$match": {
+                    "org_email": org_email,
                     "project_cost_center": project_cost_center
                 }
             },
@@ -216,20 +265,22 @@ impl ExpenseRepository {
         let mut cursor = self.collection.aggregate(pipeline, None).await?;
 
         if let Some(result) = cursor.try_next().await? {
-            if let Ok(total) = result.get_f64("total") {
+            if let Some(total) = result.get("total").and_then(Money::from_bson) {
                 return Ok(total);
             }
         }
 
-        Ok(0.0)
+        Ok(Money::ZERO)
     }
 
     /// Search expenses by title (case-insensitive)
     pub async fn search_expenses_by_title(
         &self,
+        org_email: &str,
         search_term: &str,
     ) -> mongodb::error::Result<Vec<Expense>> {
         let filter = doc! {
+            "org_email": org_email,
             "expense_title": {
                 "$regex": search_term,
                 "$options": "i" // case-insensitive
@@ -251,10 +302,10 @@ impl ExpenseRepository {
     }
 
     /// Get all unique project/cost centers
-    pub async fn get_all_projects(&self) -> mongodb::error::Result<Vec<String>> {
+    pub async fn get_all_projects(&self, org_email: &str) -> mongodb::error::Result<Vec<String>> {
         let distinct_results = self
             .collection
-            .distinct("project_cost_center", None, None)
+            .distinct("project_cost_center", doc! { "org_email": org_email }, None)
             .await?;
 
         let projects: Vec<String> = distinct_results
@@ -265,28 +316,50 @@ impl ExpenseRepository {
         Ok(projects)
     }
 
+    /// Whether `filename` is a receipt on one of the organisation's expenses
+    pub async fn has_receipt(
+        &self,
+        org_email: &str,
+        filename: &str,
+    ) -> mongodb::error::Result<bool> {
+        let filter = doc! { "org_email": org_email, "items.receipt_file": filename };
+        Ok(self.collection.count_documents(filter, None).await? > 0)
+    }
+
     /// Get expense statistics summary
-    pub async fn get_expense_summary(&self) -> mongodb::error::Result<ExpenseSummary> {
-        let pipeline = vec![doc! {
-            "$group": {
-                "_id": null,
-                "total_expenses": { "$sum": 1 },
-                "total_amount": { "$sum": "$total_amount" },
-                "avg_amount": { "$avg": "$total_amount" },
-                "min_amount": { "$min": "$total_amount" },
-                "max_amount": { "$max": "$total_amount" }
-            }
-        }];
+    pub async fn get_expense_summary(
+        &self,
+        org_email: &str,
+    ) -> mongodb::error::Result<ExpenseSummary> {
+        let pipeline = vec![
+            doc! { "$match": { "org_email": org_email } },
+            doc! {
+                "$group": {
+                    "_id": null,
+                    "total_expenses": { "$sum": 1 },
+                    "total_amount": { "$sum": "$total_amount" },
+                    "avg_amount": { "$avg": "$total_amount" },
+                    "min_amount": { "$min": "$total_amount" },
+                    "max_amount": { "$max": "$total_amount" }
+                }
+            },
+        ];
 
         let mut cursor = self.collection.aggregate(pipeline, None).await?;
 
         if let Some(result) = cursor.try_next().await? {
+            let amount = |key: &str| {
+                result
+                    .get(key)
+                    .and_then(Money::from_bson)
+                    .unwrap_or_default()
+            };
             return Ok(ExpenseSummary {
                 total_expenses: result.get_i32("total_expenses").unwrap_or(0) as u32,
-                total_amount: result.get_f64("total_amount").unwrap_or(0.0),
-                avg_amount: result.get_f64("avg_amount").unwrap_or(0.0),
-                min_amount: result.get_f64("min_amount").unwrap_or(0.0),
-                max_amount: result.get_f64("max_amount").unwrap_or(0.0),
+                total_amount: amount("total_amount"),
+                avg_amount: amount("avg_amount").round_paise(),
+                min_amount: amount("min_amount"),
+                max_amount: amount("max_amount"),
             });
         }
 
@@ -298,8 +371,8 @@ impl ExpenseRepository {
 #[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
 pub struct ExpenseSummary {
     pub total_expenses: u32,
-    pub total_amount: f64,
-    pub avg_amount: f64,
-    pub min_amount: f64,
-    pub max_amount: f64,
+    pub total_amount: Money,
+    pub avg_amount: Money,
+    pub min_amount: Money,
+    pub max_amount: Money,
 }
\ No newline at end of file
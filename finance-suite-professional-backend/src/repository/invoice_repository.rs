@@ -1,12 +1,79 @@
 use std::sync::Arc;
 
 use mongodb::{
-    bson::{doc, oid::ObjectId},
-    error::Error as MongoError,
-    Collection,
+    bson::{doc, oid::ObjectId, Bson, Document},
+    error::{Error as MongoError, ErrorKind, WriteFailure},
+    options::{FindOneAndUpdateOptions, IndexOptions, ReturnDocument, UpdateModifications},
+    Collection, IndexModel,
 };
 
-use crate::models::invoice::Invoice;
+use crate::models::invoice::{Invoice, InvoiceStatus};
+use crate::models::money::Money;
+
+/// Filter on a status, including the spellings older documents carry:
+/// no status or "" for drafts and "Pending" for issued invoices
+fn status_filter(status: InvoiceStatus) -> Document {
+    let mut values = vec![mongodb::bson::to_bson(&status).unwrap_or(Bson::Null)];
+    match status {
+        InvoiceStatus::Draft => values.extend([Bson::String(String::new()), Bson::Null]),
+        InvoiceStatus::Issued => values.push(Bson::String("Pending".to_string())),
+        _ => {}
+    }
+    doc! { "status": { "$in": values } }
+}
+
+/// True when an insert was refused by a unique index, e.g. a recurring run
+/// that was already generated
+pub fn is_duplicate_key(err: &MongoError) -> bool {
+    matches!(
+        err.kind.as_ref(),
+        ErrorKind::Write(WriteFailure::WriteError(e)) if e.code == 11000
+    )
+}
+
+/// Statuses in which payments and notes change the balance, as
+/// `Invoice::can_adjust`, plus the legacy spelling of Issued
+const ADJUSTABLE: [&str; 5] = ["Issued", "Pending", "PartiallyPaid", "Paid", "Overdue"];
+
+/// Pipeline stages recomputing `balanceDue` from its parts and moving an
+/// invoice between Issued, PartiallyPaid and Paid, as `Invoice::settle_status`
+/// does; overdue invoices stay overdue until paid, and drafts, cancelled and
+/// void invoices keep their status
+fn settle_stages() -> Vec<Document> {
+    let zero = Bson::from(Money::ZERO);
+    vec![
+        doc! { "$set": { "balanceDue": { "$subtract": [
+            { "$add": ["$total", { "$ifNull": ["$debitedAmount", zero.clone()] }] },
+            { "$add": [
+                { "$ifNull": ["$creditedAmount", zero.clone()] },
+                { "$ifNull": ["$amountPaid", zero.clone()] },
+            ] },
+        ] } } },
+        doc! { "$set": { "status": { "$cond": [
+            { "$in": ["$status", ADJUSTABLE.to_vec()] },
+            { "$switch": {
+                "branches": [
+                    { "case": { "$lte": ["$balanceDue", zero.clone()] }, "then": "Paid" },
+                    { "case": { "$eq": ["$status", "Overdue"] }, "then": "Overdue" },
+                    { "case": { "$gt": ["$amountPaid", zero] }, "then": "PartiallyPaid" },
+                ],
+                "default": "Issued",
+            } },
+            "$status",
+        ] } } },
+    ]
+}
+
+/// Pipeline adding `amount` to the credited or debited total, then settling
+fn note_stages(amount: Money, credit: bool) -> Vec<Document> {
+    let field = if credit { "creditedAmount" } else { "debitedAmount" };
+    let mut stages = vec![doc! { "$set": { field: { "$add": [
+        { "$ifNull": [format!("${}", field), Bson::from(Money::ZERO)] },
+        Bson::from(amount),
+    ] } } }];
+    stages.extend(settle_stages());
+    stages
+}
 
 #[derive(Clone)]
 pub struct InvoiceRepository {
@@ -28,8 +95,12 @@ impl InvoiceRepository {
         Ok(invoice)
     }
 
-    pub async fn get_all_invoices(&self) -> Result<Vec<Invoice>, MongoError> {
-        let mut cursor = self.collection.find(None, None).await?;
+    /// Invoices raised by an organisation
+    pub async fn get_all_invoices(&self, org_email: &str) -> Result<Vec<Invoice>, MongoError> {
+        let mut cursor = self
+            .collection
+            .find(doc! { "org_email": org_email }, None)
+            .await?;
         let mut invoices = Vec::new();
 
         while let Some(doc) = cursor.try_next().await.unwrap_or(None) {
@@ -39,8 +110,10 @@ impl InvoiceRepository {
         Ok(invoices)
     }
 
+    /// An invoice of the organisation; `None` for other organisations' invoices
     pub async fn get_invoice_by_id(
         &self,
+        org_email: &str,
         id: &str,
     ) -> Result<Option<Invoice>, MongoError> {
         let oid = match ObjectId::parse_str(id) {
@@ -48,45 +121,232 @@ impl InvoiceRepository {
             Err(_) => return Ok(None),
         };
 
-        let filter = doc! { "_id": oid };
+        let filter = doc! { "_id": oid, "org_email": org_email };
         let invoice = self.collection.find_one(filter, None).await?;
         Ok(invoice)
     }
 
-    pub async fn update_invoice(
+    /// Replace an invoice of `invoice.org_email` only while it is still in
+    /// `expected` status, so a change made since it was read (an issue, a
+    /// payment) is not lost. `None` when the invoice is gone or has moved on.
+    pub async fn replace_in_status(
         &self,
         id: &str,
-        invoice: Invoice,
+        expected: InvoiceStatus,
+        mut invoice: Invoice,
     ) -> Result<Option<Invoice>, MongoError> {
         let oid = match ObjectId::parse_str(id) {
             Ok(oid) => oid,
             Err(_) => return Ok(None),
         };
+        invoice.id = Some(oid);
 
-        // Make sure we don't overwrite _id with some other value
-        let mut invoice_to_update = invoice.clone();
-        invoice_to_update.id = Some(oid);
+        let mut filter = status_filter(expected);
+        filter.insert("_id", oid);
+        filter.insert("org_email", &invoice.org_email);
+        let result = self.collection.replace_one(filter, &invoice, None).await?;
+        Ok((result.matched_count > 0).then_some(invoice))
+    }
 
-        let filter = doc! { "_id": oid };
-        let update_result = self
-            .collection
-            .replace_one(filter, invoice_to_update, None)
-            .await?;
+    /// Allocate part of a payment in one atomic update, guarded on the
+    /// invoice still accepting payments and owing at least `amount`. `None`
+    /// when the guard failed, e.g. a concurrent payment got there first.
+    pub async fn apply_payment(
+        &self,
+        id: &str,
+        amount: Money,
+    ) -> Result<Option<Invoice>, MongoError> {
+        let oid = match ObjectId::parse_str(id) {
+            Ok(oid) => oid,
+            Err(_) => return Ok(None),
+        };
+
+        let filter = doc! {
+            "_id": oid,
+            "status": { "$in": ["Issued", "Pending", "PartiallyPaid", "Overdue"] },
+            "balanceDue": { "$gte": Bson::from(amount) },
+        };
+        let mut pipeline = vec![doc! { "$set": {
+            "amountPaid": { "$add": ["$amountPaid", Bson::from(amount)] },
+        } }];
+        pipeline.extend(settle_stages());
+        self.update_returning(filter, pipeline).await
+    }
+
+    /// Undo an allocation in one atomic update when its payment is deleted
+    pub async fn reverse_payment(
+        &self,
+        id: &str,
+        amount: Money,
+    ) -> Result<Option<Invoice>, MongoError> {
+        let oid = match ObjectId::parse_str(id) {
+            Ok(oid) => oid,
+            Err(_) => return Ok(None),
+        };
+
+        let mut pipeline = vec![doc! { "$set": { "amountPaid": { "$max": [
+            { "$subtract": ["$amountPaid", Bson::from(amount)] },
+            Bson::from(Money::ZERO),
+        ] } } }];
+        pipeline.extend(settle_stages());
+        self.update_returning(doc! { "_id": oid }, pipeline).await
+    }
+
+    /// Add a credit (`credit == true`) or debit note in one atomic update,
+    /// guarded on the invoice being open to adjustment and, for credits, on
+    /// the note not exceeding the adjusted invoice value. `None` when the
+    /// guard failed.
+    pub async fn apply_note(
+        &self,
+        id: &str,
+        amount: Money,
+        credit: bool,
+    ) -> Result<Option<Invoice>, MongoError> {
+        let oid = match ObjectId::parse_str(id) {
+            Ok(oid) => oid,
+            Err(_) => return Ok(None),
+        };
 
-        if update_result.matched_count == 0 {
-            Ok(None)
-        } else {
-            self.get_invoice_by_id(id).await
+        let zero = Bson::from(Money::ZERO);
+        let mut filter = doc! { "_id": oid, "status": { "$in": ADJUSTABLE.to_vec() } };
+        if credit {
+            filter.insert(
+                "$expr",
+                doc! { "$gte": [
+                    { "$subtract": [
+                        { "$add": ["$total", { "$ifNull": ["$debitedAmount", zero.clone()] }] },
+                        { "$ifNull": ["$creditedAmount", zero.clone()] },
+                    ] },
+                    Bson::from(amount),
+                ] },
+            );
         }
+        self.update_returning(filter, note_stages(amount, credit)).await
+    }
+
+    /// Take a credit or debit note back out when it is cancelled
+    pub async fn reverse_note(
+        &self,
+        id: &str,
+        amount: Money,
+        credit: bool,
+    ) -> Result<Option<Invoice>, MongoError> {
+        let oid = match ObjectId::parse_str(id) {
+            Ok(oid) => oid,
+            Err(_) => return Ok(None),
+        };
+
+        self.update_returning(doc! { "_id": oid }, note_stages(-amount, credit))
+            .await
+    }
+
+    /// Overwrite only the given fields, leaving payment and note totals
+    /// to their own atomic updates
+    pub async fn set_fields(
+        &self,
+        id: &str,
+        fields: Document,
+    ) -> Result<Option<Invoice>, MongoError> {
+        let oid = match ObjectId::parse_str(id) {
+            Ok(oid) => oid,
+            Err(_) => return Ok(None),
+        };
+
+        self.update_returning(doc! { "_id": oid }, doc! { "$set": fields })
+            .await
+    }
+
+    async fn update_returning(
+        &self,
+        filter: Document,
+        update: impl Into<UpdateModifications>,
+    ) -> Result<Option<Invoice>, MongoError> {
+        let options = FindOneAndUpdateOptions::builder()
+            .return_document(ReturnDocument::After)
+            .build();
+        self.collection
+            .find_one_and_update(filter, update, options)
+            .await
+    }
+
+    /// Unique index so one recurring run can never produce two invoices,
+    /// even with several server instances
+    pub async fn ensure_indexes(&self) -> Result<(), MongoError> {
+        let index = IndexModel::builder()
+            .keys(doc! { "recurring_id": 1, "recurring_run_date": 1 })
+            .options(
+                IndexOptions::builder()
+                    .name("recurring_run_unique".to_string())
+                    .unique(true)
+                    .partial_filter_expression(doc! { "recurring_id": { "$exists": true } })
+                    .build(),
+            )
+            .build();
+        self.collection.create_index(index, None).await?;
+        Ok(())
+    }
+
+    /// Invoice already generated for a recurring schedule run, if any
+    pub async fn find_recurring_run(
+        &self,
+        recurring_id: &str,
+        run_date: &str,
+    ) -> Result<Option<Invoice>, MongoError> {
+        let filter = doc! { "recurring_id": recurring_id, "recurring_run_date": run_date };
+        self.collection.find_one(filter, None).await
+    }
+
+    /// Flag issued or partially paid invoices whose due date (YYYY-MM-DD)
+    /// is before `today` as overdue. Returns the number of invoices changed.
+    pub async fn mark_overdue_invoices(&self, today: &str) -> Result<u64, MongoError> {
+        let filter = doc! {
+            "status": { "$in": ["Issued", "Pending", "PartiallyPaid"] },
+            "invoice_dueDate": { "$gt": "", "$lt": today },
+        };
+        let update = doc! { "$set": { "status": "Overdue" } };
+        let result = self.collection.update_many(filter, update, None).await?;
+        Ok(result.modified_count)
+    }
+
+    /// Invoices of an organisation dated within `from..=to` (YYYY-MM-DD).
+    /// Invoices saved before `org_email` was recorded match on the company email.
+    pub async fn find_for_period(
+        &self,
+        org_email: &str,
+        from: &str,
+        to: &str,
+    ) -> Result<Vec<Invoice>, MongoError> {
+        let filter = doc! {
+            "invoice_date": { "$gte": from, "$lte": to },
+            "$or": [
+                { "org_email": org_email },
+                { "org_email": { "$in": [null, ""] }, "company_email": org_email },
+            ],
+        };
+        let options = mongodb::options::FindOptions::builder()
+            .sort(doc! { "invoice_date": 1, "invoice_number": 1 })
+            .build();
+
+        let mut cursor = self.collection.find(filter, options).await?;
+        let mut invoices = Vec::new();
+
+        while let Some(invoice) = cursor.try_next().await? {
+            invoices.push(invoice);
+        }
+
+        Ok(invoices)
     }
 
-    pub async fn delete_invoice(&self, id: &str) -> Result<bool, MongoError> {
+    /// Delete an invoice of the organisation only while it is still a draft
+    pub async fn delete_draft(&self, org_email: &str, id: &str) -> Result<bool, MongoError> {
         let oid = match ObjectId::parse_str(id) {
             Ok(oid) => oid,
             Err(_) => return Ok(false),
         };
 
-        let filter = doc! { "_id": oid };
+        let mut filter = status_filter(InvoiceStatus::Draft);
+        filter.insert("_id", oid);
+        filter.insert("org_email", org_email);
         let result = self.collection.delete_one(filter, None).await?;
         Ok(result.deleted_count > 0)
     }
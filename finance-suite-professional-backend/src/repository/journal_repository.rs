@@ -0,0 +1,106 @@
+use mongodb::bson::{doc, Document};
+use mongodb::options::{FindOptions, IndexOptions};
+use mongodb::{Collection, IndexModel};
+
+use crate::error::ApiError;
+use crate::models::ledger::{JournalEntry, JournalSource};
+
+/// Append-only store of journal entries; there is deliberately no update
+/// or delete.
+#[derive(Clone)]
+pub struct JournalRepository {
+    collection: Collection<JournalEntry>,
+}
+
+impl JournalRepository {
+    pub fn new(collection: Collection<JournalEntry>) -> Self {
+        Self { collection }
+    }
+
+    /// A business event is posted at most once per currency
+    pub async fn ensure_indexes(&self) -> Result<(), ApiError> {
+        let index = IndexModel::builder()
+            .keys(doc! { "org_email": 1, "source": 1, "source_id": 1, "currency": 1 })
+            .options(
+                IndexOptions::builder()
+                    .name("journal_source_unique".to_string())
+                    .unique(true)
+                    .build(),
+            )
+            .build();
+        self.collection.create_index(index, None).await?;
+        Ok(())
+    }
+
+    pub async fn create(&self, mut entry: JournalEntry) -> Result<JournalEntry, ApiError> {
+        let result = self.collection.insert_one(&entry, None).await?;
+        entry.id = result.inserted_id.as_object_id();
+        Ok(entry)
+    }
+
+    /// Entries already posted for a business event
+    pub async fn find_by_source(
+        &self,
+        org_email: &str,
+        source: JournalSource,
+        source_id: &str,
+    ) -> Result<Vec<JournalEntry>, ApiError> {
+        let filter = doc! {
+            "org_email": org_email,
+            "source": source.as_str(),
+            "source_id": source_id,
+        };
+        self.collect(filter).await
+    }
+
+    /// Entries dated within `from..=to` (YYYY-MM-DD, either bound optional),
+    /// oldest first
+    pub async fn find_in_range(
+        &self,
+        org_email: &str,
+        from: Option<&str>,
+        to: Option<&str>,
+    ) -> Result<Vec<JournalEntry>, ApiError> {
+        let mut filter = doc! { "org_email": org_email };
+        let mut range = Document::new();
+        if let Some(from) = from {
+            range.insert("$gte", from);
+        }
+        if let Some(to) = to {
+            range.insert("$lte", to);
+        }
+        if !range.is_empty() {
+            filter.insert("entry_date", range);
+        }
+        self.collect(filter).await
+    }
+
+    /// Entries touching one account, oldest first
+    pub async fn find_by_account(
+        &self,
+        org_email: &str,
+        account_code: &str,
+        to: Option<&str>,
+    ) -> Result<Vec<JournalEntry>, ApiError> {
+        let mut filter = doc! { "org_email": org_email, "lines.account_code": account_code };
+        if let Some(to) = to {
+            filter.insert("entry_date", doc! { "$lte": to });
+        }
+        self.collect(filter).await
+    }
+
+    async fn collect(&self, filter: Document) -> Result<Vec<JournalEntry>, ApiError> {
+        let options = FindOptions::builder()
+            .sort(doc! { "entry_date": 1, "_id": 1 })
+            .build();
+
+        let mut cursor = self.collection.find(filter, options).await?;
+        let mut entries = Vec::new();
+
+        while cursor.advance().await? {
+            entries.push(cursor.deserialize_current()?);
+        }
+
+        Ok(entries)
+    }
+}
@@ -2,8 +2,36 @@ pub mod customer_repository;
 pub mod organisation_repository;
 pub mod invoice_repository;
 pub mod expense_repository;
+pub mod payment_repository;
+pub mod credit_debit_note_repository;
+pub mod recurring_invoice_repository;
+pub mod estimate_repository;
+pub mod purchase_order_repository;
+pub mod vendor_repository;
+pub mod vendor_bill_repository;
+pub mod bill_payment_repository;
+pub mod account_repository;
+pub mod journal_repository;
+pub mod catalog_item_repository;
+pub mod tds_challan_repository;
+pub mod user_repository;
+pub mod refresh_token_repository;
 
 pub use customer_repository::CustomerRepository;
 pub use organisation_repository::OrganisationRepository;
 pub use invoice_repository::InvoiceRepository;
 pub use expense_repository::ExpenseRepository;
+pub use payment_repository::PaymentRepository;
+pub use credit_debit_note_repository::CreditDebitNoteRepository;
+pub use recurring_invoice_repository::RecurringInvoiceRepository;
+pub use estimate_repository::EstimateRepository;
+pub use purchase_order_repository::PurchaseOrderRepository;
+pub use vendor_repository::VendorRepository;
+pub use vendor_bill_repository::VendorBillRepository;
+pub use bill_payment_repository::BillPaymentRepository;
+pub use account_repository::AccountRepository;
+pub use journal_repository::JournalRepository;
+pub use catalog_item_repository::CatalogItemRepository;
+pub use tds_challan_repository::TdsChallanRepository;
+pub use user_repository::UserRepository;
+pub use refresh_token_repository::RefreshTokenRepository;
@@ -129,6 +129,18 @@ impl OrganisationRepository{
         if let Some(starting_invoice_no) = req.starting_invoice_no {
             update_doc.get_document_mut("$set").unwrap().insert("startingInvoiceNo", starting_invoice_no);
         }
+        if let Some(credit_note_prefix) = req.credit_note_prefix {
+            update_doc.get_document_mut("$set").unwrap().insert("creditNotePrefix", credit_note_prefix);
+        }
+        if let Some(debit_note_prefix) = req.debit_note_prefix {
+            update_doc.get_document_mut("$set").unwrap().insert("debitNotePrefix", debit_note_prefix);
+        }
+        if let Some(estimate_prefix) = req.estimate_prefix {
+            update_doc.get_document_mut("$set").unwrap().insert("estimatePrefix", estimate_prefix);
+        }
+        if let Some(purchase_order_prefix) = req.purchase_order_prefix {
+            update_doc.get_document_mut("$set").unwrap().insert("purchaseOrderPrefix", purchase_order_prefix);
+        }
         if let Some(date_format) = req.date_format {
             update_doc.get_document_mut("$set").unwrap().insert("dateFormat", date_format);
         }
@@ -191,12 +203,23 @@ impl OrganisationRepository{
         if let Some(require_hsn) = req.require_hsn {
             update_doc.get_document_mut("$set").unwrap().insert("requireHSN", require_hsn);
         }
+        if let Some(turnover_band) = req.turnover_band {
+            update_doc.get_document_mut("$set").unwrap().insert("turnoverBand", turnover_band);
+        }
         if let Some(rounding_rule) = req.rounding_rule {
             update_doc.get_document_mut("$set").unwrap().insert("roundingRule", rounding_rule);
         }
         if let Some(tax_notes) = req.tax_notes {
             update_doc.get_document_mut("$set").unwrap().insert("taxNotes", tax_notes);
         }
+        if let Some(tan) = req.tan {
+            update_doc.get_document_mut("$set").unwrap().insert("tan", tan);
+        }
+        if let Some(tds_sections) = req.tds_sections {
+            let tds_sections_bson = mongodb::bson::to_bson(&tds_sections)
+                .map_err(|e| ApiError::InternalServerError(e.to_string()))?;
+            update_doc.get_document_mut("$set").unwrap().insert("tdsSections", tds_sections_bson);
+        }
 
         // Payment fields
         if let Some(enabled_methods) = req.enabled_methods {
@@ -332,4 +355,24 @@ impl OrganisationRepository{
             }
         }
     }
-}
\ No newline at end of file
+
+    /// Atomically increment and return a document sequence counter such as
+    /// `lastCreditNoteSequence`
+    pub async fn next_sequence(&self, org_email: &str, field: &str) -> Result<i32, ApiError> {
+        let filter = doc! { "email": org_email };
+        let update = doc! { "$inc": { field: 1 } };
+
+        let options = mongodb::options::FindOneAndUpdateOptions::builder()
+            .upsert(false)
+            .return_document(mongodb::options::ReturnDocument::After)
+            .build();
+
+        let collection = self.collection.clone_with_type::<mongodb::bson::Document>();
+        let result = collection.find_one_and_update(filter, update, options).await?;
+
+        match result {
+            Some(doc) => Ok(doc.get_i32(field).unwrap_or(1)),
+            None => Err(ApiError::NotFound("Organisation not found".to_string())),
+        }
+    }
+}
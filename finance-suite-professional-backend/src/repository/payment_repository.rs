@@ -0,0 +1,94 @@
+use mongodb::bson::{doc, oid::ObjectId};
+use mongodb::options::FindOptions;
+use mongodb::Collection;
+
+use crate::error::ApiError;
+use crate::models::Payment;
+
+#[derive(Clone)]
+pub struct PaymentRepository {
+    collection: Collection<Payment>,
+}
+
+impl PaymentRepository {
+    pub fn new(collection: Collection<Payment>) -> Self {
+        Self { collection }
+    }
+
+    pub async fn create(&self, mut payment: Payment) -> Result<Payment, ApiError> {
+        let result = self.collection.insert_one(&payment, None).await?;
+        payment.id = result.inserted_id.as_object_id();
+        Ok(payment)
+    }
+
+    /// The organisation's payments, newest first; optionally only those
+    /// allocated to one invoice
+    pub async fn find_all(
+        &self,
+        org_email: &str,
+        invoice_id: Option<&str>,
+    ) -> Result<Vec<Payment>, ApiError> {
+        let mut filter = doc! { "org_email": org_email };
+        if let Some(id) = invoice_id {
+            filter.insert("allocations.invoice_id", id);
+        }
+        let options = FindOptions::builder()
+            .sort(doc! { "payment_date": -1, "_id": -1 })
+            .build();
+
+        let mut cursor = self.collection.find(filter, options).await?;
+        let mut payments = Vec::new();
+
+        while cursor.advance().await? {
+            payments.push(cursor.deserialize_current()?);
+        }
+
+        Ok(payments)
+    }
+
+    /// Payments received by the organisation dated between `from` and `to`
+    /// (YYYY-MM-DD), including payments recorded without an organisation
+    pub async fn find_in_range(
+        &self,
+        org_email: &str,
+        from: &str,
+        to: &str,
+    ) -> Result<Vec<Payment>, ApiError> {
+        let filter = doc! {
+            "payment_date": { "$gte": from, "$lte": to },
+            "org_email": { "$in": [org_email, "", null] },
+        };
+        let options = FindOptions::builder()
+            .sort(doc! { "payment_date": 1, "_id": 1 })
+            .build();
+
+        let mut cursor = self.collection.find(filter, options).await?;
+        let mut payments = Vec::new();
+
+        while cursor.advance().await? {
+            payments.push(cursor.deserialize_current()?);
+        }
+
+        Ok(payments)
+    }
+
+    pub async fn find_by_id(&self, org_email: &str, id: &str) -> Result<Option<Payment>, ApiError> {
+        let object_id = ObjectId::parse_str(id)
+            .map_err(|_| ApiError::ValidationError("Invalid ID format".to_string()))?;
+
+        let filter = doc! { "_id": object_id, "org_email": org_email };
+        let payment = self.collection.find_one(filter, None).await?;
+
+        Ok(payment)
+    }
+
+    pub async fn delete(&self, org_email: &str, id: &str) -> Result<bool, ApiError> {
+        let object_id = ObjectId::parse_str(id)
+            .map_err(|_| ApiError::ValidationError("Invalid ID format".to_string()))?;
+
+        let filter = doc! { "_id": object_id, "org_email": org_email };
+        let result = self.collection.delete_one(filter, None).await?;
+
+        Ok(result.deleted_count > 0)
+    }
+}
@@ -0,0 +1,229 @@
+use mongodb::bson::{doc, oid::ObjectId, Bson, Document};
+use mongodb::options::FindOptions;
+use mongodb::Collection;
+
+use crate::error::ApiError;
+use crate::models::money::{self, Money};
+use crate::models::purchase_order::{PurchaseOrder, PurchaseOrderKind, PurchaseOrderStatus};
+
+#[derive(Clone)]
+pub struct PurchaseOrderRepository {
+    collection: Collection<PurchaseOrder>,
+}
+
+impl PurchaseOrderRepository {
+    pub fn new(collection: Collection<PurchaseOrder>) -> Self {
+        Self { collection }
+    }
+
+    pub async fn create(&self, mut po: PurchaseOrder) -> Result<PurchaseOrder, ApiError> {
+        let result = self.collection.insert_one(&po, None).await?;
+        po.id = result.inserted_id.as_object_id();
+        Ok(po)
+    }
+
+    /// POs of an organisation, newest first; optionally only one kind
+    pub async fn find_all(
+        &self,
+        org_email: &str,
+        kind: Option<PurchaseOrderKind>,
+    ) -> Result<Vec<PurchaseOrder>, ApiError> {
+        let mut filter = doc! { "org_email": org_email };
+        if let Some(kind) = kind {
+            filter.insert("kind", kind.as_str());
+        }
+        let options = FindOptions::builder()
+            .sort(doc! { "po_date": -1, "_id": -1 })
+            .build();
+
+        let mut cursor = self.collection.find(filter, options).await?;
+        let mut orders = Vec::new();
+
+        while cursor.advance().await? {
+            orders.push(cursor.deserialize_current()?);
+        }
+
+        Ok(orders)
+    }
+
+    /// A PO of the organisation; `None` for other organisations' POs
+    pub async fn find_by_id(
+        &self,
+        org_email: &str,
+        id: &str,
+    ) -> Result<Option<PurchaseOrder>, ApiError> {
+        let object_id = ObjectId::parse_str(id)
+            .map_err(|_| ApiError::ValidationError("Invalid ID format".to_string()))?;
+
+        let filter = doc! { "_id": object_id, "org_email": org_email };
+        let po = self.collection.find_one(filter, None).await?;
+
+        Ok(po)
+    }
+
+    /// The organisation's most recent PO of `kind` with this number (and
+    /// party, when given)
+    pub async fn find_by_number(
+        &self,
+        org_email: &str,
+        kind: PurchaseOrderKind,
+        po_number: &str,
+        party_name: Option<&str>,
+    ) -> Result<Option<PurchaseOrder>, ApiError> {
+        let mut filter = doc! {
+            "org_email": org_email,
+            "kind": kind.as_str(),
+            "po_number": po_number,
+        };
+        if let Some(party) = party_name.filter(|p| !p.trim().is_empty()) {
+            filter.insert("party_name", party);
+        }
+        let options = mongodb::options::FindOneOptions::builder()
+            .sort(doc! { "po_date": -1 })
+            .build();
+
+        let po = self.collection.find_one(filter, options).await?;
+        Ok(po)
+    }
+
+    /// Save edited details and lines, provided the PO is still in `expected`
+    /// status and its new value covers what has been billed. The billed value
+    /// and status are left to `bill`/`release`. Returns false when another
+    /// request changed the PO first.
+    pub async fn update_details(
+        &self,
+        po: &PurchaseOrder,
+        expected: PurchaseOrderStatus,
+    ) -> Result<bool, ApiError> {
+        let object_id = po
+            .id
+            .ok_or_else(|| ApiError::InternalServerError("Purchase order has no id".to_string()))?;
+
+        let sub_total = Bson::from(po.sub_total);
+        let filter = doc! {
+            "_id": object_id,
+            "org_email": &po.org_email,
+            "status": money::to_bson(&expected)?,
+            "$expr": { "$lte": ["$billedAmount", sub_total.clone()] },
+        };
+        let update = doc! {
+            "$set": {
+                "po_date": &po.po_date,
+                "delivery_date": &po.delivery_date,
+                "party_name": &po.party_name,
+                "party_gstin": &po.party_gstin,
+                "party_address": &po.party_address,
+                "currency": &po.currency,
+                "items": money::to_bson(&po.items)?,
+                "subTotal": sub_total,
+                "notes": &po.notes,
+                "updated_at": po.updated_at,
+            }
+        };
+
+        let result = self.collection.update_one(filter, update, None).await?;
+        Ok(result.matched_count > 0)
+    }
+
+    /// Save a status change, provided the PO is still in `expected` status.
+    /// Returns false when another request moved it first.
+    pub async fn update_status(
+        &self,
+        po: &PurchaseOrder,
+        expected: PurchaseOrderStatus,
+    ) -> Result<bool, ApiError> {
+        let object_id = po
+            .id
+            .ok_or_else(|| ApiError::InternalServerError("Purchase order has no id".to_string()))?;
+
+        let filter = doc! {
+            "_id": object_id,
+            "org_email": &po.org_email,
+            "status": money::to_bson(&expected)?,
+        };
+        let update = doc! {
+            "$set": {
+                "status": money::to_bson(&po.status)?,
+                "updated_at": po.updated_at,
+            }
+        };
+
+        let result = self.collection.update_one(filter, update, None).await?;
+        Ok(result.matched_count > 0)
+    }
+
+    /// Atomically add `amount` to the billed value, refusing to go over the
+    /// PO value. Returns false when the PO is not billable or lacks headroom.
+    pub async fn bill(&self, id: ObjectId, amount: Money) -> Result<bool, ApiError> {
+        let amount = Bson::from(amount);
+        let filter = doc! {
+            "_id": id,
+            "status": { "$in": ["Open", "PartiallyBilled"] },
+            "$expr": {
+                "$lte": [{ "$add": ["$billedAmount", amount.clone()] }, "$subTotal"]
+            },
+        };
+        let update = vec![
+            doc! { "$set": { "billedAmount": { "$add": ["$billedAmount", amount] } } },
+            Self::status_stage(),
+        ];
+
+        let result = self.collection.update_one(filter, update, None).await?;
+        Ok(result.modified_count > 0)
+    }
+
+    /// Give back `amount` of billed value, e.g. when an invoice is cancelled
+    pub async fn release(&self, id: ObjectId, amount: Money) -> Result<bool, ApiError> {
+        let filter = doc! { "_id": id };
+        let update = vec![
+            doc! {
+                "$set": {
+                    "billedAmount": {
+                        "$max": [
+                            { "$subtract": ["$billedAmount", Bson::from(amount)] },
+                            Bson::from(Money::ZERO),
+                        ]
+                    }
+                }
+            },
+            Self::status_stage(),
+        ];
+
+        let result = self.collection.update_one(filter, update, None).await?;
+        Ok(result.matched_count > 0)
+    }
+
+    /// Recompute Open / PartiallyBilled / Billed from the billed value;
+    /// Draft, Closed and Cancelled are left alone
+    fn status_stage() -> Document {
+        doc! {
+            "$set": {
+                "status": {
+                    "$cond": [
+                        { "$in": ["$status", ["Open", "PartiallyBilled", "Billed"]] },
+                        {
+                            "$switch": {
+                                "branches": [
+                                    { "case": { "$gte": ["$billedAmount", "$subTotal"] }, "then": "Billed" },
+                                    { "case": { "$gt": ["$billedAmount", 0] }, "then": "PartiallyBilled" },
+                                ],
+                                "default": "Open",
+                            }
+                        },
+                        "$status",
+                    ]
+                }
+            }
+        }
+    }
+
+    pub async fn delete(&self, org_email: &str, id: &str) -> Result<bool, ApiError> {
+        let object_id = ObjectId::parse_str(id)
+            .map_err(|_| ApiError::ValidationError("Invalid ID format".to_string()))?;
+
+        let filter = doc! { "_id": object_id, "org_email": org_email };
+        let result = self.collection.delete_one(filter, None).await?;
+
+        Ok(result.deleted_count > 0)
+    }
+}
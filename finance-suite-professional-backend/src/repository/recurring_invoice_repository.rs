@@ -0,0 +1,117 @@
+use mongodb::bson::{doc, oid::ObjectId, Bson};
+use mongodb::Collection;
+
+use crate::error::ApiError;
+use crate::models::recurring_invoice::RecurringInvoice;
+
+#[derive(Clone)]
+pub struct RecurringInvoiceRepository {
+    collection: Collection<RecurringInvoice>,
+}
+
+impl RecurringInvoiceRepository {
+    pub fn new(collection: Collection<RecurringInvoice>) -> Self {
+        Self { collection }
+    }
+
+    pub async fn create(&self, mut schedule: RecurringInvoice) -> Result<RecurringInvoice, ApiError> {
+        let result = self.collection.insert_one(&schedule, None).await?;
+        schedule.id = result.inserted_id.as_object_id();
+        Ok(schedule)
+    }
+
+    pub async fn find_all(&self, org_email: &str) -> Result<Vec<RecurringInvoice>, ApiError> {
+        let filter = doc! { "org_email": org_email };
+        let mut cursor = self.collection.find(filter, None).await?;
+        let mut schedules = Vec::new();
+
+        while cursor.advance().await? {
+            schedules.push(cursor.deserialize_current()?);
+        }
+
+        Ok(schedules)
+    }
+
+    /// Active schedules whose next run is on or before `as_of` (YYYY-MM-DD);
+    /// every organisation's when `org_email` is `None`
+    pub async fn find_due(
+        &self,
+        as_of: &str,
+        org_email: Option<&str>,
+    ) -> Result<Vec<RecurringInvoice>, ApiError> {
+        let mut filter = doc! {
+            "active": true,
+            "next_run_date": { "$ne": Bson::Null, "$lte": as_of },
+        };
+        if let Some(org_email) = org_email {
+            filter.insert("org_email", org_email);
+        }
+        let mut cursor = self.collection.find(filter, None).await?;
+        let mut schedules = Vec::new();
+
+        while cursor.advance().await? {
+            schedules.push(cursor.deserialize_current()?);
+        }
+
+        Ok(schedules)
+    }
+
+    pub async fn find_by_id(
+        &self,
+        org_email: &str,
+        id: &str,
+    ) -> Result<Option<RecurringInvoice>, ApiError> {
+        let object_id = ObjectId::parse_str(id)
+            .map_err(|_| ApiError::ValidationError("Invalid ID format".to_string()))?;
+
+        let filter = doc! { "_id": object_id, "org_email": org_email };
+        let schedule = self.collection.find_one(filter, None).await?;
+
+        Ok(schedule)
+    }
+
+    pub async fn replace(&self, schedule: &RecurringInvoice) -> Result<bool, ApiError> {
+        let object_id = schedule
+            .id
+            .ok_or_else(|| ApiError::InternalServerError("Schedule has no id".to_string()))?;
+
+        let filter = doc! { "_id": object_id, "org_email": &schedule.org_email };
+        let result = self.collection.replace_one(filter, schedule, None).await?;
+
+        Ok(result.matched_count > 0)
+    }
+
+    /// Advance the schedule past `run_date`, but only if no one else already
+    /// did. Returns false when the run had already been recorded.
+    pub async fn record_run(
+        &self,
+        id: ObjectId,
+        runs_completed: u32,
+        run_date: &str,
+        next_run_date: Option<String>,
+        invoice_id: Option<String>,
+    ) -> Result<bool, ApiError> {
+        let filter = doc! { "_id": id, "runs_completed": runs_completed as i64 };
+        let update = doc! {
+            "$set": {
+                "runs_completed": (runs_completed + 1) as i64,
+                "last_run_date": run_date,
+                "next_run_date": next_run_date,
+                "last_invoice_id": invoice_id,
+            }
+        };
+        let result = self.collection.update_one(filter, update, None).await?;
+
+        Ok(result.modified_count > 0)
+    }
+
+    pub async fn delete(&self, org_email: &str, id: &str) -> Result<bool, ApiError> {
+        let object_id = ObjectId::parse_str(id)
+            .map_err(|_| ApiError::ValidationError("Invalid ID format".to_string()))?;
+
+        let filter = doc! { "_id": object_id, "org_email": org_email };
+        let result = self.collection.delete_one(filter, None).await?;
+
+        Ok(result.deleted_count > 0)
+    }
+}
@@ -0,0 +1,45 @@
+use mongodb::bson::{doc, DateTime};
+use mongodb::Collection;
+
+use crate::error::ApiError;
+use crate::models::user::RefreshToken;
+
+#[derive(Clone)]
+pub struct RefreshTokenRepository {
+    collection: Collection<RefreshToken>,
+}
+
+impl RefreshTokenRepository {
+    pub fn new(collection: Collection<RefreshToken>) -> Self {
+        Self { collection }
+    }
+
+    pub async fn create(&self, mut token: RefreshToken) -> Result<RefreshToken, ApiError> {
+        let result = self.collection.insert_one(&token, None).await?;
+        token.id = result.inserted_id.as_object_id();
+        Ok(token)
+    }
+
+    pub async fn find_by_hash(&self, token_hash: &str) -> Result<Option<RefreshToken>, ApiError> {
+        let filter = doc! { "token_hash": token_hash };
+        let token = self.collection.find_one(filter, None).await?;
+        Ok(token)
+    }
+
+    /// Revoke one token. Returns false when it was unknown or already revoked,
+    /// so two requests racing to use the same token cannot both succeed.
+    pub async fn revoke(&self, token_hash: &str) -> Result<bool, ApiError> {
+        let filter = doc! { "token_hash": token_hash, "revoked_at": null };
+        let update = doc! { "$set": { "revoked_at": DateTime::now() } };
+        let result = self.collection.update_one(filter, update, None).await?;
+        Ok(result.modified_count > 0)
+    }
+
+    /// Sign the user out everywhere
+    pub async fn revoke_all_for_user(&self, user_id: &str) -> Result<u64, ApiError> {
+        let filter = doc! { "user_id": user_id, "revoked_at": null };
+        let update = doc! { "$set": { "revoked_at": DateTime::now() } };
+        let result = self.collection.update_many(filter, update, None).await?;
+        Ok(result.modified_count)
+    }
+}
@@ -0,0 +1,69 @@
+use mongodb::bson::{doc, oid::ObjectId};
+use mongodb::options::FindOptions;
+use mongodb::Collection;
+
+use crate::error::ApiError;
+use crate::models::tds::TdsChallan;
+
+#[derive(Clone)]
+pub struct TdsChallanRepository {
+    collection: Collection<TdsChallan>,
+}
+
+impl TdsChallanRepository {
+    pub fn new(collection: Collection<TdsChallan>) -> Self {
+        Self { collection }
+    }
+
+    pub async fn create(&self, mut challan: TdsChallan) -> Result<TdsChallan, ApiError> {
+        let result = self.collection.insert_one(&challan, None).await?;
+        challan.id = result.inserted_id.as_object_id();
+        Ok(challan)
+    }
+
+    /// Challans of the organisation for one quarter, oldest deposit first
+    pub async fn find_for_quarter(
+        &self,
+        org_email: &str,
+        financial_year: &str,
+        quarter: &str,
+    ) -> Result<Vec<TdsChallan>, ApiError> {
+        let filter = doc! {
+            "org_email": org_email,
+            "financial_year": financial_year,
+            "quarter": quarter,
+        };
+        let options = FindOptions::builder()
+            .sort(doc! { "deposit_date": 1, "_id": 1 })
+            .build();
+
+        let mut cursor = self.collection.find(filter, options).await?;
+        let mut challans = Vec::new();
+
+        while cursor.advance().await? {
+            challans.push(cursor.deserialize_current()?);
+        }
+
+        Ok(challans)
+    }
+
+    /// Challan that already deposits the TDS of a bill payment
+    pub async fn find_by_payment_id(
+        &self,
+        payment_id: &str,
+    ) -> Result<Option<TdsChallan>, ApiError> {
+        let filter = doc! { "payment_ids": payment_id };
+        let challan = self.collection.find_one(filter, None).await?;
+        Ok(challan)
+    }
+
+    pub async fn delete(&self, org_email: &str, id: &str) -> Result<bool, ApiError> {
+        let object_id = ObjectId::parse_str(id)
+            .map_err(|_| ApiError::ValidationError("Invalid ID format".to_string()))?;
+
+        let filter = doc! { "_id": object_id, "org_email": org_email };
+        let result = self.collection.delete_one(filter, None).await?;
+
+        Ok(result.deleted_count > 0)
+    }
+}
@@ -0,0 +1,85 @@
+use mongodb::bson::{doc, oid::ObjectId, DateTime};
+use mongodb::Collection;
+
+use crate::error::ApiError;
+use crate::models::user::User;
+
+#[derive(Clone)]
+pub struct UserRepository {
+    collection: Collection<User>,
+}
+
+impl UserRepository {
+    pub fn new(collection: Collection<User>) -> Self {
+        Self { collection }
+    }
+
+    pub async fn create(&self, mut user: User) -> Result<User, ApiError> {
+        let result = self.collection.insert_one(&user, None).await?;
+        user.id = result.inserted_id.as_object_id();
+        Ok(user)
+    }
+
+    pub async fn find_by_id(&self, id: &str) -> Result<Option<User>, ApiError> {
+        let object_id = ObjectId::parse_str(id)
+            .map_err(|_| ApiError::ValidationError("Invalid ID format".to_string()))?;
+
+        let filter = doc! { "_id": object_id };
+        let user = self.collection.find_one(filter, None).await?;
+
+        Ok(user)
+    }
+
+    /// Emails are stored lower-case; pass a normalised address
+    pub async fn find_by_email(&self, email: &str) -> Result<Option<User>, ApiError> {
+        let filter = doc! { "email": email };
+        let user = self.collection.find_one(filter, None).await?;
+        Ok(user)
+    }
+
+    pub async fn find_by_org(&self, org_email: &str) -> Result<Vec<User>, ApiError> {
+        let filter = doc! { "orgEmail": org_email };
+        let mut cursor = self.collection.find(filter, None).await?;
+        let mut users = Vec::new();
+
+        while cursor.advance().await? {
+            users.push(cursor.deserialize_current()?);
+        }
+
+        Ok(users)
+    }
+
+    pub async fn count_by_org(&self, org_email: &str) -> Result<u64, ApiError> {
+        let filter = doc! { "orgEmail": org_email };
+        let count = self.collection.count_documents(filter, None).await?;
+        Ok(count)
+    }
+
+    pub async fn replace(&self, user: &User) -> Result<bool, ApiError> {
+        let object_id = user
+            .id
+            .ok_or_else(|| ApiError::InternalServerError("User has no id".to_string()))?;
+
+        let filter = doc! { "_id": object_id };
+        let result = self.collection.replace_one(filter, user, None).await?;
+
+        Ok(result.matched_count > 0)
+    }
+
+    pub async fn record_login(&self, id: ObjectId) -> Result<(), ApiError> {
+        let filter = doc! { "_id": id };
+        let update = doc! { "$set": { "lastLoginAt": DateTime::now() } };
+        self.collection.update_one(filter, update, None).await?;
+        Ok(())
+    }
+
+    pub async fn delete(&self, id: &str) -> Result<bool, ApiError> {
+        let object_id = ObjectId::parse_str(id)
+            .map_err(|_| ApiError::ValidationError("Invalid ID format".to_string()))?;
+
+        let filter = doc! { "_id": object_id };
+        let result = self.collection.delete_one(filter, None).await?;
+
+        Ok(result.deleted_count > 0)
+    }
+}
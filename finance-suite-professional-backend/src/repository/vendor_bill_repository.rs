@@ -0,0 +1,211 @@
+use mongodb::bson::{doc, oid::ObjectId, Bson, Document};
+use mongodb::options::FindOptions;
+use mongodb::Collection;
+
+use crate::error::ApiError;
+use crate::models::money::Money;
+use crate::models::vendor_bill::VendorBill;
+
+/// Pipeline stages recomputing `balanceDue` and moving the bill between
+/// Open, PartiallyPaid and Paid, as `VendorBill::settle_status` does
+fn settle_stages() -> Vec<Document> {
+    let zero = Bson::from(Money::ZERO);
+    vec![
+        doc! { "$set": {
+            "balanceDue": { "$subtract": ["$total", "$amountPaid"] },
+            "updated_at": "$$NOW",
+        } },
+        doc! { "$set": { "status": { "$switch": {
+            "branches": [
+                { "case": { "$lte": ["$balanceDue", zero.clone()] }, "then": "Paid" },
+                { "case": { "$gt": ["$amountPaid", zero] }, "then": "PartiallyPaid" },
+            ],
+            "default": "Open",
+        } } } },
+    ]
+}
+
+#[derive(Clone)]
+pub struct VendorBillRepository {
+    collection: Collection<VendorBill>,
+}
+
+impl VendorBillRepository {
+    pub fn new(collection: Collection<VendorBill>) -> Self {
+        Self { collection }
+    }
+
+    pub async fn create(&self, mut bill: VendorBill) -> Result<VendorBill, ApiError> {
+        let result = self.collection.insert_one(&bill, None).await?;
+        bill.id = result.inserted_id.as_object_id();
+        Ok(bill)
+    }
+
+    /// The organisation's bills, latest first; optionally for one vendor
+    pub async fn find_all(
+        &self,
+        org_email: &str,
+        vendor_id: Option<&str>,
+    ) -> Result<Vec<VendorBill>, ApiError> {
+        let mut filter = doc! { "org_email": org_email };
+        if let Some(id) = vendor_id {
+            filter.insert("vendor_id", id);
+        }
+        let options = FindOptions::builder()
+            .sort(doc! { "bill_date": -1, "_id": -1 })
+            .build();
+
+        let mut cursor = self.collection.find(filter, options).await?;
+        let mut bills = Vec::new();
+
+        while cursor.advance().await? {
+            bills.push(cursor.deserialize_current()?);
+        }
+
+        Ok(bills)
+    }
+
+    pub async fn find_by_id(
+        &self,
+        org_email: &str,
+        id: &str,
+    ) -> Result<Option<VendorBill>, ApiError> {
+        let object_id = ObjectId::parse_str(id)
+            .map_err(|_| ApiError::ValidationError("Invalid ID format".to_string()))?;
+
+        let filter = doc! { "_id": object_id, "org_email": org_email };
+        let bill = self.collection.find_one(filter, None).await?;
+
+        Ok(bill)
+    }
+
+    /// Bills of the organisation dated between `from` and `to` (YYYY-MM-DD),
+    /// including bills recorded without an organisation
+    pub async fn find_in_range(
+        &self,
+        org_email: &str,
+        from: &str,
+        to: &str,
+    ) -> Result<Vec<VendorBill>, ApiError> {
+        let filter = doc! {
+            "bill_date": { "$gte": from, "$lte": to },
+            "org_email": { "$in": [org_email, "", null] },
+        };
+        let options = FindOptions::builder()
+            .sort(doc! { "bill_date": 1, "_id": 1 })
+            .build();
+
+        let mut cursor = self.collection.find(filter, options).await?;
+        let mut bills = Vec::new();
+
+        while cursor.advance().await? {
+            bills.push(cursor.deserialize_current()?);
+        }
+
+        Ok(bills)
+    }
+
+    /// The same vendor invoice number must not be booked twice
+    pub async fn find_by_vendor_bill_number(
+        &self,
+        org_email: &str,
+        vendor_id: &str,
+        bill_number: &str,
+    ) -> Result<Option<VendorBill>, ApiError> {
+        let filter = doc! {
+            "org_email": org_email,
+            "vendor_id": vendor_id,
+            "bill_number": bill_number,
+        };
+        let bill = self.collection.find_one(filter, None).await?;
+        Ok(bill)
+    }
+
+    /// Whether any organisation has booked a bill from the vendor
+    pub async fn exists_for_vendor(&self, vendor_id: &str) -> Result<bool, ApiError> {
+        let count = self
+            .collection
+            .count_documents(doc! { "vendor_id": vendor_id }, None)
+            .await?;
+        Ok(count > 0)
+    }
+
+    /// Replace a bill provided nothing has been paid against it yet.
+    /// Returns false when a payment was recorded since it was read.
+    pub async fn replace_unpaid(&self, bill: &VendorBill) -> Result<bool, ApiError> {
+        let object_id = bill
+            .id
+            .ok_or_else(|| ApiError::InternalServerError("Bill has no id".to_string()))?;
+
+        let filter = doc! {
+            "_id": object_id,
+            "org_email": &bill.org_email,
+            "amountPaid": Bson::from(Money::ZERO),
+        };
+        let result = self.collection.replace_one(filter, bill, None).await?;
+
+        Ok(result.matched_count > 0)
+    }
+
+    /// Allocate part of a bill payment in one atomic update, guarded on the
+    /// bill not being paid and owing at least `amount`. Returns false when
+    /// the guard failed, e.g. a concurrent payment got there first.
+    pub async fn apply_payment(&self, id: &str, amount: Money) -> Result<bool, ApiError> {
+        let object_id = ObjectId::parse_str(id)
+            .map_err(|_| ApiError::ValidationError("Invalid ID format".to_string()))?;
+
+        let filter = doc! {
+            "_id": object_id,
+            "status": { "$ne": "Paid" },
+            "balanceDue": { "$gte": Bson::from(amount) },
+        };
+        let mut pipeline = vec![doc! { "$set": {
+            "amountPaid": { "$add": ["$amountPaid", Bson::from(amount)] },
+        } }];
+        pipeline.extend(settle_stages());
+
+        let result = self.collection.update_one(filter, pipeline, None).await?;
+        Ok(result.matched_count > 0)
+    }
+
+    /// Undo an allocation in one atomic update when its payment is deleted.
+    /// Returns false when the bill no longer exists.
+    pub async fn reverse_payment(&self, id: &str, amount: Money) -> Result<bool, ApiError> {
+        let object_id = ObjectId::parse_str(id)
+            .map_err(|_| ApiError::ValidationError("Invalid ID format".to_string()))?;
+
+        let mut pipeline = vec![doc! { "$set": { "amountPaid": { "$max": [
+            { "$subtract": ["$amountPaid", Bson::from(amount)] },
+            Bson::from(Money::ZERO),
+        ] } } }];
+        pipeline.extend(settle_stages());
+
+        let result = self
+            .collection
+            .update_one(doc! { "_id": object_id }, pipeline, None)
+            .await?;
+        Ok(result.matched_count > 0)
+    }
+
+    /// Flag open or partially paid bills whose due date (YYYY-MM-DD) is
+    /// before `today` as overdue
+    pub async fn mark_overdue(&self, today: &str) -> Result<u64, ApiError> {
+        let filter = doc! {
+            "status": { "$in": ["Open", "PartiallyPaid"] },
+            "due_date": { "$gt": "", "$lt": today },
+        };
+        let update = doc! { "$set": { "status": "Overdue" } };
+        let result = self.collection.update_many(filter, update, None).await?;
+        Ok(result.modified_count)
+    }
+
+    pub async fn delete(&self, org_email: &str, id: &str) -> Result<bool, ApiError> {
+        let object_id = ObjectId::parse_str(id)
+            .map_err(|_| ApiError::ValidationError("Invalid ID format".to_string()))?;
+
+        let filter = doc! { "_id": object_id, "org_email": org_email };
+        let result = self.collection.delete_one(filter, None).await?;
+
+        Ok(result.deleted_count > 0)
+    }
+}
@@ -0,0 +1,96 @@
+use mongodb::bson::{doc, oid::ObjectId};
+use mongodb::Collection;
+
+use crate::error::ApiError;
+use crate::models::vendor::Vendor;
+
+#[derive(Clone)]
+pub struct VendorRepository {
+    collection: Collection<Vendor>,
+}
+
+impl VendorRepository {
+    pub fn new(collection: Collection<Vendor>) -> Self {
+        Self { collection }
+    }
+
+    pub async fn create(&self, mut vendor: Vendor) -> Result<Vendor, ApiError> {
+        let result = self.collection.insert_one(&vendor, None).await?;
+        vendor.id = result.inserted_id.as_object_id();
+        Ok(vendor)
+    }
+
+    pub async fn find_all(&self, org_email: &str) -> Result<Vec<Vendor>, ApiError> {
+        let filter = doc! { "org_email": org_email };
+        let mut cursor = self.collection.find(filter, None).await?;
+        let mut vendors = Vec::new();
+
+        while cursor.advance().await? {
+            vendors.push(cursor.deserialize_current()?);
+        }
+
+        Ok(vendors)
+    }
+
+    pub async fn find_by_id(&self, org_email: &str, id: &str) -> Result<Option<Vendor>, ApiError> {
+        let object_id = ObjectId::parse_str(id)
+            .map_err(|_| ApiError::ValidationError("Invalid ID format".to_string()))?;
+
+        let filter = doc! { "_id": object_id, "org_email": org_email };
+        let vendor = self.collection.find_one(filter, None).await?;
+
+        Ok(vendor)
+    }
+
+    pub async fn find_by_gstin(
+        &self,
+        org_email: &str,
+        gstin: &str,
+    ) -> Result<Option<Vendor>, ApiError> {
+        let filter = doc! { "org_email": org_email, "gstIN": gstin };
+        let vendor = self.collection.find_one(filter, None).await?;
+        Ok(vendor)
+    }
+
+    pub async fn replace(&self, vendor: &Vendor) -> Result<bool, ApiError> {
+        let object_id = vendor
+            .id
+            .ok_or_else(|| ApiError::InternalServerError("Vendor has no id".to_string()))?;
+
+        let filter = doc! { "_id": object_id, "org_email": &vendor.org_email };
+        let result = self.collection.replace_one(filter, vendor, None).await?;
+
+        Ok(result.matched_count > 0)
+    }
+
+    pub async fn delete(&self, org_email: &str, id: &str) -> Result<bool, ApiError> {
+        let object_id = ObjectId::parse_str(id)
+            .map_err(|_| ApiError::ValidationError("Invalid ID format".to_string()))?;
+
+        let filter = doc! { "_id": object_id, "org_email": org_email };
+        let result = self.collection.delete_one(filter, None).await?;
+
+        Ok(result.deleted_count > 0)
+    }
+
+    pub async fn search(&self, org_email: &str, query: &str) -> Result<Vec<Vendor>, ApiError> {
+        let filter = doc! {
+            "org_email": org_email,
+            "$or": [
+                { "vendorName": { "$regex": query, "$options": "i" } },
+                { "companyName": { "$regex": query, "$options": "i" } },
+                { "gstIN": { "$regex": query, "$options": "i" } },
+                { "pan": { "$regex": query, "$options": "i" } }
+            ]
+        };
+
+        let mut cursor = self.collection.find(filter, None).await?;
+        let mut vendors = Vec::new();
+
+        while cursor.advance().await? {
+            vendors.push(cursor.deserialize_current()?);
+        }
+
+        Ok(vendors)
+    }
+}
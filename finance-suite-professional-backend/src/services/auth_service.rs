@@ -0,0 +1,267 @@
+//! Sign-in for user accounts: short-lived signed access tokens (JWT, HS256)
+//! and long-lived opaque refresh tokens that are stored hashed, rotated on
+//! every use and revoked on logout.
+
+use argon2::password_hash::rand_core::OsRng;
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::Argon2;
+use chrono::{Duration, Utc};
+use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
+use mongodb::bson::DateTime;
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::error::ApiError;
+use crate::models::user::{
+    ChangePasswordRequest, Claims, LoginRequest, RefreshToken, RegisterRequest, Role,
+    TokenResponse, User, UserProfile,
+};
+use crate::repository::{OrganisationRepository, RefreshTokenRepository, UserRepository};
+
+const INVALID_CREDENTIALS: &str = "Invalid email or password";
+
+/// Signing key and token lifetimes, read from the environment in main.rs
+#[derive(Clone)]
+pub struct JwtConfig {
+    pub secret: String,
+    pub access_ttl: Duration,
+    pub refresh_ttl: Duration,
+}
+
+/// Lower-case, trimmed email used for look-ups
+pub(crate) fn normalize_email(email: &str) -> String {
+    email.trim().to_lowercase()
+}
+
+pub(crate) fn hash_password(password: &str) -> Result<String, ApiError> {
+    let salt = SaltString::generate(&mut OsRng);
+    Argon2::default()
+        .hash_password(password.as_bytes(), &salt)
+        .map(|hash| hash.to_string())
+        .map_err(|e| ApiError::InternalServerError(format!("Failed to hash password: {}", e)))
+}
+
+fn verify_password(password: &str, hash: &str) -> bool {
+    PasswordHash::new(hash)
+        .map(|parsed| {
+            Argon2::default()
+                .verify_password(password.as_bytes(), &parsed)
+                .is_ok()
+        })
+        .unwrap_or(false)
+}
+
+fn token_hash(token: &str) -> String {
+    Sha256::digest(token.as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+#[derive(Clone)]
+pub struct AuthService {
+    user_repo: UserRepository,
+    token_repo: RefreshTokenRepository,
+    org_repo: OrganisationRepository,
+    config: JwtConfig,
+}
+
+impl AuthService {
+    pub fn new(
+        user_repo: UserRepository,
+        token_repo: RefreshTokenRepository,
+        org_repo: OrganisationRepository,
+        config: JwtConfig,
+    ) -> Self {
+        Self {
+            user_repo,
+            token_repo,
+            org_repo,
+            config,
+        }
+    }
+
+    /// Sign up the first user of a new organisation as its administrator.
+    /// Users of an organisation already on record, even one left without
+    /// users, are added by an administrator.
+    pub async fn register(&self, mut req: RegisterRequest) -> Result<TokenResponse, ApiError> {
+        req.validate()?;
+        req.email = normalize_email(&req.email);
+        req.org_email = normalize_email(&req.org_email);
+
+        if self.user_repo.find_by_email(&req.email).await?.is_some() {
+            return Err(ApiError::Conflict(format!(
+                "A user with email {} already exists",
+                req.email
+            )));
+        }
+        if self.user_repo.count_by_org(&req.org_email).await? > 0
+            || self.org_repo.find_by_email(&req.org_email).await?.is_some()
+        {
+            return Err(ApiError::Conflict(
+                "Organisation is already registered; ask an administrator to add you".to_string(),
+            ));
+        }
+
+        let now = DateTime::now();
+        let user = User {
+            id: None,
+            name: req.name.trim().to_string(),
+            email: req.email,
+            password_hash: hash_password(&req.password)?,
+            org_email: req.org_email,
+            role: Role::Admin,
+            is_active: true,
+            last_login_at: Some(now),
+            created_at: Some(now),
+            updated_at: Some(now),
+        };
+        let user = self.user_repo.create(user).await?;
+        self.issue_tokens(&user).await
+    }
+
+    pub async fn login(&self, req: LoginRequest) -> Result<TokenResponse, ApiError> {
+        let email = normalize_email(&req.email);
+        let user = self
+            .user_repo
+            .find_by_email(&email)
+            .await?
+            .filter(|user| verify_password(&req.password, &user.password_hash))
+            .ok_or_else(|| ApiError::Unauthorized(INVALID_CREDENTIALS.to_string()))?;
+        if !user.is_active {
+            return Err(ApiError::Unauthorized("User account is disabled".to_string()));
+        }
+
+        if let Some(id) = user.id {
+            self.user_repo.record_login(id).await?;
+        }
+        self.issue_tokens(&user).await
+    }
+
+    /// Exchange a refresh token for a new pair. Presenting a token that was
+    /// already used signs the user out everywhere, since it may have leaked.
+    pub async fn refresh(&self, refresh_token: &str) -> Result<TokenResponse, ApiError> {
+        let invalid = || ApiError::Unauthorized("Invalid or expired refresh token".to_string());
+        let hash = token_hash(refresh_token.trim());
+        let stored = self
+            .token_repo
+            .find_by_hash(&hash)
+            .await?
+            .ok_or_else(invalid)?;
+
+        if stored.revoked_at.is_some() {
+            self.token_repo.revoke_all_for_user(&stored.user_id).await?;
+            return Err(invalid());
+        }
+        if stored.expires_at.timestamp_millis() <= Utc::now().timestamp_millis() {
+            return Err(invalid());
+        }
+        if !self.token_repo.revoke(&hash).await? {
+            return Err(invalid());
+        }
+
+        let user = self
+            .user_repo
+            .find_by_id(&stored.user_id)
+            .await?
+            .filter(|user| user.is_active)
+            .ok_or_else(invalid)?;
+        self.issue_tokens(&user).await
+    }
+
+    /// Revoke a refresh token; unknown tokens are ignored
+    pub async fn logout(&self, refresh_token: &str) -> Result<(), ApiError> {
+        self.token_repo
+            .revoke(&token_hash(refresh_token.trim()))
+            .await?;
+        Ok(())
+    }
+
+    /// Change the caller's password and sign out their other sessions
+    pub async fn change_password(
+        &self,
+        user: &User,
+        req: ChangePasswordRequest,
+    ) -> Result<(), ApiError> {
+        req.validate()?;
+        if !verify_password(&req.current_password, &user.password_hash) {
+            return Err(ApiError::Unauthorized(
+                "Current password is incorrect".to_string(),
+            ));
+        }
+
+        let mut user = user.clone();
+        user.password_hash = hash_password(&req.new_password)?;
+        user.updated_at = Some(DateTime::now());
+        self.user_repo.replace(&user).await?;
+        if let Some(id) = user.id {
+            self.token_repo.revoke_all_for_user(&id.to_hex()).await?;
+        }
+        Ok(())
+    }
+
+    /// Check a bearer access token and load the user it was issued to. The
+    /// stored account is the source of truth, so a disabled user or a changed
+    /// role takes effect without waiting for the token to expire.
+    pub async fn authenticate(&self, access_token: &str) -> Result<User, ApiError> {
+        let claims = decode::<Claims>(
+            access_token,
+            &DecodingKey::from_secret(self.config.secret.as_bytes()),
+            &Validation::default(),
+        )
+        .map_err(|_| ApiError::Unauthorized("Invalid or expired access token".to_string()))?
+        .claims;
+
+        self.user_repo
+            .find_by_id(&claims.sub)
+            .await?
+            .filter(|user| user.is_active)
+            .ok_or_else(|| ApiError::Unauthorized("User account is disabled".to_string()))
+    }
+
+    async fn issue_tokens(&self, user: &User) -> Result<TokenResponse, ApiError> {
+        let user_id = user
+            .id
+            .ok_or_else(|| ApiError::InternalServerError("User has no id".to_string()))?
+            .to_hex();
+        let now = Utc::now();
+
+        let claims = Claims {
+            sub: user_id.clone(),
+            email: user.email.clone(),
+            org_email: user.org_email.clone(),
+            role: user.role,
+            iat: now.timestamp(),
+            exp: (now + self.config.access_ttl).timestamp(),
+        };
+        let access_token = encode(
+            &Header::default(),
+            &claims,
+            &EncodingKey::from_secret(self.config.secret.as_bytes()),
+        )
+        .map_err(|e| ApiError::InternalServerError(format!("Failed to sign token: {}", e)))?;
+
+        let refresh_token = format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple());
+        self.token_repo
+            .create(RefreshToken {
+                id: None,
+                user_id,
+                token_hash: token_hash(&refresh_token),
+                expires_at: DateTime::from_millis(
+                    (now + self.config.refresh_ttl).timestamp_millis(),
+                ),
+                revoked_at: None,
+                created_at: Some(DateTime::now()),
+            })
+            .await?;
+
+        Ok(TokenResponse {
+            access_token,
+            refresh_token,
+            token_type: "Bearer",
+            expires_in: self.config.access_ttl.num_seconds(),
+            user: UserProfile::from(user),
+        })
+    }
+}
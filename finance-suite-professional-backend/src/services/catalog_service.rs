@@ -0,0 +1,204 @@
+use mongodb::bson::DateTime;
+use rust_decimal::Decimal;
+use validator::Validate;
+
+use crate::error::ApiError;
+use crate::models::catalog_item::{CatalogItem, CatalogItemKind, CatalogItemRequest};
+use crate::models::invoice::InvoiceItem;
+use crate::repository::CatalogItemRepository;
+use crate::utils::{gst, validation};
+
+#[derive(Clone)]
+pub struct CatalogService {
+    repository: CatalogItemRepository,
+}
+
+impl CatalogService {
+    pub fn new(repository: CatalogItemRepository) -> Self {
+        Self { repository }
+    }
+
+    /// Normalise and check the HSN/SAC code, GST rate, unit and price
+    fn check_item(req: &mut CatalogItemRequest) -> Result<(), ApiError> {
+        req.validate()?;
+
+        req.hsn_sac = validation::normalize_hsn_sac(&req.hsn_sac);
+        validation::validate_hsn_sac(&req.hsn_sac, 4).map_err(ApiError::ValidationError)?;
+        // Services Accounting Codes all sit in chapter 99
+        let is_sac = req.hsn_sac.starts_with("99");
+        match req.kind {
+            CatalogItemKind::Services if !is_sac => {
+                return Err(ApiError::ValidationError(format!(
+                    "SAC code '{}' for a service must start with 99",
+                    req.hsn_sac
+                )))
+            }
+            CatalogItemKind::Goods if is_sac => {
+                return Err(ApiError::ValidationError(format!(
+                    "'{}' is a SAC code; goods need an HSN code",
+                    req.hsn_sac
+                )))
+            }
+            _ => {}
+        }
+
+        req.gst_rate = req.gst_rate.trim().to_string();
+        let gst_rate = gst::parse_decimal("GST rate", &req.gst_rate)?;
+        if gst_rate > Decimal::ONE_HUNDRED {
+            return Err(ApiError::ValidationError(
+                "GST rate cannot exceed 100 percent".to_string(),
+            ));
+        }
+
+        if req.rate.is_negative() {
+            return Err(ApiError::ValidationError("Rate cannot be negative".to_string()));
+        }
+
+        req.name = req.name.trim().to_string();
+        req.unit = req.unit.trim().to_uppercase();
+        Ok(())
+    }
+
+    pub async fn create_item(
+        &self,
+        mut req: CatalogItemRequest,
+        org_email: &str,
+    ) -> Result<CatalogItem, ApiError> {
+        Self::check_item(&mut req)?;
+        if self
+            .repository
+            .find_by_name(org_email, &req.name)
+            .await?
+            .is_some()
+        {
+            return Err(ApiError::Conflict(format!(
+                "Catalogue item '{}' already exists",
+                req.name
+            )));
+        }
+
+        let now = DateTime::now();
+        req.id = None;
+        req.org_email = org_email.to_string();
+        req.created_at = Some(now);
+        req.updated_at = Some(now);
+
+        self.repository.create(req).await
+    }
+
+    pub async fn get_all_items(&self, org_email: &str) -> Result<Vec<CatalogItem>, ApiError> {
+        self.repository.find_all(org_email).await
+    }
+
+    pub async fn get_item_by_id(&self, id: &str, org_email: &str) -> Result<CatalogItem, ApiError> {
+        self.repository
+            .find_by_id(org_email, id)
+            .await?
+            .ok_or_else(|| ApiError::NotFound(format!("Catalogue item with id {} not found", id)))
+    }
+
+    pub async fn update_item(
+        &self,
+        id: &str,
+        mut req: CatalogItemRequest,
+        org_email: &str,
+    ) -> Result<CatalogItem, ApiError> {
+        Self::check_item(&mut req)?;
+        let existing = self.get_item_by_id(id, org_email).await?;
+
+        if req.name != existing.name {
+            if let Some(other) = self.repository.find_by_name(org_email, &req.name).await? {
+                if other.id != existing.id {
+                    return Err(ApiError::Conflict(format!(
+                        "Catalogue item '{}' already exists",
+                        req.name
+                    )));
+                }
+            }
+        }
+
+        req.id = existing.id;
+        req.org_email = existing.org_email;
+        req.created_at = existing.created_at;
+        req.updated_at = Some(DateTime::now());
+
+        self.repository.replace(&req).await?;
+        Ok(req)
+    }
+
+    /// Invoices keep their own copy of the line details, so removing an
+    /// item does not touch documents already raised from it
+    pub async fn delete_item(&self, id: &str, org_email: &str) -> Result<bool, ApiError> {
+        self.repository.delete(org_email, id).await
+    }
+
+    pub async fn search_items(
+        &self,
+        query: &str,
+        org_email: &str,
+    ) -> Result<Vec<CatalogItem>, ApiError> {
+        self.repository.search(org_email, query).await
+    }
+
+    /// Fill blank description, HSN/SAC, rate and GST percent of lines that
+    /// reference an item in the organisation's catalogue. Values the client
+    /// typed win over the catalogue defaults. HSN/SAC codes on every line
+    /// are tidied up.
+    pub async fn apply_to_items(
+        &self,
+        items: &mut [InvoiceItem],
+        org_email: &str,
+    ) -> Result<(), ApiError> {
+        for (idx, item) in items.iter_mut().enumerate() {
+            item.hsn_sac = validation::normalize_hsn_sac(&item.hsn_sac);
+            let Some(catalog_id) = item.catalog_item_id.as_deref().filter(|id| !id.is_empty())
+            else {
+                continue;
+            };
+            let entry = self
+                .repository
+                .find_by_id(org_email, catalog_id)
+                .await?
+                .ok_or_else(|| {
+                    ApiError::ValidationError(format!(
+                        "Item {}: catalogue item {} not found",
+                        idx + 1,
+                        catalog_id
+                    ))
+                })?;
+            if !entry.is_active {
+                return Err(ApiError::ValidationError(format!(
+                    "Item {}: catalogue item '{}' is inactive",
+                    idx + 1,
+                    entry.name
+                )));
+            }
+
+            if item.description.trim().is_empty() {
+                item.description = if entry.description.is_empty() {
+                    entry.name
+                } else {
+                    entry.description
+                };
+            }
+            if item.hsn_sac.trim().is_empty() {
+                item.hsn_sac = entry.hsn_sac;
+            }
+            if item.rate.is_zero() {
+                item.rate = entry.rate;
+            }
+            let no_tax_given = [
+                &item.cgst.cgst_percent,
+                &item.sgst.sgst_percent,
+                &item.igst.igst_percent,
+            ]
+            .iter()
+            .all(|percent| percent.trim().is_empty());
+            if no_tax_given {
+                // compute_taxes re-splits the rate by supply type
+                item.igst.igst_percent = entry.gst_rate;
+            }
+        }
+        Ok(())
+    }
+}
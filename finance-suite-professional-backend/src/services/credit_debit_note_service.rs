@@ -0,0 +1,216 @@
+use mongodb::bson::DateTime;
+
+use crate::error::ApiError;
+use crate::models::credit_debit_note::{CreateNoteRequest, CreditDebitNote, NoteStatus, NoteType};
+use crate::repository::{CreditDebitNoteRepository, InvoiceRepository, OrganisationRepository};
+use crate::utils::gst;
+
+#[derive(Clone)]
+pub struct CreditDebitNoteService {
+    repository: CreditDebitNoteRepository,
+    invoice_repo: InvoiceRepository,
+    org_repo: OrganisationRepository,
+}
+
+impl CreditDebitNoteService {
+    pub fn new(
+        repository: CreditDebitNoteRepository,
+        invoice_repo: InvoiceRepository,
+        org_repo: OrganisationRepository,
+    ) -> Self {
+        Self {
+            repository,
+            invoice_repo,
+            org_repo,
+        }
+    }
+
+    /// Issue a credit or debit note against an issued invoice. Party, place of
+    /// supply and currency come from the original invoice; taxes are computed
+    /// with the same supply type, and the invoice balance is adjusted.
+    pub async fn create_note(
+        &self,
+        note_type: NoteType,
+        mut note: CreateNoteRequest,
+        org_email: &str,
+    ) -> Result<CreditDebitNote, ApiError> {
+        note.validate().map_err(ApiError::ValidationError)?;
+
+        let mut invoice = self
+            .invoice_repo
+            .get_invoice_by_id(org_email, &note.original_invoice_id)
+            .await?
+            .ok_or_else(|| {
+                ApiError::NotFound(format!("Invoice {} not found", note.original_invoice_id))
+            })?;
+        let org = self.org_repo.get_organisation_by_email(org_email).await?;
+
+        note.id = None;
+        note.note_type = note_type;
+        note.original_invoice_number = invoice.invoice_number.clone();
+        note.original_invoice_date = invoice.invoice_date.clone();
+        note.billcustomer_name = invoice.billcustomer_name.clone();
+        note.billcustomer_gstin = invoice.billcustomer_gstin.clone();
+        note.place_of_supply = invoice.place_of_supply.clone();
+        note.invoice_type = invoice.invoice_type.clone();
+        note.currency = invoice.currency.clone();
+        note.org_email = org_email.to_string();
+        note.status = NoteStatus::Issued;
+        note.created_at = Some(DateTime::now());
+        note.cancelled_at = None;
+        note.cancel_reason = None;
+
+        let supplier_state = org.state_code().map(str::to_string);
+        let supply_type = gst::determine_supply_type(supplier_state.as_deref(), &invoice)?;
+        let totals = gst::compute_item_taxes(&mut note.items, supply_type, org.rounding())?;
+        note.sub_total = totals.sub_total;
+        note.totalcgst = totals.total_cgst;
+        note.totalsgst = totals.total_sgst;
+        note.totaligst = totals.total_igst;
+        note.total = totals.total;
+
+        match note_type {
+            NoteType::Credit => invoice.apply_credit_note(note.total),
+            NoteType::Debit => invoice.apply_debit_note(note.total),
+        }
+        .map_err(ApiError::Conflict)?;
+
+        // Adjust the invoice atomically before numbering the note, so a
+        // concurrent payment or note is never overwritten and a refused
+        // adjustment does not burn a note number
+        let credit = note_type == NoteType::Credit;
+        self.invoice_repo
+            .apply_note(&note.original_invoice_id, note.total, credit)
+            .await?
+            .ok_or_else(|| {
+                ApiError::Conflict(format!(
+                    "Invoice {} changed while the note was issued; reload and retry",
+                    invoice.invoice_number
+                ))
+            })?;
+
+        let prefix = match note_type {
+            NoteType::Credit => &org.credit_note_prefix,
+            NoteType::Debit => &org.debit_note_prefix,
+        };
+        let prefix = if prefix.trim().is_empty() {
+            note_type.default_prefix()
+        } else {
+            prefix.trim()
+        };
+        let created = async {
+            let sequence = self
+                .org_repo
+                .next_sequence(org_email, note_type.sequence_field())
+                .await?;
+            note.note_number = format!("{}-{:03}", prefix, sequence);
+            self.repository.create(note.clone()).await
+        }
+        .await;
+        let created = match created {
+            Ok(created) => created,
+            Err(e) => {
+                if let Err(err) = self
+                    .invoice_repo
+                    .reverse_note(&note.original_invoice_id, note.total, credit)
+                    .await
+                {
+                    log::error!(
+                        "Could not take the failed note back off invoice {}: {}",
+                        note.original_invoice_number,
+                        err
+                    );
+                }
+                return Err(e);
+            }
+        };
+
+        log::info!(
+            "Issued {:?} note {} for {} against invoice {}",
+            note_type,
+            created.note_number,
+            created.total,
+            created.original_invoice_number
+        );
+        Ok(created)
+    }
+
+    pub async fn get_notes(
+        &self,
+        note_type: NoteType,
+        invoice_id: Option<&str>,
+        org_email: &str,
+    ) -> Result<Vec<CreditDebitNote>, ApiError> {
+        self.repository
+            .find_all(org_email, note_type, invoice_id)
+            .await
+    }
+
+    pub async fn get_note_by_id(
+        &self,
+        note_type: NoteType,
+        id: &str,
+        org_email: &str,
+    ) -> Result<CreditDebitNote, ApiError> {
+        self.repository
+            .find_by_id(org_email, note_type, id)
+            .await?
+            .ok_or_else(|| ApiError::NotFound(format!("{:?} note with id {} not found", note_type, id)))
+    }
+
+    /// Cancel an issued note and take it back off the original invoice.
+    /// Issued notes are never deleted so the note series has no gaps.
+    pub async fn cancel_note(
+        &self,
+        note_type: NoteType,
+        id: &str,
+        reason: Option<String>,
+        org_email: &str,
+    ) -> Result<CreditDebitNote, ApiError> {
+        let note = self.get_note_by_id(note_type, id, org_email).await?;
+        if note.status == NoteStatus::Cancelled {
+            return Err(ApiError::Conflict(format!(
+                "Note {} is already cancelled",
+                note.note_number
+            )));
+        }
+
+        // Claim the cancellation first so two requests cannot both reverse it
+        let cancelled = self
+            .repository
+            .cancel(org_email, note_type, id, reason)
+            .await?
+            .ok_or_else(|| {
+                ApiError::Conflict(format!("Note {} is already cancelled", note.note_number))
+            })?;
+
+        let reversed = self
+            .invoice_repo
+            .reverse_note(
+                &note.original_invoice_id,
+                note.total,
+                note_type == NoteType::Credit,
+            )
+            .await;
+        match reversed {
+            Ok(Some(_)) => {}
+            Ok(None) => log::warn!(
+                "Invoice {} referenced by note {} no longer exists",
+                note.original_invoice_id,
+                note.note_number
+            ),
+            Err(e) => {
+                self.repository.restore(id).await?;
+                return Err(e.into());
+            }
+        }
+
+        log::info!(
+            "Cancelled {:?} note {} against invoice {}",
+            note_type,
+            cancelled.note_number,
+            cancelled.original_invoice_number
+        );
+        Ok(cancelled)
+    }
+}
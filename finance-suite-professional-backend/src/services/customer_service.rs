@@ -2,6 +2,7 @@ use validator::Validate;
 use crate::error::ApiError;
 use crate::models::{CreateCustomerRequest, Customer, UpdateCustomerRequest};
 use crate::repository::CustomerRepository;
+use crate::utils::validation;
 
 #[derive(Clone)]
 pub struct CustomerService {
@@ -13,9 +14,16 @@ impl CustomerService {
         Self { repository }
     }
 
-    pub async fn create_customer(&self, req: CreateCustomerRequest) -> Result<Customer, ApiError> {
+    pub async fn create_customer(
+        &self,
+        mut req: CreateCustomerRequest,
+        org_email: &str,
+    ) -> Result<Customer, ApiError> {
         // Validate request
         req.validate()?;
+        req.gst_in =
+            validation::clean_required_gstin(&req.gst_in).map_err(ApiError::ValidationError)?;
+        req.tan = validation::clean_optional_tan(&req.tan).map_err(ApiError::ValidationError)?;
 
         // Additional business logic validation
         if req.addresses.is_empty() {
@@ -27,22 +35,26 @@ impl CustomerService {
         for address in &req.addresses {
             address.validate()?;
         }
-        if let Some(_) = self.repository.find_by_email(&req.email).await? {
+        if let Some(_) = self.repository.find_by_email(org_email, &req.email).await? {
             return Err(ApiError::ValidationError(format!(
                 "Customer with email already exists"
             )));
         }
         // Create customer
-        self.repository.create(req).await
+        self.repository.create(org_email, req).await
     }
 
-    pub async fn get_all_customers(&self) -> Result<Vec<Customer>, ApiError> {
-        self.repository.find_all().await
+    pub async fn get_all_customers(&self, org_email: &str) -> Result<Vec<Customer>, ApiError> {
+        self.repository.find_all(org_email).await
     }
 
-    pub async fn get_customer_by_id(&self, id: &str) -> Result<Customer, ApiError> {
+    pub async fn get_customer_by_id(
+        &self,
+        id: &str,
+        org_email: &str,
+    ) -> Result<Customer, ApiError> {
         self.repository
-            .find_by_id(id)
+            .find_by_id(org_email, id)
             .await?
             .ok_or_else(|| ApiError::NotFound(format!("Customer with id {} not found", id)))
     }
@@ -50,16 +62,23 @@ impl CustomerService {
     pub async fn update_customer(
         &self,
         id: &str,
-        req: UpdateCustomerRequest,
+        mut req: UpdateCustomerRequest,
+        org_email: &str,
     ) -> Result<Customer, ApiError> {
         // Validate request
         req.validate()?;
+        if let Some(gst_in) = req.gst_in.as_deref() {
+            req.gst_in = Some(
+                validation::clean_required_gstin(gst_in).map_err(ApiError::ValidationError)?,
+            );
+        }
+        if let Some(tan) = req.tan.as_deref() {
+            req.tan =
+                Some(validation::clean_optional_tan(tan).map_err(ApiError::ValidationError)?);
+        }
 
         // Check if customer exists
-        self.repository
-            .find_by_id(id)
-            .await?
-            .ok_or_else(|| ApiError::NotFound(format!("Customer with id {} not found", id)))?;
+        self.get_customer_by_id(id, org_email).await?;
 
         // Validate addresses if provided
         if let Some(ref addresses) = req.addresses {
@@ -74,31 +93,37 @@ impl CustomerService {
         }
 
         // Update customer
-        self.repository.update(id, req).await
+        self.repository.update(org_email, id, req).await
     }
 
-    pub async fn delete_customer(&self, id: &str) -> Result<bool, ApiError> {
+    pub async fn delete_customer(&self, id: &str, org_email: &str) -> Result<bool, ApiError> {
         // Check if customer exists
-        self.repository
-            .find_by_id(id)
-            .await?
-            .ok_or_else(|| ApiError::NotFound(format!("Customer with id {} not found", id)))?;
+        self.get_customer_by_id(id, org_email).await?;
 
-        self.repository.delete(id).await
+        self.repository.delete(org_email, id).await
     }
 
-    pub async fn delete_customer_by_gstin(&self, gstin: &str) -> Result<bool, ApiError> {
-        // Validate GSTIN is not empty
-        if gstin.trim().is_empty() {
+    pub async fn delete_customer_by_gstin(
+        &self,
+        gstin: &str,
+        org_email: &str,
+    ) -> Result<bool, ApiError> {
+        // Stored GSTINs are normalised, so match the same form
+        let gstin = validation::normalize_gstin(gstin);
+        if gstin.is_empty() {
             return Err(ApiError::ValidationError(
                 "GSTIN cannot be empty".to_string(),
             ));
         }
 
-        self.repository.delete_by_gstin(gstin).await
+        self.repository.delete_by_gstin(org_email, &gstin).await
     }
 
-    pub async fn delete_customer_by_email(&self, email: &str) -> Result<bool, ApiError> {
+    pub async fn delete_customer_by_email(
+        &self,
+        email: &str,
+        org_email: &str,
+    ) -> Result<bool, ApiError> {
         // Validate email is not empty
         if email.trim().is_empty() {
             return Err(ApiError::ValidationError(
@@ -106,16 +131,20 @@ impl CustomerService {
             ));
         }
 
-        self.repository.delete_by_email(email).await
+        self.repository.delete_by_email(org_email, email).await
     }
 
-    pub async fn search_customers(&self, query: &str) -> Result<Vec<Customer>, ApiError> {
+    pub async fn search_customers(
+        &self,
+        query: &str,
+        org_email: &str,
+    ) -> Result<Vec<Customer>, ApiError> {
         if query.trim().is_empty() {
             return Err(ApiError::ValidationError(
                 "Search query cannot be empty".to_string(),
             ));
         }
 
-        self.repository.search(query).await
+        self.repository.search(org_email, query).await
     }
 }
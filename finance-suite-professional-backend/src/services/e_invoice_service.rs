@@ -0,0 +1,659 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use lazy_static::lazy_static;
+use mongodb::bson::{doc, DateTime};
+use regex::Regex;
+use rust_decimal::prelude::ToPrimitive;
+
+use crate::error::ApiError;
+use crate::models::customer::Customer;
+use crate::models::e_invoice::{
+    BuyerDtls, DocDtls, EInvoiceDetails, EInvoiceItem, EInvoicePayload, EInvoicePreview,
+    ExpDtls, IrpResponse, PartyDtls, TranDtls, ValDtls, EINVOICE_SCHEMA_VERSION,
+};
+use crate::models::invoice::{ExportType, Invoice};
+use crate::models::money::{self, Money, DEFAULT_CURRENCY};
+use crate::models::organisation::Organisation;
+use crate::repository::{
+    CatalogItemRepository, CustomerRepository, InvoiceRepository, OrganisationRepository,
+};
+use crate::services::gstr1_service::amount;
+use crate::services::irp_client::IrpClient;
+use crate::utils::date::{self, NIC_DATE_FORMAT};
+use crate::utils::{gst, validation};
+
+/// State code and PIN the IRP expects for overseas buyers
+const EXPORT_STATE_CODE: &str = "96";
+const EXPORT_PIN: u32 = 999_999;
+
+/// GST rates the IRP accepts on a line
+const GST_RATES: &[f64] = &[
+    0.0, 0.1, 0.25, 1.0, 1.5, 3.0, 5.0, 6.0, 7.5, 12.0, 18.0, 28.0, 40.0,
+];
+
+lazy_static! {
+    /// NIC v1.1 document number: 16 characters, not starting with 0, / or -
+    static ref DOC_NO_REGEX: Regex = Regex::new(r"^[A-Za-z1-9][A-Za-z0-9/-]{0,15}$").unwrap();
+    static ref IRN_REGEX: Regex = Regex::new(r"^[0-9a-fA-F]{64}$").unwrap();
+}
+
+pub(crate) fn clip(value: &str, max: usize) -> String {
+    value.trim().chars().take(max).collect()
+}
+
+pub(crate) fn non_empty(value: &str) -> Option<String> {
+    let value = value.trim();
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+pub(crate) fn first_non_empty<'a>(values: &[&'a str]) -> &'a str {
+    values
+        .iter()
+        .map(|v| v.trim())
+        .find(|v| !v.is_empty())
+        .unwrap_or("")
+}
+
+/// Phone as the 6-12 digits the schema allows
+pub(crate) fn phone(value: &str) -> Option<String> {
+    let digits: String = value.chars().filter(char::is_ascii_digit).collect();
+    (6..=12).contains(&digits.len()).then_some(digits)
+}
+
+fn email(value: &str) -> Option<String> {
+    let value = value.trim();
+    (6..=100).contains(&value.len()).then(|| value.to_string())
+}
+
+/// A free-form address broken into the schema's lines
+pub(crate) struct AddressParts {
+    pub addr1: String,
+    pub addr2: Option<String>,
+    pub loc: String,
+    pub pin: Option<u32>,
+}
+
+/// Split "Street, Area, City, State 560001" into address lines, locality and
+/// PIN. State and country names are dropped since the state code carries them.
+pub(crate) fn split_address(value: &str) -> AddressParts {
+    let pin = validation::PINCODE_REGEX
+        .find_iter(value)
+        .last()
+        .and_then(|m| m.as_str().parse().ok());
+    let without_pin = validation::PINCODE_REGEX.replace_all(value, "");
+
+    let mut parts: Vec<&str> = without_pin
+        .split([',', '\n'])
+        .map(|p| p.trim().trim_end_matches('-').trim())
+        .filter(|p| !p.is_empty())
+        .filter(|p| {
+            !p.eq_ignore_ascii_case("india")
+                && !gst::STATE_CODES
+                    .iter()
+                    .any(|(_, name)| p.eq_ignore_ascii_case(name))
+        })
+        .collect();
+
+    let loc = if parts.len() > 1 {
+        parts.pop().unwrap_or_default().to_string()
+    } else {
+        parts.first().copied().unwrap_or_default().to_string()
+    };
+    let addr1 = parts.first().copied().unwrap_or_default();
+    let rest = parts.iter().skip(1).copied().collect::<Vec<_>>().join(", ");
+
+    AddressParts {
+        addr1: clip(addr1, 100),
+        addr2: non_empty(&clip(&rest, 100)),
+        loc: clip(&loc, 50),
+        pin,
+    }
+}
+
+pub(crate) fn is_export(invoice: &Invoice) -> bool {
+    invoice.invoice_type.eq_ignore_ascii_case("international")
+}
+
+/// Organisation that raised the invoice; older invoices only carry the
+/// company email
+pub(crate) fn invoice_org_email(invoice: &Invoice) -> &str {
+    if invoice.org_email.is_empty() {
+        &invoice.company_email
+    } else {
+        &invoice.org_email
+    }
+}
+
+/// Unit quantity codes of the items in the invoicing organisation's
+/// catalogue that the invoice lines reference
+pub(crate) async fn catalog_units(
+    catalog_repo: &CatalogItemRepository,
+    invoice: &Invoice,
+) -> Result<HashMap<String, String>, ApiError> {
+    let org_email = invoice_org_email(invoice);
+    let mut units = HashMap::new();
+    for id in invoice.items.iter().filter_map(|i| i.catalog_item_id.as_ref()) {
+        if units.contains_key(id) {
+            continue;
+        }
+        if let Some(entry) = catalog_repo.find_by_id(org_email, id).await? {
+            units.insert(id.clone(), entry.unit);
+        }
+    }
+    Ok(units)
+}
+
+/// Build the NIC v1.1 payload. Problems found while mapping the invoice are
+/// returned with it instead of aborting, so the caller sees all of them at once.
+fn build_payload(
+    invoice: &Invoice,
+    org: &Organisation,
+    customer: Option<&Customer>,
+    units: &HashMap<String, String>,
+) -> (EInvoicePayload, Vec<String>) {
+    let mut errors = Vec::new();
+    let export = is_export(invoice);
+
+    // The IRP takes INR values; the foreign currency is reported in ExpDtls
+    let for_cur = (invoice.currency != DEFAULT_CURRENCY).then(|| invoice.currency.clone());
+    let converted = invoice.in_inr().unwrap_or_else(|message| {
+        errors.push(message);
+        invoice.clone()
+    });
+    let invoice = &converted;
+
+    let dt = date::reformat(&invoice.invoice_date, NIC_DATE_FORMAT).unwrap_or_else(|| {
+        errors.push(format!(
+            "Invoice date '{}' is not YYYY-MM-DD",
+            invoice.invoice_date
+        ));
+        invoice.invoice_date.clone()
+    });
+
+    // Seller
+    let seller_gstin =
+        validation::normalize_gstin(first_non_empty(&[&invoice.gst_in, &org.gst_in]));
+    let org_address = org.addresses.first().map(|a| a.value.as_str()).unwrap_or("");
+    let seller_address =
+        split_address(first_non_empty(&[&invoice.company_address, org_address]));
+    let legal_name = first_non_empty(&[
+        &org.company_name,
+        &org.organisation_name,
+        &invoice.company_name,
+    ]);
+    let seller = PartyDtls {
+        gstin: Some(seller_gstin.clone()),
+        lgl_nm: clip(legal_name, 100),
+        trd_nm: non_empty(&org.organisation_name)
+            .filter(|name| name != legal_name)
+            .map(|name| clip(&name, 100)),
+        addr1: seller_address.addr1,
+        addr2: seller_address.addr2,
+        loc: seller_address.loc,
+        pin: seller_address.pin.unwrap_or_else(|| {
+            errors.push("Seller address has no PIN code".to_string());
+            0
+        }),
+        stcd: seller_gstin.get(0..2).unwrap_or_default().to_string(),
+        ph: phone(first_non_empty(&[&invoice.company_phone, &org.phone])),
+        em: email(first_non_empty(&[&invoice.company_email, &org.email])),
+    };
+
+    // Buyer
+    let buyer_address = split_address(&invoice.billcustomer_address);
+    let buyer_name = first_non_empty(&[
+        &invoice.billcustomer_name,
+        customer.map(|c| c.company_name.as_str()).unwrap_or(""),
+    ]);
+    let buyer = if export {
+        BuyerDtls {
+            gstin: "URP".to_string(),
+            lgl_nm: clip(buyer_name, 100),
+            trd_nm: None,
+            pos: EXPORT_STATE_CODE.to_string(),
+            addr1: buyer_address.addr1,
+            addr2: buyer_address.addr2,
+            loc: buyer_address.loc,
+            pin: EXPORT_PIN,
+            stcd: EXPORT_STATE_CODE.to_string(),
+            ph: customer.and_then(|c| phone(&c.phone)),
+            em: customer.and_then(|c| email(&c.email)),
+        }
+    } else {
+        let gstin = validation::normalize_gstin(&invoice.billcustomer_gstin);
+        if gstin.is_empty() || validation::is_unregistered(&gstin) {
+            errors.push(
+                "Buyer has no GSTIN; only B2B supplies and exports are e-invoiced".to_string(),
+            );
+        }
+        let pos = gst::resolve_state_code(&invoice.place_of_supply).unwrap_or_else(|| {
+            errors.push(format!(
+                "Place of supply '{}' is not a known state",
+                invoice.place_of_supply
+            ));
+            ""
+        });
+        BuyerDtls {
+            stcd: gstin.get(0..2).unwrap_or_default().to_string(),
+            gstin,
+            lgl_nm: clip(buyer_name, 100),
+            trd_nm: None,
+            pos: pos.to_string(),
+            addr1: buyer_address.addr1,
+            addr2: buyer_address.addr2,
+            loc: buyer_address.loc,
+            pin: buyer_address.pin.unwrap_or_else(|| {
+                errors.push("Buyer address has no PIN code".to_string());
+                0
+            }),
+            ph: customer.and_then(|c| phone(&c.phone)),
+            em: customer.and_then(|c| email(&c.email)),
+        }
+    };
+
+    // Ship-to, only when it differs from the buyer
+    let ship_differs = !invoice.shipcustomer_name.trim().is_empty()
+        && (invoice.shipcustomer_name.trim() != invoice.billcustomer_name.trim()
+            || invoice.shipcustomer_address.trim() != invoice.billcustomer_address.trim());
+    let ship = ship_differs.then(|| {
+        let address = split_address(&invoice.shipcustomer_address);
+        let gstin = validation::normalize_gstin(&invoice.shipcustomer_gstin);
+        let stcd = if export {
+            EXPORT_STATE_CODE.to_string()
+        } else {
+            gstin
+                .get(0..2)
+                .or_else(|| gst::resolve_state_code(&invoice.place_of_supply))
+                .unwrap_or_default()
+                .to_string()
+        };
+        PartyDtls {
+            gstin: non_empty(&gstin).filter(|g| !validation::is_unregistered(g)),
+            lgl_nm: clip(&invoice.shipcustomer_name, 100),
+            trd_nm: None,
+            addr1: address.addr1,
+            addr2: address.addr2,
+            loc: address.loc,
+            pin: if export { EXPORT_PIN } else { address.pin.unwrap_or(0) },
+            stcd,
+            ph: None,
+            em: None,
+        }
+    });
+
+    // Lines
+    let mut item_list = Vec::with_capacity(invoice.items.len());
+    for (idx, item) in invoice.items.iter().enumerate() {
+        let line = idx + 1;
+        let gst_rt = gst::item_gst_rate(item).unwrap_or_else(|e| {
+            errors.push(format!("Item {}: {}", line, e));
+            Default::default()
+        });
+        let qty = gst::parse_decimal("Hours", &item.hours).unwrap_or_else(|e| {
+            errors.push(format!("Item {}: {}", line, e));
+            Default::default()
+        });
+        let hsn_cd = item.hsn_sac.trim().to_string();
+        let is_service = hsn_cd.starts_with("99");
+        let unit = item
+            .catalog_item_id
+            .as_ref()
+            .and_then(|id| units.get(id))
+            .filter(|unit| !unit.is_empty())
+            .cloned()
+            .or_else(|| (!is_service).then(|| "OTH".to_string()));
+        let tax = item.igst.igst_amount + item.cgst.cgst_amount + item.sgst.sgst_amount;
+
+        item_list.push(EInvoiceItem {
+            sl_no: line.to_string(),
+            prd_desc: non_empty(&clip(&item.description, 300)),
+            is_servc: if is_service { "Y" } else { "N" }.to_string(),
+            hsn_cd,
+            qty: qty.round_dp(3).to_f64().unwrap_or(0.0),
+            unit,
+            unit_price: amount(item.rate),
+            tot_amt: amount(item.item_total),
+            discount: 0.0,
+            ass_amt: amount(item.item_total),
+            gst_rt: gst_rt.normalize().to_f64().unwrap_or(0.0),
+            igst_amt: amount(item.igst.igst_amount),
+            cgst_amt: amount(item.cgst.cgst_amount),
+            sgst_amt: amount(item.sgst.sgst_amount),
+            ces_rt: 0.0,
+            ces_amt: 0.0,
+            oth_chrg: 0.0,
+            tot_item_val: amount(item.item_total + tax),
+        });
+    }
+
+    let items_total: Money = invoice
+        .items
+        .iter()
+        .map(|i| i.item_total + i.igst.igst_amount + i.cgst.cgst_amount + i.sgst.sgst_amount)
+        .sum();
+    let val_dtls = ValDtls {
+        ass_val: amount(invoice.sub_total),
+        cgst_val: amount(invoice.totalcgst),
+        sgst_val: amount(invoice.totalsgst),
+        igst_val: amount(invoice.totaligst),
+        ces_val: 0.0,
+        st_ces_val: 0.0,
+        discount: 0.0,
+        oth_chrg: 0.0,
+        rnd_off_amt: amount(invoice.total - items_total),
+        tot_inv_val: amount(invoice.total),
+    };
+
+    let export_type = invoice.effective_export_type();
+    let sup_typ = match (export, export_type) {
+        (true, ExportType::WithoutPayment) => "EXPWOP",
+        (true, ExportType::WithPayment) => "EXPWP",
+        (false, _) => "B2B",
+    };
+    let exp_dtls = export.then(|| ExpDtls {
+        ship_b_no: non_empty(&invoice.shipping_bill_no),
+        ship_b_dt: date::reformat(&invoice.shipping_bill_date, NIC_DATE_FORMAT),
+        port: non_empty(&invoice.port_code),
+        // IGST paid on an export is claimed back as a refund
+        ref_clm: if export_type == ExportType::WithPayment { "Y" } else { "N" }.to_string(),
+        for_cur,
+        cnt_code: customer
+            .map(|c| c.country_code.trim().to_uppercase())
+            .filter(|code| code.len() == 2 && code.chars().all(|c| c.is_ascii_alphabetic())),
+    });
+
+    let payload = EInvoicePayload {
+        version: EINVOICE_SCHEMA_VERSION.to_string(),
+        tran_dtls: TranDtls {
+            tax_sch: "GST".to_string(),
+            sup_typ: sup_typ.to_string(),
+            reg_rev: "N".to_string(),
+            igst_on_intra: "N".to_string(),
+        },
+        doc_dtls: DocDtls {
+            typ: "INV".to_string(),
+            no: invoice.invoice_number.clone(),
+            dt,
+        },
+        seller_dtls: seller,
+        buyer_dtls: buyer,
+        ship_dtls: ship,
+        item_list,
+        val_dtls,
+        exp_dtls,
+    };
+    (payload, errors)
+}
+
+fn check_length(errors: &mut Vec<String>, field: &str, value: &str, min: usize, max: usize) {
+    let len = value.chars().count();
+    if len < min || len > max {
+        errors.push(format!(
+            "{} must be {} to {} characters, got {}",
+            field, min, max, len
+        ));
+    }
+}
+
+fn check_state_code(errors: &mut Vec<String>, field: &str, code: &str) {
+    if code != EXPORT_STATE_CODE && code != "97" && gst::state_name(code).is_none() {
+        errors.push(format!("{} '{}' is not a valid state code", field, code));
+    }
+}
+
+fn check_party(errors: &mut Vec<String>, label: &str, party: &PartyDtls) {
+    if let Some(gstin) = &party.gstin {
+        if let Err(e) = validation::validate_gstin(gstin) {
+            errors.push(format!("{}: {}", label, e));
+        }
+    }
+    check_length(errors, &format!("{} legal name", label), &party.lgl_nm, 3, 100);
+    check_length(errors, &format!("{} address line 1", label), &party.addr1, 1, 100);
+    check_length(errors, &format!("{} location", label), &party.loc, 3, 50);
+    if !(100_000..=999_999).contains(&party.pin) {
+        errors.push(format!("{} PIN code must be six digits", label));
+    }
+    check_state_code(errors, &format!("{} state code", label), &party.stcd);
+}
+
+/// The NIC v1.1 schema rules the IRP rejects most often, checked locally
+fn schema_errors(payload: &EInvoicePayload) -> Vec<String> {
+    let mut errors = Vec::new();
+
+    if !DOC_NO_REGEX.is_match(&payload.doc_dtls.no) {
+        errors.push(format!(
+            "Invoice number '{}' must be at most 16 letters, digits, / or -, not starting with 0, / or -",
+            payload.doc_dtls.no
+        ));
+    }
+    match date::parse_as(&payload.doc_dtls.dt, NIC_DATE_FORMAT) {
+        Some(date) if date > chrono::Utc::now().date_naive() => {
+            errors.push("Invoice date cannot be in the future".to_string())
+        }
+        Some(_) => {}
+        None => errors.push(format!(
+            "Document date '{}' is not dd/mm/yyyy",
+            payload.doc_dtls.dt
+        )),
+    }
+
+    check_party(&mut errors, "Seller", &payload.seller_dtls);
+
+    let buyer = &payload.buyer_dtls;
+    if buyer.gstin != "URP" {
+        if let Err(e) = validation::validate_gstin(&buyer.gstin) {
+            errors.push(format!("Buyer: {}", e));
+        }
+        if buyer.gstin == payload.seller_dtls.gstin.clone().unwrap_or_default() {
+            errors.push("Buyer GSTIN cannot be the seller's own GSTIN".to_string());
+        }
+    }
+    check_length(&mut errors, "Buyer legal name", &buyer.lgl_nm, 3, 100);
+    check_length(&mut errors, "Buyer address line 1", &buyer.addr1, 1, 100);
+    check_length(&mut errors, "Buyer location", &buyer.loc, 3, 50);
+    if !(100_000..=999_999).contains(&buyer.pin) {
+        errors.push("Buyer PIN code must be six digits".to_string());
+    }
+    check_state_code(&mut errors, "Buyer state code", &buyer.stcd);
+    check_state_code(&mut errors, "Place of supply", &buyer.pos);
+
+    if let Some(ship) = &payload.ship_dtls {
+        check_party(&mut errors, "Ship-to", ship);
+    }
+
+    if payload.item_list.is_empty() || payload.item_list.len() > 1000 {
+        errors.push("An e-invoice must have 1 to 1000 items".to_string());
+    }
+    for item in &payload.item_list {
+        let line = &item.sl_no;
+        if let Err(e) = validation::validate_hsn_sac(&item.hsn_cd, 4) {
+            errors.push(format!("Item {}: {}", line, e));
+        }
+        if let Some(desc) = &item.prd_desc {
+            check_length(&mut errors, &format!("Item {} description", line), desc, 3, 300);
+        }
+        if let Some(unit) = &item.unit {
+            check_length(&mut errors, &format!("Item {} unit", line), unit, 3, 8);
+        }
+        if !GST_RATES.iter().any(|rt| (rt - item.gst_rt).abs() < 0.001) {
+            errors.push(format!("Item {}: GST rate {}% is not a notified rate", line, item.gst_rt));
+        }
+        if item.qty <= 0.0 && item.is_servc == "N" {
+            errors.push(format!("Item {}: quantity is required for goods", line));
+        }
+    }
+
+    let val = &payload.val_dtls;
+    let ass_val: f64 = payload.item_list.iter().map(|i| i.ass_amt).sum();
+    if (ass_val - val.ass_val).abs() > 1.0 {
+        errors.push(format!(
+            "Total assessable value {} does not match the sum of the items {:.2}",
+            val.ass_val, ass_val
+        ));
+    }
+    if val.rnd_off_amt.abs() > 99.99 {
+        errors.push(format!("Round-off amount {} exceeds ₹99.99", val.rnd_off_amt));
+    }
+
+    errors
+}
+
+#[derive(Clone)]
+pub struct EInvoiceService {
+    invoice_repo: InvoiceRepository,
+    org_repo: OrganisationRepository,
+    customer_repo: CustomerRepository,
+    catalog_repo: CatalogItemRepository,
+    client: Arc<dyn IrpClient>,
+}
+
+impl EInvoiceService {
+    pub fn new(
+        invoice_repo: InvoiceRepository,
+        org_repo: OrganisationRepository,
+        customer_repo: CustomerRepository,
+        catalog_repo: CatalogItemRepository,
+        client: Arc<dyn IrpClient>,
+    ) -> Self {
+        Self {
+            invoice_repo,
+            org_repo,
+            customer_repo,
+            catalog_repo,
+            client,
+        }
+    }
+
+    async fn load_invoice(&self, id: &str, org_email: &str) -> Result<Invoice, ApiError> {
+        self.invoice_repo
+            .get_invoice_by_id(org_email, id)
+            .await?
+            .ok_or_else(|| ApiError::NotFound(format!("Invoice with id {} not found", id)))
+    }
+
+    /// Payload for the invoice with everything that stops it registering
+    async fn prepare(&self, invoice: &Invoice) -> Result<(EInvoicePayload, Vec<String>), ApiError> {
+        let org = self
+            .org_repo
+            .get_organisation_by_email(invoice_org_email(invoice))
+            .await?;
+
+        let gstin = validation::normalize_gstin(&invoice.billcustomer_gstin);
+        let customer = if gstin.is_empty() || validation::is_unregistered(&gstin) {
+            None
+        } else {
+            self.customer_repo
+                .find_by_gstin(invoice_org_email(invoice), &gstin)
+                .await?
+        };
+
+        let units = catalog_units(&self.catalog_repo, invoice).await?;
+        let (payload, mut errors) = build_payload(invoice, &org, customer.as_ref(), &units);
+        if !org.turnover_above_five_crore() {
+            errors.insert(
+                0,
+                "E-invoicing applies above ₹5 crore turnover; set the organisation's turnoverBand"
+                    .to_string(),
+            );
+        }
+        if !invoice.can_adjust() {
+            errors.insert(0, "Only issued invoices can be e-invoiced".to_string());
+        }
+        errors.extend(schema_errors(&payload));
+        Ok((payload, errors))
+    }
+
+    /// NIC v1.1 JSON for the invoice with the local validation result
+    pub async fn preview(&self, id: &str, org_email: &str) -> Result<EInvoicePreview, ApiError> {
+        let invoice = self.load_invoice(id, org_email).await?;
+        let (payload, errors) = self.prepare(&invoice).await?;
+        Ok(EInvoicePreview {
+            invoice_id: id.to_string(),
+            payload,
+            errors,
+        })
+    }
+
+    /// Validate the payload, register it through the IRP client and store
+    /// the IRN on the invoice
+    pub async fn generate_irn(&self, id: &str, org_email: &str) -> Result<Invoice, ApiError> {
+        let invoice = self.load_invoice(id, org_email).await?;
+        if invoice.has_irn() {
+            return Err(ApiError::Conflict(format!(
+                "Invoice {} already has an IRN",
+                invoice.invoice_number
+            )));
+        }
+
+        let (payload, errors) = self.prepare(&invoice).await?;
+        if !errors.is_empty() {
+            return Err(ApiError::ValidationError(errors.join("; ")));
+        }
+
+        let response = self.client.generate_irn(&payload).await?;
+        log::info!(
+            "Invoice {} registered with IRN {} via {}",
+            invoice.invoice_number,
+            response.irn,
+            self.client.name()
+        );
+        self.store(id, response, self.client.name()).await
+    }
+
+    /// Store an IRN obtained outside the system, e.g. registered on the IRP
+    /// portal directly
+    pub async fn record_irn(
+        &self,
+        id: &str,
+        response: IrpResponse,
+        org_email: &str,
+    ) -> Result<Invoice, ApiError> {
+        let invoice = self.load_invoice(id, org_email).await?;
+        if invoice.has_irn() {
+            return Err(ApiError::Conflict(format!(
+                "Invoice {} already has an IRN",
+                invoice.invoice_number
+            )));
+        }
+        if !invoice.can_adjust() {
+            return Err(ApiError::Conflict(
+                "Only issued invoices can be e-invoiced".to_string(),
+            ));
+        }
+        if !IRN_REGEX.is_match(response.irn.trim()) {
+            return Err(ApiError::ValidationError(
+                "IRN must be 64 hexadecimal characters".to_string(),
+            ));
+        }
+        if response.ack_no.trim().is_empty() || response.signed_qr_code.trim().is_empty() {
+            return Err(ApiError::ValidationError(
+                "Acknowledgement number and signed QR code are required".to_string(),
+            ));
+        }
+
+        self.store(id, response, "manual").await
+    }
+
+    async fn store(
+        &self,
+        id: &str,
+        response: IrpResponse,
+        source: &str,
+    ) -> Result<Invoice, ApiError> {
+        let details = EInvoiceDetails {
+            irn: response.irn.trim().to_lowercase(),
+            ack_no: response.ack_no.trim().to_string(),
+            ack_date: response.ack_dt.trim().to_string(),
+            signed_qr_code: response.signed_qr_code.trim().to_string(),
+            signed_invoice: response.signed_invoice,
+            source: source.to_string(),
+            registered_at: DateTime::now(),
+        };
+
+        self.invoice_repo
+            .set_fields(id, doc! { "eInvoice": money::to_bson(&details)? })
+            .await?
+            .ok_or_else(|| ApiError::NotFound(format!("Invoice with id {} not found", id)))
+    }
+}
@@ -0,0 +1,444 @@
+use std::collections::HashMap;
+
+use lazy_static::lazy_static;
+use mongodb::bson::{doc, DateTime};
+use regex::Regex;
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+
+use crate::error::ApiError;
+use crate::models::e_way_bill::{
+    EWayBillDetails, EWayBillEntry, EWayBillFile, EWayBillItem, EWayBillPreview,
+    RecordEWayBillRequest, TransportDetails, TransportMode, EWB_BULK_VERSION,
+};
+use crate::models::invoice::{Invoice, InvoiceItem};
+use crate::models::money::{self, Money};
+use crate::models::organisation::Organisation;
+use crate::repository::{CatalogItemRepository, InvoiceRepository, OrganisationRepository};
+use crate::services::e_invoice_service::{
+    catalog_units, clip, first_non_empty, invoice_org_email, is_export, split_address,
+};
+use crate::services::gstr1_service::amount;
+use crate::utils::date::{self, NIC_DATE_FORMAT};
+use crate::utils::{gst, validation};
+
+/// Consignments above this value need an e-way bill
+const EWB_THRESHOLD: i64 = 50_000;
+
+/// Longest distance the portal accepts, in km
+const MAX_DISTANCE_KM: u32 = 4000;
+
+/// State code the portal uses for recipients outside India
+const EXPORT_STATE_CODE: u8 = 99;
+
+lazy_static! {
+    /// Registration plates: "KA01AB1234", "DL3CAF0001", and Bharat series "22BH1234AB"
+    static ref VEHICLE_REGEX: Regex =
+        Regex::new(r"^([A-Z]{2}[0-9]{1,2}[A-Z]{0,3}[0-9]{4}|[0-9]{2}BH[0-9]{4}[A-Z]{1,2})$")
+            .unwrap();
+    static ref EWB_NO_REGEX: Regex = Regex::new(r"^[0-9]{12}$").unwrap();
+}
+
+/// Upper-case with spaces and hyphens removed
+fn normalize_vehicle_number(value: &str) -> String {
+    value
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .collect::<String>()
+        .to_uppercase()
+}
+
+fn state_code_number(code: &str) -> u8 {
+    code.parse().unwrap_or(0)
+}
+
+fn percent(value: &str) -> f64 {
+    gst::parse_decimal("Tax percent", value)
+        .ok()
+        .and_then(|p| p.normalize().to_f64())
+        .unwrap_or(0.0)
+}
+
+/// Services do not move, so only HSN lines go on the e-way bill
+fn is_goods(item: &InvoiceItem) -> bool {
+    !item.hsn_sac.trim().starts_with("99")
+}
+
+/// Check the Part-B transport details the portal insists on
+fn transport_errors(transport: &TransportDetails) -> Vec<String> {
+    let mut errors = Vec::new();
+
+    if transport.distance_km > MAX_DISTANCE_KM {
+        errors.push(format!("Distance cannot exceed {} km", MAX_DISTANCE_KM));
+    }
+
+    let transporter_id = validation::normalize_gstin(&transport.transporter_id);
+    if !transporter_id.is_empty() && !validation::GSTIN_REGEX.is_match(&transporter_id) {
+        errors.push(format!(
+            "Transporter ID '{}' must be a 15-character GSTIN or TRANSIN",
+            transport.transporter_id
+        ));
+    }
+
+    match transport.mode {
+        TransportMode::Road => {
+            let vehicle = normalize_vehicle_number(&transport.vehicle_number);
+            if vehicle.is_empty() && transporter_id.is_empty() {
+                errors.push(
+                    "Road transport needs a vehicle number or a transporter ID".to_string(),
+                );
+            }
+            if !vehicle.is_empty() && !VEHICLE_REGEX.is_match(&vehicle) {
+                errors.push(format!(
+                    "Vehicle number '{}' is not a valid registration number",
+                    transport.vehicle_number
+                ));
+            }
+        }
+        TransportMode::Rail | TransportMode::Air | TransportMode::Ship => {
+            if transport.transport_doc_number.trim().is_empty() {
+                errors.push(format!(
+                    "{:?} transport needs the transport document number",
+                    transport.mode
+                ));
+            }
+            if date::reformat(&transport.transport_doc_date, NIC_DATE_FORMAT).is_none() {
+                errors.push(format!(
+                    "{:?} transport needs the transport document date as YYYY-MM-DD",
+                    transport.mode
+                ));
+            }
+        }
+    }
+
+    errors
+}
+
+/// Build the bulk-upload entry for the goods lines of an invoice, with the
+/// problems found along the way
+fn build_file(
+    invoice: &Invoice,
+    org: &Organisation,
+    transport: &TransportDetails,
+    units: &HashMap<String, String>,
+) -> (EWayBillFile, Vec<String>, Vec<String>) {
+    let mut errors = Vec::new();
+    let mut warnings = Vec::new();
+    let export = is_export(invoice);
+
+    if !invoice.can_adjust() {
+        errors.push("Only issued invoices can have an e-way bill".to_string());
+    }
+    // Values on the e-way bill are in INR at the invoice's exchange rate
+    let converted = invoice.in_inr().unwrap_or_else(|message| {
+        errors.push(message);
+        invoice.clone()
+    });
+    let invoice = &converted;
+    if let Some(existing) = &invoice.e_way_bill {
+        warnings.push(format!(
+            "Invoice already has e-way bill {} valid up to {}",
+            existing.ewb_no, existing.valid_upto
+        ));
+    }
+
+    let doc_date = date::reformat(&invoice.invoice_date, NIC_DATE_FORMAT).unwrap_or_else(|| {
+        errors.push(format!(
+            "Invoice date '{}' is not YYYY-MM-DD",
+            invoice.invoice_date
+        ));
+        invoice.invoice_date.clone()
+    });
+
+    // Consignor
+    let from_gstin =
+        validation::normalize_gstin(first_non_empty(&[&invoice.gst_in, &org.gst_in]));
+    if let Err(e) = validation::validate_gstin(&from_gstin) {
+        errors.push(format!("Supplier: {}", e));
+    }
+    let from_state = state_code_number(from_gstin.get(0..2).unwrap_or_default());
+    let org_address = org.addresses.first().map(|a| a.value.as_str()).unwrap_or("");
+    let from_address =
+        split_address(first_non_empty(&[&invoice.company_address, org_address]));
+    if from_address.pin.is_none() {
+        errors.push("Supplier address has no PIN code".to_string());
+    }
+
+    // Consignee: bill-to party, delivered to the ship-to address when there is one
+    let ship_to = !invoice.shipcustomer_address.trim().is_empty()
+        && invoice.shipcustomer_address.trim() != invoice.billcustomer_address.trim();
+    let delivery_address = split_address(if ship_to {
+        &invoice.shipcustomer_address
+    } else {
+        &invoice.billcustomer_address
+    });
+    if delivery_address.pin.is_none() {
+        errors.push(if export {
+            "Ship-to address (port of export) has no PIN code".to_string()
+        } else {
+            "Delivery address has no PIN code".to_string()
+        });
+    }
+
+    let (to_gstin, to_state, actual_to_state) = if export {
+        let port_state = transport.port_state_code.trim();
+        if gst::state_name(port_state).is_none() {
+            errors.push("Exports need the state code of the port of export".to_string());
+        }
+        (
+            "URP".to_string(),
+            EXPORT_STATE_CODE,
+            state_code_number(port_state),
+        )
+    } else {
+        let gstin = validation::normalize_gstin(&invoice.billcustomer_gstin);
+        let gstin = if gstin.is_empty() || validation::is_unregistered(&gstin) {
+            "URP".to_string()
+        } else {
+            gstin
+        };
+        let pos = gst::resolve_state_code(&invoice.place_of_supply).unwrap_or_else(|| {
+            errors.push(format!(
+                "Place of supply '{}' is not a known state",
+                invoice.place_of_supply
+            ));
+            ""
+        });
+        let ship_gstin = validation::normalize_gstin(&invoice.shipcustomer_gstin);
+        let actual = match ship_gstin.get(0..2) {
+            Some(code) if ship_to && gst::state_name(code).is_some() => code,
+            _ => pos,
+        };
+        (gstin, state_code_number(pos), state_code_number(actual))
+    };
+
+    // Goods lines
+    let goods: Vec<&InvoiceItem> = invoice.items.iter().filter(|i| is_goods(i)).collect();
+    let services = invoice.items.len() - goods.len();
+    if goods.is_empty() {
+        errors.push("Invoice has no goods lines; e-way bills cover goods only".to_string());
+    } else if services > 0 {
+        warnings.push(format!(
+            "{} service line(s) left out of the e-way bill",
+            services
+        ));
+    }
+
+    let mut item_list = Vec::with_capacity(goods.len());
+    let mut taxable = Money::ZERO;
+    let mut cgst = Money::ZERO;
+    let mut sgst = Money::ZERO;
+    let mut igst = Money::ZERO;
+    let mut main_hsn = (Money::ZERO, 0u32);
+    for (idx, item) in goods.iter().enumerate() {
+        let line = idx + 1;
+        let hsn = item.hsn_sac.trim();
+        if let Err(e) = validation::validate_hsn_sac(hsn, 4) {
+            errors.push(format!("Item {}: {}", line, e));
+        }
+        let hsn_code: u32 = hsn.parse().unwrap_or(0);
+        let quantity = gst::parse_decimal("Quantity", &item.hours).unwrap_or_else(|e| {
+            errors.push(format!("Item {}: {}", line, e));
+            Decimal::ZERO
+        });
+        if quantity.is_zero() {
+            errors.push(format!("Item {}: quantity is required", line));
+        }
+
+        taxable += item.item_total;
+        cgst += item.cgst.cgst_amount;
+        sgst += item.sgst.sgst_amount;
+        igst += item.igst.igst_amount;
+        if item.item_total > main_hsn.0 || main_hsn.1 == 0 {
+            main_hsn = (item.item_total, hsn_code);
+        }
+
+        let unit = item
+            .catalog_item_id
+            .as_ref()
+            .and_then(|id| units.get(id))
+            .filter(|unit| !unit.is_empty())
+            .cloned()
+            .unwrap_or_else(|| "OTH".to_string());
+        item_list.push(EWayBillItem {
+            item_no: line as u32,
+            product_name: clip(&item.description, 100),
+            product_desc: clip(&item.description, 100),
+            hsn_code,
+            quantity: quantity.round_dp(3).to_f64().unwrap_or(0.0),
+            qty_unit: unit,
+            taxable_amount: amount(item.item_total),
+            sgst_rate: percent(&item.sgst.sgst_percent),
+            cgst_rate: percent(&item.cgst.cgst_percent),
+            igst_rate: percent(&item.igst.igst_percent),
+            cess_rate: 0.0,
+            cess_non_advol: 0.0,
+        });
+    }
+
+    let consignment = taxable + cgst + sgst + igst;
+    if consignment < Money::new(Decimal::from(EWB_THRESHOLD)) {
+        warnings.push(format!(
+            "Consignment value {} is below the ₹{} threshold; an e-way bill is optional",
+            consignment, EWB_THRESHOLD
+        ));
+    }
+
+    errors.extend(transport_errors(transport));
+
+    let entry = EWayBillEntry {
+        user_gstin: from_gstin.clone(),
+        supply_type: "O".to_string(),
+        sub_supply_type: if export { 3 } else { 1 },
+        sub_supply_desc: String::new(),
+        doc_type: "INV".to_string(),
+        doc_no: invoice.invoice_number.clone(),
+        doc_date,
+        trans_type: if ship_to { 2 } else { 1 },
+
+        from_trd_name: clip(
+            first_non_empty(&[&org.company_name, &org.organisation_name, &invoice.company_name]),
+            100,
+        ),
+        from_gstin,
+        from_addr1: from_address.addr1,
+        from_addr2: from_address.addr2.unwrap_or_default(),
+        from_place: from_address.loc,
+        from_pincode: from_address.pin.unwrap_or(0),
+        from_state_code: from_state,
+        actual_from_state_code: from_state,
+
+        to_gstin,
+        to_trd_name: clip(&invoice.billcustomer_name, 100),
+        to_addr1: delivery_address.addr1,
+        to_addr2: delivery_address.addr2.unwrap_or_default(),
+        to_place: delivery_address.loc,
+        to_pincode: delivery_address.pin.unwrap_or(0),
+        to_state_code: to_state,
+        actual_to_state_code: actual_to_state,
+
+        total_value: amount(taxable),
+        cgst_value: amount(cgst),
+        sgst_value: amount(sgst),
+        igst_value: amount(igst),
+        cess_value: 0.0,
+        tot_non_advol_val: 0.0,
+        oth_value: 0.0,
+        tot_inv_value: amount(consignment),
+
+        trans_mode: transport.mode.code(),
+        trans_distance: transport.distance_km,
+        transporter_name: clip(&transport.transporter_name, 100),
+        transporter_id: validation::normalize_gstin(&transport.transporter_id),
+        trans_doc_no: clip(&transport.transport_doc_number, 15),
+        trans_doc_date: date::reformat(&transport.transport_doc_date, NIC_DATE_FORMAT).unwrap_or_default(),
+        vehicle_no: normalize_vehicle_number(&transport.vehicle_number),
+        vehicle_type: if transport.over_dimensional { "O" } else { "R" }.to_string(),
+        main_hsn_code: main_hsn.1,
+        item_list,
+    };
+
+    let file = EWayBillFile {
+        version: EWB_BULK_VERSION.to_string(),
+        bill_lists: vec![entry],
+    };
+    (file, errors, warnings)
+}
+
+#[derive(Clone)]
+pub struct EWayBillService {
+    invoice_repo: InvoiceRepository,
+    org_repo: OrganisationRepository,
+    catalog_repo: CatalogItemRepository,
+}
+
+impl EWayBillService {
+    pub fn new(
+        invoice_repo: InvoiceRepository,
+        org_repo: OrganisationRepository,
+        catalog_repo: CatalogItemRepository,
+    ) -> Self {
+        Self {
+            invoice_repo,
+            org_repo,
+            catalog_repo,
+        }
+    }
+
+    async fn load_invoice(&self, id: &str, org_email: &str) -> Result<Invoice, ApiError> {
+        self.invoice_repo
+            .get_invoice_by_id(org_email, id)
+            .await?
+            .ok_or_else(|| ApiError::NotFound(format!("Invoice with id {} not found", id)))
+    }
+
+    /// Bulk-upload JSON for the invoice and the given transport, with the
+    /// mandatory fields that are missing or malformed
+    pub async fn prepare(
+        &self,
+        id: &str,
+        transport: &TransportDetails,
+        org_email: &str,
+    ) -> Result<EWayBillPreview, ApiError> {
+        let invoice = self.load_invoice(id, org_email).await?;
+        let org = self
+            .org_repo
+            .get_organisation_by_email(invoice_org_email(&invoice))
+            .await?;
+        let units = catalog_units(&self.catalog_repo, &invoice).await?;
+
+        let (file, errors, warnings) = build_file(&invoice, &org, transport, &units);
+        Ok(EWayBillPreview {
+            invoice_id: id.to_string(),
+            file,
+            errors,
+            warnings,
+        })
+    }
+
+    /// Store the e-way bill number and validity the portal returned
+    pub async fn record(
+        &self,
+        id: &str,
+        req: RecordEWayBillRequest,
+        org_email: &str,
+    ) -> Result<Invoice, ApiError> {
+        let invoice = self.load_invoice(id, org_email).await?;
+        if !invoice.can_adjust() {
+            return Err(ApiError::Conflict(
+                "Only issued invoices can have an e-way bill".to_string(),
+            ));
+        }
+
+        let ewb_no: String = req.ewb_no.chars().filter(|c| !c.is_whitespace()).collect();
+        if !EWB_NO_REGEX.is_match(&ewb_no) {
+            return Err(ApiError::ValidationError(
+                "E-way bill number must be 12 digits".to_string(),
+            ));
+        }
+        if req.valid_upto.trim().is_empty() {
+            return Err(ApiError::ValidationError(
+                "Validity (validUpto) is required".to_string(),
+            ));
+        }
+        if let Some(transport) = &req.transport {
+            let errors = transport_errors(transport);
+            if !errors.is_empty() {
+                return Err(ApiError::ValidationError(errors.join("; ")));
+            }
+        }
+
+        let details = EWayBillDetails {
+            ewb_no,
+            ewb_date: req.ewb_date.trim().to_string(),
+            valid_upto: req.valid_upto.trim().to_string(),
+            transport: req.transport,
+            recorded_at: DateTime::now(),
+        };
+
+        self.invoice_repo
+            .set_fields(id, doc! { "eWayBill": money::to_bson(&details)? })
+            .await?
+            .ok_or_else(|| ApiError::NotFound(format!("Invoice with id {} not found", id)))
+    }
+}
@@ -0,0 +1,222 @@
+use mongodb::bson::DateTime;
+
+use crate::error::ApiError;
+use crate::models::estimate::{Estimate, EstimateRequest};
+use crate::models::invoice::Invoice;
+use crate::models::money;
+use crate::models::organisation::Organisation;
+use crate::repository::{EstimateRepository, OrganisationRepository};
+use crate::services::InvoiceService;
+use crate::utils::{date, gst};
+
+#[derive(Clone)]
+pub struct EstimateService {
+    repository: EstimateRepository,
+    org_repo: OrganisationRepository,
+    invoice_service: InvoiceService,
+}
+
+impl EstimateService {
+    pub fn new(
+        repository: EstimateRepository,
+        org_repo: OrganisationRepository,
+        invoice_service: InvoiceService,
+    ) -> Self {
+        Self {
+            repository,
+            org_repo,
+            invoice_service,
+        }
+    }
+
+    /// Validate and compute line taxes and totals the same way invoices do
+    fn compute_taxes(estimate: &mut Estimate, org: &Organisation) -> Result<(), ApiError> {
+        if estimate.estimate_date.trim().is_empty() {
+            return Err(ApiError::ValidationError("Estimate date is required".to_string()));
+        }
+        if !estimate.valid_until.trim().is_empty() && estimate.valid_until < estimate.estimate_date
+        {
+            return Err(ApiError::ValidationError(
+                "Valid until cannot be before the estimate date".to_string(),
+            ));
+        }
+        if !money::is_valid_currency_code(&estimate.currency) {
+            return Err(ApiError::ValidationError(format!(
+                "Invalid currency code '{}'",
+                estimate.currency
+            )));
+        }
+
+        let supply_type = gst::supply_type_for(
+            org.state_code(),
+            &estimate.invoice_type,
+            &estimate.place_of_supply,
+        )?;
+        let totals = gst::compute_item_taxes(&mut estimate.items, supply_type, org.rounding())?;
+        estimate.sub_total = totals.sub_total;
+        estimate.totalcgst = totals.total_cgst;
+        estimate.totalsgst = totals.total_sgst;
+        estimate.totaligst = totals.total_igst;
+        estimate.total = totals.total;
+        Ok(())
+    }
+
+    pub async fn create_estimate(
+        &self,
+        mut estimate: EstimateRequest,
+        org_email: &str,
+    ) -> Result<Estimate, ApiError> {
+        let org = self.org_repo.get_organisation_by_email(org_email).await?;
+        Self::compute_taxes(&mut estimate, &org)?;
+
+        let sequence = self
+            .org_repo
+            .next_sequence(org_email, "lastEstimateSequence")
+            .await?;
+        let prefix = if org.estimate_prefix.trim().is_empty() {
+            "EST"
+        } else {
+            org.estimate_prefix.trim()
+        };
+
+        let now = DateTime::now();
+        estimate.id = None;
+        estimate.estimate_number = format!("{}-{:03}", prefix, sequence);
+        estimate.status = Default::default();
+        estimate.invoice_id = None;
+        estimate.org_email = org_email.to_string();
+        estimate.created_at = Some(now);
+        estimate.updated_at = Some(now);
+
+        self.repository.create(estimate).await
+    }
+
+    pub async fn get_estimates(&self, org_email: &str) -> Result<Vec<Estimate>, ApiError> {
+        let today = date::today();
+        let expired = self.repository.mark_expired(&today).await?;
+        if expired > 0 {
+            log::info!("Marked {} estimate(s) expired", expired);
+        }
+        self.repository.find_all(org_email).await
+    }
+
+    pub async fn get_estimate_by_id(
+        &self,
+        id: &str,
+        org_email: &str,
+    ) -> Result<Estimate, ApiError> {
+        self.repository
+            .find_by_id(org_email, id)
+            .await?
+            .ok_or_else(|| ApiError::NotFound(format!("Estimate with id {} not found", id)))
+    }
+
+    pub async fn update_estimate(
+        &self,
+        id: &str,
+        mut estimate: EstimateRequest,
+        org_email: &str,
+    ) -> Result<Estimate, ApiError> {
+        let existing = self.get_estimate_by_id(id, org_email).await?;
+        if !existing.is_editable() {
+            return Err(ApiError::Conflict(format!(
+                "Estimate in {:?} status cannot be edited",
+                existing.status
+            )));
+        }
+
+        let org = self.org_repo.get_organisation_by_email(org_email).await?;
+        Self::compute_taxes(&mut estimate, &org)?;
+
+        estimate.id = existing.id;
+        estimate.estimate_number = existing.estimate_number;
+        estimate.status = existing.status;
+        estimate.invoice_id = existing.invoice_id;
+        estimate.org_email = existing.org_email;
+        estimate.created_at = existing.created_at;
+        estimate.updated_at = Some(DateTime::now());
+
+        self.repository.replace(&estimate).await?;
+        Ok(estimate)
+    }
+
+    pub async fn send_estimate(&self, id: &str, org_email: &str) -> Result<Estimate, ApiError> {
+        self.transition(id, org_email, Estimate::send).await
+    }
+
+    pub async fn accept_estimate(&self, id: &str, org_email: &str) -> Result<Estimate, ApiError> {
+        self.transition(id, org_email, Estimate::accept).await
+    }
+
+    pub async fn decline_estimate(&self, id: &str, org_email: &str) -> Result<Estimate, ApiError> {
+        self.transition(id, org_email, Estimate::decline).await
+    }
+
+    async fn transition<F>(&self, id: &str, org_email: &str, apply: F) -> Result<Estimate, ApiError>
+    where
+        F: FnOnce(&mut Estimate) -> Result<(), String>,
+    {
+        let mut estimate = self.get_estimate_by_id(id, org_email).await?;
+        apply(&mut estimate).map_err(ApiError::Conflict)?;
+        self.repository.replace(&estimate).await?;
+        Ok(estimate)
+    }
+
+    /// Create a Draft invoice from the estimate and link both documents.
+    /// Seller details come from the organisation; the invoice is dated today.
+    pub async fn convert_to_invoice(
+        &self,
+        id: &str,
+        org_email: &str,
+    ) -> Result<(Estimate, Invoice), ApiError> {
+        let mut estimate = self.get_estimate_by_id(id, org_email).await?;
+        if !estimate.can_convert() {
+            return Err(ApiError::Conflict(format!(
+                "Estimate in {:?} status cannot be converted",
+                estimate.status
+            )));
+        }
+
+        let org = self.org_repo.get_organisation_by_email(org_email).await?;
+        let mut invoice = estimate.to_invoice();
+        invoice.company_name = org.company_name.clone();
+        invoice.gst_in = org.gst_in.clone();
+        invoice.company_address = org
+            .addresses
+            .first()
+            .map(|a| a.value.clone())
+            .unwrap_or_default();
+        invoice.company_phone = org.phone.clone();
+        invoice.company_email = org.email.clone();
+        invoice.invoice_date = date::today();
+        invoice.invoice_terms = org.payment_terms.clone();
+
+        let invoice = self
+            .invoice_service
+            .create_linked_invoice(invoice, org_email)
+            .await?;
+
+        let invoice_id = invoice.id.map(|id| id.to_hex()).unwrap_or_default();
+        estimate.mark_invoiced(invoice_id);
+        self.repository.replace(&estimate).await?;
+
+        log::info!(
+            "Estimate {} converted to invoice {}",
+            estimate.estimate_number,
+            invoice.invoice_number
+        );
+        Ok((estimate, invoice))
+    }
+
+    pub async fn delete_estimate(&self, id: &str, org_email: &str) -> Result<bool, ApiError> {
+        let existing = self.get_estimate_by_id(id, org_email).await?;
+        if !existing.is_editable() {
+            return Err(ApiError::Conflict(format!(
+                "Estimate in {:?} status cannot be deleted",
+                existing.status
+            )));
+        }
+
+        self.repository.delete(org_email, id).await
+    }
+}
@@ -1,15 +1,21 @@
-use crate::models::expense::Expense;
+use crate::error::ApiError;
+use crate::models::expense::{
+    Expense, ReviewAction, ReviewExpenseRequest, SubmitExpenseRequest,
+};
+use crate::models::money::Money;
 use crate::repository::expense_repository::{ExpenseRepository, ExpenseSummary};
+use crate::services::LedgerService;
 use mongodb::bson::DateTime;
 
 #[derive(Clone)]
 pub struct ExpenseService {
     repo: ExpenseRepository,
+    ledger: LedgerService,
 }
 
 impl ExpenseService {
-    pub fn new(repo: ExpenseRepository) -> Self {
-        Self { repo }
+    pub fn new(repo: ExpenseRepository, ledger: LedgerService) -> Self {
+        Self { repo, ledger }
     }
 
     /// Create a new expense with validation
@@ -26,8 +32,7 @@ impl ExpenseService {
         }
 
         // Calculate total amount from items
-        let calculated_total: f64 = req.items.iter().map(|item| item.amount).sum();
-        req.total_amount = calculated_total;
+        req.calculate_total();
 
         // Ensure timestamps are set
         let now = DateTime::now();
@@ -39,14 +44,14 @@ impl ExpenseService {
         }
 
         // Validate amounts are non-negative
-        if req.total_amount < 0.0 {
+        if req.total_amount.is_negative() {
             return Err(mongodb::error::Error::custom(
                 "Total amount cannot be negative",
             ));
         }
 
         for (idx, item) in req.items.iter().enumerate() {
-            if item.amount < 0.0 {
+            if item.amount.is_negative() {
                 return Err(mongodb::error::Error::custom(format!(
                     "Item {} amount cannot be negative",
                     idx + 1
@@ -62,8 +67,9 @@ impl ExpenseService {
         &self,
         page: Option<u64>,
         limit: Option<i64>,
+        org_email: &str,
     ) -> mongodb::error::Result<Vec<Expense>> {
-        self.repo.get_all_expenses(page, limit).await
+        self.repo.get_all_expenses(org_email, page, limit).await
     }
 
     /// Get expenses by project/cost center
@@ -72,9 +78,10 @@ impl ExpenseService {
         project_cost_center: &str,
         page: Option<u64>,
         limit: Option<i64>,
+        org_email: &str,
     ) -> mongodb::error::Result<Vec<Expense>> {
         self.repo
-            .get_expenses_by_project(project_cost_center, page, limit)
+            .get_expenses_by_project(org_email, project_cost_center, page, limit)
             .await
     }
 
@@ -83,6 +90,7 @@ impl ExpenseService {
         &self,
         start_date: DateTime,
         end_date: DateTime,
+        org_email: &str,
     ) -> mongodb::error::Result<Vec<Expense>> {
         if start_date > end_date {
             return Err(mongodb::error::Error::custom(
@@ -91,13 +99,17 @@ impl ExpenseService {
         }
 
         self.repo
-            .get_expenses_by_date_range(start_date, end_date)
+            .get_expenses_by_date_range(org_email, start_date, end_date)
             .await
     }
 
     /// Get a single expense by ID
-    pub async fn get_expense_by_id(&self, id: &str) -> mongodb::error::Result<Option<Expense>> {
-        self.repo.get_expense_by_id(id).await
+    pub async fn get_expense_by_id(
+        &self,
+        id: &str,
+        org_email: &str,
+    ) -> mongodb::error::Result<Option<Expense>> {
+        self.repo.get_expense_by_id(org_email, id).await
     }
 
     /// Update an existing expense
@@ -105,9 +117,10 @@ impl ExpenseService {
         &self,
         id: &str,
         mut req: Expense,
+        org_email: &str,
     ) -> mongodb::error::Result<Option<Expense>> {
         // Validate the expense exists
-        let existing = self.repo.get_expense_by_id(id).await?;
+        let existing = self.repo.get_expense_by_id(org_email, id).await?;
         if existing.is_none() {
             return Ok(None);
         }
@@ -124,21 +137,21 @@ impl ExpenseService {
         }
 
         // Recalculate total amount from items
-        let calculated_total: f64 = req.items.iter().map(|item| item.amount).sum();
-        req.total_amount = calculated_total;
+        req.calculate_total();
+        req.org_email = org_email.to_string();
 
         // Update the updated_at timestamp
         req.updated_at = Some(DateTime::now());
 
         // Validate amounts are non-negative
-        if req.total_amount < 0.0 {
+        if req.total_amount.is_negative() {
             return Err(mongodb::error::Error::custom(
                 "Total amount cannot be negative",
             ));
         }
 
         for (idx, item) in req.items.iter().enumerate() {
-            if item.amount < 0.0 {
+            if item.amount.is_negative() {
                 return Err(mongodb::error::Error::custom(format!(
                     "Item {} amount cannot be negative",
                     idx + 1
@@ -146,26 +159,153 @@ impl ExpenseService {
             }
         }
 
-        self.repo.update_expense(id, req).await
+        self.repo.update_expense(org_email, id, req).await
+    }
+
+    async fn get_existing(&self, id: &str, org_email: &str) -> Result<Expense, ApiError> {
+        self.repo
+            .get_expense_by_id(org_email, id)
+            .await?
+            .ok_or_else(|| ApiError::NotFound(format!("Expense with id {} not found", id)))
+    }
+
+    /// Apply a lifecycle change and save it, failing if the expense moved
+    /// on in the meantime
+    async fn transition<F>(&self, id: &str, org_email: &str, apply: F) -> Result<Expense, ApiError>
+    where
+        F: FnOnce(&mut Expense) -> Result<(), String>,
+    {
+        let (_, expense) = self.save_transition(id, org_email, apply).await?;
+        Ok(expense)
+    }
+
+    /// Like `transition`, returning the expense as it was before the change
+    /// alongside the saved one
+    async fn save_transition<F>(
+        &self,
+        id: &str,
+        org_email: &str,
+        apply: F,
+    ) -> Result<(Expense, Expense), ApiError>
+    where
+        F: FnOnce(&mut Expense) -> Result<(), String>,
+    {
+        let before = self.get_existing(id, org_email).await?;
+        let mut expense = before.clone();
+        apply(&mut expense).map_err(ApiError::Conflict)?;
+
+        if !self.repo.update_status(&expense, &before.status).await? {
+            return Err(ApiError::Conflict(
+                "Expense was changed by another request; reload and retry".to_string(),
+            ));
+        }
+        Ok((before, expense))
+    }
+
+    /// Put a saved transition back after its ledger posting failed
+    async fn undo_transition(&self, id: &str, before: &Expense, after: &Expense) {
+        match self.repo.update_status(before, &after.status).await {
+            Ok(true) => {}
+            Ok(false) => log::error!(
+                "Expense {} moved on before its failed posting could be undone",
+                id
+            ),
+            Err(e) => log::error!(
+                "Could not undo the status of expense {} after a failed posting: {}",
+                id,
+                e
+            ),
+        }
+    }
+
+    /// Submit a draft expense for approval
+    pub async fn submit_expense(
+        &self,
+        id: &str,
+        req: SubmitExpenseRequest,
+        org_email: &str,
+    ) -> Result<Expense, ApiError> {
+        let expense = self.get_existing(id, org_email).await?;
+        expense.validate().map_err(ApiError::ValidationError)?;
+
+        let user_id = req
+            .submitted_by
+            .or(expense.submitted_by)
+            .filter(|u| !u.trim().is_empty())
+            .ok_or_else(|| ApiError::ValidationError("submitted_by is required".to_string()))?;
+        self.transition(id, org_email, |e| e.submit(user_id)).await
+    }
+
+    /// Approve or reject a submitted expense. Approval posts the expense
+    /// to the organisation's ledger once the new
+    /// status is saved; a failed posting puts the status back.
+    pub async fn review_expense(
+        &self,
+        id: &str,
+        req: ReviewExpenseRequest,
+        org_email: &str,
+    ) -> Result<Expense, ApiError> {
+        let reviewer_id = req
+            .reviewer_id
+            .filter(|r| !r.trim().is_empty())
+            .ok_or_else(|| ApiError::ValidationError("reviewer_id is required".to_string()))?;
+
+        match req.action {
+            ReviewAction::Approve => {
+                let (before, expense) = self
+                    .save_transition(id, org_email, |e| e.approve(reviewer_id))
+                    .await?;
+                if let Err(e) = self.ledger.post_expense_approved(&expense, org_email).await {
+                    self.undo_transition(id, &before, &expense).await;
+                    return Err(e);
+                }
+                Ok(expense)
+            }
+            ReviewAction::Reject => {
+                let reason = req.reason.filter(|r| !r.trim().is_empty()).ok_or_else(|| {
+                    ApiError::ValidationError("A reason is required to reject".to_string())
+                })?;
+                self.transition(id, org_email, |e| e.reject(reviewer_id, reason))
+                    .await
+            }
+        }
+    }
+
+    /// Mark an approved expense as paid out and post the payment, putting
+    /// the status back if the posting fails
+    pub async fn reimburse_expense(&self, id: &str, org_email: &str) -> Result<Expense, ApiError> {
+        let (before, expense) = self
+            .save_transition(id, org_email, Expense::mark_reimbursed)
+            .await?;
+        if let Err(e) = self
+            .ledger
+            .post_expense_reimbursed(&expense, org_email)
+            .await
+        {
+            self.undo_transition(id, &before, &expense).await;
+            return Err(e);
+        }
+        Ok(expense)
     }
 
     /// Delete an expense
-    pub async fn delete_expense(&self, id: &str) -> mongodb::error::Result<bool> {
-        self.repo.delete_expense(id).await
+    pub async fn delete_expense(&self, id: &str, org_email: &str) -> mongodb::error::Result<bool> {
+        self.repo.delete_expense(org_email, id).await
     }
 
     /// Get total count of expenses
-    pub async fn count_expenses(&self) -> mongodb::error::Result<u64> {
-        self.repo.count_expenses().await
+    pub async fn count_expenses(&self, org_email: &str) -> mongodb::error::Result<u64> {
+        self.repo.count_expenses(org_email).await
     }
 
     /// Get count of expenses by project
     pub async fn count_expenses_by_project(
         &self,
         project_cost_center: &str,
+        org_email: &str,
     ) -> mongodb::error::Result<u64> {
         self.repo
-            .count_expenses_by_project(project_cost_center)
+            .count_expenses_by_project(org_email, project_cost_center)
             .await
     }
 
@@ -173,29 +313,48 @@ impl ExpenseService {
     pub async fn get_total_amount_by_project(
         &self,
         project_cost_center: &str,
-    ) -> mongodb::error::Result<f64> {
+        org_email: &str,
+    ) -> mongodb::error::Result<Money> {
         self.repo
-            .get_total_amount_by_project(project_cost_center)
+            .get_total_amount_by_project(org_email, project_cost_center)
             .await
     }
 
     /// Search expenses by title
-    pub async fn search_expenses(&self, search_term: &str) -> mongodb::error::Result<Vec<Expense>> {
+    pub async fn search_expenses(
+        &self,
+        search_term: &str,
+        org_email: &str,
+    ) -> mongodb::error::Result<Vec<Expense>> {
         if search_term.trim().is_empty() {
-            return self.repo.get_all_expenses(None, None).await;
+            return self.repo.get_all_expenses(org_email, None, None).await;
         }
 
-        self.repo.search_expenses_by_title(search_term).await
+        self.repo
+            .search_expenses_by_title(org_email, search_term)
+            .await
     }
 
     /// Get all unique project/cost centers
-    pub async fn get_all_projects(&self) -> mongodb::error::Result<Vec<String>> {
-        self.repo.get_all_projects().await
+    pub async fn get_all_projects(&self, org_email: &str) -> mongodb::error::Result<Vec<String>> {
+        self.repo.get_all_projects(org_email).await
+    }
+
+    /// Whether `filename` is a receipt on one of the organisation's expenses
+    pub async fn has_receipt(
+        &self,
+        filename: &str,
+        org_email: &str,
+    ) -> mongodb::error::Result<bool> {
+        self.repo.has_receipt(org_email, filename).await
     }
 
     /// Get expense statistics summary
-    pub async fn get_expense_summary(&self) -> mongodb::error::Result<ExpenseSummary> {
-        self.repo.get_expense_summary().await
+    pub async fn get_expense_summary(
+        &self,
+        org_email: &str,
+    ) -> mongodb::error::Result<ExpenseSummary> {
+        self.repo.get_expense_summary(org_email).await
     }
 
     /// Get expenses with filters
@@ -206,12 +365,13 @@ impl ExpenseService {
         end_date: Option<DateTime>,
         page: Option<u64>,
         limit: Option<i64>,
+        org_email: &str,
     ) -> mongodb::error::Result<Vec<Expense>> {
         match (project_cost_center, start_date, end_date) {
             // Filter by project only
             (Some(project), None, None) => {
                 self.repo
-                    .get_expenses_by_project(&project, page, limit)
+                    .get_expenses_by_project(org_email, &project, page, limit)
                     .await
             }
             // Filter by date range only
@@ -221,12 +381,14 @@ impl ExpenseService {
                         "Start date must be before end date",
                     ));
                 }
-                self.repo.get_expenses_by_date_range(start, end).await
+                self.repo
+                    .get_expenses_by_date_range(org_email, start, end)
+                    .await
             }
             // No filters - get all
-            (None, None, None) => self.repo.get_all_expenses(page, limit).await,
+            (None, None, None) => self.repo.get_all_expenses(org_email, page, limit).await,
             // Other combinations - fallback to getting all expenses
-            _ => self.repo.get_all_expenses(page, limit).await,
+            _ => self.repo.get_all_expenses(org_email, page, limit).await,
         }
     }
 
@@ -245,7 +407,7 @@ impl ExpenseService {
         }
 
         for (idx, item) in expense.items.iter().enumerate() {
-            if item.amount < 0.0 {
+            if item.amount.is_negative() {
                 return Err(format!("Item {} amount cannot be negative", idx + 1));
             }
 
@@ -258,8 +420,8 @@ impl ExpenseService {
             }
         }
 
-        let calculated_total: f64 = expense.items.iter().map(|item| item.amount).sum();
-        if calculated_total < 0.0 {
+        let calculated_total: Money = expense.items.iter().map(|item| item.amount).sum();
+        if calculated_total.is_negative() {
             return Err("Total amount cannot be negative".to_string());
         }
 
@@ -270,31 +432,32 @@ impl ExpenseService {
     pub async fn get_project_statistics(
         &self,
         project_cost_center: &str,
+        org_email: &str,
     ) -> mongodb::error::Result<ProjectStatistics> {
         let expenses = self
             .repo
-            .get_expenses_by_project(project_cost_center, None, None)
+            .get_expenses_by_project(org_email, project_cost_center, None, None)
             .await?;
 
         let total_count = expenses.len();
-        let total_amount: f64 = expenses.iter().map(|e| e.total_amount).sum();
+        let total_amount: Money = expenses.iter().map(|e| e.total_amount).sum();
         let avg_amount = if total_count > 0 {
-            total_amount / (total_count as f64)
+            (total_amount / rust_decimal::Decimal::from(total_count)).round_paise()
         } else {
-            0.0
+            Money::ZERO
         };
 
         let min_amount = expenses
             .iter()
             .map(|e| e.total_amount)
-            .min_by(|a, b| a.partial_cmp(b).unwrap())
-            .unwrap_or(0.0);
+            .min()
+            .unwrap_or_default();
 
         let max_amount = expenses
             .iter()
             .map(|e| e.total_amount)
-            .max_by(|a, b| a.partial_cmp(b).unwrap())
-            .unwrap_or(0.0);
+            .max()
+            .unwrap_or_default();
 
         Ok(ProjectStatistics {
             project_name: project_cost_center.to_string(),
@@ -312,8 +475,8 @@ impl ExpenseService {
 pub struct ProjectStatistics {
     pub project_name: String,
     pub total_expenses: usize,
-    pub total_amount: f64,
-    pub avg_amount: f64,
-    pub min_amount: f64,
-    pub max_amount: f64,
+    pub total_amount: Money,
+    pub avg_amount: Money,
+    pub min_amount: Money,
+    pub max_amount: Money,
 }
\ No newline at end of file
@@ -0,0 +1,738 @@
+use std::collections::BTreeMap;
+
+use chrono::NaiveDate;
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+
+use crate::error::ApiError;
+use crate::models::credit_debit_note::{CreditDebitNote, NoteType};
+use crate::models::gstr1::{
+    B2bInvoice, B2bParty, B2clInvoice, B2clState, B2csEntry, CdnrNote, CdnrParty, DocDetail,
+    DocIssue, DocRange, ExpGroup, ExpInvoice, ExpItem, Gstr1, Gstr1Counts, Gstr1Report,
+    HsnEntry, HsnSummary, ItemDetail, NumberedItem, ReturnIssue, TaxPeriod,
+};
+use crate::models::invoice::{Invoice, InvoiceItem, InvoiceStatus};
+use crate::models::money::{Money, DEFAULT_CURRENCY};
+use crate::repository::{CreditDebitNoteRepository, InvoiceRepository, OrganisationRepository};
+use crate::services::e_invoice_service::non_empty;
+use crate::utils::date::{self, GSTN_DATE_FORMAT};
+use crate::utils::{gst, validation};
+
+/// Offline tool schema version the file is written for
+const GSTR1_VERSION: &str = "GST3.0.4";
+
+/// Amount as the plain rupee figure the GSTN JSON uses
+pub(crate) fn amount(value: Money) -> f64 {
+    value.round_paise().to_f64()
+}
+
+fn rate(value: Decimal) -> f64 {
+    value.normalize().to_f64().unwrap_or(0.0)
+}
+
+/// Inter-state B2C invoices above this value are reported invoice-wise
+/// (B2CL): ₹2.5 lakh, lowered to ₹1 lakh from August 2024
+fn b2cl_threshold(period: &TaxPeriod) -> Money {
+    let cutover = NaiveDate::from_ymd_opt(2024, 8, 1).expect("valid date");
+    if period.first_day() >= cutover {
+        Money::new(Decimal::from(100_000))
+    } else {
+        Money::new(Decimal::from(250_000))
+    }
+}
+
+/// Taxable value and tax of one rate slab
+#[derive(Debug, Clone, Copy, Default)]
+struct Slab {
+    txval: Money,
+    iamt: Money,
+    camt: Money,
+    samt: Money,
+}
+
+impl Slab {
+    fn add_item(&mut self, item: &InvoiceItem) {
+        self.txval += item.item_total;
+        self.iamt += item.igst.igst_amount;
+        self.camt += item.cgst.cgst_amount;
+        self.samt += item.sgst.sgst_amount;
+    }
+
+    fn add(&mut self, other: &Slab) {
+        self.txval += other.txval;
+        self.iamt += other.iamt;
+        self.camt += other.camt;
+        self.samt += other.samt;
+    }
+
+    /// Inter-state supplies report IGST only, intra-state CGST + SGST only
+    fn detail(&self, rt: Decimal, inter_state: bool) -> ItemDetail {
+        ItemDetail {
+            txval: amount(self.txval),
+            rt: rate(rt),
+            iamt: inter_state.then(|| amount(self.iamt)),
+            camt: (!inter_state).then(|| amount(self.camt)),
+            samt: (!inter_state).then(|| amount(self.samt)),
+            csamt: 0.0,
+        }
+    }
+}
+
+/// Group document lines by GST rate
+fn slabs(items: &[InvoiceItem]) -> Result<BTreeMap<Decimal, Slab>, ApiError> {
+    let mut slabs: BTreeMap<Decimal, Slab> = BTreeMap::new();
+    for item in items {
+        let rt = gst::item_gst_rate(item)?.normalize();
+        slabs.entry(rt).or_default().add_item(item);
+    }
+    Ok(slabs)
+}
+
+fn numbered(slabs: &BTreeMap<Decimal, Slab>, inter_state: bool) -> Vec<NumberedItem> {
+    slabs
+        .iter()
+        .enumerate()
+        .map(|(idx, (rt, slab))| NumberedItem {
+            num: idx as u32 + 1,
+            itm_det: slab.detail(*rt, inter_state),
+        })
+        .collect()
+}
+
+#[derive(Default)]
+struct HsnTotals {
+    desc: String,
+    qty: Decimal,
+    slab: Slab,
+}
+
+/// Accumulates documents into the GSTR-1 sections
+struct Gstr1Builder {
+    supplier_state: Option<String>,
+    b2cl_threshold: Money,
+    b2b: BTreeMap<String, Vec<B2bInvoice>>,
+    b2cl: BTreeMap<String, Vec<B2clInvoice>>,
+    b2cs: BTreeMap<(String, String, Decimal), Slab>,
+    exp: BTreeMap<String, Vec<ExpInvoice>>,
+    cdnr: BTreeMap<String, Vec<CdnrNote>>,
+    hsn: BTreeMap<(String, Decimal), HsnTotals>,
+    /// doc_num -> (document number, cancelled)
+    docs: BTreeMap<u32, Vec<(String, bool)>>,
+    counts: Gstr1Counts,
+    issues: Vec<ReturnIssue>,
+}
+
+impl Gstr1Builder {
+    fn new(supplier_state: Option<&str>, period: &TaxPeriod) -> Self {
+        Self {
+            supplier_state: supplier_state.map(str::to_string),
+            b2cl_threshold: b2cl_threshold(period),
+            b2b: BTreeMap::new(),
+            b2cl: BTreeMap::new(),
+            b2cs: BTreeMap::new(),
+            exp: BTreeMap::new(),
+            cdnr: BTreeMap::new(),
+            hsn: BTreeMap::new(),
+            docs: BTreeMap::new(),
+            counts: Gstr1Counts::default(),
+            issues: Vec::new(),
+        }
+    }
+
+    fn issue(&mut self, document_type: &str, id: Option<String>, number: &str, message: String) {
+        self.issues.push(ReturnIssue {
+            document_type: document_type.to_string(),
+            document_id: id.unwrap_or_default(),
+            document_number: number.to_string(),
+            message,
+        });
+    }
+
+    fn skip_invoice(&mut self, invoice: &Invoice, message: String) {
+        self.counts.skipped += 1;
+        let id = invoice.id.map(|id| id.to_hex());
+        self.issue("invoice", id, &invoice.invoice_number, message);
+    }
+
+    fn add_invoice(&mut self, invoice: &Invoice) {
+        match invoice.status {
+            InvoiceStatus::Draft => {
+                let id = invoice.id.map(|id| id.to_hex());
+                self.issue(
+                    "invoice",
+                    id,
+                    &invoice.invoice_number,
+                    "Draft invoice is not reported until it is issued".to_string(),
+                );
+                return;
+            }
+            InvoiceStatus::Cancelled | InvoiceStatus::Void => {
+                self.docs
+                    .entry(1)
+                    .or_default()
+                    .push((invoice.invoice_number.clone(), true));
+                return;
+            }
+            _ => {}
+        }
+        self.docs
+            .entry(1)
+            .or_default()
+            .push((invoice.invoice_number.clone(), false));
+
+        if let Err(message) = self.classify_invoice(invoice) {
+            self.skip_invoice(invoice, message);
+        }
+    }
+
+    fn classify_invoice(&mut self, invoice: &Invoice) -> Result<(), String> {
+        let idt = date::reformat(&invoice.invoice_date, GSTN_DATE_FORMAT)
+            .ok_or_else(|| format!("Invoice date '{}' is not YYYY-MM-DD", invoice.invoice_date))?;
+        // GSTR-1 takes INR values; foreign-currency exports convert at the invoice's rate
+        let invoice = &invoice.in_inr()?;
+        let slabs = slabs(&invoice.items).map_err(|e| e.to_string())?;
+        let export = invoice.invoice_type.eq_ignore_ascii_case("international");
+
+        if export {
+            let exp_typ = invoice.effective_export_type().as_str();
+            let itms = slabs
+                .iter()
+                .map(|(rt, slab)| ExpItem {
+                    txval: amount(slab.txval),
+                    rt: rate(*rt),
+                    iamt: amount(slab.iamt),
+                    csamt: 0.0,
+                })
+                .collect();
+            self.exp.entry(exp_typ.to_string()).or_default().push(ExpInvoice {
+                inum: invoice.invoice_number.clone(),
+                idt,
+                val: amount(invoice.total),
+                sbpcode: non_empty(&invoice.port_code),
+                sbnum: non_empty(&invoice.shipping_bill_no),
+                sbdt: date::reformat(&invoice.shipping_bill_date, GSTN_DATE_FORMAT),
+                itms,
+            });
+            self.counts.exp += 1;
+            self.add_hsn(invoice);
+            return Ok(());
+        }
+
+        let pos = gst::resolve_state_code(&invoice.place_of_supply).ok_or_else(|| {
+            format!(
+                "Place of supply '{}' is not a known state",
+                invoice.place_of_supply
+            )
+        })?;
+        let inter_state = self.supplier_state.as_deref() != Some(pos);
+        let ctin = validation::normalize_gstin(&invoice.billcustomer_gstin);
+
+        if !ctin.is_empty() && !validation::is_unregistered(&ctin) {
+            validation::validate_gstin(&ctin)?;
+            self.b2b.entry(ctin).or_default().push(B2bInvoice {
+                inum: invoice.invoice_number.clone(),
+                idt,
+                val: amount(invoice.total),
+                pos: pos.to_string(),
+                rchrg: "N".to_string(),
+                inv_typ: "R".to_string(),
+                itms: numbered(&slabs, inter_state),
+            });
+            self.counts.b2b += 1;
+        } else if inter_state && invoice.total > self.b2cl_threshold {
+            self.b2cl.entry(pos.to_string()).or_default().push(B2clInvoice {
+                inum: invoice.invoice_number.clone(),
+                idt,
+                val: amount(invoice.total),
+                itms: numbered(&slabs, true),
+            });
+            self.counts.b2cl += 1;
+        } else {
+            let sply_ty = if inter_state { "INTER" } else { "INTRA" };
+            for (rt, slab) in &slabs {
+                self.b2cs
+                    .entry((sply_ty.to_string(), pos.to_string(), *rt))
+                    .or_default()
+                    .add(slab);
+            }
+            self.counts.b2cs += 1;
+        }
+
+        self.add_hsn(invoice);
+        Ok(())
+    }
+
+    fn add_hsn(&mut self, invoice: &Invoice) {
+        let mut missing = Vec::new();
+        for (idx, item) in invoice.items.iter().enumerate() {
+            let hsn = item.hsn_sac.trim();
+            if hsn.is_empty() {
+                missing.push((idx + 1).to_string());
+                continue;
+            }
+            let Ok(rt) = gst::item_gst_rate(item) else {
+                continue;
+            };
+            let totals = self.hsn.entry((hsn.to_string(), rt.normalize())).or_default();
+            if totals.desc.is_empty() {
+                totals.desc = item.description.chars().take(30).collect();
+            }
+            totals.qty += gst::parse_decimal("hours", &item.hours).unwrap_or_default();
+            totals.slab.add_item(item);
+        }
+
+        if !missing.is_empty() {
+            let id = invoice.id.map(|id| id.to_hex());
+            self.issue(
+                "invoice",
+                id,
+                &invoice.invoice_number,
+                format!(
+                    "Line(s) {} have no HSN/SAC and are left out of the HSN summary",
+                    missing.join(", ")
+                ),
+            );
+        }
+    }
+
+    fn add_note(&mut self, note: &CreditDebitNote) {
+        let (doc_num, document_type) = match note.note_type {
+            NoteType::Credit => (5, "credit_note"),
+            NoteType::Debit => (4, "debit_note"),
+        };
+        self.docs
+            .entry(doc_num)
+            .or_default()
+            .push((note.note_number.clone(), false));
+
+        if let Err(message) = self.classify_note(note) {
+            self.counts.skipped += 1;
+            let id = note.id.map(|id| id.to_hex());
+            self.issue(document_type, id, &note.note_number, message);
+        }
+    }
+
+    fn classify_note(&mut self, note: &CreditDebitNote) -> Result<(), String> {
+        if !note.is_registered_recipient() {
+            return Err(
+                "Note to an unregistered recipient belongs in CDNUR, which is not generated"
+                    .to_string(),
+            );
+        }
+        if note.currency != DEFAULT_CURRENCY {
+            return Err(format!(
+                "Amounts are in {}; GSTR-1 needs INR values",
+                note.currency
+            ));
+        }
+        let nt_dt = date::reformat(&note.note_date, GSTN_DATE_FORMAT)
+            .ok_or_else(|| format!("Note date '{}' is not YYYY-MM-DD", note.note_date))?;
+        let pos = gst::resolve_state_code(&note.place_of_supply).ok_or_else(|| {
+            format!("Place of supply '{}' is not a known state", note.place_of_supply)
+        })?;
+        let inter_state = self.supplier_state.as_deref() != Some(pos);
+        let slabs = slabs(&note.items).map_err(|e| e.to_string())?;
+
+        self.cdnr
+            .entry(note.billcustomer_gstin.trim().to_uppercase())
+            .or_default()
+            .push(CdnrNote {
+                ntty: note.note_type.gst_code().to_string(),
+                nt_num: note.note_number.clone(),
+                nt_dt,
+                val: amount(note.total),
+                pos: pos.to_string(),
+                rchrg: if note.reverse_charge { "Y" } else { "N" }.to_string(),
+                inv_typ: "R".to_string(),
+                itms: numbered(&slabs, inter_state),
+            });
+        self.counts.cdnr += 1;
+        Ok(())
+    }
+
+    /// Number ranges per series (the prefix before the trailing digits)
+    fn doc_issue(&self) -> DocIssue {
+        let doc_det = self
+            .docs
+            .iter()
+            .map(|(doc_num, numbers)| {
+                let mut series: BTreeMap<&str, Vec<&(String, bool)>> = BTreeMap::new();
+                for entry in numbers.iter().filter(|(n, _)| !n.is_empty()) {
+                    let prefix = entry.0.trim_end_matches(|c: char| c.is_ascii_digit());
+                    series.entry(prefix).or_default().push(entry);
+                }
+
+                let docs = series
+                    .into_values()
+                    .enumerate()
+                    .map(|(idx, mut entries)| {
+                        entries.sort_by(|a, b| (a.0.len(), &a.0).cmp(&(b.0.len(), &b.0)));
+                        let totnum = entries.len() as u32;
+                        let cancel = entries.iter().filter(|(_, c)| *c).count() as u32;
+                        DocRange {
+                            num: idx as u32 + 1,
+                            from: entries.first().map(|e| e.0.clone()).unwrap_or_default(),
+                            to: entries.last().map(|e| e.0.clone()).unwrap_or_default(),
+                            totnum,
+                            cancel,
+                            net_issue: totnum - cancel,
+                        }
+                    })
+                    .collect();
+                DocDetail {
+                    doc_num: *doc_num,
+                    docs,
+                }
+            })
+            .collect();
+        DocIssue { doc_det }
+    }
+
+    fn finish(self, gstin: String, fp: String) -> Gstr1Report {
+        let doc_issue = self.doc_issue();
+
+        let b2b = self
+            .b2b
+            .into_iter()
+            .map(|(ctin, inv)| B2bParty { ctin, inv })
+            .collect();
+        let b2cl = self
+            .b2cl
+            .into_iter()
+            .map(|(pos, inv)| B2clState { pos, inv })
+            .collect();
+        let b2cs = self
+            .b2cs
+            .into_iter()
+            .map(|((sply_ty, pos, rt), slab)| {
+                let detail = slab.detail(rt, sply_ty == "INTER");
+                B2csEntry {
+                    sply_ty,
+                    rt: detail.rt,
+                    typ: "OE".to_string(),
+                    pos,
+                    txval: detail.txval,
+                    iamt: detail.iamt,
+                    camt: detail.camt,
+                    samt: detail.samt,
+                    csamt: 0.0,
+                }
+            })
+            .collect();
+        let exp = self
+            .exp
+            .into_iter()
+            .map(|(exp_typ, inv)| ExpGroup { exp_typ, inv })
+            .collect();
+        let cdnr = self
+            .cdnr
+            .into_iter()
+            .map(|(ctin, nt)| CdnrParty { ctin, nt })
+            .collect();
+        let hsn = HsnSummary {
+            data: self
+                .hsn
+                .into_iter()
+                .enumerate()
+                .map(|(idx, ((hsn_sc, rt), totals))| HsnEntry {
+                    num: idx as u32 + 1,
+                    // Services (SAC 99xx) carry no unit of quantity
+                    uqc: if hsn_sc.starts_with("99") { "NA" } else { "OTH" }.to_string(),
+                    hsn_sc,
+                    desc: totals.desc,
+                    qty: rate(totals.qty),
+                    rt: rate(rt),
+                    txval: amount(totals.slab.txval),
+                    iamt: amount(totals.slab.iamt),
+                    camt: amount(totals.slab.camt),
+                    samt: amount(totals.slab.samt),
+                    csamt: 0.0,
+                })
+                .collect(),
+        };
+
+        Gstr1Report {
+            gstr1: Gstr1 {
+                gstin,
+                fp,
+                version: GSTR1_VERSION.to_string(),
+                b2b,
+                b2cl,
+                b2cs,
+                exp,
+                cdnr,
+                hsn,
+                doc_issue,
+            },
+            counts: self.counts,
+            issues: self.issues,
+        }
+    }
+}
+
+/// Credit and debit notes of the organisation dated between `from` and `to`.
+/// Notes saved before `org_email` was recorded are matched through their
+/// original invoice.
+pub(crate) async fn notes_for_period(
+    invoice_repo: &InvoiceRepository,
+    note_repo: &CreditDebitNoteRepository,
+    org_email: &str,
+    from: &str,
+    to: &str,
+) -> Result<Vec<CreditDebitNote>, ApiError> {
+    let mut notes = Vec::new();
+    for note in note_repo.find_in_range(org_email, from, to).await? {
+        let belongs = !note.org_email.is_empty()
+            || invoice_repo
+                .get_invoice_by_id(org_email, &note.original_invoice_id)
+                .await?
+                .is_some();
+        if belongs {
+            notes.push(note);
+        }
+    }
+    Ok(notes)
+}
+
+#[derive(Clone)]
+pub struct Gstr1Service {
+    invoice_repo: InvoiceRepository,
+    note_repo: CreditDebitNoteRepository,
+    org_repo: OrganisationRepository,
+}
+
+impl Gstr1Service {
+    pub fn new(
+        invoice_repo: InvoiceRepository,
+        note_repo: CreditDebitNoteRepository,
+        org_repo: OrganisationRepository,
+    ) -> Self {
+        Self {
+            invoice_repo,
+            note_repo,
+            org_repo,
+        }
+    }
+
+    /// Build GSTR-1 for the tax period `fp` (MMYYYY) from the organisation's
+    /// invoices and credit/debit notes dated in that month
+    pub async fn generate(&self, org_email: &str, fp: &str) -> Result<Gstr1Report, ApiError> {
+        let period = TaxPeriod::parse(fp).map_err(ApiError::ValidationError)?;
+        let org = self.org_repo.get_organisation_by_email(org_email).await?;
+        let gstin = org.gst_in.trim().to_uppercase();
+        if gstin.is_empty() {
+            return Err(ApiError::ValidationError(
+                "Organisation GSTIN is required to prepare GSTR-1".to_string(),
+            ));
+        }
+
+        let from = date::format(period.first_day());
+        let to = date::format(period.last_day());
+
+        let invoices = self.invoice_repo.find_for_period(org_email, &from, &to).await?;
+        let mut builder = Gstr1Builder::new(org.state_code(), &period);
+        for invoice in &invoices {
+            builder.add_invoice(invoice);
+        }
+
+        let notes =
+            notes_for_period(&self.invoice_repo, &self.note_repo, org_email, &from, &to).await?;
+        for note in &notes {
+            builder.add_note(note);
+        }
+
+        let report = builder.finish(gstin, period.fp());
+        log::info!(
+            "Prepared GSTR-1 {} for {}: {} invoice(s), {} issue(s)",
+            report.gstr1.fp,
+            org_email,
+            invoices.len(),
+            report.issues.len()
+        );
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::invoice::IGST;
+
+    fn money(value: &str) -> Money {
+        value.parse().unwrap()
+    }
+
+    fn builder(fp: &str) -> Gstr1Builder {
+        Gstr1Builder::new(Some("27"), &TaxPeriod::parse(fp).unwrap())
+    }
+
+    fn item(value: &str) -> InvoiceItem {
+        InvoiceItem {
+            description: "Consulting".to_string(),
+            hsn_sac: "998311".to_string(),
+            hours: "1".to_string(),
+            rate: money(value),
+            igst: IGST {
+                igst_percent: "18".to_string(),
+                igst_amount: Money::ZERO,
+            },
+            item_total: money(value),
+            ..Default::default()
+        }
+    }
+
+    fn invoice(number: &str, gstin: &str, place_of_supply: &str, total: &str) -> Invoice {
+        Invoice {
+            invoice_number: number.to_string(),
+            invoice_date: "2024-08-10".to_string(),
+            status: InvoiceStatus::Issued,
+            currency: DEFAULT_CURRENCY.to_string(),
+            billcustomer_gstin: gstin.to_string(),
+            place_of_supply: place_of_supply.to_string(),
+            items: vec![item(total)],
+            sub_total: money(total),
+            total: money(total),
+            ..Default::default()
+        }
+    }
+
+    fn note(note_type: NoteType, number: &str, gstin: &str) -> CreditDebitNote {
+        CreditDebitNote {
+            id: None,
+            note_type,
+            note_number: number.to_string(),
+            status: Default::default(),
+            note_date: "2024-08-20".to_string(),
+            reason: "Sales Return".to_string(),
+            original_invoice_id: String::new(),
+            original_invoice_number: "INV-0001".to_string(),
+            original_invoice_date: "2024-08-10".to_string(),
+            billcustomer_name: "Customer".to_string(),
+            billcustomer_gstin: gstin.to_string(),
+            place_of_supply: "29".to_string(),
+            invoice_type: String::new(),
+            reverse_charge: false,
+            currency: DEFAULT_CURRENCY.to_string(),
+            items: vec![item("1000")],
+            sub_total: money("1000"),
+            totalcgst: Money::ZERO,
+            totalsgst: Money::ZERO,
+            totaligst: Money::ZERO,
+            total: money("1000"),
+            notes: String::new(),
+            org_email: "org@example.com".to_string(),
+            created_at: None,
+            cancelled_at: None,
+            cancel_reason: None,
+        }
+    }
+
+    #[test]
+    fn registered_customer_goes_to_b2b() {
+        let mut builder = builder("082024");
+        builder.add_invoice(&invoice("INV-0001", "29aagcb7383j1z4", "29", "500000"));
+
+        assert_eq!(builder.counts.b2b, 1);
+        assert_eq!(builder.b2b["29AAGCB7383J1Z4"][0].pos, "29");
+        assert!(builder.b2cl.is_empty());
+    }
+
+    #[test]
+    fn b2cl_threshold_drops_from_august_2024() {
+        let large = invoice("INV-0001", "", "29", "150000");
+
+        let mut before = builder("072024");
+        before.add_invoice(&large);
+        assert_eq!(before.counts.b2cl, 0);
+        assert_eq!(before.counts.b2cs, 1);
+
+        let mut after = builder("082024");
+        after.add_invoice(&large);
+        assert_eq!(after.counts.b2cl, 1);
+        assert_eq!(after.b2cl["29"][0].inum, "INV-0001");
+    }
+
+    #[test]
+    fn small_and_intra_state_b2c_go_to_b2cs() {
+        let mut builder = builder("082024");
+        builder.add_invoice(&invoice("INV-0001", "", "29", "50000"));
+        builder.add_invoice(&invoice("INV-0002", "URP", "27", "500000"));
+
+        assert_eq!(builder.counts.b2cs, 2);
+        assert_eq!(builder.counts.b2cl, 0);
+        let inter = ("INTER".to_string(), "29".to_string(), Decimal::from(18));
+        let intra = ("INTRA".to_string(), "27".to_string(), Decimal::from(18));
+        assert_eq!(builder.b2cs[&inter].txval, money("50000"));
+        assert_eq!(builder.b2cs[&intra].txval, money("500000"));
+    }
+
+    #[test]
+    fn international_invoice_goes_to_exp() {
+        let mut builder = builder("082024");
+        let export = Invoice {
+            invoice_type: "International".to_string(),
+            ..invoice("INV-0001", "", "", "200000")
+        };
+        builder.add_invoice(&export);
+
+        assert_eq!(builder.counts.exp, 1);
+        assert_eq!(builder.counts.b2cs + builder.counts.b2cl, 0);
+        assert_eq!(builder.exp.values().map(Vec::len).sum::<usize>(), 1);
+    }
+
+    #[test]
+    fn notes_to_registered_recipients_go_to_cdnr() {
+        let mut builder = builder("082024");
+        builder.add_note(&note(NoteType::Credit, "CN-0001", "29aagcb7383j1z4"));
+        builder.add_note(&note(NoteType::Debit, "DN-0001", ""));
+
+        assert_eq!(builder.counts.cdnr, 1);
+        assert_eq!(builder.cdnr["29AAGCB7383J1Z4"][0].nt_num, "CN-0001");
+        assert_eq!(builder.counts.skipped, 1);
+        assert_eq!(builder.issues[0].document_type, "debit_note");
+    }
+
+    #[test]
+    fn drafts_are_left_out_and_cancellations_counted() {
+        let mut builder = builder("082024");
+        builder.add_invoice(&Invoice {
+            status: InvoiceStatus::Draft,
+            ..invoice("INV-0001", "", "29", "1000")
+        });
+        builder.add_invoice(&Invoice {
+            status: InvoiceStatus::Cancelled,
+            ..invoice("INV-0002", "", "29", "1000")
+        });
+        builder.add_invoice(&invoice("INV-0003", "", "29", "1000"));
+
+        assert_eq!(builder.counts.b2cs, 1);
+        assert_eq!(builder.issues.len(), 1);
+        let doc_issue = builder.doc_issue();
+        let range = &doc_issue.doc_det[0].docs[0];
+        assert_eq!(range.from, "INV-0002");
+        assert_eq!(range.to, "INV-0003");
+        assert_eq!((range.totnum, range.cancel, range.net_issue), (2, 1, 1));
+    }
+
+    #[test]
+    fn doc_issue_ranges_each_series() {
+        let mut builder = builder("082024");
+        for number in ["INV-10", "INV-9", "EXP-1", "INV-11"] {
+            builder.add_invoice(&invoice(number, "", "29", "1000"));
+        }
+        builder.add_note(&note(NoteType::Credit, "CN-0001", "29AAGCB7383J1Z4"));
+
+        let doc_issue = builder.doc_issue();
+        assert_eq!(doc_issue.doc_det.len(), 2);
+        let invoices = &doc_issue.doc_det[0];
+        assert_eq!(invoices.doc_num, 1);
+        assert_eq!(invoices.docs.len(), 2);
+        assert_eq!(invoices.docs[0].from, "EXP-1");
+        assert_eq!(invoices.docs[0].totnum, 1);
+        let series = &invoices.docs[1];
+        assert_eq!(series.from, "INV-9");
+        assert_eq!(series.to, "INV-11");
+        assert_eq!(series.totnum, 3);
+        assert_eq!(doc_issue.doc_det[1].doc_num, 5);
+    }
+}
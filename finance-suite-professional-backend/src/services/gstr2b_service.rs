@@ -0,0 +1,578 @@
+use chrono::Months;
+use rust_decimal::Decimal;
+
+use crate::error::ApiError;
+use crate::models::gstr1::TaxPeriod;
+use crate::models::gstr2b::{
+    BookedPurchase, Gstr2bData, Gstr2bFile, Gstr2bInvoice, Gstr2bItem, Gstr2bReconciliation,
+    PortalDocument, ReconciledPair, ReconciliationTotals,
+};
+use crate::models::gstr3b::TaxHeads;
+use crate::models::money::{Money, DEFAULT_CURRENCY};
+use crate::repository::{
+    ExpenseRepository, JournalRepository, OrganisationRepository, VendorBillRepository,
+};
+use crate::services::gstr3b_service::{approved_expenses, expense_item_tax};
+use crate::utils::date::{self, GSTN_DATE_FORMAT};
+
+/// Differences per tax head up to this many rupees are rounding
+const TAX_TOLERANCE: i64 = 1;
+
+/// Supplier and booked invoice dates this many days apart still match
+const DATE_TOLERANCE_DAYS: i64 = 3;
+
+/// Purchases booked up to this many months before the statement period
+/// can still appear in it
+const LOOKBACK_MONTHS: u32 = 12;
+
+fn money(value: f64) -> Money {
+    Money::from_f64(value).unwrap_or_default().round_paise()
+}
+
+/// GSTINs and names compared on upper-case letters and digits only
+fn normalize(value: &str) -> String {
+    value
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .collect::<String>()
+        .to_uppercase()
+}
+
+/// Upper-case letters and digits only, with leading zeros dropped from each
+/// run of digits, so "inv/0042" and "INV-42" compare equal
+fn normalize_number(value: &str) -> String {
+    let mut out = String::new();
+    let mut digits = String::new();
+    let flush = |digits: &mut String, out: &mut String| {
+        if !digits.is_empty() {
+            let trimmed = digits.trim_start_matches('0');
+            out.push_str(if trimmed.is_empty() { "0" } else { trimmed });
+            digits.clear();
+        }
+    };
+    for c in value.chars().filter(|c| c.is_ascii_alphanumeric()) {
+        if c.is_ascii_digit() {
+            digits.push(c);
+        } else {
+            flush(&mut digits, &mut out);
+            out.push(c.to_ascii_uppercase());
+        }
+    }
+    flush(&mut digits, &mut out);
+    out
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut cur = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let cost = usize::from(ca != *cb);
+            cur.push((prev[j] + cost).min(prev[j + 1] + 1).min(cur[j] + 1));
+        }
+        prev = cur;
+    }
+    prev[b.len()]
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum NumberMatch {
+    /// Not recorded in books; date and tax must agree instead
+    Unrecorded,
+    /// Off by a typo or a prefix
+    Close,
+    Exact,
+}
+
+fn compare_numbers(portal: &str, books: &str) -> Option<NumberMatch> {
+    let portal = normalize_number(portal);
+    let books = normalize_number(books);
+    if books.is_empty() {
+        return Some(NumberMatch::Unrecorded);
+    }
+    if portal == books {
+        return Some(NumberMatch::Exact);
+    }
+    let suffix = portal.len().min(books.len()) >= 3
+        && (portal.ends_with(&books) || books.ends_with(&portal));
+    if suffix || edit_distance(&portal, &books) <= 1 {
+        return Some(NumberMatch::Close);
+    }
+    None
+}
+
+/// Heads differing by more than the rounding tolerance
+fn tax_differences(portal: &TaxHeads, books: &TaxHeads) -> Vec<String> {
+    let tolerance = Money::new(Decimal::from(TAX_TOLERANCE));
+    [
+        ("IGST", portal.igst, books.igst),
+        ("CGST", portal.cgst, books.cgst),
+        ("SGST", portal.sgst, books.sgst),
+        ("Cess", portal.cess, books.cess),
+    ]
+    .into_iter()
+    .filter(|(_, p, b)| (*p - *b).max(*b - *p) > tolerance)
+    .map(|(head, p, b)| format!("{} differs: 2B {}, books {}", head, p, b))
+    .collect()
+}
+
+/// Days between the two dates; `None` when either cannot be read
+fn date_gap(portal: &str, books: &str) -> Option<i64> {
+    let portal = date::parse(portal)?;
+    let books = date::parse(books)?;
+    Some((portal - books).num_days().abs())
+}
+
+struct Candidate {
+    index: usize,
+    number: NumberMatch,
+    tax_differences: Vec<String>,
+    date_gap: Option<i64>,
+    by_name: bool,
+}
+
+impl Candidate {
+    /// Higher is better: invoice number first, then agreeing tax, then date
+    fn rank(&self) -> (NumberMatch, bool, i64) {
+        (
+            self.number,
+            self.tax_differences.is_empty(),
+            -self.date_gap.unwrap_or(i64::MAX / 2),
+        )
+    }
+}
+
+fn candidate(portal: &PortalDocument, books: &BookedPurchase, index: usize) -> Option<Candidate> {
+    let books_gstin = normalize(&books.supplier_gstin);
+    let by_name = if books_gstin.is_empty() {
+        let portal_name = normalize(&portal.supplier_name);
+        let books_name = normalize(&books.supplier_name);
+        if portal_name.is_empty()
+            || books_name.is_empty()
+            || !(portal_name.contains(&books_name) || books_name.contains(&portal_name))
+        {
+            return None;
+        }
+        true
+    } else if books_gstin == portal.supplier_gstin {
+        false
+    } else {
+        return None;
+    };
+
+    let number = compare_numbers(&portal.invoice_number, &books.invoice_number)?;
+    let tax_differences = tax_differences(&portal.tax, &books.tax);
+    let date_gap = date_gap(&portal.invoice_date, &books.invoice_date);
+
+    // Without a number, date and tax have to agree
+    if number == NumberMatch::Unrecorded
+        && (!tax_differences.is_empty() || date_gap.is_none_or(|d| d > DATE_TOLERANCE_DAYS))
+    {
+        return None;
+    }
+
+    Some(Candidate {
+        index,
+        number,
+        tax_differences,
+        date_gap,
+        by_name,
+    })
+}
+
+fn portal_document(
+    supplier_gstin: &str,
+    supplier_name: &str,
+    inv: &Gstr2bInvoice,
+) -> PortalDocument {
+    let items = |f: fn(&Gstr2bItem) -> f64| -> f64 { inv.items.iter().map(f).sum() };
+    let tax = TaxHeads {
+        igst: money(inv.igst.unwrap_or_else(|| items(|i| i.igst))),
+        cgst: money(inv.cgst.unwrap_or_else(|| items(|i| i.cgst))),
+        sgst: money(inv.sgst.unwrap_or_else(|| items(|i| i.sgst))),
+        cess: money(inv.cess.unwrap_or_else(|| items(|i| i.cess))),
+    };
+
+    PortalDocument {
+        supplier_gstin: normalize(supplier_gstin),
+        supplier_name: supplier_name.to_string(),
+        invoice_number: inv.inum.trim().to_string(),
+        invoice_date: date::parse_as(&inv.dt, GSTN_DATE_FORMAT)
+            .map(date::format)
+            .unwrap_or_else(|| inv.dt.clone()),
+        invoice_value: money(inv.val),
+        taxable_value: money(inv.txval.unwrap_or_else(|| items(|i| i.txval))),
+        tax,
+        itc_available: !inv.itcavl.eq_ignore_ascii_case("N"),
+        reverse_charge: inv.rev.eq_ignore_ascii_case("Y"),
+    }
+}
+
+/// Match every B2B invoice in the statement to at most one booked
+/// purchase. Purchases from earlier months are candidates too, since
+/// suppliers file late; only those dated in the statement period are
+/// reported as missing from 2B.
+fn match_documents(
+    org_email: &str,
+    gstin: String,
+    period: &TaxPeriod,
+    data: &Gstr2bData,
+    books: Vec<BookedPurchase>,
+) -> Gstr2bReconciliation {
+    let period_from = period.first_day();
+    let period_to = period.last_day();
+
+    let portal: Vec<PortalDocument> = data
+        .docdata
+        .b2b
+        .iter()
+        .flat_map(|supplier| {
+            supplier
+                .inv
+                .iter()
+                .map(|inv| portal_document(&supplier.ctin, &supplier.trdnm, inv))
+        })
+        .collect();
+
+    let mut totals = ReconciliationTotals::default();
+    let mut used = vec![false; books.len()];
+    let mut matched = Vec::new();
+    let mut mismatched = Vec::new();
+    let mut missing_in_books = Vec::new();
+
+    for doc in portal {
+        totals.portal_tax += doc.tax;
+
+        let best = books
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| !used[*i])
+            .filter_map(|(i, b)| candidate(&doc, b, i))
+            .max_by_key(Candidate::rank);
+        let Some(best) = best else {
+            missing_in_books.push(doc);
+            continue;
+        };
+        used[best.index] = true;
+        let booked = books[best.index].clone();
+
+        let mut differences = best.tax_differences.clone();
+        let date_off = match best.date_gap {
+            Some(0) => false,
+            Some(days) => {
+                differences.push(format!(
+                    "Invoice date differs: 2B {}, books {}",
+                    doc.invoice_date, booked.invoice_date
+                ));
+                days > DATE_TOLERANCE_DAYS
+            }
+            None => {
+                differences.push(format!(
+                    "Books date '{}' could not be compared",
+                    booked.invoice_date
+                ));
+                false
+            }
+        };
+        let material = !best.tax_differences.is_empty() || date_off;
+
+        match best.number {
+            NumberMatch::Close => differences.push(format!(
+                "Invoice number differs: 2B {}, books {}",
+                doc.invoice_number, booked.invoice_number
+            )),
+            NumberMatch::Unrecorded => {
+                differences.push("Invoice number not recorded in books".to_string())
+            }
+            NumberMatch::Exact => {}
+        }
+        if best.by_name {
+            differences.push("Supplier GSTIN not recorded in books".to_string());
+        }
+        if !doc.itc_available {
+            differences.push("Supplier reports ITC as not available".to_string());
+        }
+
+        let pair = ReconciledPair {
+            portal: doc,
+            books: booked,
+            differences,
+        };
+        if material {
+            mismatched.push(pair);
+        } else {
+            matched.push(pair);
+        }
+    }
+
+    let in_period =
+        |value: &str| date::parse(value).is_some_and(|d| d >= period_from && d <= period_to);
+    for booked in books.iter().filter(|b| in_period(&b.invoice_date)) {
+        totals.books_tax += booked.tax;
+    }
+    let missing_in_2b: Vec<BookedPurchase> = books
+        .into_iter()
+        .enumerate()
+        .filter(|(i, b)| !used[*i] && in_period(&b.invoice_date))
+        .map(|(_, b)| b)
+        .collect();
+
+    totals.matched = matched.len();
+    totals.mismatched = mismatched.len();
+    totals.missing_in_books = missing_in_books.len();
+    totals.missing_in_2b = missing_in_2b.len();
+
+    Gstr2bReconciliation {
+        org_email: org_email.to_string(),
+        gstin,
+        period: period.fp(),
+        matched,
+        mismatched,
+        missing_in_books,
+        missing_in_2b,
+        totals,
+    }
+}
+
+/// Reconciles a GSTR-2B statement against vendor bills and expense lines
+#[derive(Clone)]
+pub struct Gstr2bService {
+    org_repo: OrganisationRepository,
+    bill_repo: VendorBillRepository,
+    journal_repo: JournalRepository,
+    expense_repo: ExpenseRepository,
+}
+
+impl Gstr2bService {
+    pub fn new(
+        org_repo: OrganisationRepository,
+        bill_repo: VendorBillRepository,
+        journal_repo: JournalRepository,
+        expense_repo: ExpenseRepository,
+    ) -> Self {
+        Self {
+            org_repo,
+            bill_repo,
+            journal_repo,
+            expense_repo,
+        }
+    }
+
+    /// Accepts the portal download as is, or just its `data` object
+    fn parse(body: &[u8]) -> Result<Gstr2bData, ApiError> {
+        serde_json::from_slice::<Gstr2bFile>(body)
+            .map(|file| file.data)
+            .or_else(|_| serde_json::from_slice::<Gstr2bData>(body))
+            .map_err(|e| ApiError::ValidationError(format!("Invalid GSTR-2B JSON: {}", e)))
+    }
+
+    /// Vendor bills and INR expense lines carrying tax, dated between `from`
+    /// and `to`
+    async fn booked_purchases(
+        &self,
+        org_email: &str,
+        supplier_state: Option<&str>,
+        from: &str,
+        to: &str,
+    ) -> Result<Vec<BookedPurchase>, ApiError> {
+        let mut purchases = Vec::new();
+
+        for bill in self.bill_repo.find_in_range(org_email, from, to).await? {
+            // Imports are reported through ICEGATE, not GSTR-2B B2B
+            if bill.currency != DEFAULT_CURRENCY || bill.bill_type.eq_ignore_ascii_case("import") {
+                continue;
+            }
+            purchases.push(BookedPurchase {
+                source: "vendor_bill".to_string(),
+                source_id: bill.id.map(|id| id.to_hex()).unwrap_or_default(),
+                line: None,
+                supplier_gstin: bill.vendor_gstin.clone(),
+                supplier_name: bill.vendor_name.clone(),
+                invoice_number: bill.bill_number.clone(),
+                invoice_date: bill.bill_date.clone(),
+                taxable_value: bill.sub_total,
+                tax: TaxHeads {
+                    igst: bill.totaligst,
+                    cgst: bill.totalcgst,
+                    sgst: bill.totalsgst,
+                    cess: Money::ZERO,
+                },
+            });
+        }
+
+        let (expenses, _) =
+            approved_expenses(&self.journal_repo, &self.expense_repo, org_email, from, to).await?;
+        for expense in &expenses {
+            for (idx, item) in expense.items.iter().enumerate() {
+                if item.currency != DEFAULT_CURRENCY
+                    || item.tax_amount.unwrap_or_default().is_zero()
+                {
+                    continue;
+                }
+                purchases.push(BookedPurchase {
+                    source: "expense".to_string(),
+                    source_id: expense.id.map(|id| id.to_hex()).unwrap_or_default(),
+                    line: Some(idx + 1),
+                    supplier_gstin: item.vendor_gstin.clone().unwrap_or_default(),
+                    supplier_name: item.vendor.clone().unwrap_or_default(),
+                    invoice_number: item.invoice_number.clone().unwrap_or_default(),
+                    invoice_date: item.expense_date.trim().to_string(),
+                    taxable_value: item.amount,
+                    tax: expense_item_tax(item, supplier_state),
+                });
+            }
+        }
+
+        Ok(purchases)
+    }
+
+    /// Reconcile an uploaded GSTR-2B statement against the organisation's
+    /// vendor bills and expenses
+    pub async fn reconcile(
+        &self,
+        org_email: &str,
+        body: &[u8],
+    ) -> Result<Gstr2bReconciliation, ApiError> {
+        let data = Self::parse(body)?;
+        let period = TaxPeriod::parse(&data.rtnprd).map_err(ApiError::ValidationError)?;
+
+        let org = self.org_repo.get_organisation_by_email(org_email).await?;
+        let gstin = org.gst_in.trim().to_uppercase();
+        if !data.gstin.is_empty() && normalize(&data.gstin) != gstin {
+            return Err(ApiError::ValidationError(format!(
+                "Statement is for GSTIN {} but the organisation GSTIN is {}",
+                data.gstin, gstin
+            )));
+        }
+
+        let period_from = period.first_day();
+        let period_to = period.last_day();
+        let window_from = period_from
+            .checked_sub_months(Months::new(LOOKBACK_MONTHS))
+            .unwrap_or(period_from);
+        let books = self
+            .booked_purchases(
+                org_email,
+                org.state_code(),
+                &date::format(window_from),
+                &date::format(period_to),
+            )
+            .await?;
+
+        let reconciliation = match_documents(org_email, gstin, &period, &data, books);
+        let totals = &reconciliation.totals;
+        log::info!(
+            "Reconciled GSTR-2B {} for {}: {} matched, {} mismatched, {} missing in books, {} missing in 2B",
+            period.fp(),
+            org_email,
+            totals.matched,
+            totals.mismatched,
+            totals.missing_in_books,
+            totals.missing_in_2b
+        );
+
+        Ok(reconciliation)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SUPPLIER: &str = "29AAGCB7383J1Z4";
+
+    fn statement() -> Gstr2bData {
+        let body = r#"{
+            "data": {
+                "gstin": "27AAPFU0939F1ZV",
+                "rtnprd": "082024",
+                "docdata": {
+                    "b2b": [{
+                        "ctin": "29AAGCB7383J1Z4",
+                        "trdnm": "Bright Supplies",
+                        "inv": [
+                            { "inum": "INV-001", "dt": "10-08-2024", "val": 1180,
+                              "txval": 1000, "igst": 180 },
+                            { "inum": "INV-002", "dt": "12-08-2024", "val": 2360,
+                              "txval": 2000, "igst": 360 },
+                            { "inum": "SUP/7781", "dt": "14-08-2024", "val": 590,
+                              "txval": 500, "igst": 90 }
+                        ]
+                    }]
+                }
+            }
+        }"#;
+        Gstr2bService::parse(body.as_bytes()).unwrap()
+    }
+
+    fn bill(number: &str, date: &str, igst: &str) -> BookedPurchase {
+        BookedPurchase {
+            source: "vendor_bill".to_string(),
+            source_id: number.to_string(),
+            line: None,
+            supplier_gstin: SUPPLIER.to_lowercase(),
+            supplier_name: "Bright Supplies".to_string(),
+            invoice_number: number.to_string(),
+            invoice_date: date.to_string(),
+            taxable_value: Money::ZERO,
+            tax: TaxHeads {
+                igst: igst.parse().unwrap(),
+                ..Default::default()
+            },
+        }
+    }
+
+    fn reconcile(books: Vec<BookedPurchase>) -> Gstr2bReconciliation {
+        let period = TaxPeriod::parse("082024").unwrap();
+        match_documents(
+            "org@example.com",
+            "27AAPFU0939F1ZV".to_string(),
+            &period,
+            &statement(),
+            books,
+        )
+    }
+
+    #[test]
+    fn exact_match_ignores_number_formatting() {
+        let result = reconcile(vec![bill("inv/1", "2024-08-10", "180")]);
+
+        assert_eq!(result.matched.len(), 1);
+        assert_eq!(result.matched[0].portal.invoice_number, "INV-001");
+        assert!(result.matched[0].differences.is_empty());
+    }
+
+    #[test]
+    fn differing_tax_is_mismatched() {
+        let result = reconcile(vec![
+            bill("INV-001", "2024-08-10", "180"),
+            bill("INV-002", "2024-08-12", "300"),
+        ]);
+
+        assert_eq!(result.matched.len(), 1);
+        assert_eq!(result.mismatched.len(), 1);
+        assert_eq!(result.mismatched[0].books.invoice_number, "INV-002");
+        assert_eq!(
+            result.mismatched[0].differences,
+            vec!["IGST differs: 2B 360.00, books 300.00".to_string()]
+        );
+    }
+
+    #[test]
+    fn unmatched_documents_are_missing_on_either_side() {
+        let result = reconcile(vec![
+            bill("INV-001", "2024-08-10", "180"),
+            bill("INV-002", "2024-08-12", "360"),
+            bill("INV-950", "2024-08-20", "45"),
+            bill("INV-870", "2024-07-28", "45"),
+        ]);
+
+        assert_eq!(result.totals.matched, 2);
+        assert_eq!(result.missing_in_books.len(), 1);
+        assert_eq!(result.missing_in_books[0].invoice_number, "SUP/7781");
+        // Purchases before the period may still be filed late by the supplier
+        assert_eq!(result.missing_in_2b.len(), 1);
+        assert_eq!(result.missing_in_2b[0].invoice_number, "INV-950");
+    }
+}
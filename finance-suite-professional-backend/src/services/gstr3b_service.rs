@@ -0,0 +1,608 @@
+use std::collections::BTreeSet;
+
+use crate::error::ApiError;
+use crate::models::credit_debit_note::NoteType;
+use crate::models::expense::{Expense, ExpenseItem};
+use crate::models::gstr1::{ReturnIssue, TaxPeriod};
+use crate::models::gstr3b::{
+    CashPaid, Gstr3b, Gstr3bReport, Gstr3bRow, Gstr3bSummary, InwardLine, InwardSupplies,
+    InwardSupplyRow, ItcEligibility, ItcLine, ItcPaid, SupDetails, SupplyValue, TaxAmounts,
+    TaxHeads, TaxPayment, TaxPaymentRow,
+};
+use crate::models::invoice::{InvoiceItem, InvoiceStatus};
+use crate::models::ledger::JournalSource;
+use crate::models::money::{Money, DEFAULT_CURRENCY};
+use crate::repository::{
+    CreditDebitNoteRepository, ExpenseRepository, InvoiceRepository, JournalRepository,
+    OrganisationRepository,
+};
+use crate::services::gstr1_service::{amount, notes_for_period};
+use crate::utils::{date, gst};
+
+fn issue(document_type: &str, id: Option<String>, number: &str, message: String) -> ReturnIssue {
+    ReturnIssue {
+        document_type: document_type.to_string(),
+        document_id: id.unwrap_or_default(),
+        document_number: number.to_string(),
+        message,
+    }
+}
+
+fn item_tax(item: &InvoiceItem) -> TaxHeads {
+    TaxHeads {
+        igst: item.igst.igst_amount,
+        cgst: item.cgst.cgst_amount,
+        sgst: item.sgst.sgst_amount,
+        cess: Money::ZERO,
+    }
+}
+
+fn tax_amounts(tax: &TaxHeads) -> TaxAmounts {
+    TaxAmounts {
+        iamt: amount(tax.igst),
+        camt: amount(tax.cgst),
+        samt: amount(tax.sgst),
+        csamt: amount(tax.cess),
+    }
+}
+
+fn itc_line(ty: &str, tax: &TaxHeads) -> ItcLine {
+    ItcLine {
+        ty: ty.to_string(),
+        amounts: tax_amounts(tax),
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct Bucket {
+    value: Money,
+    tax: TaxHeads,
+}
+
+impl Bucket {
+    fn add(&mut self, value: Money, tax: TaxHeads) {
+        self.value += value;
+        self.tax += tax;
+    }
+
+    fn row(&self, table: &str, description: &str) -> Gstr3bRow {
+        Gstr3bRow {
+            table: table.to_string(),
+            description: description.to_string(),
+            taxable_value: self.value,
+            tax: self.tax,
+        }
+    }
+}
+
+/// Table 3.1 outward supplies
+#[derive(Default)]
+struct Outward {
+    taxable: Bucket,
+    zero_rated: Bucket,
+    nil_exempt: Bucket,
+}
+
+impl Outward {
+    /// Credit notes reduce the figures of the period they are issued in
+    fn add_items(
+        &mut self,
+        items: &[InvoiceItem],
+        export: bool,
+        reduce: bool,
+    ) -> Result<(), ApiError> {
+        for item in items {
+            let rt = gst::item_gst_rate(item)?;
+            let (value, tax) = if reduce {
+                (-item.item_total, TaxHeads::default() - item_tax(item))
+            } else {
+                (item.item_total, item_tax(item))
+            };
+
+            let bucket = if export {
+                &mut self.zero_rated
+            } else if rt.is_zero() {
+                &mut self.nil_exempt
+            } else {
+                &mut self.taxable
+            };
+            bucket.add(value, tax);
+        }
+        Ok(())
+    }
+
+    /// Tax payable on outward supplies (no reverse-charge inward supplies
+    /// are recorded)
+    fn liability(&self) -> TaxHeads {
+        let mut tax = self.taxable.tax;
+        tax += self.zero_rated.tax;
+        tax
+    }
+}
+
+/// Table 4 credit and table 5 exempt purchases from expense items
+#[derive(Default)]
+struct Inward {
+    itc: Bucket,
+    exempt_inter: Money,
+    exempt_intra: Money,
+}
+
+/// Credit used against each head under the order of rule 88A: IGST credit
+/// first (IGST, then CGST, then SGST), then CGST credit (CGST, then IGST),
+/// then SGST credit (SGST, then IGST). CGST and SGST never cross.
+#[derive(Default)]
+struct SetOff {
+    payable: TaxHeads,
+    i_pdi: Money,
+    i_pdc: Money,
+    i_pds: Money,
+    c_pdi: Money,
+    c_pdc: Money,
+    s_pdi: Money,
+    s_pds: Money,
+    cs_pdcs: Money,
+    cash: TaxHeads,
+}
+
+fn take(credit: &mut Money, liability: &mut Money) -> Money {
+    let used = (*credit).min(*liability).max(Money::ZERO);
+    *credit -= used;
+    *liability -= used;
+    used
+}
+
+fn set_off(payable: TaxHeads, credit: TaxHeads) -> SetOff {
+    // A period where credit notes exceed supplies has nothing to pay
+    let mut due = TaxHeads {
+        igst: payable.igst.max(Money::ZERO),
+        cgst: payable.cgst.max(Money::ZERO),
+        sgst: payable.sgst.max(Money::ZERO),
+        cess: payable.cess.max(Money::ZERO),
+    };
+    let mut credit = credit;
+
+    let i_pdi = take(&mut credit.igst, &mut due.igst);
+    let c_pdi = take(&mut credit.igst, &mut due.cgst);
+    let s_pdi = take(&mut credit.igst, &mut due.sgst);
+    let c_pdc = take(&mut credit.cgst, &mut due.cgst);
+    let i_pdc = take(&mut credit.cgst, &mut due.igst);
+    let s_pds = take(&mut credit.sgst, &mut due.sgst);
+    let i_pds = take(&mut credit.sgst, &mut due.igst);
+    let cs_pdcs = take(&mut credit.cess, &mut due.cess);
+
+    SetOff {
+        payable,
+        i_pdi,
+        i_pdc,
+        i_pds,
+        c_pdi,
+        c_pdc,
+        s_pdi,
+        s_pds,
+        cs_pdcs,
+        cash: due,
+    }
+}
+
+impl SetOff {
+    fn rows(&self) -> Vec<TaxPaymentRow> {
+        let row = |head: &str, payable, through: [Money; 4], cash| TaxPaymentRow {
+            head: head.to_string(),
+            payable,
+            paid_through_igst: through[0],
+            paid_through_cgst: through[1],
+            paid_through_sgst: through[2],
+            paid_through_cess: through[3],
+            paid_in_cash: cash,
+        };
+        let zero = Money::ZERO;
+        vec![
+            row(
+                "IGST",
+                self.payable.igst,
+                [self.i_pdi, self.i_pdc, self.i_pds, zero],
+                self.cash.igst,
+            ),
+            row(
+                "CGST",
+                self.payable.cgst,
+                [self.c_pdi, self.c_pdc, zero, zero],
+                self.cash.cgst,
+            ),
+            row(
+                "SGST",
+                self.payable.sgst,
+                [self.s_pdi, zero, self.s_pds, zero],
+                self.cash.sgst,
+            ),
+            row(
+                "Cess",
+                self.payable.cess,
+                [zero, zero, zero, self.cs_pdcs],
+                self.cash.cess,
+            ),
+        ]
+    }
+
+    fn payment(&self) -> TaxPayment {
+        TaxPayment {
+            pditc: ItcPaid {
+                i_pdi: amount(self.i_pdi),
+                i_pdc: amount(self.i_pdc),
+                i_pds: amount(self.i_pds),
+                c_pdi: amount(self.c_pdi),
+                c_pdc: amount(self.c_pdc),
+                s_pdi: amount(self.s_pdi),
+                s_pds: amount(self.s_pds),
+                cs_pdcs: amount(self.cs_pdcs),
+            },
+            pdcash: vec![CashPaid {
+                ipd: amount(self.cash.igst),
+                cpd: amount(self.cash.cgst),
+                spd: amount(self.cash.sgst),
+                cspd: amount(self.cash.cess),
+            }],
+        }
+    }
+}
+
+/// Expenses approved between `from` and `to`, found through their journal
+/// postings, plus the ids of posted expenses that no longer exist
+pub(crate) async fn approved_expenses(
+    journal_repo: &JournalRepository,
+    expense_repo: &ExpenseRepository,
+    org_email: &str,
+    from: &str,
+    to: &str,
+) -> Result<(Vec<Expense>, Vec<String>), ApiError> {
+    let expense_ids: BTreeSet<String> = journal_repo
+        .find_in_range(org_email, Some(from), Some(to))
+        .await?
+        .into_iter()
+        .filter(|e| e.source == JournalSource::ExpenseApproved)
+        .map(|e| e.source_id)
+        .collect();
+
+    let mut expenses = Vec::new();
+    let mut missing = Vec::new();
+    for id in expense_ids {
+        match expense_repo.get_expense_by_id(org_email, &id).await? {
+            Some(expense) => expenses.push(expense),
+            None => missing.push(id),
+        }
+    }
+    Ok((expenses, missing))
+}
+
+/// Inter-state when the vendor GSTIN is from another state; items without a
+/// vendor GSTIN are taken as local purchases
+fn expense_inter_state(item: &ExpenseItem, supplier_state: Option<&str>) -> bool {
+    let vendor_state = item
+        .vendor_gstin
+        .as_deref()
+        .and_then(|g| g.get(0..2))
+        .filter(|c| c.chars().all(|ch| ch.is_ascii_digit()));
+    vendor_state.is_some() && vendor_state != supplier_state
+}
+
+/// Tax on an expense line as IGST for inter-state purchases, otherwise
+/// split equally between CGST and SGST
+pub(crate) fn expense_item_tax(item: &ExpenseItem, supplier_state: Option<&str>) -> TaxHeads {
+    let tax = item.tax_amount.unwrap_or_default();
+    if expense_inter_state(item, supplier_state) {
+        TaxHeads {
+            igst: tax,
+            ..TaxHeads::default()
+        }
+    } else {
+        let cgst = tax.percent(50.into());
+        TaxHeads {
+            cgst,
+            sgst: tax - cgst,
+            ..TaxHeads::default()
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct Gstr3bService {
+    invoice_repo: InvoiceRepository,
+    note_repo: CreditDebitNoteRepository,
+    org_repo: OrganisationRepository,
+    journal_repo: JournalRepository,
+    expense_repo: ExpenseRepository,
+}
+
+impl Gstr3bService {
+    pub fn new(
+        invoice_repo: InvoiceRepository,
+        note_repo: CreditDebitNoteRepository,
+        org_repo: OrganisationRepository,
+        journal_repo: JournalRepository,
+        expense_repo: ExpenseRepository,
+    ) -> Self {
+        Self {
+            invoice_repo,
+            note_repo,
+            org_repo,
+            journal_repo,
+            expense_repo,
+        }
+    }
+
+    /// Summarise the tax period `fp` (MMYYYY): outward supplies from issued
+    /// invoices and notes dated in the month, input tax credit from expenses
+    /// approved in the month
+    pub async fn generate(&self, org_email: &str, fp: &str) -> Result<Gstr3bReport, ApiError> {
+        let period = TaxPeriod::parse(fp).map_err(ApiError::ValidationError)?;
+        let org = self.org_repo.get_organisation_by_email(org_email).await?;
+        let gstin = org.gst_in.trim().to_uppercase();
+        if gstin.is_empty() {
+            return Err(ApiError::ValidationError(
+                "Organisation GSTIN is required to prepare GSTR-3B".to_string(),
+            ));
+        }
+        let claims_itc = org.claims_input_tax_credit();
+
+        let from = date::format(period.first_day());
+        let to = date::format(period.last_day());
+        let mut issues = Vec::new();
+
+        let (outward, invoice_count) = self.outward(org_email, &from, &to, &mut issues).await?;
+        let inward = self
+            .inward(org_email, org.state_code(), &from, &to, &mut issues)
+            .await?;
+
+        let (available, ineligible) = if claims_itc {
+            (inward.itc, Bucket::default())
+        } else {
+            (Bucket::default(), inward.itc)
+        };
+        let reversed = Bucket::default();
+        let net = Bucket {
+            value: available.value - reversed.value,
+            tax: available.tax - reversed.tax,
+        };
+        let inward_supply = Bucket::default();
+        let non_gst = Bucket::default();
+
+        let set_off = set_off(outward.liability(), net.tax);
+
+        let summary = Gstr3bSummary {
+            org_email: org_email.to_string(),
+            gstin: gstin.clone(),
+            period: period.fp(),
+            input_tax_credit_claimed: claims_itc,
+            outward_supplies: vec![
+                outward.taxable.row(
+                    "3.1(a)",
+                    "Outward taxable supplies (other than zero rated, nil rated and exempted)",
+                ),
+                outward
+                    .zero_rated
+                    .row("3.1(b)", "Outward taxable supplies (zero rated)"),
+                outward
+                    .nil_exempt
+                    .row("3.1(c)", "Other outward supplies (nil rated, exempted)"),
+                inward_supply.row("3.1(d)", "Inward supplies (liable to reverse charge)"),
+                non_gst.row("3.1(e)", "Non-GST outward supplies"),
+            ],
+            input_tax_credit: vec![
+                available.row("4(A)(5)", "All other ITC"),
+                reversed.row("4(B)(2)", "ITC reversed: others"),
+                net.row("4(C)", "Net ITC available (A) - (B)"),
+                ineligible.row("4(D)(2)", "Ineligible ITC: others"),
+            ],
+            inward_exempt_supplies: vec![
+                InwardSupplyRow {
+                    description: "From a supplier under composition scheme, exempt and nil rated supply"
+                        .to_string(),
+                    inter_state: inward.exempt_inter,
+                    intra_state: inward.exempt_intra,
+                },
+                InwardSupplyRow {
+                    description: "Non-GST supply".to_string(),
+                    inter_state: Money::ZERO,
+                    intra_state: Money::ZERO,
+                },
+            ],
+            tax_payment: set_off.rows(),
+            issues,
+        };
+
+        let supply = |bucket: &Bucket| SupplyValue {
+            txval: amount(bucket.value),
+            iamt: Some(amount(bucket.tax.igst)),
+            camt: Some(amount(bucket.tax.cgst)),
+            samt: Some(amount(bucket.tax.sgst)),
+            csamt: Some(amount(bucket.tax.cess)),
+        };
+        let none = TaxHeads::default();
+        let gstr3b = Gstr3b {
+            gstin,
+            ret_period: period.fp(),
+            sup_details: SupDetails {
+                osup_det: supply(&outward.taxable),
+                osup_zero: SupplyValue {
+                    camt: None,
+                    samt: None,
+                    ..supply(&outward.zero_rated)
+                },
+                osup_nil_exmp: SupplyValue {
+                    txval: amount(outward.nil_exempt.value),
+                    ..SupplyValue::default()
+                },
+                isup_rev: supply(&inward_supply),
+                osup_nongst: SupplyValue {
+                    txval: amount(non_gst.value),
+                    ..SupplyValue::default()
+                },
+            },
+            itc_elg: ItcEligibility {
+                itc_avl: vec![
+                    itc_line("IMPG", &none),
+                    itc_line("IMPS", &none),
+                    itc_line("ISRC", &none),
+                    itc_line("ISD", &none),
+                    itc_line("OTH", &available.tax),
+                ],
+                itc_rev: vec![itc_line("RUL", &none), itc_line("OTH", &reversed.tax)],
+                itc_net: tax_amounts(&net.tax),
+                itc_inelg: vec![itc_line("RUL", &none), itc_line("OTH", &ineligible.tax)],
+            },
+            inward_sup: InwardSupplies {
+                isup_details: vec![
+                    InwardLine {
+                        ty: "GST".to_string(),
+                        inter: amount(inward.exempt_inter),
+                        intra: amount(inward.exempt_intra),
+                    },
+                    InwardLine {
+                        ty: "NONGST".to_string(),
+                        inter: 0.0,
+                        intra: 0.0,
+                    },
+                ],
+            },
+            tx_pmt: set_off.payment(),
+        };
+
+        log::info!(
+            "Prepared GSTR-3B {} for {}: {} invoice(s), {} issue(s)",
+            gstr3b.ret_period,
+            org_email,
+            invoice_count,
+            summary.issues.len()
+        );
+        Ok(Gstr3bReport { summary, gstr3b })
+    }
+
+    /// Issued invoices and notes dated in the period
+    async fn outward(
+        &self,
+        org_email: &str,
+        from: &str,
+        to: &str,
+        issues: &mut Vec<ReturnIssue>,
+    ) -> Result<(Outward, usize), ApiError> {
+        let mut outward = Outward::default();
+        let mut count = 0;
+
+        for invoice in self.invoice_repo.find_for_period(org_email, from, to).await? {
+            if !matches!(
+                invoice.status,
+                InvoiceStatus::Issued
+                    | InvoiceStatus::PartiallyPaid
+                    | InvoiceStatus::Paid
+                    | InvoiceStatus::Overdue
+            ) {
+                continue;
+            }
+            let id = invoice.id.map(|id| id.to_hex());
+            // Foreign-currency exports are reported at the invoice's exchange rate
+            let invoice = match invoice.in_inr() {
+                Ok(converted) => converted,
+                Err(message) => {
+                    issues.push(issue("invoice", id, &invoice.invoice_number, message));
+                    continue;
+                }
+            };
+            let export = invoice.invoice_type.eq_ignore_ascii_case("international");
+            if let Err(e) = outward.add_items(&invoice.items, export, false) {
+                issues.push(issue("invoice", id, &invoice.invoice_number, e.to_string()));
+                continue;
+            }
+            count += 1;
+        }
+
+        let notes =
+            notes_for_period(&self.invoice_repo, &self.note_repo, org_email, from, to).await?;
+        for note in notes {
+            let (document_type, reduce) = match note.note_type {
+                NoteType::Credit => ("credit_note", true),
+                NoteType::Debit => ("debit_note", false),
+            };
+            let id = note.id.map(|id| id.to_hex());
+            if note.currency != DEFAULT_CURRENCY {
+                issues.push(issue(
+                    document_type,
+                    id,
+                    &note.note_number,
+                    format!("Amounts are in {}; GSTR-3B needs INR values", note.currency),
+                ));
+                continue;
+            }
+            let export = note.invoice_type.eq_ignore_ascii_case("international");
+            if let Err(e) = outward.add_items(&note.items, export, reduce) {
+                issues.push(issue(document_type, id, &note.note_number, e.to_string()));
+            }
+        }
+
+        Ok((outward, count))
+    }
+
+    /// Expenses approved in the period
+    async fn inward(
+        &self,
+        org_email: &str,
+        supplier_state: Option<&str>,
+        from: &str,
+        to: &str,
+        issues: &mut Vec<ReturnIssue>,
+    ) -> Result<Inward, ApiError> {
+        let (expenses, missing) =
+            approved_expenses(&self.journal_repo, &self.expense_repo, org_email, from, to).await?;
+        for id in missing {
+            issues.push(issue(
+                "expense",
+                Some(id),
+                "",
+                "Approved expense no longer exists".to_string(),
+            ));
+        }
+
+        let mut inward = Inward::default();
+        for expense in &expenses {
+            self.add_expense(&mut inward, expense, supplier_state, issues);
+        }
+        Ok(inward)
+    }
+
+    fn add_expense(
+        &self,
+        inward: &mut Inward,
+        expense: &Expense,
+        supplier_state: Option<&str>,
+        issues: &mut Vec<ReturnIssue>,
+    ) {
+        for (idx, item) in expense.items.iter().enumerate() {
+            if item.currency != DEFAULT_CURRENCY {
+                issues.push(issue(
+                    "expense",
+                    expense.id.map(|id| id.to_hex()),
+                    &expense.expense_title,
+                    format!(
+                        "Line {} is in {}; left out of GSTR-3B",
+                        idx + 1,
+                        item.currency
+                    ),
+                ));
+                continue;
+            }
+
+            let tax = item.tax_amount.unwrap_or_default();
+            if tax.is_zero() {
+                if expense_inter_state(item, supplier_state) {
+                    inward.exempt_inter += item.amount;
+                } else {
+                    inward.exempt_intra += item.amount;
+                }
+                continue;
+            }
+
+            let heads = expense_item_tax(item, supplier_state);
+            inward.itc.add(item.amount, heads);
+        }
+    }
+}
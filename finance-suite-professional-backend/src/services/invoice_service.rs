@@ -1,22 +1,46 @@
 use std::sync::Arc;
 
+use mongodb::bson::{doc, oid::ObjectId};
+
 use crate::{
-    models::invoice::Invoice,
-    repository::{invoice_repository::InvoiceRepository, organisation_repository::OrganisationRepository},
+    models::{
+        invoice::{ExportType, Invoice, InvoiceStatus, ShippingBillRequest},
+        money::{self, Money, DEFAULT_CURRENCY},
+        organisation::Organisation,
+        purchase_order::PurchaseOrderKind,
+    },
+    services::{CatalogService, LedgerService},
+    repository::{
+        invoice_repository::InvoiceRepository, organisation_repository::OrganisationRepository,
+        purchase_order_repository::PurchaseOrderRepository,
+    },
     error::ApiError,
+    utils::{date, gst, invoice_pdf, validation},
 };
 
 #[derive(Clone)]
 pub struct InvoiceService {
     repo: Arc<InvoiceRepository>,
     org_repo: Arc<OrganisationRepository>,
+    po_repo: Arc<PurchaseOrderRepository>,
+    ledger: LedgerService,
+    catalog: CatalogService,
 }
 
 impl InvoiceService {
-    pub fn new(repo: InvoiceRepository, org_repo: OrganisationRepository) -> Self {
+    pub fn new(
+        repo: InvoiceRepository,
+        org_repo: OrganisationRepository,
+        po_repo: PurchaseOrderRepository,
+        ledger: LedgerService,
+        catalog: CatalogService,
+    ) -> Self {
         Self {
             repo: Arc::new(repo),
             org_repo: Arc::new(org_repo),
+            po_repo: Arc::new(po_repo),
+            ledger,
+            catalog,
         }
     }
 
@@ -52,46 +76,632 @@ impl InvoiceService {
         Ok(invoice_number)
     }
 
-    pub async fn create_invoice(&self, mut invoice: Invoice, org_email: &str) -> anyhow::Result<Invoice> {
+    /// Derive line taxes and totals server-side. Without an organisation the
+    /// supplier state comes from the invoice's own GSTIN and the default
+    /// rounding rule applies.
+    fn compute_taxes(invoice: &mut Invoice, org: Option<&Organisation>) -> Result<(), ApiError> {
+        if !money::is_valid_currency_code(&invoice.currency) {
+            return Err(ApiError::ValidationError(format!(
+                "Invalid currency code '{}'",
+                invoice.currency
+            )));
+        }
+
+        let supplier_state = org
+            .and_then(Organisation::state_code)
+            .or_else(|| {
+                invoice
+                    .gst_in
+                    .get(0..2)
+                    .filter(|c| c.chars().all(|ch| ch.is_ascii_digit()))
+            })
+            .map(str::to_string);
+        let rounding = org.map(Organisation::rounding).unwrap_or_default();
+
+        let supply_type = gst::determine_supply_type(supplier_state.as_deref(), invoice)?;
+        gst::compute_invoice_taxes(invoice, supply_type, rounding)?;
+        invoice.refresh_balance();
+        Ok(())
+    }
+
+    /// Normalise the customer GSTINs; blank or "unregistered" is a B2C supply
+    fn check_gstins(invoice: &mut Invoice) -> Result<(), ApiError> {
+        invoice.billcustomer_gstin = validation::clean_optional_gstin(&invoice.billcustomer_gstin)
+            .map_err(|e| ApiError::ValidationError(format!("Billing customer: {}", e)))?;
+        invoice.shipcustomer_gstin = validation::clean_optional_gstin(&invoice.shipcustomer_gstin)
+            .map_err(|e| ApiError::ValidationError(format!("Shipping customer: {}", e)))?;
+        Ok(())
+    }
+
+    /// Check the currency conversion and export details. International
+    /// invoices default to export without payment of IGST when an LUT is
+    /// given; shipping bill details may follow once the goods are cleared.
+    fn check_export(invoice: &mut Invoice) -> Result<(), ApiError> {
+        invoice.exchange_rate = invoice.exchange_rate.trim().to_string();
+        if invoice.currency == DEFAULT_CURRENCY {
+            invoice.exchange_rate.clear();
+        } else if !invoice.exchange_rate.is_empty()
+            && gst::parse_decimal("Exchange rate", &invoice.exchange_rate)?.is_zero()
+        {
+            return Err(ApiError::ValidationError(
+                "Exchange rate must be greater than zero".to_string(),
+            ));
+        }
+
+        invoice.shipping_bill_no = invoice.shipping_bill_no.trim().to_string();
+        invoice.shipping_bill_date = invoice.shipping_bill_date.trim().to_string();
+        invoice.port_code = invoice.port_code.trim().to_uppercase();
+
+        if !invoice.invoice_type.eq_ignore_ascii_case("international") {
+            if invoice.export_type.is_some()
+                || !invoice.shipping_bill_no.is_empty()
+                || !invoice.shipping_bill_date.is_empty()
+                || !invoice.port_code.is_empty()
+            {
+                return Err(ApiError::ValidationError(
+                    "Export details only apply to international invoices".to_string(),
+                ));
+            }
+            return Ok(());
+        }
+
+        let has_lut = !invoice.lut_no.trim().is_empty();
+        let export_type = *invoice.export_type.get_or_insert(if has_lut {
+            ExportType::WithoutPayment
+        } else {
+            ExportType::WithPayment
+        });
+        if export_type == ExportType::WithoutPayment && !has_lut {
+            return Err(ApiError::ValidationError(
+                "Exports without payment of IGST need an LUT number".to_string(),
+            ));
+        }
+
+        if invoice.shipping_bill_no.is_empty() != invoice.shipping_bill_date.is_empty() {
+            return Err(ApiError::ValidationError(
+                "Shipping bill number and date go together".to_string(),
+            ));
+        }
+        if !invoice.shipping_bill_no.is_empty()
+            && !validation::SHIPPING_BILL_REGEX.is_match(&invoice.shipping_bill_no)
+        {
+            return Err(ApiError::ValidationError(
+                "Shipping bill number must be up to 7 digits".to_string(),
+            ));
+        }
+        if !invoice.shipping_bill_date.is_empty() {
+            let shipped = date::parse(&invoice.shipping_bill_date).ok_or_else(|| {
+                ApiError::ValidationError("Shipping bill date must be YYYY-MM-DD".to_string())
+            })?;
+            if matches!(date::parse(&invoice.invoice_date), Some(invoiced) if shipped < invoiced) {
+                return Err(ApiError::ValidationError(
+                    "Shipping bill date cannot be before the invoice date".to_string(),
+                ));
+            }
+        }
+        if !invoice.port_code.is_empty() && !validation::PORT_CODE_REGEX.is_match(&invoice.port_code)
+        {
+            return Err(ApiError::ValidationError(
+                "Port code must be 6 letters or digits, e.g. INMAA1".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Check every line carries an HSN/SAC code as long as the organisation's
+    /// `requireHSN` setting and turnover band demand. Registered customers
+    /// and exports count as B2B.
+    fn check_hsn(invoice: &Invoice, org: &Organisation) -> Result<(), ApiError> {
+        let b2b = !invoice.billcustomer_gstin.trim().is_empty()
+            || invoice.invoice_type.eq_ignore_ascii_case("international");
+        let Some(min_digits) = org.hsn_digits_required(b2b) else {
+            return Ok(());
+        };
+
+        let problems: Vec<String> = invoice
+            .items
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, item)| {
+                validation::validate_hsn_sac(&item.hsn_sac, min_digits)
+                    .err()
+                    .map(|e| format!("Item {}: {}", idx + 1, e))
+            })
+            .collect();
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(ApiError::ValidationError(problems.join("; ")))
+        }
+    }
+
+    /// Link the invoice to a customer PO on file (by `po_id`, else by
+    /// `po_number`) and check it fits in the PO's unbilled value. PO numbers
+    /// that are not on file are kept as free text.
+    async fn resolve_purchase_order(
+        &self,
+        invoice: &mut Invoice,
+        org_email: &str,
+    ) -> Result<(), ApiError> {
+        let po = match invoice.po_id.as_deref().filter(|id| !id.is_empty()) {
+            Some(po_id) => Some(
+                self.po_repo
+                    .find_by_id(org_email, po_id)
+                    .await?
+                    .filter(|po| po.kind == PurchaseOrderKind::Customer)
+                    .ok_or_else(|| {
+                        ApiError::NotFound(format!("Customer purchase order {} not found", po_id))
+                    })?,
+            ),
+            None if !invoice.po_number.trim().is_empty() => {
+                let number = invoice.po_number.trim();
+                match self
+                    .po_repo
+                    .find_by_number(
+                        org_email,
+                        PurchaseOrderKind::Customer,
+                        number,
+                        Some(&invoice.billcustomer_name),
+                    )
+                    .await?
+                {
+                    Some(po) => Some(po),
+                    None => {
+                        self.po_repo
+                            .find_by_number(org_email, PurchaseOrderKind::Customer, number, None)
+                            .await?
+                    }
+                }
+            }
+            None => None,
+        };
+
+        let Some(po) = po else {
+            invoice.po_id = None;
+            return Ok(());
+        };
+
+        if !po.can_bill() {
+            return Err(ApiError::Conflict(format!(
+                "Purchase order {} is {:?} and cannot be billed",
+                po.po_number, po.status
+            )));
+        }
+        if po.currency != invoice.currency {
+            return Err(ApiError::ValidationError(format!(
+                "Purchase order {} is in {} but the invoice is in {}",
+                po.po_number, po.currency, invoice.currency
+            )));
+        }
+        if invoice.sub_total > po.remaining_amount() {
+            return Err(ApiError::ValidationError(format!(
+                "Invoice value {} exceeds the unbilled value {} of purchase order {}",
+                invoice.sub_total,
+                po.remaining_amount(),
+                po.po_number
+            )));
+        }
+
+        invoice.po_id = po.id.map(|id| id.to_hex());
+        invoice.po_number = po.po_number.clone();
+        if invoice.po_date.trim().is_empty() {
+            invoice.po_date = po.po_date.clone();
+        }
+        Ok(())
+    }
+
+    pub async fn create_invoice(
+        &self,
+        mut invoice: Invoice,
+        org_email: &str,
+    ) -> anyhow::Result<Invoice> {
+        // Only the recurring and estimate services link an invoice to its source
+        invoice.recurring_id = None;
+        invoice.recurring_run_date = None;
+        invoice.estimate_id = None;
+        self.create_linked_invoice(invoice, org_email).await
+    }
+
+    /// Create a draft invoice keeping the recurring schedule run or estimate
+    /// it was generated from
+    pub(crate) async fn create_linked_invoice(
+        &self,
+        mut invoice: Invoice,
+        org_email: &str,
+    ) -> anyhow::Result<Invoice> {
         log::info!("Creating invoice for org_email: {}", org_email);
-        
+
+        let org = self.org_repo.get_organisation_by_email(org_email).await?;
+        Self::check_gstins(&mut invoice)?;
+        self.catalog
+            .apply_to_items(&mut invoice.items, org_email)
+            .await?;
+        Self::check_export(&mut invoice)?;
+        Self::compute_taxes(&mut invoice, Some(&org))?;
+        self.resolve_purchase_order(&mut invoice, org_email).await?;
+
+        // New invoices always start as drafts; use the transition endpoints to move on
+        invoice.status = InvoiceStatus::Draft;
+        invoice.issued_at = None;
+        invoice.closed_at = None;
+        invoice.closed_reason = None;
+        invoice.amount_paid = Money::ZERO;
+        invoice.credited_amount = Money::ZERO;
+        invoice.debited_amount = Money::ZERO;
+        invoice.refresh_balance();
+        invoice.org_email = org_email.to_string();
+        invoice.invoice_number = String::new();
+        // IRN and e-way bill details only come back from the portals
+        invoice.e_invoice = None;
+        invoice.e_way_bill = None;
+
+        // Save before numbering, so an insert refused by the recurring run
+        // index (another instance generated the run first) burns no number
+        let mut created = self.repo.create_invoice(invoice).await?;
+        let id = created.id.map(|id| id.to_hex()).unwrap_or_default();
+
         match self.generate_invoice_number(org_email).await {
             Ok(invoice_number) => {
                 log::info!("Generated invoice number: {}", invoice_number);
-                invoice.invoice_number = invoice_number;
+                self.repo
+                    .set_fields(&id, doc! { "invoice_number": &invoice_number })
+                    .await?;
+                created.invoice_number = invoice_number;
             }
             Err(e) => {
                 log::error!("Failed to generate invoice number: {}", e);
+                self.repo.delete_draft(org_email, &id).await?;
                 return Err(e);
             }
         }
-        
-        let created = self.repo.create_invoice(invoice).await?;
+
         log::info!("Invoice created successfully with ID: {:?}", created.id);
         Ok(created)
     }
 
-    pub async fn get_all_invoices(&self) -> anyhow::Result<Vec<Invoice>> {
-        let invoices = self.repo.get_all_invoices().await?;
+    /// Compute taxes and totals for an invoice without saving or numbering it
+    pub async fn preview_invoice(&self, mut invoice: Invoice, org_email: &str) -> anyhow::Result<Invoice> {
+        let org = self.org_repo.get_organisation_by_email(org_email).await?;
+        self.catalog
+            .apply_to_items(&mut invoice.items, org_email)
+            .await?;
+        Self::check_export(&mut invoice)?;
+        Self::compute_taxes(&mut invoice, Some(&org))?;
+        invoice.status = InvoiceStatus::Draft;
+        Ok(invoice)
+    }
+
+    pub async fn get_all_invoices(&self, org_email: &str) -> anyhow::Result<Vec<Invoice>> {
+        let invoices = self.repo.get_all_invoices(org_email).await?;
         Ok(invoices)
     }
 
-    pub async fn get_invoice_by_id(&self, id: &str) -> anyhow::Result<Option<Invoice>> {
-        let invoice = self.repo.get_invoice_by_id(id).await?;
+    /// Flag unpaid invoices past their due date as overdue
+    pub async fn mark_overdue(&self) -> anyhow::Result<u64> {
+        let today = date::today();
+        Ok(self.repo.mark_overdue_invoices(&today).await?)
+    }
+
+    /// Background loop marking overdue invoices every `interval`, so reads
+    /// never write
+    pub fn spawn_overdue_scheduler(self, interval: std::time::Duration) {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                match self.mark_overdue().await {
+                    Ok(0) => {}
+                    Ok(marked) => log::info!("Marked {} invoice(s) overdue", marked),
+                    Err(e) => log::error!("Overdue invoice check failed: {}", e),
+                }
+            }
+        });
+    }
+
+    pub async fn get_invoice_by_id(
+        &self,
+        id: &str,
+        org_email: &str,
+    ) -> anyhow::Result<Option<Invoice>> {
+        let invoice = self.repo.get_invoice_by_id(org_email, id).await?;
         Ok(invoice)
     }
 
     pub async fn update_invoice(
         &self,
         id: &str,
-        invoice: Invoice,
+        mut invoice: Invoice,
+        org_email: &str,
     ) -> anyhow::Result<Option<Invoice>> {
-        let updated = self.repo.update_invoice(id, invoice).await?;
+        let Some(existing) = self.repo.get_invoice_by_id(org_email, id).await? else {
+            return Ok(None);
+        };
+        if !existing.is_editable() {
+            return Err(ApiError::Conflict(format!(
+                "Invoice in {:?} status cannot be edited",
+                existing.status
+            ))
+            .into());
+        }
+
+        // Lifecycle fields and the number only change through dedicated endpoints
+        invoice.status = existing.status;
+        invoice.issued_at = existing.issued_at;
+        invoice.closed_at = existing.closed_at;
+        invoice.closed_reason = existing.closed_reason;
+        invoice.invoice_number = existing.invoice_number;
+        invoice.amount_paid = existing.amount_paid;
+        invoice.credited_amount = existing.credited_amount;
+        invoice.debited_amount = existing.debited_amount;
+        invoice.recurring_id = existing.recurring_id;
+        invoice.recurring_run_date = existing.recurring_run_date;
+        invoice.estimate_id = existing.estimate_id;
+        invoice.org_email = existing.org_email;
+        invoice.e_invoice = existing.e_invoice;
+        invoice.e_way_bill = existing.e_way_bill;
+
+        let org = self.org_repo.get_organisation_by_email(org_email).await?;
+        Self::check_gstins(&mut invoice)?;
+        self.catalog
+            .apply_to_items(&mut invoice.items, org_email)
+            .await?;
+        Self::check_export(&mut invoice)?;
+        Self::compute_taxes(&mut invoice, Some(&org))?;
+        self.resolve_purchase_order(&mut invoice, org_email).await?;
+
+        match self
+            .repo
+            .replace_in_status(id, InvoiceStatus::Draft, invoice)
+            .await?
+        {
+            Some(updated) => Ok(Some(updated)),
+            None => self.moved_on(id, org_email, "edited").await,
+        }
+    }
+
+    /// Report a guarded write that matched nothing: the invoice was deleted
+    /// or left the status it was read in
+    async fn moved_on(
+        &self,
+        id: &str,
+        org_email: &str,
+        action: &str,
+    ) -> anyhow::Result<Option<Invoice>> {
+        match self.repo.get_invoice_by_id(org_email, id).await? {
+            Some(current) => Err(ApiError::Conflict(format!(
+                "Invoice in {:?} status cannot be {}",
+                current.status, action
+            ))
+            .into()),
+            None => Ok(None),
+        }
+    }
+
+    /// Render the invoice as a tax-invoice PDF. `None` when the invoice does not exist.
+    pub async fn render_invoice_pdf(
+        &self,
+        id: &str,
+        org_email: &str,
+    ) -> anyhow::Result<Option<(Invoice, Vec<u8>)>> {
+        let Some(invoice) = self.repo.get_invoice_by_id(org_email, id).await? else {
+            return Ok(None);
+        };
+        let org = self.org_repo.get_organisation_by_email(org_email).await?;
+        let pdf = invoice_pdf::render_invoice_pdf(&invoice, &org)?;
+        Ok(Some((invoice, pdf)))
+    }
+
+    /// Move a draft invoice to Issued once its HSN/SAC codes satisfy the
+    /// organisation's requirement and a foreign-currency invoice has its
+    /// exchange rate
+    pub async fn issue_invoice(
+        &self,
+        id: &str,
+        org_email: &str,
+    ) -> anyhow::Result<Option<Invoice>> {
+        let Some(invoice) = self.repo.get_invoice_by_id(org_email, id).await? else {
+            return Ok(None);
+        };
+        if invoice.status == InvoiceStatus::Draft {
+            let org = self.org_repo.get_organisation_by_email(org_email).await?;
+            Self::check_hsn(&invoice, &org)?;
+            invoice.inr_rate().map_err(ApiError::ValidationError)?;
+        }
+
+        self.transition(id, org_email, |invoice| invoice.issue()).await
+    }
+
+    /// Record the shipping bill of an export invoice. Allowed after issue
+    /// and after IRN registration, since the shipping bill is only known
+    /// once the goods are cleared.
+    pub async fn record_shipping_bill(
+        &self,
+        id: &str,
+        req: ShippingBillRequest,
+        org_email: &str,
+    ) -> anyhow::Result<Option<Invoice>> {
+        let Some(mut invoice) = self.repo.get_invoice_by_id(org_email, id).await? else {
+            return Ok(None);
+        };
+        if !invoice.invoice_type.eq_ignore_ascii_case("international") {
+            return Err(ApiError::BadRequest(
+                "Only international invoices have a shipping bill".to_string(),
+            )
+            .into());
+        }
+        if matches!(invoice.status, InvoiceStatus::Cancelled | InvoiceStatus::Void) {
+            return Err(ApiError::Conflict(format!(
+                "Invoice in {:?} status cannot be changed",
+                invoice.status
+            ))
+            .into());
+        }
+
+        // Keep what was charged on invoices saved before export types were recorded
+        if invoice.export_type.is_none() && !invoice.lut_no.trim().is_empty() {
+            invoice.export_type = Some(invoice.effective_export_type());
+        }
+        invoice.shipping_bill_no = req.shipping_bill_no;
+        invoice.shipping_bill_date = req.shipping_bill_date;
+        invoice.port_code = req.port_code;
+        Self::check_export(&mut invoice)?;
+
+        let mut fields = doc! {
+            "shippingBillNo": &invoice.shipping_bill_no,
+            "shippingBillDate": &invoice.shipping_bill_date,
+            "portCode": &invoice.port_code,
+        };
+        if let Some(export_type) = invoice.export_type {
+            fields.insert("exportType", money::to_bson(&export_type)?);
+        }
+        let updated = self.repo.set_fields(id, fields).await?;
         Ok(updated)
     }
 
-    pub async fn delete_invoice(&self, id: &str) -> anyhow::Result<bool> {
-        let deleted = self.repo.delete_invoice(id).await?;
-        Ok(deleted)
+    /// Cancel a draft or unpaid issued invoice
+    pub async fn cancel_invoice(
+        &self,
+        id: &str,
+        reason: Option<String>,
+        org_email: &str,
+    ) -> anyhow::Result<Option<Invoice>> {
+        self.transition(id, org_email, |invoice| invoice.cancel(reason)).await
+    }
+
+    /// Void an issued invoice; its number stays consumed
+    pub async fn void_invoice(
+        &self,
+        id: &str,
+        reason: Option<String>,
+        org_email: &str,
+    ) -> anyhow::Result<Option<Invoice>> {
+        self.transition(id, org_email, |invoice| invoice.void(reason)).await
+    }
+
+    async fn transition<F>(
+        &self,
+        id: &str,
+        org_email: &str,
+        apply: F,
+    ) -> anyhow::Result<Option<Invoice>>
+    where
+        F: FnOnce(&mut Invoice) -> Result<(), String>,
+    {
+        let Some(original) = self.repo.get_invoice_by_id(org_email, id).await? else {
+            return Ok(None);
+        };
+
+        let before = original.status;
+        let mut invoice = original.clone();
+        apply(&mut invoice).map_err(ApiError::Conflict)?;
+
+        // Issuing bills the linked customer PO; cancelling or voiding an
+        // issued invoice gives the value back
+        let po_id = invoice
+            .po_id
+            .as_deref()
+            .and_then(|id| ObjectId::parse_str(id).ok());
+        let billed_now = before == InvoiceStatus::Draft && invoice.status == InvoiceStatus::Issued;
+        let released_now = before != InvoiceStatus::Draft
+            && matches!(invoice.status, InvoiceStatus::Cancelled | InvoiceStatus::Void);
+
+        if let (Some(po_id), true) = (po_id, billed_now) {
+            if !self.po_repo.bill(po_id, invoice.sub_total).await? {
+                return Err(ApiError::Conflict(format!(
+                    "Invoice value {} exceeds the unbilled value of purchase order {}",
+                    invoice.sub_total, invoice.po_number
+                ))
+                .into());
+            }
+        }
+
+        // Save only if nobody moved the invoice on since it was read, then
+        // post: the journal is append-only, so a posting for a transition
+        // that lost a race could not be taken back
+        let saved = match self.repo.replace_in_status(id, before, invoice.clone()).await {
+            Ok(Some(saved)) => Ok(saved),
+            Ok(None) => Err(anyhow::Error::from(ApiError::Conflict(format!(
+                "Invoice {} changed status while being updated; reload and retry",
+                invoice.invoice_number
+            )))),
+            Err(e) => Err(e.into()),
+        };
+        let updated = match saved {
+            Ok(updated) => updated,
+            Err(e) => {
+                if let (Some(po_id), true) = (po_id, billed_now) {
+                    self.po_repo.release(po_id, invoice.sub_total).await?;
+                }
+                return Err(e);
+            }
+        };
+
+        if let Err(e) = self.post_transition(&invoice, billed_now, released_now).await {
+            self.repo
+                .replace_in_status(id, invoice.status, original)
+                .await?;
+            if let (Some(po_id), true) = (po_id, billed_now) {
+                self.po_repo.release(po_id, invoice.sub_total).await?;
+            }
+            return Err(e.into());
+        }
+
+        if let (Some(po_id), true) = (po_id, released_now) {
+            self.po_repo.release(po_id, invoice.sub_total).await?;
+        }
+
+        Ok(Some(updated))
+    }
+
+    /// Journal entry for an issue or a cancel/void of an issued invoice.
+    /// Invoices created before the ledger existed carry no organisation and
+    /// fall back to the company email.
+    async fn post_transition(
+        &self,
+        invoice: &Invoice,
+        issued_now: bool,
+        reversed_now: bool,
+    ) -> Result<(), ApiError> {
+        if !issued_now && !reversed_now {
+            return Ok(());
+        }
+
+        let org_email = if invoice.org_email.is_empty() {
+            invoice.company_email.as_str()
+        } else {
+            invoice.org_email.as_str()
+        };
+        if org_email.is_empty() {
+            log::warn!(
+                "Invoice {} has no organisation; skipping ledger posting",
+                invoice.invoice_number
+            );
+            return Ok(());
+        }
+
+        if issued_now {
+            self.ledger.post_invoice_issued(invoice, org_email).await?;
+        } else {
+            self.ledger.post_invoice_reversed(invoice, org_email).await?;
+        }
+        Ok(())
+    }
+
+    pub async fn delete_invoice(&self, id: &str, org_email: &str) -> anyhow::Result<bool> {
+        let Some(existing) = self.repo.get_invoice_by_id(org_email, id).await? else {
+            return Ok(false);
+        };
+        if !existing.is_editable() {
+            return Err(ApiError::Conflict(format!(
+                "Invoice in {:?} status cannot be deleted",
+                existing.status
+            ))
+            .into());
+        }
+
+        if self.repo.delete_draft(org_email, id).await? {
+            return Ok(true);
+        }
+        Ok(self.moved_on(id, org_email, "deleted").await?.is_some())
     }
 }
@@ -0,0 +1,124 @@
+//! Clients for the Invoice Registration Portal. The e-invoice service only
+//! talks to [`IrpClient`], so a GSP-backed client can replace the local stub
+//! without touching it.
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use chrono::{Datelike, Utc};
+use futures::future::BoxFuture;
+use sha2::{Digest, Sha256};
+
+use crate::error::ApiError;
+use crate::models::e_invoice::{EInvoicePayload, IrpResponse};
+use crate::utils::date::{self, NIC_DATE_FORMAT};
+
+pub trait IrpClient: Send + Sync {
+    /// Short name stored with each registration, e.g. "stub"
+    fn name(&self) -> &'static str;
+
+    /// Register the invoice and return the IRN, acknowledgement and signed QR
+    fn generate_irn<'a>(
+        &'a self,
+        payload: &'a EInvoicePayload,
+    ) -> BoxFuture<'a, Result<IrpResponse, ApiError>>;
+}
+
+/// The IRN is the SHA-256 of supplier GSTIN, financial year, document type
+/// and document number, which is also how the IRP derives it
+pub fn compute_irn(payload: &EInvoicePayload) -> Result<String, ApiError> {
+    let date = date::parse_as(&payload.doc_dtls.dt, NIC_DATE_FORMAT).ok_or_else(|| {
+        ApiError::ValidationError(format!(
+            "Document date '{}' is not dd/mm/yyyy",
+            payload.doc_dtls.dt
+        ))
+    })?;
+    let start_year = if date.month() >= 4 {
+        date.year()
+    } else {
+        date.year() - 1
+    };
+    let financial_year = format!("{}-{:02}", start_year, (start_year + 1) % 100);
+    let gstin = payload.seller_dtls.gstin.as_deref().unwrap_or_default();
+
+    let digest = Sha256::digest(
+        format!(
+            "{}{}{}{}",
+            gstin,
+            financial_year,
+            payload.doc_dtls.typ,
+            payload.doc_dtls.no.to_uppercase()
+        )
+        .as_bytes(),
+    );
+    Ok(digest.iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+/// Registers invoices locally without calling the IRP. The QR token carries
+/// the same claims the IRP puts in its signed QR but is not signed, so it is
+/// only fit for development and testing.
+pub struct StubIrpClient;
+
+impl StubIrpClient {
+    fn register(payload: &EInvoicePayload) -> Result<IrpResponse, ApiError> {
+        let irn = compute_irn(payload)?;
+        let now = Utc::now();
+        let ack_no = format!("{:015}", now.timestamp_millis() % 1_000_000_000_000_000);
+        let ack_dt = now.format("%Y-%m-%d %H:%M:%S").to_string();
+
+        let main_hsn = payload
+            .item_list
+            .iter()
+            .max_by(|a, b| a.ass_amt.total_cmp(&b.ass_amt))
+            .map(|item| item.hsn_cd.clone())
+            .unwrap_or_default();
+        let qr_data = serde_json::json!({
+            "SellerGstin": payload.seller_dtls.gstin,
+            "BuyerGstin": payload.buyer_dtls.gstin,
+            "DocNo": payload.doc_dtls.no,
+            "DocTyp": payload.doc_dtls.typ,
+            "DocDt": payload.doc_dtls.dt,
+            "TotInvVal": payload.val_dtls.tot_inv_val,
+            "ItemCnt": payload.item_list.len(),
+            "MainHsnCode": main_hsn,
+            "Irn": irn,
+            "IrnDt": ack_dt,
+        });
+        let invoice_data = serde_json::to_string(payload).map_err(|e| {
+            ApiError::InternalServerError(format!("Failed to encode e-invoice: {}", e))
+        })?;
+
+        Ok(IrpResponse {
+            ack_no,
+            ack_dt,
+            irn,
+            signed_invoice: unsigned_token(&serde_json::json!({
+                "data": invoice_data,
+                "iss": "stub",
+            })),
+            signed_qr_code: unsigned_token(&serde_json::json!({
+                "data": qr_data.to_string(),
+                "iss": "stub",
+            })),
+        })
+    }
+}
+
+/// JWT with `alg: none` and an empty signature
+fn unsigned_token(claims: &serde_json::Value) -> String {
+    let header = URL_SAFE_NO_PAD.encode(r#"{"alg":"none","typ":"JWT"}"#);
+    let body = URL_SAFE_NO_PAD.encode(claims.to_string());
+    format!("{}.{}.", header, body)
+}
+
+impl IrpClient for StubIrpClient {
+    fn name(&self) -> &'static str {
+        "stub"
+    }
+
+    fn generate_irn<'a>(
+        &'a self,
+        payload: &'a EInvoicePayload,
+    ) -> BoxFuture<'a, Result<IrpResponse, ApiError>> {
+        Box::pin(async move { Self::register(payload) })
+    }
+}
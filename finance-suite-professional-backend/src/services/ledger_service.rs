@@ -0,0 +1,669 @@
+use std::collections::BTreeMap;
+
+use mongodb::bson::DateTime;
+
+use crate::error::ApiError;
+use crate::models::expense::Expense;
+use crate::models::invoice::Invoice;
+use crate::models::ledger::{
+    self, Account, AccountBalance, AccountLedger, AccountType, CreateAccountRequest, ForeignAmount,
+    JournalEntry, JournalLine, JournalSource, LedgerLine,
+};
+use crate::models::money::{Money, DEFAULT_CURRENCY};
+use crate::repository::{AccountRepository, JournalRepository};
+use crate::utils::date;
+
+#[derive(Clone)]
+pub struct LedgerService {
+    accounts: AccountRepository,
+    journal: JournalRepository,
+}
+
+/// Signed balance from the account's normal side
+fn signed_balance(account_type: AccountType, debit: Money, credit: Money) -> Money {
+    if account_type.is_debit_normal() {
+        debit - credit
+    } else {
+        credit - debit
+    }
+}
+
+impl LedgerService {
+    pub fn new(accounts: AccountRepository, journal: JournalRepository) -> Self {
+        Self { accounts, journal }
+    }
+
+    /// Chart of accounts, seeding the default accounts on first use
+    pub async fn get_accounts(&self, org_email: &str) -> Result<Vec<Account>, ApiError> {
+        let mut accounts = self.accounts.find_all(org_email).await?;
+
+        let missing: Vec<_> = ledger::DEFAULT_ACCOUNTS
+            .iter()
+            .filter(|(code, _, _)| !accounts.iter().any(|a| a.code == *code))
+            .collect();
+        if missing.is_empty() {
+            return Ok(accounts);
+        }
+
+        for (code, name, account_type) in missing {
+            let account = Account::new(org_email, code, name, *account_type);
+            // A concurrent request may have seeded it already
+            if let Err(err) = self.accounts.create(account).await {
+                log::debug!("Seeding account {} for {}: {}", code, org_email, err);
+            }
+        }
+        accounts = self.accounts.find_all(org_email).await?;
+        Ok(accounts)
+    }
+
+    /// Add a custom account to the chart
+    pub async fn create_account(
+        &self,
+        org_email: &str,
+        mut account: CreateAccountRequest,
+    ) -> Result<Account, ApiError> {
+        account.code = account.code.trim().to_string();
+        account.name = account.name.trim().to_string();
+        if account.code.is_empty() || account.name.is_empty() {
+            return Err(ApiError::ValidationError(
+                "Account code and name are required".to_string(),
+            ));
+        }
+
+        let chart = self.get_accounts(org_email).await?;
+        if chart.iter().any(|a| a.code == account.code) {
+            return Err(ApiError::ValidationError(format!(
+                "Account {} already exists",
+                account.code
+            )));
+        }
+
+        account.id = None;
+        account.org_email = org_email.to_string();
+        account.system = false;
+        account.created_at = Some(DateTime::now());
+
+        self.accounts.create(account).await
+    }
+
+    /// Validate and store an entry. Posting the same event twice returns the
+    /// entry already on file instead of duplicating it.
+    async fn post(&self, mut entry: JournalEntry) -> Result<JournalEntry, ApiError> {
+        let existing = self
+            .journal
+            .find_by_source(&entry.org_email, entry.source, &entry.source_id)
+            .await?;
+        if let Some(posted) = existing.into_iter().find(|e| e.currency == entry.currency) {
+            return Ok(posted);
+        }
+
+        let chart = self.get_accounts(&entry.org_email).await?;
+        for line in entry.lines.iter_mut() {
+            let account = chart
+                .iter()
+                .find(|a| a.code == line.account_code)
+                .ok_or_else(|| {
+                    ApiError::ValidationError(format!("Unknown account {}", line.account_code))
+                })?;
+            line.account_name = account.name.clone();
+        }
+        entry.validate().map_err(ApiError::ValidationError)?;
+
+        entry.id = None;
+        entry.created_at = Some(DateTime::now());
+        let created = self.journal.create(entry).await?;
+        log::info!(
+            "Posted {} journal entry for {} ({} {})",
+            created.source.as_str(),
+            created.reference,
+            created.total_debit(),
+            created.currency
+        );
+        Ok(created)
+    }
+
+    fn invoice_lines(invoice: &Invoice) -> Vec<JournalLine> {
+        let taxes = invoice.totalcgst + invoice.totalsgst + invoice.totaligst;
+        // Invoice-level rounding of the tax totals
+        let rounding = invoice.total - invoice.sub_total - taxes;
+
+        let mut lines = vec![
+            JournalLine::debit(ledger::ACCOUNTS_RECEIVABLE, invoice.total),
+            JournalLine::credit(ledger::SALES, invoice.sub_total),
+            JournalLine::credit(ledger::OUTPUT_CGST, invoice.totalcgst),
+            JournalLine::credit(ledger::OUTPUT_SGST, invoice.totalsgst),
+            JournalLine::credit(ledger::OUTPUT_IGST, invoice.totaligst),
+        ];
+        if rounding.is_negative() {
+            lines.push(JournalLine::debit(ledger::ROUNDING, -rounding));
+        } else {
+            lines.push(JournalLine::credit(ledger::ROUNDING, rounding));
+        }
+        lines
+    }
+
+    /// Issue entry in INR, the currency the books are kept in. Invoices in
+    /// another currency are converted at their exchange rate, keeping the
+    /// invoice total as a memo.
+    pub(crate) fn invoice_issued_entry(
+        invoice: &Invoice,
+        org_email: &str,
+    ) -> Result<JournalEntry, ApiError> {
+        let inr = invoice.in_inr().map_err(ApiError::ValidationError)?;
+        let foreign_amount = (invoice.currency != DEFAULT_CURRENCY).then(|| ForeignAmount {
+            currency: invoice.currency.clone(),
+            amount: invoice.total,
+            exchange_rate: invoice.exchange_rate.trim().to_string(),
+        });
+        let entry_date = if invoice.invoice_date.trim().is_empty() {
+            date::today()
+        } else {
+            invoice.invoice_date.clone()
+        };
+
+        Ok(JournalEntry {
+            id: None,
+            org_email: org_email.to_string(),
+            entry_date,
+            source: JournalSource::InvoiceIssued,
+            source_id: invoice.id.map(|id| id.to_hex()).unwrap_or_default(),
+            reference: invoice.invoice_number.clone(),
+            narration: format!(
+                "Invoice {} to {}",
+                invoice.invoice_number, invoice.billcustomer_name
+            ),
+            currency: inr.currency.clone(),
+            foreign_amount,
+            lines: Self::invoice_lines(&inr),
+            created_at: None,
+        })
+    }
+
+    /// Dr Accounts Receivable, Cr Sales and output GST
+    pub async fn post_invoice_issued(
+        &self,
+        invoice: &Invoice,
+        org_email: &str,
+    ) -> Result<JournalEntry, ApiError> {
+        self.post(Self::invoice_issued_entry(invoice, org_email)?)
+            .await
+    }
+
+    /// Mirror of the issue entry, dated today. `None` when the invoice was
+    /// issued before the ledger existed and so has nothing to reverse.
+    pub async fn post_invoice_reversed(
+        &self,
+        invoice: &Invoice,
+        org_email: &str,
+    ) -> Result<Option<JournalEntry>, ApiError> {
+        let source_id = invoice.id.map(|id| id.to_hex()).unwrap_or_default();
+        let issued = self
+            .journal
+            .find_by_source(org_email, JournalSource::InvoiceIssued, &source_id)
+            .await?;
+        let Some(issued) = issued.into_iter().next() else {
+            return Ok(None);
+        };
+
+        self.post(Self::reversal_entry(issued, invoice))
+            .await
+            .map(Some)
+    }
+
+    /// The issue entry with every debit and credit swapped
+    fn reversal_entry(issued: JournalEntry, invoice: &Invoice) -> JournalEntry {
+        let lines = issued
+            .lines
+            .into_iter()
+            .map(|l| JournalLine {
+                debit: l.credit,
+                credit: l.debit,
+                ..l
+            })
+            .collect();
+
+        JournalEntry {
+            id: None,
+            org_email: issued.org_email,
+            entry_date: date::today(),
+            source: JournalSource::InvoiceReversed,
+            source_id: issued.source_id,
+            reference: invoice.invoice_number.clone(),
+            narration: format!(
+                "Reversal of invoice {} ({:?})",
+                invoice.invoice_number, invoice.status
+            ),
+            currency: issued.currency,
+            foreign_amount: issued.foreign_amount,
+            lines,
+            created_at: None,
+        }
+    }
+
+    /// Dr expense accounts and input tax, Cr reimbursements payable; one
+    /// entry per item currency. Items go to the expense account named like
+    /// their category, or General Expenses.
+    pub async fn post_expense_approved(
+        &self,
+        expense: &Expense,
+        org_email: &str,
+    ) -> Result<Vec<JournalEntry>, ApiError> {
+        let chart = self.get_accounts(org_email).await?;
+
+        let mut entries = Vec::new();
+        for (currency, lines) in Self::expense_approved_lines(expense, &chart) {
+            entries.push(
+                self.post(JournalEntry {
+                    id: None,
+                    org_email: org_email.to_string(),
+                    entry_date: date::today(),
+                    source: JournalSource::ExpenseApproved,
+                    source_id: expense.id.map(|id| id.to_hex()).unwrap_or_default(),
+                    reference: expense.expense_title.clone(),
+                    narration: format!("Expense approved: {}", expense.expense_title),
+                    currency,
+                    foreign_amount: None,
+                    lines,
+                    created_at: None,
+                })
+                .await?,
+            );
+        }
+        Ok(entries)
+    }
+
+    /// Approval lines per item currency
+    fn expense_approved_lines(
+        expense: &Expense,
+        chart: &[Account],
+    ) -> BTreeMap<String, Vec<JournalLine>> {
+        let mut by_currency: BTreeMap<String, BTreeMap<String, Money>> = BTreeMap::new();
+        for item in &expense.items {
+            let code = chart
+                .iter()
+                .find(|a| {
+                    a.account_type == AccountType::Expense
+                        && a.name.eq_ignore_ascii_case(item.expense_category.trim())
+                })
+                .map(|a| a.code.as_str())
+                .unwrap_or(ledger::GENERAL_EXPENSES);
+
+            let debits = by_currency.entry(item.currency.clone()).or_default();
+            *debits.entry(code.to_string()).or_default() += item.amount;
+            if let Some(tax) = item.tax_amount {
+                *debits.entry(ledger::INPUT_TAX.to_string()).or_default() += tax;
+            }
+        }
+
+        by_currency
+            .into_iter()
+            .map(|(currency, debits)| {
+                let total: Money = debits.values().sum();
+                let mut lines: Vec<JournalLine> = debits
+                    .iter()
+                    .map(|(code, amount)| JournalLine::debit(code, *amount))
+                    .collect();
+                lines.push(JournalLine::credit(ledger::REIMBURSEMENTS_PAYABLE, total));
+                (currency, lines)
+            })
+            .collect()
+    }
+
+    /// Dr reimbursements payable, Cr bank
+    pub async fn post_expense_reimbursed(
+        &self,
+        expense: &Expense,
+        org_email: &str,
+    ) -> Result<Vec<JournalEntry>, ApiError> {
+        let mut entries = Vec::new();
+        for (currency, lines) in Self::expense_reimbursed_lines(expense) {
+            entries.push(
+                self.post(JournalEntry {
+                    id: None,
+                    org_email: org_email.to_string(),
+                    entry_date: date::today(),
+                    source: JournalSource::ExpenseReimbursed,
+                    source_id: expense.id.map(|id| id.to_hex()).unwrap_or_default(),
+                    reference: expense.expense_title.clone(),
+                    narration: format!("Expense reimbursed: {}", expense.expense_title),
+                    currency,
+                    foreign_amount: None,
+                    lines,
+                    created_at: None,
+                })
+                .await?,
+            );
+        }
+        Ok(entries)
+    }
+
+    /// Reimbursement lines per item currency
+    fn expense_reimbursed_lines(expense: &Expense) -> BTreeMap<String, Vec<JournalLine>> {
+        let mut by_currency: BTreeMap<String, Money> = BTreeMap::new();
+        for item in &expense.items {
+            *by_currency.entry(item.currency.clone()).or_default() += item.total_with_tax();
+        }
+
+        by_currency
+            .into_iter()
+            .map(|(currency, total)| {
+                let lines = vec![
+                    JournalLine::debit(ledger::REIMBURSEMENTS_PAYABLE, total),
+                    JournalLine::credit(ledger::BANK, total),
+                ];
+                (currency, lines)
+            })
+            .collect()
+    }
+
+    pub async fn get_journal(
+        &self,
+        org_email: &str,
+        from: Option<&str>,
+        to: Option<&str>,
+    ) -> Result<Vec<JournalEntry>, ApiError> {
+        self.journal.find_in_range(org_email, from, to).await
+    }
+
+    /// Debit/credit totals and balance per account and currency for entries
+    /// dated within the range
+    pub async fn get_balances(
+        &self,
+        org_email: &str,
+        from: Option<&str>,
+        to: Option<&str>,
+    ) -> Result<Vec<AccountBalance>, ApiError> {
+        let chart = self.get_accounts(org_email).await?;
+        let entries = self.journal.find_in_range(org_email, from, to).await?;
+
+        let mut totals: BTreeMap<(String, String), (Money, Money)> = BTreeMap::new();
+        for entry in &entries {
+            for line in &entry.lines {
+                let total = totals
+                    .entry((line.account_code.clone(), entry.currency.clone()))
+                    .or_default();
+                total.0 += line.debit;
+                total.1 += line.credit;
+            }
+        }
+
+        let balances = totals
+            .into_iter()
+            .filter_map(|((code, currency), (debit, credit))| {
+                let account = chart.iter().find(|a| a.code == code)?;
+                Some(AccountBalance {
+                    account_code: code,
+                    account_name: account.name.clone(),
+                    account_type: account.account_type,
+                    currency,
+                    debit,
+                    credit,
+                    balance: signed_balance(account.account_type, debit, credit),
+                })
+            })
+            .collect();
+        Ok(balances)
+    }
+
+    /// Postings to one account within the range, one ledger per currency,
+    /// with the opening balance brought forward from earlier entries
+    pub async fn get_account_ledger(
+        &self,
+        org_email: &str,
+        account_code: &str,
+        from: Option<&str>,
+        to: Option<&str>,
+    ) -> Result<Vec<AccountLedger>, ApiError> {
+        let chart = self.get_accounts(org_email).await?;
+        let account = chart
+            .iter()
+            .find(|a| a.code == account_code)
+            .ok_or_else(|| ApiError::NotFound(format!("Account {} not found", account_code)))?;
+
+        let entries = self
+            .journal
+            .find_by_account(org_email, account_code, to)
+            .await?;
+
+        let mut ledgers: BTreeMap<String, AccountLedger> = BTreeMap::new();
+        for entry in &entries {
+            let (debit, credit) = entry
+                .lines
+                .iter()
+                .filter(|l| l.account_code == account_code)
+                .fold((Money::ZERO, Money::ZERO), |(d, c), l| (d + l.debit, c + l.credit));
+            let movement = signed_balance(account.account_type, debit, credit);
+
+            let ledger = ledgers
+                .entry(entry.currency.clone())
+                .or_insert_with(|| AccountLedger {
+                    account_code: account.code.clone(),
+                    account_name: account.name.clone(),
+                    account_type: account.account_type,
+                    currency: entry.currency.clone(),
+                    opening_balance: Money::ZERO,
+                    lines: Vec::new(),
+                    closing_balance: Money::ZERO,
+                });
+
+            ledger.closing_balance += movement;
+            if from.is_some_and(|from| entry.entry_date.as_str() < from) {
+                ledger.opening_balance += movement;
+                continue;
+            }
+
+            ledger.lines.push(LedgerLine {
+                entry_id: entry.id,
+                entry_date: entry.entry_date.clone(),
+                source: entry.source,
+                source_id: entry.source_id.clone(),
+                reference: entry.reference.clone(),
+                narration: entry.narration.clone(),
+                debit,
+                credit,
+                balance: ledger.closing_balance,
+            });
+        }
+
+        Ok(ledgers.into_values().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::expense::ExpenseItem;
+
+    fn money(value: &str) -> Money {
+        value.parse().unwrap()
+    }
+
+    fn chart() -> Vec<Account> {
+        let mut chart: Vec<Account> = ledger::DEFAULT_ACCOUNTS
+            .iter()
+            .map(|(code, name, account_type)| {
+                Account::new("org@example.com", code, name, *account_type)
+            })
+            .collect();
+        chart.push(Account::new(
+            "org@example.com",
+            "5100",
+            "Travel",
+            AccountType::Expense,
+        ));
+        chart
+    }
+
+    fn invoice_with(sub_total: &str, cgst: &str, sgst: &str, igst: &str, total: &str) -> Invoice {
+        Invoice {
+            invoice_number: "INV-0042".to_string(),
+            invoice_date: "2024-07-15".to_string(),
+            currency: DEFAULT_CURRENCY.to_string(),
+            sub_total: money(sub_total),
+            totalcgst: money(cgst),
+            totalsgst: money(sgst),
+            totaligst: money(igst),
+            total: money(total),
+            ..Default::default()
+        }
+    }
+
+    fn item(category: &str, currency: &str, amount: &str, tax: Option<&str>) -> ExpenseItem {
+        ExpenseItem {
+            expense_category: category.to_string(),
+            currency: currency.to_string(),
+            amount: money(amount),
+            expense_date: "2024-07-10".to_string(),
+            comment: String::new(),
+            receipt_file: None,
+            original_filename: None,
+            payment_method: None,
+            vendor: None,
+            vendor_gstin: None,
+            invoice_number: None,
+            billable: false,
+            tax_amount: tax.map(money),
+        }
+    }
+
+    /// Run the same checks `post` does before saving
+    fn assert_balanced(lines: Vec<JournalLine>) -> JournalEntry {
+        let mut entry = JournalEntry {
+            id: None,
+            org_email: "org@example.com".to_string(),
+            entry_date: "2024-07-15".to_string(),
+            source: JournalSource::ExpenseApproved,
+            source_id: String::new(),
+            reference: String::new(),
+            narration: String::new(),
+            currency: DEFAULT_CURRENCY.to_string(),
+            foreign_amount: None,
+            lines,
+            created_at: None,
+        };
+        entry.validate().unwrap();
+        assert_eq!(entry.total_debit(), entry.total_credit());
+        entry
+    }
+
+    fn amount(entry: &JournalEntry, code: &str) -> (Money, Money) {
+        entry
+            .lines
+            .iter()
+            .filter(|l| l.account_code == code)
+            .fold((Money::ZERO, Money::ZERO), |(d, c), l| {
+                (d + l.debit, c + l.credit)
+            })
+    }
+
+    #[test]
+    fn intra_state_invoice_balances() {
+        let invoice = invoice_with("1000.00", "90.00", "90.00", "0", "1180.00");
+        let entry = assert_balanced(LedgerService::invoice_lines(&invoice));
+
+        assert_eq!(
+            amount(&entry, ledger::ACCOUNTS_RECEIVABLE).0,
+            money("1180.00")
+        );
+        assert_eq!(amount(&entry, ledger::SALES).1, money("1000.00"));
+        assert_eq!(amount(&entry, ledger::OUTPUT_CGST).1, money("90.00"));
+        assert_eq!(amount(&entry, ledger::OUTPUT_SGST).1, money("90.00"));
+        assert!(entry
+            .lines
+            .iter()
+            .all(|l| l.account_code != ledger::OUTPUT_IGST));
+        assert!(entry
+            .lines
+            .iter()
+            .all(|l| l.account_code != ledger::ROUNDING));
+    }
+
+    #[test]
+    fn inter_state_invoice_balances() {
+        let invoice = invoice_with("1000.00", "0", "0", "180.00", "1180.00");
+        let entry = assert_balanced(LedgerService::invoice_lines(&invoice));
+
+        assert_eq!(
+            amount(&entry, ledger::ACCOUNTS_RECEIVABLE).0,
+            money("1180.00")
+        );
+        assert_eq!(amount(&entry, ledger::OUTPUT_IGST).1, money("180.00"));
+        assert!(entry
+            .lines
+            .iter()
+            .all(|l| l.account_code != ledger::OUTPUT_CGST));
+    }
+
+    #[test]
+    fn invoice_rounding_balances_either_way() {
+        // Rounded down: the difference is a debit to rounding off
+        let invoice = invoice_with("999.50", "0", "0", "179.91", "1179.00");
+        let entry = assert_balanced(LedgerService::invoice_lines(&invoice));
+        assert_eq!(
+            amount(&entry, ledger::ROUNDING),
+            (money("0.41"), Money::ZERO)
+        );
+
+        // Rounded up: the difference is credited
+        let invoice = invoice_with("999.50", "0", "0", "179.91", "1180.00");
+        let entry = assert_balanced(LedgerService::invoice_lines(&invoice));
+        assert_eq!(
+            amount(&entry, ledger::ROUNDING),
+            (Money::ZERO, money("0.59"))
+        );
+    }
+
+    #[test]
+    fn reversal_negates_the_issue_entry() {
+        let invoice = invoice_with("999.50", "89.96", "89.96", "0", "1179.00");
+        let issued = LedgerService::invoice_issued_entry(&invoice, "org@example.com").unwrap();
+        let reversal = LedgerService::reversal_entry(issued.clone(), &invoice);
+
+        assert_eq!(reversal.source, JournalSource::InvoiceReversed);
+        assert_eq!(reversal.currency, issued.currency);
+        assert_eq!(reversal.lines.len(), issued.lines.len());
+        for (original, reversed) in issued.lines.iter().zip(&reversal.lines) {
+            assert_eq!(reversed.account_code, original.account_code);
+            assert_eq!(reversed.debit, original.credit);
+            assert_eq!(reversed.credit, original.debit);
+        }
+        assert_balanced(reversal.lines);
+    }
+
+    #[test]
+    fn expense_with_tax_balances_per_currency() {
+        let mut expense = Expense::new("Client visit".to_string(), "Sales".to_string());
+        expense.items = vec![
+            item("Travel", "INR", "1000.00", Some("50.00")),
+            item("Meals", "INR", "200.00", None),
+            item("travel", "USD", "30.00", Some("3.00")),
+        ];
+
+        let approved = LedgerService::expense_approved_lines(&expense, &chart());
+        assert_eq!(approved.len(), 2);
+
+        let inr = assert_balanced(approved["INR"].clone());
+        assert_eq!(amount(&inr, "5100").0, money("1000.00"));
+        assert_eq!(amount(&inr, ledger::GENERAL_EXPENSES).0, money("200.00"));
+        assert_eq!(amount(&inr, ledger::INPUT_TAX).0, money("50.00"));
+        assert_eq!(
+            amount(&inr, ledger::REIMBURSEMENTS_PAYABLE).1,
+            money("1250.00")
+        );
+
+        let usd = assert_balanced(approved["USD"].clone());
+        assert_eq!(
+            amount(&usd, ledger::REIMBURSEMENTS_PAYABLE).1,
+            money("33.00")
+        );
+
+        let reimbursed = LedgerService::expense_reimbursed_lines(&expense);
+        let inr = assert_balanced(reimbursed["INR"].clone());
+        assert_eq!(
+            amount(&inr, ledger::REIMBURSEMENTS_PAYABLE).0,
+            money("1250.00")
+        );
+        assert_eq!(amount(&inr, ledger::BANK).1, money("1250.00"));
+        assert_balanced(reimbursed["USD"].clone());
+    }
+}
@@ -2,9 +2,46 @@ pub mod customer_service;
 pub mod organisation_service;
 pub mod invoice_service;
 pub mod expense_service;
+pub mod payment_service;
+pub mod credit_debit_note_service;
+pub mod recurring_invoice_service;
+pub mod estimate_service;
+pub mod purchase_order_service;
+pub mod vendor_service;
+pub mod vendor_bill_service;
+pub mod ledger_service;
+pub mod report_service;
+pub mod gstr1_service;
+pub mod gstr3b_service;
+pub mod gstr2b_service;
+pub mod catalog_service;
+pub mod irp_client;
+pub mod e_invoice_service;
+pub mod e_way_bill_service;
+pub mod tds_service;
+pub mod auth_service;
+pub mod user_service;
 
 // Re-export services for easier import across the app
 pub use customer_service::CustomerService;
 pub use organisation_service::OrganisationService;
 pub use invoice_service::InvoiceService;
 pub use expense_service::ExpenseService;
+pub use payment_service::PaymentService;
+pub use credit_debit_note_service::CreditDebitNoteService;
+pub use recurring_invoice_service::RecurringInvoiceService;
+pub use estimate_service::EstimateService;
+pub use purchase_order_service::PurchaseOrderService;
+pub use vendor_service::VendorService;
+pub use vendor_bill_service::VendorBillService;
+pub use ledger_service::LedgerService;
+pub use report_service::ReportService;
+pub use gstr1_service::Gstr1Service;
+pub use gstr3b_service::Gstr3bService;
+pub use gstr2b_service::Gstr2bService;
+pub use catalog_service::CatalogService;
+pub use e_invoice_service::EInvoiceService;
+pub use e_way_bill_service::EWayBillService;
+pub use tds_service::TdsService;
+pub use auth_service::AuthService;
+pub use user_service::UserService;
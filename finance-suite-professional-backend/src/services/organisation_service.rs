@@ -1,19 +1,60 @@
 use validator::Validate;
 use crate::error::ApiError;
+use crate::models::tds::TdsSection;
 use crate::models::{CreateOrganisationRequest, Organisation, UpdateOrganizationRequest};
 use crate::repository::OrganisationRepository;
+use crate::utils::validation;
 
 #[derive(Clone)]
 pub struct OrganisationService{
     repository:OrganisationRepository,
 }
 
+/// Upper-case the TAN and check its format; blank is allowed
+fn check_tan(tan: &str) -> Result<String, ApiError> {
+    validation::clean_optional_tan(tan).map_err(ApiError::ValidationError)
+}
+
+/// Check every TDS section has a unique code, usable rates and limits
+fn check_tds_sections(sections: &mut [TdsSection]) -> Result<(), ApiError> {
+    for idx in 0..sections.len() {
+        let section = &mut sections[idx];
+        section.section = section.section.trim().to_uppercase();
+        if section.section.is_empty() {
+            return Err(ApiError::ValidationError(format!(
+                "TDS section {} has no section code",
+                idx + 1
+            )));
+        }
+        section.deduction_rate(true).map_err(ApiError::ValidationError)?;
+        section.deduction_rate(false).map_err(ApiError::ValidationError)?;
+        if section.single_limit.is_negative() || section.annual_limit.is_negative() {
+            return Err(ApiError::ValidationError(format!(
+                "Section {} limits cannot be negative",
+                section.section
+            )));
+        }
+        let code = sections[idx].section.clone();
+        if sections[..idx].iter().any(|s| s.section == code) {
+            return Err(ApiError::ValidationError(format!(
+                "TDS section {} is listed more than once",
+                code
+            )));
+        }
+    }
+    Ok(())
+}
+
 impl OrganisationService{
     pub fn new(repository:OrganisationRepository)->Self{
         Self{repository}
     }
-    pub async fn create_organisation(&self,req:CreateOrganisationRequest)->Result<Organisation,ApiError>{
+    pub async fn create_organisation(&self,mut req:CreateOrganisationRequest)->Result<Organisation,ApiError>{
         req.validate()?;
+        // Blank or "unregistered" leaves the organisation without a GSTIN
+        req.gst_in = validation::clean_optional_gstin(&req.gst_in).map_err(ApiError::ValidationError)?;
+        req.tan = check_tan(&req.tan)?;
+        check_tds_sections(&mut req.tds_sections)?;
         if req.addresses.is_empty(){
             return Err(ApiError::ValidationError(
                 "At least one address is required".to_string(),
@@ -47,10 +88,21 @@ impl OrganisationService{
      pub async fn update_organisation(
         &self,
         id: &str,
-        req: UpdateOrganizationRequest,
+        mut req: UpdateOrganizationRequest,
     ) -> Result<Organisation, ApiError> {
         // Validate request
         req.validate()?;
+        if let Some(gst_in) = req.gst_in.as_deref() {
+            req.gst_in = Some(
+                validation::clean_optional_gstin(gst_in).map_err(ApiError::ValidationError)?,
+            );
+        }
+        if let Some(tan) = req.tan.as_deref() {
+            req.tan = Some(check_tan(tan)?);
+        }
+        if let Some(sections) = req.tds_sections.as_mut() {
+            check_tds_sections(sections)?;
+        }
 
         // Check if customer exists
        let _existing = self.repository
@@ -0,0 +1,210 @@
+use mongodb::bson::DateTime;
+
+use crate::error::ApiError;
+use crate::models::payment::PaymentAllocation;
+use crate::models::{money, CreatePaymentRequest, Payment};
+use crate::repository::{InvoiceRepository, OrganisationRepository, PaymentRepository};
+
+#[derive(Clone)]
+pub struct PaymentService {
+    repository: PaymentRepository,
+    invoice_repo: InvoiceRepository,
+    org_repo: OrganisationRepository,
+}
+
+impl PaymentService {
+    pub fn new(
+        repository: PaymentRepository,
+        invoice_repo: InvoiceRepository,
+        org_repo: OrganisationRepository,
+    ) -> Self {
+        Self {
+            repository,
+            invoice_repo,
+            org_repo,
+        }
+    }
+
+    /// Record a receipt and allocate it to invoices, moving each invoice to
+    /// PartiallyPaid or Paid. The payment method must be enabled for the
+    /// organisation. A customer paying short with a
+    /// TDS section given has the shortfall recorded as TDS.
+    pub async fn record_payment(
+        &self,
+        mut payment: CreatePaymentRequest,
+        org_email: &str,
+    ) -> Result<Payment, ApiError> {
+        payment.id = None;
+        payment.created_at = Some(DateTime::now());
+
+        if !money::is_valid_currency_code(&payment.currency) {
+            return Err(ApiError::ValidationError(format!(
+                "Invalid currency code '{}'",
+                payment.currency
+            )));
+        }
+        payment.take_shortfall_as_tds();
+        payment.validate().map_err(ApiError::ValidationError)?;
+
+        payment.org_email = org_email.to_string();
+        let org = self.org_repo.get_organisation_by_email(org_email).await?;
+        if !payment.method.is_enabled(&org.enabled_methods) {
+            return Err(ApiError::ValidationError(format!(
+                "Payment method {:?} is not enabled for this organisation",
+                payment.method
+            )));
+        }
+
+        // Check every allocation before touching any invoice
+        for allocation in payment.allocations.iter_mut() {
+            let mut invoice = self
+                .invoice_repo
+                .get_invoice_by_id(org_email, &allocation.invoice_id)
+                .await?
+                .ok_or_else(|| {
+                    ApiError::NotFound(format!("Invoice {} not found", allocation.invoice_id))
+                })?;
+
+            if invoice.currency != payment.currency {
+                return Err(ApiError::ValidationError(format!(
+                    "Invoice {} is in {} but the payment is in {}",
+                    invoice.invoice_number, invoice.currency, payment.currency
+                )));
+            }
+
+            invoice
+                .apply_payment(allocation.amount)
+                .map_err(ApiError::Conflict)?;
+            allocation.invoice_number = invoice.invoice_number.clone();
+            if payment.org_email.is_empty() {
+                payment.org_email = if invoice.org_email.is_empty() {
+                    invoice.company_email.clone()
+                } else {
+                    invoice.org_email.clone()
+                };
+            }
+        }
+
+        // Apply each allocation atomically; if one is refused, because
+        // another payment or a status change got there first, undo the rest
+        let allocations = payment.allocations.clone();
+        for (idx, allocation) in allocations.iter().enumerate() {
+            let applied = self
+                .invoice_repo
+                .apply_payment(&allocation.invoice_id, allocation.amount)
+                .await;
+            match applied {
+                Ok(Some(_)) => {}
+                Ok(None) => {
+                    self.release(&allocations[..idx]).await;
+                    return Err(ApiError::Conflict(format!(
+                        "Invoice {} changed while the payment was recorded; reload and retry",
+                        allocation.invoice_number
+                    )));
+                }
+                Err(e) => {
+                    self.release(&allocations[..idx]).await;
+                    return Err(e.into());
+                }
+            }
+        }
+
+        let created = match self.repository.create(payment).await {
+            Ok(created) => created,
+            Err(e) => {
+                self.release(&allocations).await;
+                return Err(e);
+            }
+        };
+
+        log::info!(
+            "Recorded payment {:?} of {} {} across {} invoice(s)",
+            created.id,
+            created.amount,
+            created.currency,
+            created.allocations.len()
+        );
+        Ok(created)
+    }
+
+    pub async fn get_payments(
+        &self,
+        invoice_id: Option<&str>,
+        org_email: &str,
+    ) -> Result<Vec<Payment>, ApiError> {
+        self.repository.find_all(org_email, invoice_id).await
+    }
+
+    pub async fn get_payment_by_id(&self, id: &str, org_email: &str) -> Result<Payment, ApiError> {
+        self.repository
+            .find_by_id(org_email, id)
+            .await?
+            .ok_or_else(|| ApiError::NotFound(format!("Payment with id {} not found", id)))
+    }
+
+    /// Delete a payment and release its allocations from the invoices
+    pub async fn delete_payment(&self, id: &str, org_email: &str) -> Result<bool, ApiError> {
+        let payment = self.get_payment_by_id(id, org_email).await?;
+
+        // Remove the payment first so two concurrent deletes cannot both
+        // release its allocations
+        if !self.repository.delete(org_email, id).await? {
+            return Ok(false);
+        }
+
+        for (idx, allocation) in payment.allocations.iter().enumerate() {
+            match self
+                .invoice_repo
+                .reverse_payment(&allocation.invoice_id, allocation.amount)
+                .await
+            {
+                Ok(Some(_)) => {}
+                Ok(None) => log::warn!(
+                    "Invoice {} allocated by payment {} no longer exists",
+                    allocation.invoice_id,
+                    id
+                ),
+                Err(e) => {
+                    // Put back what was released and the payment itself
+                    for allocation in &payment.allocations[..idx] {
+                        if let Err(err) = self
+                            .invoice_repo
+                            .apply_payment(&allocation.invoice_id, allocation.amount)
+                            .await
+                        {
+                            log::error!(
+                                "Could not re-apply payment {} to invoice {}: {}",
+                                id,
+                                allocation.invoice_id,
+                                err
+                            );
+                        }
+                    }
+                    self.repository.create(payment.clone()).await?;
+                    return Err(e.into());
+                }
+            }
+        }
+
+        Ok(true)
+    }
+
+    /// Best-effort undo of allocations already applied when recording a
+    /// payment fails part-way
+    async fn release(&self, allocations: &[PaymentAllocation]) {
+        for allocation in allocations {
+            if let Err(e) = self
+                .invoice_repo
+                .reverse_payment(&allocation.invoice_id, allocation.amount)
+                .await
+            {
+                log::error!(
+                    "Could not release {} from invoice {}: {}",
+                    allocation.amount,
+                    allocation.invoice_id,
+                    e
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,230 @@
+use mongodb::bson::DateTime;
+
+use crate::error::ApiError;
+use crate::models::money::{self, Money};
+use crate::models::purchase_order::{
+    PurchaseOrder, PurchaseOrderKind, PurchaseOrderRequest, PurchaseOrderStatus,
+};
+use crate::repository::{OrganisationRepository, PurchaseOrderRepository};
+use crate::utils::gst;
+
+#[derive(Clone)]
+pub struct PurchaseOrderService {
+    repository: PurchaseOrderRepository,
+    org_repo: OrganisationRepository,
+}
+
+impl PurchaseOrderService {
+    pub fn new(repository: PurchaseOrderRepository, org_repo: OrganisationRepository) -> Self {
+        Self {
+            repository,
+            org_repo,
+        }
+    }
+
+    /// Validate and compute line amounts and the PO value
+    fn compute_amounts(po: &mut PurchaseOrder) -> Result<(), ApiError> {
+        if po.po_date.trim().is_empty() {
+            return Err(ApiError::ValidationError("PO date is required".to_string()));
+        }
+        if po.party_name.trim().is_empty() {
+            return Err(ApiError::ValidationError("Party name is required".to_string()));
+        }
+        if !money::is_valid_currency_code(&po.currency) {
+            return Err(ApiError::ValidationError(format!(
+                "Invalid currency code '{}'",
+                po.currency
+            )));
+        }
+
+        let mut sub_total = Money::ZERO;
+        for (idx, item) in po.items.iter_mut().enumerate() {
+            let quantity = gst::parse_decimal(&format!("Item {} quantity", idx + 1), &item.quantity)?;
+            if item.rate.is_negative() {
+                return Err(ApiError::ValidationError(format!(
+                    "Item {} rate cannot be negative",
+                    idx + 1
+                )));
+            }
+            item.amount = (item.rate * quantity).round_paise();
+            sub_total += item.amount;
+        }
+        po.sub_total = sub_total;
+        Ok(())
+    }
+
+    /// Customer POs keep the customer's number; vendor POs are numbered from
+    /// the organisation's purchase order series.
+    pub async fn create_purchase_order(
+        &self,
+        mut po: PurchaseOrderRequest,
+        org_email: &str,
+    ) -> Result<PurchaseOrder, ApiError> {
+        Self::compute_amounts(&mut po)?;
+
+        match po.kind {
+            PurchaseOrderKind::Customer => {
+                if po.po_number.trim().is_empty() {
+                    return Err(ApiError::ValidationError(
+                        "PO number is required for customer purchase orders".to_string(),
+                    ));
+                }
+                if self
+                    .repository
+                    .find_by_number(
+                        org_email,
+                        po.kind,
+                        po.po_number.trim(),
+                        Some(&po.party_name),
+                    )
+                    .await?
+                    .is_some()
+                {
+                    return Err(ApiError::ValidationError(format!(
+                        "Purchase order {} already exists for {}",
+                        po.po_number, po.party_name
+                    )));
+                }
+                po.po_number = po.po_number.trim().to_string();
+            }
+            PurchaseOrderKind::Vendor => {
+                let org = self.org_repo.get_organisation_by_email(org_email).await?;
+                let sequence = self
+                    .org_repo
+                    .next_sequence(org_email, "lastPurchaseOrderSequence")
+                    .await?;
+                let prefix = if org.purchase_order_prefix.trim().is_empty() {
+                    "PO"
+                } else {
+                    org.purchase_order_prefix.trim()
+                };
+                po.po_number = format!("{}-{:03}", prefix, sequence);
+            }
+        }
+
+        let now = DateTime::now();
+        po.id = None;
+        po.billed_amount = Money::ZERO;
+        po.status = PurchaseOrderStatus::Draft;
+        po.org_email = org_email.to_string();
+        po.created_at = Some(now);
+        po.updated_at = Some(now);
+
+        self.repository.create(po).await
+    }
+
+    pub async fn get_purchase_orders(
+        &self,
+        kind: Option<PurchaseOrderKind>,
+        org_email: &str,
+    ) -> Result<Vec<PurchaseOrder>, ApiError> {
+        self.repository.find_all(org_email, kind).await
+    }
+
+    pub async fn get_purchase_order_by_id(
+        &self,
+        id: &str,
+        org_email: &str,
+    ) -> Result<PurchaseOrder, ApiError> {
+        self.repository
+            .find_by_id(org_email, id)
+            .await?
+            .ok_or_else(|| ApiError::NotFound(format!("Purchase order with id {} not found", id)))
+    }
+
+    /// Replace lines and details. Number, kind and billing stay; the new value
+    /// may not drop below what has already been billed.
+    pub async fn update_purchase_order(
+        &self,
+        id: &str,
+        mut po: PurchaseOrderRequest,
+        org_email: &str,
+    ) -> Result<PurchaseOrder, ApiError> {
+        let existing = self.get_purchase_order_by_id(id, org_email).await?;
+        if !existing.is_editable() {
+            return Err(ApiError::Conflict(format!(
+                "Purchase order in {:?} status cannot be edited",
+                existing.status
+            )));
+        }
+
+        Self::compute_amounts(&mut po)?;
+        if po.sub_total < existing.billed_amount {
+            return Err(ApiError::ValidationError(format!(
+                "PO value {} is below the amount already billed ({})",
+                po.sub_total, existing.billed_amount
+            )));
+        }
+
+        po.id = existing.id;
+        po.org_email = existing.org_email;
+        po.updated_at = Some(DateTime::now());
+
+        // Only the edited fields are written, so billing recorded since the
+        // PO was read is kept
+        if !self.repository.update_details(&po, existing.status).await? {
+            return Err(ApiError::Conflict(format!(
+                "Purchase order {} changed while it was being edited; reload and retry",
+                existing.po_number
+            )));
+        }
+        self.get_purchase_order_by_id(id, org_email).await
+    }
+
+    pub async fn open_purchase_order(
+        &self,
+        id: &str,
+        org_email: &str,
+    ) -> Result<PurchaseOrder, ApiError> {
+        self.transition(id, org_email, PurchaseOrder::open).await
+    }
+
+    pub async fn close_purchase_order(
+        &self,
+        id: &str,
+        org_email: &str,
+    ) -> Result<PurchaseOrder, ApiError> {
+        self.transition(id, org_email, PurchaseOrder::close).await
+    }
+
+    pub async fn cancel_purchase_order(
+        &self,
+        id: &str,
+        org_email: &str,
+    ) -> Result<PurchaseOrder, ApiError> {
+        self.transition(id, org_email, PurchaseOrder::cancel).await
+    }
+
+    async fn transition<F>(
+        &self,
+        id: &str,
+        org_email: &str,
+        apply: F,
+    ) -> Result<PurchaseOrder, ApiError>
+    where
+        F: FnOnce(&mut PurchaseOrder) -> Result<(), String>,
+    {
+        let mut po = self.get_purchase_order_by_id(id, org_email).await?;
+        let before = po.status;
+        apply(&mut po).map_err(ApiError::Conflict)?;
+        if !self.repository.update_status(&po, before).await? {
+            return Err(ApiError::Conflict(format!(
+                "Purchase order {} changed while it was being updated; reload and retry",
+                po.po_number
+            )));
+        }
+        Ok(po)
+    }
+
+    pub async fn delete_purchase_order(&self, id: &str, org_email: &str) -> Result<bool, ApiError> {
+        let existing = self.get_purchase_order_by_id(id, org_email).await?;
+        if existing.status != PurchaseOrderStatus::Draft {
+            return Err(ApiError::Conflict(format!(
+                "Purchase order in {:?} status cannot be deleted",
+                existing.status
+            )));
+        }
+
+        self.repository.delete(org_email, id).await
+    }
+}
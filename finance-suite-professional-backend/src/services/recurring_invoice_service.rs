@@ -0,0 +1,353 @@
+use std::time::Duration;
+
+use chrono::{NaiveDate, Utc};
+use mongodb::bson::DateTime;
+use mongodb::error::Error as MongoError;
+
+use crate::error::ApiError;
+use crate::models::invoice::Invoice;
+use crate::models::recurring_invoice::{
+    RecurringInvoice, RecurringInvoiceRequest, RecurringRunResult,
+};
+use crate::repository::invoice_repository::is_duplicate_key;
+use crate::repository::{InvoiceRepository, RecurringInvoiceRepository};
+use crate::services::InvoiceService;
+use crate::utils::date::{parse_date, DATE_FORMAT};
+
+/// Most runs one schedule catches up in a single pass; a schedule further
+/// behind continues on the next pass instead of flooding the ledger at once
+const MAX_CATCH_UP_RUNS: usize = 12;
+
+#[derive(Clone)]
+pub struct RecurringInvoiceService {
+    repository: RecurringInvoiceRepository,
+    invoice_repo: InvoiceRepository,
+    invoice_service: InvoiceService,
+}
+
+impl RecurringInvoiceService {
+    pub fn new(
+        repository: RecurringInvoiceRepository,
+        invoice_repo: InvoiceRepository,
+        invoice_service: InvoiceService,
+    ) -> Self {
+        Self {
+            repository,
+            invoice_repo,
+            invoice_service,
+        }
+    }
+
+    fn prepare(schedule: &mut RecurringInvoice) -> Result<(), ApiError> {
+        schedule.validate().map_err(ApiError::ValidationError)?;
+        schedule.next_run_date = schedule
+            .compute_next_run()
+            .map_err(ApiError::ValidationError)?
+            .map(|d| d.format(DATE_FORMAT).to_string());
+        Ok(())
+    }
+
+    pub async fn create_schedule(
+        &self,
+        mut schedule: RecurringInvoiceRequest,
+        org_email: &str,
+    ) -> Result<RecurringInvoice, ApiError> {
+        schedule.id = None;
+        schedule.org_email = org_email.to_string();
+        schedule.runs_completed = 0;
+        schedule.last_run_date = None;
+        schedule.last_invoice_id = None;
+        schedule.created_at = Some(DateTime::now());
+        Self::prepare(&mut schedule)?;
+
+        self.repository.create(schedule).await
+    }
+
+    pub async fn get_schedules(&self, org_email: &str) -> Result<Vec<RecurringInvoice>, ApiError> {
+        self.repository.find_all(org_email).await
+    }
+
+    pub async fn get_schedule_by_id(
+        &self,
+        id: &str,
+        org_email: &str,
+    ) -> Result<RecurringInvoice, ApiError> {
+        self.repository
+            .find_by_id(org_email, id)
+            .await?
+            .ok_or_else(|| ApiError::NotFound(format!("Recurring invoice with id {} not found", id)))
+    }
+
+    /// Replace the template and schedule; run history is kept
+    pub async fn update_schedule(
+        &self,
+        id: &str,
+        mut schedule: RecurringInvoiceRequest,
+        org_email: &str,
+    ) -> Result<RecurringInvoice, ApiError> {
+        let existing = self.get_schedule_by_id(id, org_email).await?;
+
+        schedule.id = existing.id;
+        schedule.org_email = existing.org_email;
+        schedule.runs_completed = existing.runs_completed;
+        schedule.last_run_date = existing.last_run_date;
+        schedule.last_invoice_id = existing.last_invoice_id;
+        schedule.created_at = existing.created_at;
+        Self::prepare(&mut schedule)?;
+
+        self.repository.replace(&schedule).await?;
+        Ok(schedule)
+    }
+
+    pub async fn delete_schedule(&self, id: &str, org_email: &str) -> Result<bool, ApiError> {
+        self.repository.delete(org_email, id).await
+    }
+
+    /// Generate (or, with `dry_run`, only preview) every invoice due on or
+    /// before `as_of`, for one organisation or, from the background task,
+    /// all of them. A schedule that fell behind catches up one run at a
+    /// time, at most `MAX_CATCH_UP_RUNS` per call. Only a preview may look
+    /// past today.
+    ///
+    /// Each run is keyed by (schedule id, run date): if an invoice for that key
+    /// already exists — e.g. the server stopped between creating the invoice
+    /// and advancing the schedule — it is reused instead of created again.
+    pub async fn run_due(
+        &self,
+        as_of: NaiveDate,
+        dry_run: bool,
+        org_email: Option<&str>,
+    ) -> Result<Vec<RecurringRunResult>, ApiError> {
+        if !dry_run && as_of > Utc::now().date_naive() {
+            return Err(ApiError::ValidationError(
+                "as_of cannot be later than today; use the preview to look ahead".to_string(),
+            ));
+        }
+
+        let as_of_str = as_of.format(DATE_FORMAT).to_string();
+        let mut results = Vec::new();
+
+        for mut schedule in self.repository.find_due(&as_of_str, org_email).await? {
+            let mut runs = 0;
+            while let Some(run_date) = schedule
+                .compute_next_run()
+                .map_err(ApiError::ValidationError)?
+            {
+                if run_date > as_of {
+                    break;
+                }
+                if runs == MAX_CATCH_UP_RUNS {
+                    log::warn!(
+                        "Recurring invoice '{}' is more than {} runs behind; the rest follow on the next pass",
+                        schedule.name,
+                        MAX_CATCH_UP_RUNS
+                    );
+                    break;
+                }
+                runs += 1;
+
+                let result = if dry_run {
+                    self.preview_run(&schedule, run_date).await
+                } else {
+                    self.execute_run(&schedule, run_date).await
+                };
+                let failed = result.error.is_some();
+                results.push(result);
+
+                if dry_run {
+                    // Simulate the schedule advancing so catch-up runs show too
+                    schedule.runs_completed += 1;
+                    continue;
+                }
+                if failed {
+                    break;
+                }
+                let id = schedule.id.unwrap_or_default().to_hex();
+                match self.repository.find_by_id(&schedule.org_email, &id).await? {
+                    Some(updated) => schedule = updated,
+                    None => break,
+                }
+            }
+        }
+
+        Ok(results)
+    }
+
+    async fn preview_run(&self, schedule: &RecurringInvoice, run_date: NaiveDate) -> RecurringRunResult {
+        let run_date_str = run_date.format(DATE_FORMAT).to_string();
+        let recurring_id = schedule.id.map(|id| id.to_hex()).unwrap_or_default();
+        let existing = self
+            .invoice_repo
+            .find_recurring_run(&recurring_id, &run_date_str)
+            .await;
+
+        let (invoice, already_generated, error) = match existing {
+            Ok(Some(invoice)) => (Some(invoice), true, None),
+            Ok(None) => match self
+                .invoice_service
+                .preview_invoice(schedule.materialise(run_date), &schedule.org_email)
+                .await
+            {
+                Ok(invoice) => (Some(invoice), false, None),
+                Err(e) => (None, false, Some(ApiError::from(e).to_string())),
+            },
+            Err(e) => (None, false, Some(e.to_string())),
+        };
+
+        RecurringRunResult {
+            recurring_id,
+            name: schedule.name.clone(),
+            run_date: run_date_str,
+            invoice,
+            already_generated,
+            error,
+        }
+    }
+
+    async fn execute_run(&self, schedule: &RecurringInvoice, run_date: NaiveDate) -> RecurringRunResult {
+        let run_date_str = run_date.format(DATE_FORMAT).to_string();
+        let recurring_id = schedule.id.map(|id| id.to_hex()).unwrap_or_default();
+        let mut result = RecurringRunResult {
+            recurring_id: recurring_id.clone(),
+            name: schedule.name.clone(),
+            run_date: run_date_str.clone(),
+            invoice: None,
+            already_generated: false,
+            error: None,
+        };
+
+        match self.generate(schedule, run_date).await {
+            Ok((invoice, already_generated)) => {
+                result.invoice = Some(invoice);
+                result.already_generated = already_generated;
+            }
+            Err(e) => {
+                log::error!(
+                    "Recurring invoice '{}' run {} failed: {}",
+                    schedule.name,
+                    run_date_str,
+                    e
+                );
+                result.error = Some(e.to_string());
+            }
+        }
+        result
+    }
+
+    async fn generate(
+        &self,
+        schedule: &RecurringInvoice,
+        run_date: NaiveDate,
+    ) -> Result<(Invoice, bool), ApiError> {
+        let schedule_id = schedule
+            .id
+            .ok_or_else(|| ApiError::InternalServerError("Schedule has no id".to_string()))?;
+        let recurring_id = schedule_id.to_hex();
+        let run_date_str = run_date.format(DATE_FORMAT).to_string();
+
+        let existing = self
+            .invoice_repo
+            .find_recurring_run(&recurring_id, &run_date_str)
+            .await?;
+        let (invoice, already_generated) = match existing {
+            Some(invoice) => (invoice, true),
+            None => match self.create_run(schedule, run_date).await {
+                Ok(invoice) => (invoice, false),
+                // Another instance generated this run since the lookup
+                Err(e) if e.downcast_ref::<MongoError>().is_some_and(is_duplicate_key) => {
+                    let invoice = self
+                        .invoice_repo
+                        .find_recurring_run(&recurring_id, &run_date_str)
+                        .await?
+                        .ok_or_else(|| {
+                            ApiError::Conflict(format!(
+                                "Run {} of '{}' is being generated elsewhere",
+                                run_date_str, schedule.name
+                            ))
+                        })?;
+                    (invoice, true)
+                }
+                Err(e) => return Err(e.into()),
+            },
+        };
+
+        let mut advanced = schedule.clone();
+        advanced.runs_completed += 1;
+        let next_run_date = advanced
+            .compute_next_run()
+            .map_err(ApiError::ValidationError)?
+            .map(|d| d.format(DATE_FORMAT).to_string());
+
+        self.repository
+            .record_run(
+                schedule_id,
+                schedule.runs_completed,
+                &run_date_str,
+                next_run_date,
+                invoice.id.map(|id| id.to_hex()),
+            )
+            .await?;
+
+        Ok((invoice, already_generated))
+    }
+
+    /// Create (and, if the schedule says so, issue) the invoice for one run
+    async fn create_run(
+        &self,
+        schedule: &RecurringInvoice,
+        run_date: NaiveDate,
+    ) -> anyhow::Result<Invoice> {
+        let mut invoice = self
+            .invoice_service
+            .create_linked_invoice(schedule.materialise(run_date), &schedule.org_email)
+            .await?;
+        if schedule.auto_issue {
+            let id = invoice.id.map(|id| id.to_hex()).unwrap_or_default();
+            if let Some(issued) = self
+                .invoice_service
+                .issue_invoice(&id, &schedule.org_email)
+                .await?
+            {
+                invoice = issued;
+            }
+        }
+        log::info!(
+            "Recurring invoice '{}' generated {} for {}",
+            schedule.name,
+            invoice.invoice_number,
+            run_date.format(DATE_FORMAT)
+        );
+        Ok(invoice)
+    }
+
+    /// Background loop materialising due invoices every `interval`
+    pub fn spawn_scheduler(self, interval: Duration) {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                let today = Utc::now().date_naive();
+                match self.run_due(today, false, None).await {
+                    Ok(results) if !results.is_empty() => {
+                        let failed = results.iter().filter(|r| r.error.is_some()).count();
+                        log::info!(
+                            "🔁 Recurring invoices: {} run(s), {} failed",
+                            results.len(),
+                            failed
+                        );
+                    }
+                    Ok(_) => {}
+                    Err(e) => log::error!("🔁 Recurring invoice run failed: {}", e),
+                }
+            }
+        });
+    }
+}
+
+/// Parse an optional `as_of` query value, defaulting to today (UTC)
+pub fn as_of_date(value: Option<&str>) -> Result<NaiveDate, ApiError> {
+    match value.filter(|v| !v.trim().is_empty()) {
+        Some(v) => parse_date("as_of", v).map_err(ApiError::ValidationError),
+        None => Ok(Utc::now().date_naive()),
+    }
+}
@@ -0,0 +1,335 @@
+use std::collections::BTreeMap;
+
+use chrono::Utc;
+
+use crate::error::ApiError;
+use crate::models::ledger::{Account, AccountType, JournalEntry};
+use crate::models::money::{Money, DEFAULT_CURRENCY};
+use crate::models::report::{
+    BalanceSheet, Comparative, ProfitAndLoss, ReportLine, ReportPeriod, TrialBalance,
+    TrialBalanceLine,
+};
+use crate::repository::JournalRepository;
+use crate::services::LedgerService;
+
+/// Debit and credit totals per account code
+type AccountTotals = BTreeMap<String, (Money, Money)>;
+
+/// Financial statements computed from the journal
+#[derive(Clone)]
+pub struct ReportService {
+    journal: JournalRepository,
+    ledger: LedgerService,
+}
+
+impl ReportService {
+    pub fn new(journal: JournalRepository, ledger: LedgerService) -> Self {
+        Self { journal, ledger }
+    }
+
+    /// Pick the report period: a financial year, a custom range, or the
+    /// current financial year when neither is given
+    pub fn resolve_period(
+        fy: Option<&str>,
+        from: Option<&str>,
+        to: Option<&str>,
+    ) -> Result<ReportPeriod, ApiError> {
+        let period = match (fy, from, to) {
+            (Some(fy), None, None) => ReportPeriod::financial_year(fy),
+            (None, Some(from), Some(to)) => ReportPeriod::custom(from, to),
+            (None, None, None) => Ok(ReportPeriod::financial_year_of(Utc::now().date_naive())),
+            _ => Err("Give either 'fy' or both 'from' and 'to'".to_string()),
+        };
+        period.map_err(ApiError::ValidationError)
+    }
+
+    /// Totals of entries in `currency` dated up to `to`, from `from` when given
+    async fn totals(
+        &self,
+        org_email: &str,
+        currency: &str,
+        from: Option<&str>,
+        to: &str,
+    ) -> Result<AccountTotals, ApiError> {
+        let entries = self.journal.find_in_range(org_email, from, Some(to)).await?;
+        Ok(Self::sum_entries(&entries, currency))
+    }
+
+    fn sum_entries(entries: &[JournalEntry], currency: &str) -> AccountTotals {
+        let mut totals = AccountTotals::new();
+        for entry in entries.iter().filter(|e| e.currency == currency) {
+            for line in &entry.lines {
+                let total = totals.entry(line.account_code.clone()).or_default();
+                total.0 += line.debit;
+                total.1 += line.credit;
+            }
+        }
+        totals
+    }
+
+    /// Balance on the account's normal side
+    fn balance(account: &Account, totals: &AccountTotals) -> Money {
+        let (debit, credit) = totals.get(&account.code).copied().unwrap_or_default();
+        if account.account_type.is_debit_normal() {
+            debit - credit
+        } else {
+            credit - debit
+        }
+    }
+
+    /// Lines for every account of `account_type` with a figure in either period
+    fn lines(
+        chart: &[Account],
+        account_type: AccountType,
+        current: &AccountTotals,
+        previous: &AccountTotals,
+    ) -> (Vec<ReportLine>, Comparative) {
+        let mut total = Comparative::default();
+        let lines = chart
+            .iter()
+            .filter(|a| a.account_type == account_type)
+            .filter_map(|a| {
+                let amount = Comparative {
+                    current: Self::balance(a, current),
+                    previous: Self::balance(a, previous),
+                };
+                if amount.current.is_zero() && amount.previous.is_zero() {
+                    return None;
+                }
+                total += amount;
+                Some(ReportLine {
+                    account_code: a.code.clone(),
+                    account_name: a.name.clone(),
+                    account_type,
+                    amount,
+                })
+            })
+            .collect();
+        (lines, total)
+    }
+
+    fn currency(currency: Option<&str>) -> String {
+        currency
+            .map(|c| c.trim().to_uppercase())
+            .filter(|c| !c.is_empty())
+            .unwrap_or_else(|| DEFAULT_CURRENCY.to_string())
+    }
+
+    /// Closing debit or credit balance of every account at the end of the
+    /// period and of the previous period
+    pub async fn trial_balance(
+        &self,
+        org_email: &str,
+        period: ReportPeriod,
+        currency: Option<&str>,
+    ) -> Result<TrialBalance, ApiError> {
+        let currency = Self::currency(currency);
+        let previous_period = period.previous();
+        let chart = self.ledger.get_accounts(org_email).await?;
+        let current = self.totals(org_email, &currency, None, &period.to).await?;
+        let previous = self
+            .totals(org_email, &currency, None, &previous_period.to)
+            .await?;
+
+        // Net each account to a single debit or credit figure
+        let split = |totals: &AccountTotals, code: &str| -> (Money, Money) {
+            let (debit, credit) = totals.get(code).copied().unwrap_or_default();
+            let net = debit - credit;
+            if net.is_negative() {
+                (Money::ZERO, -net)
+            } else {
+                (net, Money::ZERO)
+            }
+        };
+
+        let mut total_debit = Comparative::default();
+        let mut total_credit = Comparative::default();
+        let mut lines = Vec::new();
+        for account in &chart {
+            let (cur_debit, cur_credit) = split(&current, &account.code);
+            let (prev_debit, prev_credit) = split(&previous, &account.code);
+            let debit = Comparative {
+                current: cur_debit,
+                previous: prev_debit,
+            };
+            let credit = Comparative {
+                current: cur_credit,
+                previous: prev_credit,
+            };
+            if debit == Comparative::default() && credit == Comparative::default() {
+                continue;
+            }
+
+            total_debit += debit;
+            total_credit += credit;
+            lines.push(TrialBalanceLine {
+                account_code: account.code.clone(),
+                account_name: account.name.clone(),
+                account_type: account.account_type,
+                debit,
+                credit,
+            });
+        }
+
+        Ok(TrialBalance {
+            org_email: org_email.to_string(),
+            currency,
+            period,
+            previous_period,
+            lines,
+            total_debit,
+            total_credit,
+        })
+    }
+
+    /// Income and expenses posted within the period
+    pub async fn profit_and_loss(
+        &self,
+        org_email: &str,
+        period: ReportPeriod,
+        currency: Option<&str>,
+    ) -> Result<ProfitAndLoss, ApiError> {
+        let currency = Self::currency(currency);
+        let previous_period = period.previous();
+        let chart = self.ledger.get_accounts(org_email).await?;
+        let current = self
+            .totals(org_email, &currency, Some(&period.from), &period.to)
+            .await?;
+        let previous = self
+            .totals(
+                org_email,
+                &currency,
+                Some(&previous_period.from),
+                &previous_period.to,
+            )
+            .await?;
+
+        let (income, total_income) = Self::lines(&chart, AccountType::Income, &current, &previous);
+        let (expenses, total_expenses) =
+            Self::lines(&chart, AccountType::Expense, &current, &previous);
+        let net_profit = Comparative {
+            current: total_income.current - total_expenses.current,
+            previous: total_income.previous - total_expenses.previous,
+        };
+
+        Ok(ProfitAndLoss {
+            org_email: org_email.to_string(),
+            currency,
+            period,
+            previous_period,
+            income,
+            total_income,
+            expenses,
+            total_expenses,
+            net_profit,
+        })
+    }
+
+    /// Assets, liabilities and equity at the end of the period and of the
+    /// previous period
+    pub async fn balance_sheet(
+        &self,
+        org_email: &str,
+        period: ReportPeriod,
+        currency: Option<&str>,
+    ) -> Result<BalanceSheet, ApiError> {
+        let currency = Self::currency(currency);
+        let previous_period = period.previous();
+        let chart = self.ledger.get_accounts(org_email).await?;
+        let current = self.totals(org_email, &currency, None, &period.to).await?;
+        let previous = self
+            .totals(org_email, &currency, None, &previous_period.to)
+            .await?;
+
+        let (assets, total_assets) = Self::lines(&chart, AccountType::Asset, &current, &previous);
+        let (liabilities, total_liabilities) =
+            Self::lines(&chart, AccountType::Liability, &current, &previous);
+        let (equity, equity_accounts) =
+            Self::lines(&chart, AccountType::Equity, &current, &previous);
+
+        let (_, income) = Self::lines(&chart, AccountType::Income, &current, &previous);
+        let (_, expenses) = Self::lines(&chart, AccountType::Expense, &current, &previous);
+        let retained_earnings = Comparative {
+            current: income.current - expenses.current,
+            previous: income.previous - expenses.previous,
+        };
+
+        let mut total_equity = equity_accounts;
+        total_equity += retained_earnings;
+        let mut total_liabilities_and_equity = total_liabilities;
+        total_liabilities_and_equity += total_equity;
+
+        if total_assets.current != total_liabilities_and_equity.current {
+            log::warn!(
+                "Balance sheet for {} does not balance: assets {} vs liabilities and equity {}",
+                org_email,
+                total_assets.current,
+                total_liabilities_and_equity.current
+            );
+        }
+
+        Ok(BalanceSheet {
+            org_email: org_email.to_string(),
+            currency,
+            period,
+            previous_period,
+            assets,
+            total_assets,
+            liabilities,
+            total_liabilities,
+            equity,
+            retained_earnings,
+            total_equity,
+            total_liabilities_and_equity,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::invoice::Invoice;
+    use crate::models::ledger::{self, DEFAULT_ACCOUNTS};
+
+    fn money(value: &str) -> Money {
+        value.parse().unwrap()
+    }
+
+    #[test]
+    fn export_invoice_is_reported_in_inr() {
+        let chart: Vec<Account> = DEFAULT_ACCOUNTS
+            .iter()
+            .map(|(code, name, account_type)| {
+                Account::new("org@example.com", code, name, *account_type)
+            })
+            .collect();
+        let invoice = Invoice {
+            invoice_number: "INV-0007".to_string(),
+            invoice_date: "2024-06-10".to_string(),
+            currency: "USD".to_string(),
+            exchange_rate: "83.25".to_string(),
+            sub_total: money("1200.00"),
+            total: money("1200.00"),
+            ..Default::default()
+        };
+
+        let entry = LedgerService::invoice_issued_entry(&invoice, "org@example.com").unwrap();
+        assert_eq!(entry.currency, DEFAULT_CURRENCY);
+        let memo = entry.foreign_amount.as_ref().unwrap();
+        assert_eq!(memo.currency, "USD");
+        assert_eq!(memo.amount, money("1200.00"));
+        assert_eq!(memo.exchange_rate, "83.25");
+
+        let current = ReportService::sum_entries(&[entry], DEFAULT_CURRENCY);
+        let (income, total_income) =
+            ReportService::lines(&chart, AccountType::Income, &current, &AccountTotals::new());
+        assert_eq!(income.len(), 1);
+        assert_eq!(income[0].account_code, ledger::SALES);
+        assert_eq!(total_income.current, money("99900.00"));
+
+        let (assets, _) =
+            ReportService::lines(&chart, AccountType::Asset, &current, &AccountTotals::new());
+        assert_eq!(assets[0].account_code, ledger::ACCOUNTS_RECEIVABLE);
+        assert_eq!(assets[0].amount.current, money("99900.00"));
+    }
+}
@@ -0,0 +1,994 @@
+//! Quarterly TDS position: what we deducted from vendor payments, which
+//! feeds Form 26Q, and what customers deducted from payments made to us.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use chrono::Utc;
+use mongodb::bson::DateTime;
+use rust_decimal::Decimal;
+
+use crate::error::ApiError;
+use crate::models::money::{Money, DEFAULT_CURRENCY};
+use crate::models::tds::{
+    BookedTdsCredit, CustomerTdsCredits, Form26QRequest, Form26QReturn, TdsChallan,
+    TdsChallanRequest, TdsCreditEntry, TdsCreditMatch, TdsCreditReconciliation,
+    TdsDeducteeSummary, TdsDeductorSummary, TdsQuarter, TdsSection, TdsSummary, NO_PAN_RATE,
+};
+use crate::repository::{
+    BillPaymentRepository, CustomerRepository, OrganisationRepository, PaymentRepository,
+    TdsChallanRepository,
+};
+use crate::services::e_invoice_service::{first_non_empty, phone, split_address};
+use crate::utils::date::{parse_date, DATE_FORMAT};
+use crate::utils::{form26as, form26q};
+use crate::utils::validation;
+
+/// 26AS and booked TDS up to this many rupees apart are rounding
+const CREDIT_TOLERANCE: i64 = 1;
+
+/// Deductor and customer names compared on letters and digits, with the
+/// usual company suffixes shortened
+fn name_key(value: &str) -> String {
+    value
+        .to_uppercase()
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| match word {
+            "PRIVATE" => "PVT",
+            "LIMITED" => "LTD",
+            "COMPANY" => "CO",
+            other => other,
+        })
+        .collect()
+}
+
+/// Section codes compared without case, spaces or brackets
+fn section_key(value: &str) -> String {
+    value
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .collect::<String>()
+        .to_uppercase()
+}
+
+fn quarter_of(date: &str) -> Option<TdsQuarter> {
+    parse_date("Date", date).ok().map(TdsQuarter::containing)
+}
+
+fn within_tolerance(a: Money, b: Money) -> bool {
+    (a - b).amount().abs() <= Decimal::from(CREDIT_TOLERANCE)
+}
+
+/// Differences between a 26AS credit and the receipt it was paired with
+fn credit_differences(credit: &TdsCreditEntry, books: &BookedTdsCredit) -> Vec<String> {
+    let mut differences = Vec::new();
+    if !within_tolerance(credit.tds_deducted, books.tds_deducted) {
+        differences.push(format!(
+            "TDS {} in 26AS, {} in books",
+            credit.tds_deducted, books.tds_deducted
+        ));
+    }
+    if !books.section.is_empty() && section_key(&credit.section) != section_key(&books.section)
+    {
+        differences.push(format!(
+            "Section {} in 26AS, {} in books",
+            credit.section, books.section
+        ));
+    }
+    if credit.tds_deposited < credit.tds_deducted {
+        differences.push(format!(
+            "Deductor deposited {} of the {} deducted",
+            credit.tds_deposited, credit.tds_deducted
+        ));
+    }
+    match credit.status.as_str() {
+        "U" => differences.push("Unmatched: the deductor's challan is not matched".to_string()),
+        "O" => differences.push("Overbooked: the challan does not cover the deduction".to_string()),
+        _ => {}
+    }
+    differences
+}
+
+/// Credits and receipts of one customer or deductor while matching
+struct CreditGroup {
+    customer_id: Option<String>,
+    customer_name: String,
+    tan: String,
+    credits: Vec<TdsCreditEntry>,
+    books: Vec<BookedTdsCredit>,
+}
+
+impl CreditGroup {
+    /// Pair credits with receipts: the same TDS in the same quarter first,
+    /// then the same TDS anywhere in the year (deductors often report the
+    /// invoice date), then whatever is left in the same quarter
+    fn reconcile(self) -> CustomerTdsCredits {
+        let mut books: Vec<Option<BookedTdsCredit>> = self.books.into_iter().map(Some).collect();
+        let mut credits: Vec<Option<TdsCreditEntry>> =
+            self.credits.into_iter().map(Some).collect();
+        let mut pairs = Vec::new();
+
+        let passes: [fn(&TdsCreditEntry, &BookedTdsCredit) -> bool; 3] = [
+            |credit, books| {
+                within_tolerance(credit.tds_deducted, books.tds_deducted)
+                    && quarter_of(&credit.transaction_date) == quarter_of(&books.payment_date)
+            },
+            |credit, books| within_tolerance(credit.tds_deducted, books.tds_deducted),
+            |credit, books| {
+                quarter_of(&credit.transaction_date) == quarter_of(&books.payment_date)
+            },
+        ];
+        for pass in passes {
+            for slot in credits.iter_mut() {
+                let Some(credit) = slot.as_ref() else {
+                    continue;
+                };
+                let candidate = books
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(idx, b)| b.as_ref().map(|b| (idx, b)))
+                    .filter(|(_, b)| pass(credit, b))
+                    .min_by_key(|(_, b)| (credit.tds_deducted - b.tds_deducted).amount().abs())
+                    .map(|(idx, _)| idx);
+                if let Some(idx) = candidate {
+                    let credit = slot.take().expect("credit present");
+                    let booked = books[idx].take().expect("receipt present");
+                    pairs.push((credit, booked));
+                }
+            }
+        }
+
+        let mut matched = Vec::new();
+        let mut mismatched = Vec::new();
+        for (credit, books) in pairs {
+            let differences = credit_differences(&credit, &books);
+            let pair = TdsCreditMatch {
+                credit,
+                books,
+                differences,
+            };
+            if pair.differences.is_empty() {
+                matched.push(pair);
+            } else {
+                mismatched.push(pair);
+            }
+        }
+        let unclaimed: Vec<TdsCreditEntry> = credits.into_iter().flatten().collect();
+        let not_in_26as: Vec<BookedTdsCredit> = books.into_iter().flatten().collect();
+
+        let credit_in_26as = matched
+            .iter()
+            .chain(&mismatched)
+            .map(|pair| pair.credit.tds_deposited)
+            .chain(unclaimed.iter().map(|credit| credit.tds_deposited))
+            .sum();
+        let tds_in_books = matched
+            .iter()
+            .chain(&mismatched)
+            .map(|pair| pair.books.tds_deducted)
+            .chain(not_in_26as.iter().map(|books| books.tds_deducted))
+            .sum();
+        CustomerTdsCredits {
+            customer_id: self.customer_id,
+            customer_name: self.customer_name,
+            tan: self.tan,
+            matched,
+            mismatched,
+            unclaimed,
+            not_in_26as,
+            credit_in_26as,
+            tds_in_books,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct TdsService {
+    org_repo: OrganisationRepository,
+    payment_repo: PaymentRepository,
+    bill_payment_repo: BillPaymentRepository,
+    challan_repo: TdsChallanRepository,
+    customer_repo: CustomerRepository,
+}
+
+impl TdsService {
+    pub fn new(
+        org_repo: OrganisationRepository,
+        payment_repo: PaymentRepository,
+        bill_payment_repo: BillPaymentRepository,
+        challan_repo: TdsChallanRepository,
+        customer_repo: CustomerRepository,
+    ) -> Self {
+        Self {
+            org_repo,
+            payment_repo,
+            bill_payment_repo,
+            challan_repo,
+            customer_repo,
+        }
+    }
+
+    /// Sections the organisation deducts under, with their rates and limits
+    pub async fn sections(&self, org_email: &str) -> Result<Vec<TdsSection>, ApiError> {
+        let org = self.org_repo.get_organisation_by_email(org_email).await?;
+        Ok(org.effective_tds_sections())
+    }
+
+    /// TDS deducted and suffered in a quarter, e.g. ("2025-26", "Q1").
+    /// Deductions are grouped per deductee PAN and section, the way Form 26Q
+    /// lists them.
+    pub async fn quarterly_summary(
+        &self,
+        org_email: &str,
+        financial_year: &str,
+        quarter: &str,
+    ) -> Result<TdsSummary, ApiError> {
+        let quarter =
+            TdsQuarter::parse(financial_year, quarter).map_err(ApiError::ValidationError)?;
+        let org = self.org_repo.get_organisation_by_email(org_email).await?;
+        let from = quarter.first_day().format(DATE_FORMAT).to_string();
+        let to = quarter.last_day().format(DATE_FORMAT).to_string();
+
+        let mut issues = Vec::new();
+        if org.tan.trim().is_empty() {
+            issues.push("Organisation has no TAN; it is needed to file Form 26Q".to_string());
+        }
+
+        // Vendors without a PAN are kept apart rather than merged under a blank PAN
+        let mut deducted: BTreeMap<(String, String, String), TdsDeducteeSummary> =
+            BTreeMap::new();
+        for payment in self
+            .bill_payment_repo
+            .find_in_range(org_email, &from, &to)
+            .await?
+        {
+            if payment.tds_deducted.is_zero() {
+                continue;
+            }
+            if payment.currency != DEFAULT_CURRENCY {
+                issues.push(format!(
+                    "Payment to {} on {} is in {}; TDS is reported in INR",
+                    payment.vendor_name, payment.payment_date, payment.currency
+                ));
+                continue;
+            }
+            if payment.deductee_pan.is_empty() {
+                issues.push(format!(
+                    "{} has no PAN; TDS on the payment of {} is at the no-PAN rate",
+                    payment.vendor_name, payment.payment_date
+                ));
+            }
+
+            let vendor_key = if payment.deductee_pan.is_empty() {
+                payment.vendor_id.clone()
+            } else {
+                String::new()
+            };
+            let key = (
+                payment.tds_section.clone(),
+                payment.deductee_pan.clone(),
+                vendor_key,
+            );
+            let row = deducted.entry(key).or_insert_with(|| TdsDeducteeSummary {
+                deductee_name: payment.vendor_name.clone(),
+                pan: payment.deductee_pan.clone(),
+                section: payment.tds_section.clone(),
+                payments: 0,
+                amount_paid: Money::ZERO,
+                tds_deducted: Money::ZERO,
+            });
+            row.payments += 1;
+            row.amount_paid += if payment.tds_base.is_zero() {
+                payment.gross_amount()
+            } else {
+                payment.tds_base
+            };
+            row.tds_deducted += payment.tds_deducted;
+        }
+
+        let mut received: BTreeMap<(String, String), TdsDeductorSummary> = BTreeMap::new();
+        for payment in self.payment_repo.find_in_range(org_email, &from, &to).await? {
+            if payment.tds_deducted.is_zero() {
+                continue;
+            }
+            if payment.currency != DEFAULT_CURRENCY {
+                issues.push(format!(
+                    "Receipt from {} on {} is in {}; TDS is reported in INR",
+                    payment.customer_name, payment.payment_date, payment.currency
+                ));
+                continue;
+            }
+            if payment.tds_section.trim().is_empty() {
+                issues.push(format!(
+                    "Receipt from {} on {} has TDS but no section",
+                    payment.customer_name, payment.payment_date
+                ));
+            }
+
+            let key = (payment.customer_name.clone(), payment.tds_section.clone());
+            let row = received.entry(key).or_insert_with(|| TdsDeductorSummary {
+                customer_name: payment.customer_name.clone(),
+                section: payment.tds_section.clone(),
+                payments: 0,
+                amount_received: Money::ZERO,
+                tds_deducted: Money::ZERO,
+            });
+            row.payments += 1;
+            row.amount_received += payment.gross_amount();
+            row.tds_deducted += payment.tds_deducted;
+        }
+
+        let deducted: Vec<TdsDeducteeSummary> = deducted.into_values().collect();
+        let received: Vec<TdsDeductorSummary> = received.into_values().collect();
+        Ok(TdsSummary {
+            org_email: org_email.to_string(),
+            tan: org.tan,
+            financial_year: quarter.financial_year(),
+            quarter: quarter.label(),
+            from,
+            to,
+            total_deducted: deducted.iter().map(|row| row.tds_deducted).sum(),
+            total_received: received.iter().map(|row| row.tds_deducted).sum(),
+            deducted,
+            received,
+            issues,
+        })
+    }
+
+    /// Record a challan the quarter's TDS was deposited with. The bill
+    /// payments it lists must carry TDS and not be on another challan.
+    pub async fn create_challan(
+        &self,
+        org_email: &str,
+        mut req: TdsChallanRequest,
+    ) -> Result<TdsChallan, ApiError> {
+        let quarter = TdsQuarter::parse(&req.financial_year, &req.quarter)
+            .map_err(ApiError::ValidationError)?;
+        req.org_email = org_email.to_string();
+        req.financial_year = quarter.financial_year();
+        req.quarter = quarter.label();
+        req.bsr_code = req.bsr_code.trim().to_string();
+        req.challan_serial_no = req.challan_serial_no.trim().to_string();
+        req.minor_head = req.minor_head.trim().to_string();
+
+        if req.bsr_code.len() != 7 || !req.bsr_code.chars().all(|c| c.is_ascii_digit()) {
+            return Err(ApiError::ValidationError(
+                "BSR code must be 7 digits".to_string(),
+            ));
+        }
+        if !(1..=5).contains(&req.challan_serial_no.len())
+            || !req.challan_serial_no.chars().all(|c| c.is_ascii_digit())
+        {
+            return Err(ApiError::ValidationError(
+                "Challan serial number must be 1 to 5 digits".to_string(),
+            ));
+        }
+        parse_date("Deposit date", &req.deposit_date).map_err(ApiError::ValidationError)?;
+        if req.minor_head != "200" && req.minor_head != "400" {
+            return Err(ApiError::ValidationError(
+                "Minor head must be 200 or 400".to_string(),
+            ));
+        }
+        let amounts = [
+            req.income_tax,
+            req.surcharge,
+            req.cess,
+            req.interest,
+            req.fee,
+            req.others,
+        ];
+        if amounts.iter().any(Money::is_negative) {
+            return Err(ApiError::ValidationError(
+                "Challan amounts cannot be negative".to_string(),
+            ));
+        }
+        if req.tax_deposited().is_zero() {
+            return Err(ApiError::ValidationError(
+                "Challan must deposit some tax".to_string(),
+            ));
+        }
+
+        let mut seen = HashSet::new();
+        req.payment_ids.retain(|id| seen.insert(id.clone()));
+        for payment_id in &req.payment_ids {
+            let payment = self
+                .bill_payment_repo
+                .find_by_id(org_email, payment_id)
+                .await?
+                .ok_or_else(|| {
+                    ApiError::NotFound(format!("Bill payment {} not found", payment_id))
+                })?;
+            if payment.tds_deducted.is_zero() {
+                return Err(ApiError::ValidationError(format!(
+                    "Payment to {} on {} has no TDS to deposit",
+                    payment.vendor_name, payment.payment_date
+                )));
+            }
+            if let Some(other) = self.challan_repo.find_by_payment_id(payment_id).await? {
+                return Err(ApiError::Conflict(format!(
+                    "Payment to {} on {} is already on challan {} of {}",
+                    payment.vendor_name,
+                    payment.payment_date,
+                    other.challan_serial_no,
+                    other.deposit_date
+                )));
+            }
+        }
+
+        req.id = None;
+        req.created_at = Some(DateTime::now());
+        self.challan_repo.create(req).await
+    }
+
+    pub async fn get_challans(
+        &self,
+        org_email: &str,
+        financial_year: &str,
+        quarter: &str,
+    ) -> Result<Vec<TdsChallan>, ApiError> {
+        let quarter =
+            TdsQuarter::parse(financial_year, quarter).map_err(ApiError::ValidationError)?;
+        self.challan_repo
+            .find_for_quarter(org_email, &quarter.financial_year(), &quarter.label())
+            .await
+    }
+
+    pub async fn delete_challan(&self, org_email: &str, id: &str) -> Result<(), ApiError> {
+        if !self.challan_repo.delete(org_email, id).await? {
+            return Err(ApiError::NotFound(format!("Challan {} not found", id)));
+        }
+        Ok(())
+    }
+
+    /// Form 26Q statement for a quarter from the TDS deducted on bill
+    /// payments and the challans it was deposited with. The text is built
+    /// even when there are errors so it can be inspected, but it will not
+    /// pass the FVU until they are fixed.
+    pub async fn form_26q(
+        &self,
+        org_email: &str,
+        req: &Form26QRequest,
+    ) -> Result<Form26QReturn, ApiError> {
+        let quarter = TdsQuarter::parse(&req.financial_year, &req.quarter)
+            .map_err(ApiError::ValidationError)?;
+        let org = self.org_repo.get_organisation_by_email(org_email).await?;
+        let mut errors = Vec::new();
+        let mut warnings = Vec::new();
+
+        // Deductor
+        let tan = org.tan.trim().to_uppercase();
+        if tan.is_empty() {
+            errors.push("Organisation has no TAN".to_string());
+        } else if !validation::TAN_REGEX.is_match(&tan) {
+            errors.push(format!("Organisation TAN '{}' is invalid", tan));
+        }
+        let gstin = validation::normalize_gstin(&org.gst_in);
+        let deductor_pan = if req.deductor_pan.trim().is_empty() {
+            gstin.get(2..12).unwrap_or("").to_string()
+        } else {
+            req.deductor_pan.trim().to_uppercase()
+        };
+        if !validation::PAN_REGEX.is_match(&deductor_pan) {
+            errors.push("Deductor PAN is missing or invalid".to_string());
+        }
+        let responsible_pan = req.responsible_pan.trim().to_uppercase();
+        if !validation::PAN_REGEX.is_match(&responsible_pan) {
+            errors.push("PAN of the person responsible is missing or invalid".to_string());
+        }
+        if req.responsible_name.trim().is_empty() || req.responsible_designation.trim().is_empty()
+        {
+            errors.push("Name and designation of the person responsible are required".to_string());
+        }
+        let address = org.addresses.first().map(|a| a.value.as_str()).unwrap_or("");
+        let pin = split_address(address).pin;
+        if pin.is_none() {
+            errors.push("Organisation address has no PIN code".to_string());
+        }
+        let deductor = form26q::Deductor {
+            tan: tan.clone(),
+            pan: deductor_pan,
+            name: first_non_empty(&[&org.company_name, &org.organisation_name]).to_string(),
+            branch: req.branch.trim().to_string(),
+            address: address.to_string(),
+            state: gstin.get(0..2).unwrap_or("").to_string(),
+            pin: pin.map(|p| p.to_string()).unwrap_or_default(),
+            email: org.email.trim().to_string(),
+            phone: phone(&org.phone).unwrap_or_default(),
+            deductor_type: match req.deductor_type.trim() {
+                "" => "K".to_string(),
+                value => value.to_uppercase(),
+            },
+            address_changed: req.address_changed,
+            responsible_name: req.responsible_name.trim().to_string(),
+            responsible_designation: req.responsible_designation.trim().to_string(),
+            responsible_pan,
+            responsible_mobile: phone(&req.responsible_mobile).unwrap_or_default(),
+            responsible_email: first_non_empty(&[&req.responsible_email, &org.email])
+                .to_string(),
+        };
+
+        // Deductions of the quarter
+        let from = quarter.first_day().format(DATE_FORMAT).to_string();
+        let to = quarter.last_day().format(DATE_FORMAT).to_string();
+        let mut payments: BTreeMap<String, _> = BTreeMap::new();
+        for payment in self
+            .bill_payment_repo
+            .find_in_range(org_email, &from, &to)
+            .await?
+        {
+            if payment.tds_deducted.is_zero() {
+                continue;
+            }
+            if payment.currency != DEFAULT_CURRENCY {
+                errors.push(format!(
+                    "Payment to {} on {} is in {}; TDS must be recorded in INR",
+                    payment.vendor_name, payment.payment_date, payment.currency
+                ));
+                continue;
+            }
+            if let Some(id) = payment.id {
+                payments.insert(id.to_hex(), payment);
+            }
+        }
+
+        let challans = self
+            .challan_repo
+            .find_for_quarter(org_email, &quarter.financial_year(), &quarter.label())
+            .await?;
+        if challans.is_empty() {
+            errors.push(format!(
+                "No challans recorded for {} {}",
+                quarter.financial_year(),
+                quarter.label()
+            ));
+        }
+
+        let mut covered = HashSet::new();
+        let mut records = Vec::new();
+        let mut deductees = HashSet::new();
+        let mut total_deducted = Money::ZERO;
+        for challan in &challans {
+            let name = format!(
+                "Challan {} of {}",
+                challan.challan_serial_no, challan.deposit_date
+            );
+            let deposited_on = match parse_date("Deposit date", &challan.deposit_date) {
+                Ok(date) => date,
+                Err(e) => {
+                    errors.push(format!("{}: {}", name, e));
+                    continue;
+                }
+            };
+
+            let mut deductions = Vec::new();
+            for payment_id in &challan.payment_ids {
+                let Some(payment) = payments.get(payment_id) else {
+                    errors.push(format!(
+                        "{} lists payment {}, which is not a TDS deduction of this quarter",
+                        name, payment_id
+                    ));
+                    continue;
+                };
+                covered.insert(payment_id.clone());
+                let label = format!("Payment to {} on {}", payment.vendor_name, payment.payment_date);
+
+                let Ok(paid_on) = parse_date("Payment date", &payment.payment_date) else {
+                    errors.push(format!("{} has an invalid date", label));
+                    continue;
+                };
+                if deposited_on < paid_on {
+                    errors.push(format!(
+                        "{}: deposited on {} before the deduction",
+                        label, challan.deposit_date
+                    ));
+                }
+
+                let section_code = form26q::section_code(&payment.tds_section);
+                if section_code.is_none() {
+                    errors.push(format!(
+                        "{}: section '{}' is not a Form 26Q section",
+                        label, payment.tds_section
+                    ));
+                }
+
+                let pan = payment.deductee_pan.trim().to_uppercase();
+                let amount_paid = if payment.tds_base.is_zero() {
+                    payment.gross_amount()
+                } else {
+                    payment.tds_base
+                };
+                let rate = Decimal::from_str_exact(payment.tds_rate.trim())
+                    .ok()
+                    .or_else(|| {
+                        (!amount_paid.is_zero()).then(|| {
+                            payment.tds_deducted.amount() * Decimal::ONE_HUNDRED
+                                / amount_paid.amount()
+                        })
+                    })
+                    .unwrap_or_default()
+                    .round_dp(4);
+                if pan.is_empty() {
+                    warnings.push(format!("{}: {} has no PAN", label, payment.vendor_name));
+                    if rate < NO_PAN_RATE {
+                        errors.push(format!(
+                            "{}: deducted at {}% without a PAN; at least {}% is required",
+                            label, rate, NO_PAN_RATE
+                        ));
+                    }
+                } else if !validation::PAN_REGEX.is_match(&pan) {
+                    errors.push(format!("{}: PAN '{}' is invalid", label, pan));
+                }
+
+                deductees.insert((pan.clone(), payment.vendor_id.clone()));
+                deductions.push(form26q::Deduction {
+                    pan: if pan.is_empty() {
+                        form26q::NO_PAN.to_string()
+                    } else {
+                        pan
+                    },
+                    name: payment.vendor_name.clone(),
+                    section_code: section_code.unwrap_or("").to_string(),
+                    amount_paid,
+                    tds: payment.tds_deducted,
+                    rate: format!("{:.4}", rate),
+                    paid_on,
+                });
+            }
+
+            let deducted: Money = deductions.iter().map(|d| d.tds).sum();
+            total_deducted += deducted;
+            if deducted > challan.tax_deposited() {
+                errors.push(format!(
+                    "{}: deductions of {} exceed the {} of tax deposited",
+                    name,
+                    deducted,
+                    challan.tax_deposited()
+                ));
+            } else if deducted < challan.tax_deposited() {
+                warnings.push(format!(
+                    "{}: {} of tax deposited is not matched to deductions",
+                    name,
+                    challan.tax_deposited() - deducted
+                ));
+            }
+
+            records.push(form26q::Challan {
+                serial_no: challan.challan_serial_no.clone(),
+                bsr_code: challan.bsr_code.clone(),
+                deposited_on,
+                income_tax: challan.income_tax,
+                surcharge: challan.surcharge,
+                cess: challan.cess,
+                interest: challan.interest,
+                fee: challan.fee,
+                others: challan.others,
+                minor_head: challan.minor_head.clone(),
+                deductions,
+            });
+        }
+
+        for (id, payment) in &payments {
+            if !covered.contains(id) {
+                errors.push(format!(
+                    "TDS of {} on the payment to {} on {} is not on any challan",
+                    payment.tds_deducted, payment.vendor_name, payment.payment_date
+                ));
+            }
+        }
+
+        let text = form26q::build(
+            &deductor,
+            quarter.start_year,
+            quarter.quarter,
+            &records,
+            Utc::now().date_naive(),
+        );
+        Ok(Form26QReturn {
+            file_name: format!(
+                "{}_26Q_{}_{}.txt",
+                if tan.is_empty() { "TAN" } else { &tan },
+                quarter.financial_year(),
+                quarter.label()
+            ),
+            text,
+            challans: records.len(),
+            deductees: deductees.len(),
+            total_deducted,
+            total_deposited: challans.iter().map(TdsChallan::total_deposit).sum(),
+            errors,
+            warnings,
+        })
+    }
+
+    /// Match the TDS credits in a Form 26AS text or AIS JSON export against
+    /// the TDS recorded on customer receipts of the financial year. Credits
+    /// are tied to customers through the TAN on the customer, falling back
+    /// to the deductor name.
+    pub async fn reconcile_credits(
+        &self,
+        org_email: &str,
+        financial_year: &str,
+        data: &[u8],
+    ) -> Result<TdsCreditReconciliation, ApiError> {
+        let first = TdsQuarter::parse(financial_year, "Q1").map_err(ApiError::ValidationError)?;
+        let last = TdsQuarter { quarter: 4, ..first };
+        let from = first.first_day().format(DATE_FORMAT).to_string();
+        let to = last.last_day().format(DATE_FORMAT).to_string();
+        let (source, entries) = form26as::parse(data).map_err(ApiError::ValidationError)?;
+        let mut issues = Vec::new();
+
+        let customers = self.customer_repo.find_all(org_email).await?;
+        let by_tan: HashMap<&str, usize> = customers
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| !c.tan.is_empty())
+            .map(|(idx, c)| (c.tan.as_str(), idx))
+            .collect();
+        let by_name = |name: &str| {
+            let key = name_key(name);
+            customers.iter().position(|c| {
+                !key.is_empty()
+                    && (name_key(&c.customer_name) == key || name_key(&c.company_name) == key)
+            })
+        };
+
+        let mut groups: BTreeMap<String, CreditGroup> = BTreeMap::new();
+        let group_for = |groups: &mut BTreeMap<String, CreditGroup>,
+                         customer: Option<usize>,
+                         name: &str,
+                         tan: &str| {
+            let key = match customer {
+                Some(idx) => format!("customer:{}", idx),
+                None if !tan.is_empty() => format!("tan:{}", tan),
+                None => format!("name:{}", name_key(name)),
+            };
+            groups.entry(key.clone()).or_insert_with(|| {
+                let customer = customer.map(|idx| &customers[idx]);
+                CreditGroup {
+                    customer_id: customer.and_then(|c| c.id).map(|id| id.to_hex()),
+                    customer_name: customer
+                        .map(|c| first_non_empty(&[&c.company_name, &c.customer_name]))
+                        .unwrap_or(name)
+                        .to_string(),
+                    tan: customer
+                        .map(|c| c.tan.as_str())
+                        .filter(|t| !t.is_empty())
+                        .unwrap_or(tan)
+                        .to_string(),
+                    credits: Vec::new(),
+                    books: Vec::new(),
+                }
+            });
+            key
+        };
+
+        let mut untied_tans = HashSet::new();
+        for entry in entries {
+            if entry.transaction_date < from || entry.transaction_date > to {
+                issues.push(format!(
+                    "Credit from {} dated {} is outside {}; skipped",
+                    entry.deductor_name,
+                    entry.transaction_date,
+                    first.financial_year()
+                ));
+                continue;
+            }
+            let customer = match by_tan.get(entry.tan.as_str()) {
+                Some(idx) => Some(*idx),
+                None => {
+                    let customer = by_name(&entry.deductor_name);
+                    if untied_tans.insert(entry.tan.clone()) {
+                        issues.push(match customer {
+                            Some(idx) => format!(
+                                "{} is matched by name; record TAN {} on the customer",
+                                customers[idx].customer_name, entry.tan
+                            ),
+                            None => format!(
+                                "TAN {} ({}) is not recorded on any customer",
+                                entry.tan, entry.deductor_name
+                            ),
+                        });
+                    }
+                    customer
+                }
+            };
+            let key = group_for(&mut groups, customer, &entry.deductor_name, &entry.tan);
+            if let Some(group) = groups.get_mut(&key) {
+                group.credits.push(entry);
+            }
+        }
+
+        for payment in self.payment_repo.find_in_range(org_email, &from, &to).await? {
+            if payment.tds_deducted.is_zero() {
+                continue;
+            }
+            if payment.currency != DEFAULT_CURRENCY {
+                issues.push(format!(
+                    "Receipt from {} on {} is in {}; TDS credit is in INR",
+                    payment.customer_name, payment.payment_date, payment.currency
+                ));
+                continue;
+            }
+            let customer = by_name(&payment.customer_name);
+            let key = group_for(&mut groups, customer, &payment.customer_name, "");
+            if let Some(group) = groups.get_mut(&key) {
+                group.books.push(BookedTdsCredit {
+                    payment_id: payment.id.map(|id| id.to_hex()).unwrap_or_default(),
+                    customer_name: payment.customer_name.clone(),
+                    payment_date: payment.payment_date.clone(),
+                    section: payment.tds_section.clone(),
+                    amount_received: payment.gross_amount(),
+                    tds_deducted: payment.tds_deducted,
+                });
+            }
+        }
+
+        let customers: Vec<CustomerTdsCredits> =
+            groups.into_values().map(CreditGroup::reconcile).collect();
+        Ok(TdsCreditReconciliation {
+            org_email: org_email.to_string(),
+            financial_year: first.financial_year(),
+            source: source.to_string(),
+            credit_in_26as: customers.iter().map(|c| c.credit_in_26as).sum(),
+            tds_in_books: customers.iter().map(|c| c.tds_in_books).sum(),
+            customers,
+            issues,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn money(value: &str) -> Money {
+        value.parse().unwrap()
+    }
+
+    fn credit(date: &str, tds: &str, section: &str) -> TdsCreditEntry {
+        TdsCreditEntry {
+            deductor_name: "Globex Private Limited".to_string(),
+            tan: "MUMG12345B".to_string(),
+            section: section.to_string(),
+            transaction_date: date.to_string(),
+            status: "F".to_string(),
+            amount_paid: money(tds) * Decimal::from(10),
+            tds_deducted: money(tds),
+            tds_deposited: money(tds),
+        }
+    }
+
+    fn booked(id: &str, date: &str, tds: &str, section: &str) -> BookedTdsCredit {
+        BookedTdsCredit {
+            payment_id: id.to_string(),
+            customer_name: "Globex Pvt Ltd".to_string(),
+            payment_date: date.to_string(),
+            section: section.to_string(),
+            amount_received: money(tds) * Decimal::from(10),
+            tds_deducted: money(tds),
+        }
+    }
+
+    fn reconcile(credits: Vec<TdsCreditEntry>, books: Vec<BookedTdsCredit>) -> CustomerTdsCredits {
+        CreditGroup {
+            customer_id: None,
+            customer_name: "Globex Pvt Ltd".to_string(),
+            tan: "MUMG12345B".to_string(),
+            credits,
+            books,
+        }
+        .reconcile()
+    }
+
+    #[test]
+    fn names_and_sections_compare_loosely() {
+        assert_eq!(
+            name_key("Globex Private Limited"),
+            name_key("GLOBEX PVT. LTD.")
+        );
+        assert_ne!(name_key("Globex Ltd"), name_key("Globex Co"));
+        assert_eq!(section_key("194J(b)"), section_key("194JB"));
+    }
+
+    #[test]
+    fn same_tds_in_the_same_quarter_is_matched() {
+        let result = reconcile(
+            vec![credit("2025-05-20", "1000", "194J")],
+            vec![booked("p1", "2025-05-18", "1000", "194J")],
+        );
+
+        assert_eq!(result.matched.len(), 1);
+        assert!(result.mismatched.is_empty());
+        assert!(result.unclaimed.is_empty() && result.not_in_26as.is_empty());
+        assert_eq!(result.credit_in_26as, money("1000"));
+        assert_eq!(result.tds_in_books, money("1000"));
+    }
+
+    #[test]
+    fn rounding_within_a_rupee_still_matches() {
+        let result = reconcile(
+            vec![credit("2025-05-20", "1000.60", "194J")],
+            vec![booked("p1", "2025-05-18", "1000", "194J")],
+        );
+        assert_eq!(result.matched.len(), 1);
+    }
+
+    #[test]
+    fn same_tds_in_another_quarter_pairs_when_nothing_closer_exists() {
+        // Deductor reported the invoice date, we booked the receipt later
+        let result = reconcile(
+            vec![credit("2025-06-25", "1000", "194J")],
+            vec![booked("p1", "2025-07-10", "1000", "194J")],
+        );
+        assert_eq!(result.matched.len(), 1);
+        assert_eq!(result.matched[0].books.payment_id, "p1");
+    }
+
+    #[test]
+    fn the_same_quarter_wins_over_the_same_amount_elsewhere() {
+        let result = reconcile(
+            vec![credit("2025-08-05", "500", "194C")],
+            vec![
+                booked("q1", "2025-05-05", "500", "194C"),
+                booked("q2", "2025-08-01", "500", "194C"),
+            ],
+        );
+        assert_eq!(result.matched.len(), 1);
+        assert_eq!(result.matched[0].books.payment_id, "q2");
+        assert_eq!(result.not_in_26as.len(), 1);
+        assert_eq!(result.not_in_26as[0].payment_id, "q1");
+    }
+
+    #[test]
+    fn different_tds_in_the_same_quarter_is_mismatched() {
+        let result = reconcile(
+            vec![credit("2025-05-20", "800", "194C")],
+            vec![booked("p1", "2025-05-18", "1000", "194J")],
+        );
+
+        assert!(result.matched.is_empty());
+        assert_eq!(result.mismatched.len(), 1);
+        let differences = &result.mismatched[0].differences;
+        assert_eq!(differences.len(), 2, "{:?}", differences);
+        assert!(differences[0].starts_with("TDS "));
+        assert!(differences[1].starts_with("Section 194C in 26AS"));
+        assert_eq!(result.credit_in_26as, money("800"));
+        assert_eq!(result.tds_in_books, money("1000"));
+    }
+
+    #[test]
+    fn unmatched_challans_and_short_deposits_are_flagged() {
+        let mut unmatched = credit("2025-05-20", "1000", "194J");
+        unmatched.status = "U".to_string();
+        let mut short = credit("2025-09-20", "700", "194J");
+        short.tds_deposited = money("400");
+
+        let result = reconcile(
+            vec![unmatched, short],
+            vec![
+                booked("p1", "2025-05-18", "1000", "194J"),
+                booked("p2", "2025-09-18", "700", "194J"),
+            ],
+        );
+
+        assert_eq!(result.mismatched.len(), 2);
+        assert!(result.mismatched[0].differences[0].starts_with("Unmatched"));
+        assert!(result.mismatched[1].differences[0].starts_with("Deductor deposited"));
+        // Only what was deposited counts as credit
+        assert_eq!(result.credit_in_26as, money("1400"));
+    }
+
+    #[test]
+    fn leftovers_are_reported_on_each_side() {
+        let result = reconcile(
+            vec![credit("2025-05-20", "1000", "194J")],
+            vec![booked("p1", "2025-11-18", "300", "194J")],
+        );
+
+        assert!(result.matched.is_empty() && result.mismatched.is_empty());
+        assert_eq!(result.unclaimed.len(), 1);
+        assert_eq!(result.not_in_26as.len(), 1);
+        assert_eq!(result.credit_in_26as, money("1000"));
+        assert_eq!(result.tds_in_books, money("300"));
+    }
+}
@@ -0,0 +1,151 @@
+//! User accounts of an organisation, managed by its administrators
+
+use mongodb::bson::DateTime;
+use validator::Validate;
+
+use crate::error::ApiError;
+use crate::models::user::{CreateUserRequest, Role, UpdateUserRequest, User, UserProfile};
+use crate::repository::{RefreshTokenRepository, UserRepository};
+use crate::services::auth_service::{hash_password, normalize_email};
+
+#[derive(Clone)]
+pub struct UserService {
+    user_repo: UserRepository,
+    token_repo: RefreshTokenRepository,
+}
+
+fn require_admin(caller: &User) -> Result<(), ApiError> {
+    if caller.role != Role::Admin {
+        return Err(ApiError::Forbidden(
+            "Only administrators can manage users".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+impl UserService {
+    pub fn new(user_repo: UserRepository, token_repo: RefreshTokenRepository) -> Self {
+        Self {
+            user_repo,
+            token_repo,
+        }
+    }
+
+    pub async fn list_users(&self, caller: &User) -> Result<Vec<UserProfile>, ApiError> {
+        let users = self.user_repo.find_by_org(&caller.org_email).await?;
+        Ok(users.iter().map(UserProfile::from).collect())
+    }
+
+    pub async fn create_user(
+        &self,
+        caller: &User,
+        req: CreateUserRequest,
+    ) -> Result<UserProfile, ApiError> {
+        require_admin(caller)?;
+        req.validate()?;
+        let email = normalize_email(&req.email);
+        if self.user_repo.find_by_email(&email).await?.is_some() {
+            return Err(ApiError::Conflict(format!(
+                "A user with email {} already exists",
+                email
+            )));
+        }
+
+        let now = DateTime::now();
+        let user = User {
+            id: None,
+            name: req.name.trim().to_string(),
+            email,
+            password_hash: hash_password(&req.password)?,
+            org_email: caller.org_email.clone(),
+            role: req.role,
+            is_active: true,
+            last_login_at: None,
+            created_at: Some(now),
+            updated_at: Some(now),
+        };
+        let user = self.user_repo.create(user).await?;
+        Ok(UserProfile::from(&user))
+    }
+
+    pub async fn update_user(
+        &self,
+        caller: &User,
+        id: &str,
+        req: UpdateUserRequest,
+    ) -> Result<UserProfile, ApiError> {
+        require_admin(caller)?;
+        req.validate()?;
+        let mut user = self.find_in_org(caller, id).await?;
+
+        let demoting =
+            matches!(req.role, Some(role) if role != Role::Admin) || req.is_active == Some(false);
+        if demoting && user.role == Role::Admin && user.is_active {
+            self.ensure_other_admin(&user).await?;
+        }
+
+        if let Some(name) = req.name {
+            user.name = name.trim().to_string();
+        }
+        if let Some(role) = req.role {
+            user.role = role;
+        }
+        if let Some(is_active) = req.is_active {
+            user.is_active = is_active;
+        }
+        let reset_password = req.password.is_some();
+        if let Some(password) = req.password {
+            user.password_hash = hash_password(&password)?;
+        }
+        user.updated_at = Some(DateTime::now());
+        self.user_repo.replace(&user).await?;
+
+        if reset_password || !user.is_active {
+            self.token_repo.revoke_all_for_user(id).await?;
+        }
+        Ok(UserProfile::from(&user))
+    }
+
+    pub async fn delete_user(&self, caller: &User, id: &str) -> Result<(), ApiError> {
+        require_admin(caller)?;
+        let user = self.find_in_org(caller, id).await?;
+        if user.id == caller.id {
+            return Err(ApiError::BadRequest(
+                "You cannot delete your own account".to_string(),
+            ));
+        }
+        if user.role == Role::Admin && user.is_active {
+            self.ensure_other_admin(&user).await?;
+        }
+
+        self.user_repo.delete(id).await?;
+        self.token_repo.revoke_all_for_user(id).await?;
+        Ok(())
+    }
+
+    /// Users of other organisations are reported as not found
+    async fn find_in_org(&self, caller: &User, id: &str) -> Result<User, ApiError> {
+        self.user_repo
+            .find_by_id(id)
+            .await?
+            .filter(|user| user.org_email == caller.org_email)
+            .ok_or_else(|| ApiError::NotFound(format!("User with id {} not found", id)))
+    }
+
+    /// An organisation must keep at least one active administrator
+    async fn ensure_other_admin(&self, user: &User) -> Result<(), ApiError> {
+        let others = self
+            .user_repo
+            .find_by_org(&user.org_email)
+            .await?
+            .into_iter()
+            .filter(|other| other.id != user.id && other.role == Role::Admin && other.is_active)
+            .count();
+        if others == 0 {
+            return Err(ApiError::BadRequest(
+                "The organisation must keep at least one active administrator".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
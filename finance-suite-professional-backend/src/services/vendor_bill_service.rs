@@ -0,0 +1,618 @@
+use chrono::Days;
+use mongodb::bson::{oid::ObjectId, DateTime};
+
+use crate::error::ApiError;
+use crate::models::money::{self, Money};
+use crate::models::organisation::{Organisation, RoundingRule};
+use crate::models::purchase_order::PurchaseOrderKind;
+use crate::models::tds::{default_tds_sections, TdsQuarter};
+use crate::models::vendor::Vendor;
+use crate::models::vendor_bill::{
+    BillPayment, BillPaymentAllocation, CreateBillPaymentRequest, VendorBill, VendorBillRequest,
+};
+use crate::repository::{
+    BillPaymentRepository, OrganisationRepository, PurchaseOrderRepository, VendorBillRepository,
+    VendorRepository,
+};
+use crate::utils::date::{self, parse_date, DATE_FORMAT};
+use crate::utils::gst;
+
+#[derive(Clone)]
+pub struct VendorBillService {
+    repository: VendorBillRepository,
+    payment_repo: BillPaymentRepository,
+    vendor_repo: VendorRepository,
+    org_repo: OrganisationRepository,
+    po_repo: PurchaseOrderRepository,
+}
+
+impl VendorBillService {
+    pub fn new(
+        repository: VendorBillRepository,
+        payment_repo: BillPaymentRepository,
+        vendor_repo: VendorRepository,
+        org_repo: OrganisationRepository,
+        po_repo: PurchaseOrderRepository,
+    ) -> Self {
+        Self {
+            repository,
+            payment_repo,
+            vendor_repo,
+            org_repo,
+            po_repo,
+        }
+    }
+
+    async fn get_vendor(&self, id: &str, org_email: &str) -> Result<Vendor, ApiError> {
+        self.vendor_repo
+            .find_by_id(org_email, id)
+            .await?
+            .ok_or_else(|| ApiError::NotFound(format!("Vendor with id {} not found", id)))
+    }
+
+    /// Fill vendor details and defaults, then compute input tax lines.
+    ///
+    /// The vendor is the supplier here, so CGST/SGST vs IGST is decided by
+    /// the vendor's GSTIN state against the place of supply (our state by
+    /// default). Unregistered vendors are treated as local.
+    fn prepare_bill(
+        bill: &mut VendorBill,
+        vendor: &Vendor,
+        org: &Organisation,
+    ) -> Result<(), ApiError> {
+        if bill.bill_number.trim().is_empty() {
+            return Err(ApiError::ValidationError("Bill number is required".to_string()));
+        }
+        let bill_date =
+            parse_date("Bill date", &bill.bill_date).map_err(ApiError::ValidationError)?;
+        if !money::is_valid_currency_code(&bill.currency) {
+            return Err(ApiError::ValidationError(format!(
+                "Invalid currency code '{}'",
+                bill.currency
+            )));
+        }
+
+        bill.bill_number = bill.bill_number.trim().to_string();
+        bill.vendor_name = vendor.vendor_name.clone();
+        bill.vendor_gstin = vendor.gst_in.clone();
+        bill.org_email = org.email.clone();
+
+        if bill.due_date.trim().is_empty() {
+            bill.due_date = bill_date
+                .checked_add_days(Days::new(vendor.payment_terms as u64))
+                .unwrap_or(bill_date)
+                .format(DATE_FORMAT)
+                .to_string();
+        } else {
+            let due_date =
+                parse_date("Due date", &bill.due_date).map_err(ApiError::ValidationError)?;
+            if due_date < bill_date {
+                return Err(ApiError::ValidationError(
+                    "Due date cannot be before the bill date".to_string(),
+                ));
+            }
+        }
+
+        if bill.bill_type.trim().is_empty() {
+            bill.bill_type = "domestic".to_string();
+        }
+        if bill.place_of_supply.trim().is_empty() {
+            bill.place_of_supply = org
+                .state_code()
+                .and_then(|code| gst::state_name(code).map(|name| format!("{}-{}", code, name)))
+                .unwrap_or_default();
+        }
+
+        let invoice_type = if bill.bill_type.eq_ignore_ascii_case("import") {
+            "international"
+        } else {
+            "domestic"
+        };
+        let supplier_state = vendor.state_code().or_else(|| org.state_code());
+        let supply_type =
+            gst::supply_type_for(supplier_state, invoice_type, &bill.place_of_supply)?;
+        let totals = gst::compute_item_taxes(&mut bill.items, supply_type, org.rounding())?;
+        bill.sub_total = totals.sub_total;
+        bill.totalcgst = totals.total_cgst;
+        bill.totalsgst = totals.total_sgst;
+        bill.totaligst = totals.total_igst;
+        bill.total = totals.total;
+        bill.refresh_balance();
+        Ok(())
+    }
+
+    /// Check the bill can be matched to `po_id`: a vendor PO, open for
+    /// billing, in the bill currency
+    async fn check_purchase_order(&self, bill: &VendorBill) -> Result<Option<ObjectId>, ApiError> {
+        let Some(po_id) = bill.po_id.as_deref().filter(|id| !id.trim().is_empty()) else {
+            return Ok(None);
+        };
+
+        let po = self
+            .po_repo
+            .find_by_id(&bill.org_email, po_id)
+            .await?
+            .ok_or_else(|| ApiError::NotFound(format!("Purchase order {} not found", po_id)))?;
+
+        if po.kind != PurchaseOrderKind::Vendor {
+            return Err(ApiError::ValidationError(format!(
+                "Purchase order {} is not a vendor purchase order",
+                po.po_number
+            )));
+        }
+        if !po.can_bill() {
+            return Err(ApiError::Conflict(format!(
+                "Purchase order {} in {:?} status cannot be billed",
+                po.po_number, po.status
+            )));
+        }
+        if po.currency != bill.currency {
+            return Err(ApiError::ValidationError(format!(
+                "Purchase order {} is in {} but the bill is in {}",
+                po.po_number, po.currency, bill.currency
+            )));
+        }
+
+        Ok(po.id)
+    }
+
+    async fn bill_purchase_order(&self, po_id: ObjectId, amount: Money) -> Result<(), ApiError> {
+        if !self.po_repo.bill(po_id, amount).await? {
+            return Err(ApiError::Conflict(format!(
+                "Bill value {} exceeds the amount remaining on the purchase order",
+                amount
+            )));
+        }
+        Ok(())
+    }
+
+    fn po_object_id(bill: &VendorBill) -> Option<ObjectId> {
+        bill.po_id
+            .as_deref()
+            .and_then(|id| ObjectId::parse_str(id).ok())
+    }
+
+    pub async fn create_bill(
+        &self,
+        mut bill: VendorBillRequest,
+        org_email: &str,
+    ) -> Result<VendorBill, ApiError> {
+        let vendor = self.get_vendor(&bill.vendor_id, org_email).await?;
+        let org = self.org_repo.get_organisation_by_email(org_email).await?;
+        Self::prepare_bill(&mut bill, &vendor, &org)?;
+
+        if self
+            .repository
+            .find_by_vendor_bill_number(org_email, &bill.vendor_id, &bill.bill_number)
+            .await?
+            .is_some()
+        {
+            return Err(ApiError::ValidationError(format!(
+                "Bill {} from {} is already recorded",
+                bill.bill_number, vendor.vendor_name
+            )));
+        }
+
+        let po_id = self.check_purchase_order(&bill).await?;
+        if let Some(po_id) = po_id {
+            self.bill_purchase_order(po_id, bill.sub_total).await?;
+        }
+
+        let now = DateTime::now();
+        bill.id = None;
+        bill.amount_paid = Money::ZERO;
+        bill.balance_due = bill.total;
+        bill.status = Default::default();
+        bill.created_at = Some(now);
+        bill.updated_at = Some(now);
+
+        match self.repository.create(bill.clone()).await {
+            Ok(created) => Ok(created),
+            Err(err) => {
+                if let Some(po_id) = po_id {
+                    self.po_repo.release(po_id, bill.sub_total).await?;
+                }
+                Err(err)
+            }
+        }
+    }
+
+    pub async fn get_bills(
+        &self,
+        vendor_id: Option<&str>,
+        org_email: &str,
+    ) -> Result<Vec<VendorBill>, ApiError> {
+        self.repository.find_all(org_email, vendor_id).await
+    }
+
+    pub async fn get_bill_by_id(&self, id: &str, org_email: &str) -> Result<VendorBill, ApiError> {
+        self.repository
+            .find_by_id(org_email, id)
+            .await?
+            .ok_or_else(|| ApiError::NotFound(format!("Vendor bill with id {} not found", id)))
+    }
+
+    /// Replace an unpaid bill, re-matching it against its purchase order
+    pub async fn update_bill(
+        &self,
+        id: &str,
+        mut bill: VendorBillRequest,
+        org_email: &str,
+    ) -> Result<VendorBill, ApiError> {
+        let existing = self.get_bill_by_id(id, org_email).await?;
+        if !existing.is_editable() {
+            return Err(ApiError::Conflict(
+                "Bills with payments recorded cannot be edited".to_string(),
+            ));
+        }
+
+        let vendor = self.get_vendor(&bill.vendor_id, org_email).await?;
+        let org = self.org_repo.get_organisation_by_email(org_email).await?;
+        Self::prepare_bill(&mut bill, &vendor, &org)?;
+
+        if let Some(other) = self
+            .repository
+            .find_by_vendor_bill_number(org_email, &bill.vendor_id, &bill.bill_number)
+            .await?
+        {
+            if other.id != existing.id {
+                return Err(ApiError::ValidationError(format!(
+                    "Bill {} from {} is already recorded",
+                    bill.bill_number, vendor.vendor_name
+                )));
+            }
+        }
+
+        // Give back the old PO value before matching the new one
+        let old_po = Self::po_object_id(&existing);
+        if let Some(po_id) = old_po {
+            self.po_repo.release(po_id, existing.sub_total).await?;
+        }
+        let new_po = match self.check_purchase_order(&bill).await {
+            Ok(po_id) => po_id,
+            Err(err) => {
+                if let Some(po_id) = old_po {
+                    self.po_repo.bill(po_id, existing.sub_total).await?;
+                }
+                return Err(err);
+            }
+        };
+        if let Some(po_id) = new_po {
+            if let Err(err) = self.bill_purchase_order(po_id, bill.sub_total).await {
+                if let Some(po_id) = old_po {
+                    self.po_repo.bill(po_id, existing.sub_total).await?;
+                }
+                return Err(err);
+            }
+        }
+
+        bill.id = existing.id;
+        bill.amount_paid = Money::ZERO;
+        bill.balance_due = bill.total;
+        bill.status = existing.status;
+        bill.created_at = existing.created_at;
+        bill.updated_at = Some(DateTime::now());
+
+        if !self.repository.replace_unpaid(&bill).await? {
+            if let Some(po_id) = new_po {
+                self.po_repo.release(po_id, bill.sub_total).await?;
+            }
+            if let Some(po_id) = old_po {
+                self.po_repo.bill(po_id, existing.sub_total).await?;
+            }
+            return Err(ApiError::Conflict(
+                "Bills with payments recorded cannot be edited".to_string(),
+            ));
+        }
+        Ok(bill)
+    }
+
+    /// Delete an unpaid bill and release its purchase order value
+    pub async fn delete_bill(&self, id: &str, org_email: &str) -> Result<bool, ApiError> {
+        let existing = self.get_bill_by_id(id, org_email).await?;
+        if !existing.is_editable() {
+            return Err(ApiError::Conflict(
+                "Bills with payments recorded cannot be deleted".to_string(),
+            ));
+        }
+
+        let deleted = self.repository.delete(org_email, id).await?;
+        if deleted {
+            if let Some(po_id) = Self::po_object_id(&existing) {
+                self.po_repo.release(po_id, existing.sub_total).await?;
+            }
+        }
+        Ok(deleted)
+    }
+
+    /// Work out TDS on a vendor payment under its section, else the vendor's.
+    /// The base is the taxable value of the bills settled (GST excluded) plus
+    /// any advance, and nothing is deducted until the section's single
+    /// payment or annual limit is crossed. TDS entered by hand is kept.
+    async fn apply_tds(
+        &self,
+        payment: &mut BillPayment,
+        vendor: &Vendor,
+        bills: &[VendorBill],
+    ) -> Result<(), ApiError> {
+        payment.deductee_pan = vendor.pan.as_deref().unwrap_or_default().trim().to_uppercase();
+        if payment.tds_section.trim().is_empty() && !payment.skip_tds {
+            payment.tds_section = vendor.tds_section.clone();
+        }
+        payment.tds_section = payment.tds_section.trim().to_uppercase();
+        payment.tds_rate.clear();
+        payment.tds_base = Money::ZERO;
+
+        if payment.skip_tds || payment.tds_section.is_empty() {
+            if !payment.tds_deducted.is_zero() {
+                return Err(ApiError::ValidationError(
+                    "TDS section is required when TDS is deducted".to_string(),
+                ));
+            }
+            return Ok(());
+        }
+
+        let section = if payment.org_email.is_empty() {
+            default_tds_sections()
+                .into_iter()
+                .find(|s| s.section.eq_ignore_ascii_case(&payment.tds_section))
+        } else {
+            self.org_repo
+                .get_organisation_by_email(&payment.org_email)
+                .await?
+                .tds_section(&payment.tds_section)
+        }
+        .ok_or_else(|| {
+            ApiError::ValidationError(format!(
+                "TDS section {} is not configured for this organisation",
+                payment.tds_section
+            ))
+        })?;
+        let rate = section
+            .deduction_rate(!payment.deductee_pan.is_empty())
+            .map_err(ApiError::ValidationError)?;
+
+        let taxable: Money = payment
+            .allocations
+            .iter()
+            .zip(bills)
+            .map(|(allocation, bill)| {
+                if bill.total.is_zero() {
+                    allocation.amount
+                } else {
+                    (allocation.amount * (bill.sub_total.amount() / bill.total.amount()))
+                        .round_paise()
+                }
+            })
+            .sum();
+        let base = taxable + (payment.gross_amount() - payment.allocated_amount());
+        payment.tds_base = base;
+        payment.tds_rate = rate.normalize().to_string();
+        if !payment.tds_deducted.is_zero() {
+            return Ok(());
+        }
+
+        let date =
+            parse_date("Payment date", &payment.payment_date).map_err(ApiError::ValidationError)?;
+        let year_start = TdsQuarter::containing(date).year_start().format(DATE_FORMAT).to_string();
+        let paid_earlier: Money = self
+            .payment_repo
+            .find_in_range(&payment.org_email, &year_start, &payment.payment_date)
+            .await?
+            .iter()
+            .filter(|p| p.vendor_id == payment.vendor_id && p.tds_section == payment.tds_section)
+            .map(|p| p.tds_base)
+            .sum();
+        if !section.applies(base, paid_earlier + base) {
+            return Ok(());
+        }
+
+        let tds = base.percent(rate).round(RoundingRule::Nearest);
+        if tds >= payment.gross_amount() {
+            return Err(ApiError::ValidationError(format!(
+                "TDS {} leaves nothing to pay out of {}",
+                tds,
+                payment.gross_amount()
+            )));
+        }
+        payment.tds_deducted = tds;
+        payment.amount -= tds;
+        Ok(())
+    }
+
+    /// Record a payment to a vendor and allocate it to that vendor's bills,
+    /// deducting TDS under the vendor's section
+    pub async fn record_bill_payment(
+        &self,
+        mut payment: CreateBillPaymentRequest,
+        org_email: &str,
+    ) -> Result<BillPayment, ApiError> {
+        payment.id = None;
+        payment.created_at = Some(DateTime::now());
+
+        if !money::is_valid_currency_code(&payment.currency) {
+            return Err(ApiError::ValidationError(format!(
+                "Invalid currency code '{}'",
+                payment.currency
+            )));
+        }
+        payment.validate().map_err(ApiError::ValidationError)?;
+
+        let vendor = self.get_vendor(&payment.vendor_id, org_email).await?;
+        payment.vendor_name = vendor.vendor_name.clone();
+        payment.org_email = org_email.to_string();
+
+        // Check every allocation before touching any bill
+        let mut bills: Vec<VendorBill> = Vec::with_capacity(payment.allocations.len());
+        for allocation in payment.allocations.iter_mut() {
+            let mut bill = self.get_bill_by_id(&allocation.bill_id, org_email).await?;
+
+            if bill.vendor_id != payment.vendor_id {
+                return Err(ApiError::ValidationError(format!(
+                    "Bill {} belongs to a different vendor",
+                    bill.bill_number
+                )));
+            }
+            if bill.currency != payment.currency {
+                return Err(ApiError::ValidationError(format!(
+                    "Bill {} is in {} but the payment is in {}",
+                    bill.bill_number, bill.currency, payment.currency
+                )));
+            }
+
+            bill.apply_payment(allocation.amount)
+                .map_err(ApiError::Conflict)?;
+            allocation.bill_number = bill.bill_number.clone();
+            bills.push(bill);
+        }
+
+        self.apply_tds(&mut payment, &vendor, &bills).await?;
+
+        // Apply each allocation atomically; if one is refused, because
+        // another payment got there first, undo the rest
+        let allocations = payment.allocations.clone();
+        for (idx, allocation) in allocations.iter().enumerate() {
+            match self
+                .repository
+                .apply_payment(&allocation.bill_id, allocation.amount)
+                .await
+            {
+                Ok(true) => {}
+                Ok(false) => {
+                    self.release(&allocations[..idx]).await;
+                    return Err(ApiError::Conflict(format!(
+                        "Bill {} changed while the payment was recorded; reload and retry",
+                        allocation.bill_number
+                    )));
+                }
+                Err(e) => {
+                    self.release(&allocations[..idx]).await;
+                    return Err(e);
+                }
+            }
+        }
+
+        let created = match self.payment_repo.create(payment).await {
+            Ok(created) => created,
+            Err(e) => {
+                self.release(&allocations).await;
+                return Err(e);
+            }
+        };
+
+        log::info!(
+            "Recorded bill payment {:?} of {} {} across {} bill(s)",
+            created.id,
+            created.amount,
+            created.currency,
+            created.allocations.len()
+        );
+        Ok(created)
+    }
+
+    pub async fn get_bill_payments(
+        &self,
+        bill_id: Option<&str>,
+        org_email: &str,
+    ) -> Result<Vec<BillPayment>, ApiError> {
+        self.payment_repo.find_all(org_email, bill_id).await
+    }
+
+    pub async fn get_bill_payment_by_id(
+        &self,
+        id: &str,
+        org_email: &str,
+    ) -> Result<BillPayment, ApiError> {
+        self.payment_repo
+            .find_by_id(org_email, id)
+            .await?
+            .ok_or_else(|| ApiError::NotFound(format!("Bill payment with id {} not found", id)))
+    }
+
+    /// Delete a bill payment and release its allocations from the bills
+    pub async fn delete_bill_payment(&self, id: &str, org_email: &str) -> Result<bool, ApiError> {
+        let payment = self.get_bill_payment_by_id(id, org_email).await?;
+
+        // Remove the payment first so two concurrent deletes cannot both
+        // release its allocations
+        if !self.payment_repo.delete(org_email, id).await? {
+            return Ok(false);
+        }
+
+        for (idx, allocation) in payment.allocations.iter().enumerate() {
+            match self
+                .repository
+                .reverse_payment(&allocation.bill_id, allocation.amount)
+                .await
+            {
+                Ok(true) => {}
+                Ok(false) => log::warn!(
+                    "Bill {} allocated by bill payment {} no longer exists",
+                    allocation.bill_id,
+                    id
+                ),
+                Err(e) => {
+                    // Put back what was released and the payment itself
+                    for allocation in &payment.allocations[..idx] {
+                        if let Err(err) = self
+                            .repository
+                            .apply_payment(&allocation.bill_id, allocation.amount)
+                            .await
+                        {
+                            log::error!(
+                                "Could not re-apply bill payment {} to bill {}: {}",
+                                id,
+                                allocation.bill_id,
+                                err
+                            );
+                        }
+                    }
+                    self.payment_repo.create(payment.clone()).await?;
+                    return Err(e);
+                }
+            }
+        }
+
+        Ok(true)
+    }
+
+    /// Best-effort undo of allocations already applied when recording a
+    /// bill payment fails part-way
+    async fn release(&self, allocations: &[BillPaymentAllocation]) {
+        for allocation in allocations {
+            if let Err(e) = self
+                .repository
+                .reverse_payment(&allocation.bill_id, allocation.amount)
+                .await
+            {
+                log::error!(
+                    "Could not release {} from bill {}: {}",
+                    allocation.amount,
+                    allocation.bill_id,
+                    e
+                );
+            }
+        }
+    }
+
+    /// Flag unpaid bills past their due date as overdue
+    pub async fn mark_overdue(&self) -> Result<u64, ApiError> {
+        self.repository.mark_overdue(&date::today()).await
+    }
+
+    /// Background loop marking overdue bills every `interval`, so reads
+    /// never write
+    pub fn spawn_overdue_scheduler(self, interval: std::time::Duration) {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                match self.mark_overdue().await {
+                    Ok(0) => {}
+                    Ok(marked) => log::info!("Marked {} vendor bill(s) overdue", marked),
+                    Err(e) => log::error!("Overdue vendor bill check failed: {}", e),
+                }
+            }
+        });
+    }
+}
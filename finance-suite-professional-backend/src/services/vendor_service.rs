@@ -0,0 +1,125 @@
+use mongodb::bson::DateTime;
+use validator::Validate;
+
+use crate::error::ApiError;
+use crate::models::vendor::{Vendor, VendorRequest};
+use crate::repository::{VendorBillRepository, VendorRepository};
+use crate::utils::validation;
+
+#[derive(Clone)]
+pub struct VendorService {
+    repository: VendorRepository,
+    bill_repo: VendorBillRepository,
+}
+
+impl VendorService {
+    pub fn new(repository: VendorRepository, bill_repo: VendorBillRepository) -> Self {
+        Self {
+            repository,
+            bill_repo,
+        }
+    }
+
+    pub async fn create_vendor(
+        &self,
+        mut req: VendorRequest,
+        org_email: &str,
+    ) -> Result<Vendor, ApiError> {
+        req.validate()?;
+        for address in &req.addresses {
+            address.validate()?;
+        }
+
+        req.gst_in =
+            validation::clean_optional_gstin(&req.gst_in).map_err(ApiError::ValidationError)?;
+        if !req.gst_in.is_empty()
+            && self
+                .repository
+                .find_by_gstin(org_email, &req.gst_in)
+                .await?
+                .is_some()
+        {
+            return Err(ApiError::ValidationError(format!(
+                "Vendor with GSTIN {} already exists",
+                req.gst_in
+            )));
+        }
+
+        let now = DateTime::now();
+        req.id = None;
+        req.org_email = org_email.to_string();
+        req.created_at = Some(now);
+        req.updated_at = Some(now);
+
+        self.repository.create(req).await
+    }
+
+    pub async fn get_all_vendors(&self, org_email: &str) -> Result<Vec<Vendor>, ApiError> {
+        self.repository.find_all(org_email).await
+    }
+
+    pub async fn get_vendor_by_id(&self, id: &str, org_email: &str) -> Result<Vendor, ApiError> {
+        self.repository
+            .find_by_id(org_email, id)
+            .await?
+            .ok_or_else(|| ApiError::NotFound(format!("Vendor with id {} not found", id)))
+    }
+
+    pub async fn update_vendor(
+        &self,
+        id: &str,
+        mut req: VendorRequest,
+        org_email: &str,
+    ) -> Result<Vendor, ApiError> {
+        req.validate()?;
+        for address in &req.addresses {
+            address.validate()?;
+        }
+
+        let existing = self.get_vendor_by_id(id, org_email).await?;
+
+        req.gst_in =
+            validation::clean_optional_gstin(&req.gst_in).map_err(ApiError::ValidationError)?;
+        if !req.gst_in.is_empty() && req.gst_in != existing.gst_in {
+            if let Some(other) = self
+                .repository
+                .find_by_gstin(org_email, &req.gst_in)
+                .await?
+            {
+                if other.id != existing.id {
+                    return Err(ApiError::ValidationError(format!(
+                        "Vendor with GSTIN {} already exists",
+                        req.gst_in
+                    )));
+                }
+            }
+        }
+
+        req.id = existing.id;
+        req.org_email = existing.org_email;
+        req.created_at = existing.created_at;
+        req.updated_at = Some(DateTime::now());
+
+        self.repository.replace(&req).await?;
+        Ok(req)
+    }
+
+    /// Vendors with bills on record can only be deactivated, not deleted
+    pub async fn delete_vendor(&self, id: &str, org_email: &str) -> Result<bool, ApiError> {
+        self.get_vendor_by_id(id, org_email).await?;
+        if self.bill_repo.exists_for_vendor(id).await? {
+            return Err(ApiError::Conflict(
+                "Vendor has bills on record; mark it inactive instead".to_string(),
+            ));
+        }
+        self.repository.delete(org_email, id).await
+    }
+
+    pub async fn search_vendors(
+        &self,
+        query: &str,
+        org_email: &str,
+    ) -> Result<Vec<Vendor>, ApiError> {
+        self.repository.search(org_email, query).await
+    }
+}
@@ -0,0 +1,40 @@
+//! Minimal CSV writer for report exports (RFC 4180 quoting)
+
+#[derive(Debug, Default)]
+pub struct CsvWriter {
+    out: String,
+}
+
+impl CsvWriter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append one record; fields containing commas, quotes or line breaks are quoted
+    pub fn row<S: AsRef<str>>(&mut self, fields: &[S]) {
+        let line = fields
+            .iter()
+            .map(|f| escape(f.as_ref()))
+            .collect::<Vec<_>>()
+            .join(",");
+        self.out.push_str(&line);
+        self.out.push_str("\r\n");
+    }
+
+    /// Append an empty record, used to separate report sections
+    pub fn blank(&mut self) {
+        self.out.push_str("\r\n");
+    }
+
+    pub fn finish(self) -> String {
+        self.out
+    }
+}
+
+fn escape(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
@@ -0,0 +1,43 @@
+//! Dates travel as YYYY-MM-DD strings through the API and the database.
+//! Parse and format them here instead of with ad-hoc layouts.
+
+use chrono::{NaiveDate, Utc};
+
+/// Layout of every stored and API date
+pub const DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// dd-mm-yyyy, as the GST portal writes dates in GSTR-1 and GSTR-2B
+pub const GSTN_DATE_FORMAT: &str = "%d-%m-%Y";
+
+/// dd/mm/yyyy, as the IRP and e-way bill schemas expect
+pub const NIC_DATE_FORMAT: &str = "%d/%m/%Y";
+
+/// Parse a YYYY-MM-DD value, naming `field` in the error
+pub fn parse_date(field: &str, value: &str) -> Result<NaiveDate, String> {
+    parse(value).ok_or_else(|| format!("{} must be a date in YYYY-MM-DD format", field))
+}
+
+/// Parse a YYYY-MM-DD value; `None` when blank or malformed
+pub fn parse(value: &str) -> Option<NaiveDate> {
+    parse_as(value, DATE_FORMAT)
+}
+
+/// Parse a date another system wrote in `layout`
+pub fn parse_as(value: &str, layout: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(value.trim(), layout).ok()
+}
+
+/// Rewrite a YYYY-MM-DD value in `layout`; `None` when it cannot be read
+pub fn reformat(value: &str, layout: &str) -> Option<String> {
+    parse(value).map(|d| d.format(layout).to_string())
+}
+
+/// `date` as YYYY-MM-DD
+pub fn format(date: NaiveDate) -> String {
+    date.format(DATE_FORMAT).to_string()
+}
+
+/// Today (UTC) as YYYY-MM-DD
+pub fn today() -> String {
+    format(Utc::now().date_naive())
+}
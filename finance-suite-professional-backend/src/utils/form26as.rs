@@ -0,0 +1,237 @@
+//! Readers for the TDS credits in Form 26AS and the AIS.
+//!
+//! The 26AS text export from TRACES is caret-separated: a deductor summary
+//! line (`Sr. No.^Name^TAN^...^Total paid^Total deducted^Total deposited`)
+//! followed by one line per transaction, each starting with an empty field
+//! (`^Sr. No.^Section^Transaction date^Status^Booking date^Remarks^Amount
+//! paid^Tax deducted^TDS deposited`). Only Part I, TDS on payments made to
+//! us, is read. The AIS JSON export lists the same transactions as objects.
+
+use crate::models::money::Money;
+use crate::models::tds::{AisTdsFile, AisTdsRow, TdsCreditEntry};
+use crate::utils::date::{self, DATE_FORMAT};
+use crate::utils::validation;
+
+/// Date layouts used by TRACES and the AIS
+const DATE_LAYOUTS: &[&str] = &[DATE_FORMAT, "%d-%b-%Y", "%d/%m/%Y", "%d-%m-%Y"];
+
+/// Normalise a source date to YYYY-MM-DD
+fn date(value: &str) -> Option<String> {
+    DATE_LAYOUTS
+        .iter()
+        .find_map(|layout| date::parse_as(value, layout))
+        .map(date::format)
+}
+
+/// Amounts in the text export may carry thousands separators
+fn amount(value: &str) -> Option<Money> {
+    value.trim().replace(',', "").parse().ok()
+}
+
+/// Roman numeral of a "PART-II ..." heading
+fn part_heading(line: &str) -> Option<String> {
+    let upper = line.trim().trim_start_matches('^').to_uppercase();
+    let rest = upper.strip_prefix("PART")?;
+    let rest = rest.trim_start_matches(['-', ' ']);
+    let numeral: String = rest
+        .chars()
+        .take_while(|c| matches!(c, 'I' | 'V' | 'X'))
+        .collect();
+    let whole_word = !rest[numeral.len()..].starts_with(|c: char| c.is_ascii_alphanumeric());
+    (!numeral.is_empty() && whole_word).then_some(numeral)
+}
+
+/// TDS credits in a 26AS text or AIS JSON export, with the name of the
+/// format read
+pub fn parse(data: &[u8]) -> Result<(&'static str, Vec<TdsCreditEntry>), String> {
+    let text = String::from_utf8_lossy(data);
+    let text = text.trim_start_matches('\u{feff}').trim_start();
+    if text.starts_with('{') || text.starts_with('[') {
+        let file: AisTdsFile =
+            serde_json::from_str(text).map_err(|e| format!("Invalid AIS JSON: {}", e))?;
+        let rows = match file {
+            AisTdsFile::Rows(rows) | AisTdsFile::Wrapped { tds: rows } => rows,
+        };
+        let entries = rows
+            .into_iter()
+            .enumerate()
+            .map(|(idx, row)| ais_entry(idx, row))
+            .collect::<Result<_, _>>()?;
+        return Ok(("AIS JSON", entries));
+    }
+    Ok(("26AS text", parse_text(text)?))
+}
+
+fn ais_entry(idx: usize, row: AisTdsRow) -> Result<TdsCreditEntry, String> {
+    let transaction_date = date(&row.transaction_date).ok_or_else(|| {
+        format!(
+            "AIS row {} has an invalid date '{}'",
+            idx + 1,
+            row.transaction_date
+        )
+    })?;
+    let section = row.section.trim().to_uppercase();
+    Ok(TdsCreditEntry {
+        deductor_name: row.deductor_name.trim().to_string(),
+        tan: validation::normalize_gstin(&row.tan),
+        section: section
+            .strip_prefix("TDS-")
+            .unwrap_or(&section)
+            .to_string(),
+        transaction_date,
+        status: row.status.trim().to_uppercase(),
+        amount_paid: row.amount_paid,
+        tds_deducted: row.tds_deducted,
+        tds_deposited: row.tds_deposited.unwrap_or(row.tds_deducted),
+    })
+}
+
+fn parse_text(text: &str) -> Result<Vec<TdsCreditEntry>, String> {
+    let mut entries = Vec::new();
+    let mut deductor: Option<(String, String)> = None;
+    let mut in_part_one = true;
+
+    for line in text.lines() {
+        if let Some(part) = part_heading(line) {
+            in_part_one = part == "I";
+            deductor = None;
+            continue;
+        }
+        if !in_part_one {
+            continue;
+        }
+
+        let fields: Vec<&str> = line.split('^').map(str::trim).collect();
+        if fields.len() < 3 {
+            continue;
+        }
+
+        // Deductor summary: Sr. No.^Name^TAN^...
+        let tan = validation::normalize_gstin(fields[2]);
+        if fields[0].parse::<u32>().is_ok() && validation::TAN_REGEX.is_match(&tan) {
+            deductor = Some((fields[1].to_string(), tan));
+            continue;
+        }
+
+        // Transaction: ^Sr. No.^Section^Date^Status^Booking date^Remarks^Paid^Deducted^Deposited
+        if !fields[0].is_empty() || fields.len() < 10 || fields[1].parse::<u32>().is_err() {
+            continue;
+        }
+        let Some((name, tan)) = &deductor else {
+            continue;
+        };
+        let Some(transaction_date) = date(fields[3]) else {
+            continue;
+        };
+        let parse_amount = |idx: usize| {
+            amount(fields[idx]).ok_or_else(|| {
+                format!(
+                    "26AS line for {} on {} has an invalid amount '{}'",
+                    name, fields[3], fields[idx]
+                )
+            })
+        };
+        entries.push(TdsCreditEntry {
+            deductor_name: name.clone(),
+            tan: tan.clone(),
+            section: fields[2].to_uppercase(),
+            transaction_date,
+            status: fields[4].to_uppercase(),
+            amount_paid: parse_amount(7)?,
+            tds_deducted: parse_amount(8)?,
+            tds_deposited: parse_amount(9)?,
+        });
+    }
+
+    if entries.is_empty() {
+        return Err("No Part I TDS transactions found in the 26AS file".to_string());
+    }
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn money(value: &str) -> Money {
+        value.parse().unwrap()
+    }
+
+    const TEXT: &str = "\u{feff}PART-I - Details of Tax Deducted at Source\r\n\
+        Sr. No.^Name of Deductor^TAN of Deductor^^^^Total Amount Paid^Total Tax Deducted^Total TDS Deposited\r\n\
+        1^GLOBEX PRIVATE LIMITED^mumg12345b^^^^1,50,000.00^15,000.00^15,000.00\r\n\
+        ^1^194J^15-May-2025^F^20-Jul-2025^^1,00,000.00^10,000.00^10,000.00\r\n\
+        ^2^194j^02/06/2025^u^20-Jul-2025^^50,000.00^5,000.00^5,000.00\r\n\
+        PART-II - Details of Tax Deducted at Source for 15G / 15H\r\n\
+        1^INITECH LIMITED^DELI54321C^^^^10,000.00^0.00^0.00\r\n\
+        ^1^194A^10-Jun-2025^F^20-Jul-2025^^10,000.00^0.00^0.00\r\n";
+
+    #[test]
+    fn reads_part_one_transactions_under_their_deductor() {
+        let (source, entries) = parse(TEXT.as_bytes()).unwrap();
+
+        assert_eq!(source, "26AS text");
+        assert_eq!(entries.len(), 2);
+        let first = &entries[0];
+        assert_eq!(first.deductor_name, "GLOBEX PRIVATE LIMITED");
+        assert_eq!(first.tan, "MUMG12345B");
+        assert_eq!(first.section, "194J");
+        assert_eq!(first.transaction_date, "2025-05-15");
+        assert_eq!(first.status, "F");
+        assert_eq!(first.amount_paid, money("100000"));
+        assert_eq!(first.tds_deducted, money("10000"));
+
+        let second = &entries[1];
+        assert_eq!(second.section, "194J");
+        assert_eq!(second.transaction_date, "2025-06-02");
+        assert_eq!(second.status, "U");
+    }
+
+    #[test]
+    fn part_headings_need_a_whole_numeral() {
+        assert_eq!(part_heading("PART-I - Details").as_deref(), Some("I"));
+        assert_eq!(part_heading("^PART II").as_deref(), Some("II"));
+        assert_eq!(part_heading("PARTICULARS"), None);
+    }
+
+    #[test]
+    fn a_file_without_part_one_transactions_is_rejected() {
+        let err = parse(b"PART-II\r\n1^INITECH LIMITED^DELI54321C\r\n").unwrap_err();
+        assert!(err.contains("No Part I"), "{}", err);
+    }
+
+    #[test]
+    fn a_bad_amount_names_the_line() {
+        let text = "1^GLOBEX^MUMG12345B\n^1^194J^2025-05-15^F^^^abc^10^10\n";
+        let err = parse(text.as_bytes()).unwrap_err();
+        assert!(err.contains("GLOBEX on 2025-05-15"), "{}", err);
+    }
+
+    #[test]
+    fn reads_ais_json_rows() {
+        let json = r#"{"TDS": [
+            {"TAN": "mumg12345b", "deductorName": " Globex ", "informationCode": "tds-194j",
+             "transactionDate": "15/05/2025", "amountPaid": "100000", "tdsDeducted": 10000},
+            {"tan": "MUMG12345B", "section": "194C", "date": "2025-06-02",
+             "tdsDeducted": "700", "tdsDeposited": "400"}
+        ]}"#;
+        let (source, entries) = parse(json.as_bytes()).unwrap();
+
+        assert_eq!(source, "AIS JSON");
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].deductor_name, "Globex");
+        assert_eq!(entries[0].tan, "MUMG12345B");
+        assert_eq!(entries[0].section, "194J");
+        assert_eq!(entries[0].transaction_date, "2025-05-15");
+        // Deposited defaults to the amount deducted
+        assert_eq!(entries[0].tds_deposited, money("10000"));
+        assert_eq!(entries[1].tds_deposited, money("400"));
+    }
+
+    #[test]
+    fn an_ais_row_with_a_bad_date_is_rejected() {
+        let json = r#"[{"tan": "MUMG12345B", "date": "soon", "tdsDeducted": "1"}]"#;
+        let err = parse(json.as_bytes()).unwrap_err();
+        assert!(err.contains("AIS row 1"), "{}", err);
+    }
+}
@@ -0,0 +1,653 @@
+//! Form 26Q statement in the NSDL e-TDS fixed-format layout read by the File
+//! Validation Utility (FVU): one caret-separated record per line, a file
+//! header (FH), one batch header (BH), then each challan (CD) followed by the
+//! deductions (DD) it deposits.
+
+use chrono::NaiveDate;
+
+use crate::models::money::Money;
+
+/// 26Q section codes, keyed by the section as written in the Act
+const SECTION_CODES: &[(&str, &str)] = &[
+    ("193", "193"),
+    ("194", "194"),
+    ("194A", "94A"),
+    ("194B", "94B"),
+    ("194BB", "4BB"),
+    ("194C", "94C"),
+    ("194D", "94D"),
+    ("194DA", "4DA"),
+    ("194EE", "4EE"),
+    ("194G", "94G"),
+    ("194H", "94H"),
+    ("194I(A)", "4IA"),
+    ("194I(B)", "4IB"),
+    ("194IA", "9IA"),
+    ("194IB", "9IB"),
+    ("194J(A)", "4JA"),
+    ("194J(B)", "4JB"),
+    ("194K", "94K"),
+    ("194M", "94M"),
+    ("194N", "94N"),
+    ("194O", "94O"),
+    ("194Q", "94Q"),
+    ("194R", "94R"),
+    ("194S", "94S"),
+];
+
+/// GST state code to the state code of the e-TDS layout
+const NSDL_STATE_CODES: &[(&str, &str)] = &[
+    ("01", "14"),
+    ("02", "13"),
+    ("03", "26"),
+    ("04", "06"),
+    ("05", "34"),
+    ("06", "12"),
+    ("07", "09"),
+    ("08", "27"),
+    ("09", "31"),
+    ("10", "05"),
+    ("11", "28"),
+    ("12", "03"),
+    ("13", "23"),
+    ("14", "20"),
+    ("15", "22"),
+    ("16", "30"),
+    ("17", "21"),
+    ("18", "04"),
+    ("19", "32"),
+    ("20", "35"),
+    ("21", "24"),
+    ("22", "33"),
+    ("23", "18"),
+    ("24", "11"),
+    ("25", "08"),
+    ("26", "07"),
+    ("27", "19"),
+    ("29", "15"),
+    ("30", "10"),
+    ("31", "17"),
+    ("32", "16"),
+    ("33", "29"),
+    ("34", "25"),
+    ("35", "01"),
+    ("36", "36"),
+    ("37", "02"),
+    ("38", "37"),
+];
+
+/// PAN placeholder for deductees who have not furnished one
+pub const NO_PAN: &str = "PANNOTAVBL";
+
+/// State code used for addresses outside India
+const FOREIGN_STATE_CODE: &str = "99";
+
+/// 26Q code of a section such as "194J(b)"; None when 26Q does not cover it
+pub fn section_code(section: &str) -> Option<&'static str> {
+    let section: String = section
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect::<String>()
+        .to_uppercase();
+    SECTION_CODES
+        .iter()
+        .find(|(name, _)| *name == section)
+        .map(|(_, code)| *code)
+}
+
+/// e-TDS state code for a GST state code
+pub fn state_code(gst_state: &str) -> &'static str {
+    NSDL_STATE_CODES
+        .iter()
+        .find(|(gst, _)| *gst == gst_state)
+        .map(|(_, code)| *code)
+        .unwrap_or(FOREIGN_STATE_CODE)
+}
+
+/// Date in the ddmmyyyy form every date field uses
+pub fn date(value: NaiveDate) -> String {
+    value.format("%d%m%Y").to_string()
+}
+
+/// Rupee amounts are written with exactly two decimals
+fn amount(value: Money) -> String {
+    value.round_paise().to_string()
+}
+
+/// Free text may not contain the field separator or line breaks
+fn text(value: &str, max: usize) -> String {
+    value
+        .chars()
+        .map(|c| if c == '^' || c.is_control() { ' ' } else { c })
+        .collect::<String>()
+        .trim()
+        .chars()
+        .take(max)
+        .collect()
+}
+
+/// Address split into the five 25-character lines of the layout
+fn address_lines(address: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    let mut current = String::new();
+    for word in text(address, 125).split_whitespace() {
+        if !current.is_empty() && current.chars().count() + 1 + word.chars().count() > 25 {
+            lines.push(std::mem::take(&mut current));
+        }
+        if !current.is_empty() {
+            current.push(' ');
+        }
+        current.push_str(word);
+    }
+    if !current.is_empty() {
+        lines.push(current);
+    }
+    lines.resize(5, String::new());
+    lines.into_iter().map(|line| text(&line, 25)).collect()
+}
+
+/// Deductor and the person responsible for deducting, for the batch header
+#[derive(Debug, Clone)]
+pub struct Deductor {
+    pub tan: String,
+    pub pan: String,
+    pub name: String,
+    pub branch: String,
+    pub address: String,
+    /// GST state code, e.g. "27"
+    pub state: String,
+    pub pin: String,
+    pub email: String,
+    pub phone: String,
+    /// NSDL deductor category, e.g. "K" company
+    pub deductor_type: String,
+    pub address_changed: bool,
+    pub responsible_name: String,
+    pub responsible_designation: String,
+    pub responsible_pan: String,
+    pub responsible_mobile: String,
+    pub responsible_email: String,
+}
+
+/// Deduction reported under a challan
+#[derive(Debug, Clone)]
+pub struct Deduction {
+    pub pan: String,
+    pub name: String,
+    /// 26Q section code, e.g. "94C"
+    pub section_code: String,
+    pub amount_paid: Money,
+    pub tds: Money,
+    /// Percent deducted
+    pub rate: String,
+    pub paid_on: NaiveDate,
+}
+
+/// Challan with the deductions it deposits
+#[derive(Debug, Clone)]
+pub struct Challan {
+    pub serial_no: String,
+    pub bsr_code: String,
+    pub deposited_on: NaiveDate,
+    pub income_tax: Money,
+    pub surcharge: Money,
+    pub cess: Money,
+    pub interest: Money,
+    pub fee: Money,
+    pub others: Money,
+    pub minor_head: String,
+    pub deductions: Vec<Deduction>,
+}
+
+impl Challan {
+    fn total_deposit(&self) -> Money {
+        self.income_tax + self.surcharge + self.cess + self.interest + self.fee + self.others
+    }
+}
+
+/// Code 01 for companies (PAN fourth character C), 02 for everyone else
+fn deductee_code(pan: &str) -> &'static str {
+    if pan.chars().nth(3) == Some('C') {
+        "01"
+    } else {
+        "02"
+    }
+}
+
+/// Assessment year and financial year fields, e.g. ("202627", "202526")
+fn year_fields(fy_start: i32) -> (String, String) {
+    (
+        format!("{}{:02}", fy_start + 1, (fy_start + 2) % 100),
+        format!("{}{:02}", fy_start, (fy_start + 1) % 100),
+    )
+}
+
+/// Append a record, prefixed with its line number
+fn push(lines: &mut Vec<String>, fields: Vec<String>) {
+    let mut record = vec![(lines.len() + 1).to_string()];
+    record.extend(fields);
+    lines.push(record.join("^"));
+}
+
+/// Build the statement text for one quarter. `created_on` is the file
+/// creation date in the file header.
+pub fn build(
+    deductor: &Deductor,
+    fy_start: i32,
+    quarter: u32,
+    challans: &[Challan],
+    created_on: NaiveDate,
+) -> String {
+    let mut lines: Vec<String> = Vec::new();
+
+    // File header
+    push(
+        &mut lines,
+        [
+            "FH",
+            "NS1",
+            "R",
+            &date(created_on),
+            "1",
+            "D",
+            &deductor.tan,
+            "1",
+            "",
+            "",
+            "",
+            "",
+            "",
+            "",
+            "",
+            "",
+            "",
+        ]
+        .iter()
+        .map(|f| f.to_string())
+        .collect(),
+    );
+
+    // Batch header
+    let (assessment_year, financial_year) = year_fields(fy_start);
+    let address = address_lines(&deductor.address);
+    let state = state_code(&deductor.state);
+    let change = if deductor.address_changed { "Y" } else { "N" };
+    let batch_total: Money = challans.iter().map(Challan::total_deposit).sum();
+    let mut batch = vec![
+        "BH".to_string(),
+        "1".to_string(),
+        challans.len().to_string(),
+        "26Q".to_string(),
+        String::new(),
+        String::new(),
+        String::new(),
+        String::new(),
+        String::new(),
+        String::new(),
+        String::new(),
+        deductor.tan.clone(),
+        String::new(),
+        deductor.pan.clone(),
+        assessment_year,
+        financial_year,
+        format!("Q{}", quarter),
+        text(&deductor.name, 75),
+        text(&deductor.branch, 75),
+    ];
+    batch.extend(address.iter().cloned());
+    batch.extend([
+        state.to_string(),
+        deductor.pin.clone(),
+        text(&deductor.email, 75),
+        String::new(),
+        deductor.phone.clone(),
+        change.to_string(),
+        deductor.deductor_type.clone(),
+        text(&deductor.responsible_name, 75),
+        text(&deductor.responsible_designation, 20),
+    ]);
+    batch.extend(address.iter().cloned());
+    batch.extend([
+        state.to_string(),
+        deductor.pin.clone(),
+        text(&deductor.responsible_email, 75),
+        deductor.responsible_mobile.clone(),
+        String::new(),
+        String::new(),
+        change.to_string(),
+        amount(batch_total),
+        String::new(),
+        String::new(),
+        String::new(),
+        "N".to_string(),
+        String::new(),
+        String::new(),
+        String::new(),
+        String::new(),
+        String::new(),
+        String::new(),
+        String::new(),
+        deductor.responsible_pan.clone(),
+        String::new(),
+        String::new(),
+        String::new(),
+        String::new(),
+        String::new(),
+        String::new(),
+        String::new(),
+        String::new(),
+        String::new(),
+        String::new(),
+        String::new(),
+    ]);
+    push(&mut lines, batch);
+
+    for (challan_idx, challan) in challans.iter().enumerate() {
+        let deducted: Money = challan.deductions.iter().map(|d| d.tds).sum();
+        push(
+            &mut lines,
+            vec![
+                "CD".to_string(),
+                "1".to_string(),
+                (challan_idx + 1).to_string(),
+                challan.deductions.len().to_string(),
+                "N".to_string(),
+                String::new(),
+                String::new(),
+                String::new(),
+                String::new(),
+                String::new(),
+                challan.serial_no.clone(),
+                String::new(),
+                String::new(),
+                String::new(),
+                challan.bsr_code.clone(),
+                String::new(),
+                date(challan.deposited_on),
+                String::new(),
+                String::new(),
+                String::new(),
+                amount(challan.income_tax),
+                amount(challan.surcharge),
+                amount(challan.cess),
+                amount(challan.interest),
+                amount(challan.others),
+                amount(challan.total_deposit()),
+                String::new(),
+                amount(deducted),
+                amount(deducted),
+                amount(Money::ZERO),
+                amount(Money::ZERO),
+                amount(deducted),
+                amount(challan.interest),
+                amount(challan.others),
+                String::new(),
+                "N".to_string(),
+                String::new(),
+                amount(challan.fee),
+                challan.minor_head.clone(),
+                String::new(),
+            ],
+        );
+
+        for (deduction_idx, deduction) in challan.deductions.iter().enumerate() {
+            let remark = if deduction.pan == NO_PAN { "C" } else { "" };
+            push(
+                &mut lines,
+                vec![
+                    "DD".to_string(),
+                    "1".to_string(),
+                    (challan_idx + 1).to_string(),
+                    (deduction_idx + 1).to_string(),
+                    "O".to_string(),
+                    String::new(),
+                    deductee_code(&deduction.pan).to_string(),
+                    String::new(),
+                    deduction.pan.clone(),
+                    String::new(),
+                    String::new(),
+                    text(&deduction.name, 75),
+                    amount(deduction.tds),
+                    amount(Money::ZERO),
+                    amount(Money::ZERO),
+                    amount(deduction.tds),
+                    String::new(),
+                    amount(deduction.tds),
+                    String::new(),
+                    String::new(),
+                    amount(deduction.amount_paid),
+                    date(deduction.paid_on),
+                    date(deduction.paid_on),
+                    date(challan.deposited_on),
+                    deduction.rate.clone(),
+                    String::new(),
+                    String::new(),
+                    String::new(),
+                    remark.to_string(),
+                    deduction.section_code.clone(),
+                    String::new(),
+                    String::new(),
+                ],
+            );
+        }
+    }
+
+    let mut out = lines.join("\r\n");
+    out.push_str("\r\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn money(value: &str) -> Money {
+        value.parse().unwrap()
+    }
+
+    fn day(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn deductor() -> Deductor {
+        Deductor {
+            tan: "MUMA12345B".to_string(),
+            pan: "AAACA1234A".to_string(),
+            name: "Acme Consulting Private Limited".to_string(),
+            branch: "Head Office".to_string(),
+            address: "Unit 4, Second Floor, Lotus Business Park, Andheri East Mumbai".to_string(),
+            state: "27".to_string(),
+            pin: "400069".to_string(),
+            email: "accounts@acme.example".to_string(),
+            phone: "9876543210".to_string(),
+            deductor_type: "K".to_string(),
+            address_changed: false,
+            responsible_name: "Asha Rao".to_string(),
+            responsible_designation: "Director".to_string(),
+            responsible_pan: "ABCPR1234K".to_string(),
+            responsible_mobile: "9876543210".to_string(),
+            responsible_email: "asha@acme.example".to_string(),
+        }
+    }
+
+    fn deduction(pan: &str, section_code: &str, paid: &str, tds: &str) -> Deduction {
+        Deduction {
+            pan: pan.to_string(),
+            name: "Payee^Name".to_string(),
+            section_code: section_code.to_string(),
+            amount_paid: money(paid),
+            tds: money(tds),
+            rate: "10".to_string(),
+            paid_on: day(2025, 5, 12),
+        }
+    }
+
+    fn challan(deductions: Vec<Deduction>) -> Challan {
+        let income_tax = deductions.iter().map(|d| d.tds).sum();
+        Challan {
+            serial_no: "00123".to_string(),
+            bsr_code: "0510308".to_string(),
+            deposited_on: day(2025, 6, 7),
+            income_tax,
+            surcharge: Money::ZERO,
+            cess: Money::ZERO,
+            interest: money("15"),
+            fee: Money::ZERO,
+            others: Money::ZERO,
+            minor_head: "200".to_string(),
+            deductions,
+        }
+    }
+
+    fn records(text: &str) -> Vec<Vec<&str>> {
+        text.split("\r\n")
+            .filter(|line| !line.is_empty())
+            .map(|line| line.split('^').collect())
+            .collect()
+    }
+
+    #[test]
+    fn section_codes_ignore_case_and_spacing() {
+        assert_eq!(section_code("194J(b)"), Some("4JB"));
+        assert_eq!(section_code(" 194 c "), Some("94C"));
+        assert_eq!(section_code("192"), None);
+    }
+
+    #[test]
+    fn state_codes_map_to_the_etds_table() {
+        assert_eq!(state_code("27"), "19");
+        assert_eq!(state_code("07"), "09");
+        assert_eq!(state_code("97"), FOREIGN_STATE_CODE);
+    }
+
+    #[test]
+    fn dates_and_years_use_the_etds_forms() {
+        assert_eq!(date(day(2025, 4, 1)), "01042025");
+        assert_eq!(
+            year_fields(2025),
+            ("202627".to_string(), "202526".to_string())
+        );
+        assert_eq!(year_fields(2099).0, "210001");
+    }
+
+    #[test]
+    fn addresses_wrap_into_five_lines_of_25() {
+        let lines = address_lines(&deductor().address);
+        assert_eq!(lines.len(), 5);
+        assert_eq!(lines[0], "Unit 4, Second Floor,");
+        assert_eq!(lines[1], "Lotus Business Park,");
+        assert_eq!(lines[2], "Andheri East Mumbai");
+        assert!(lines[3].is_empty() && lines[4].is_empty());
+        assert!(lines.iter().all(|line| line.chars().count() <= 25));
+    }
+
+    #[test]
+    fn text_drops_separators_and_line_breaks() {
+        assert_eq!(text("A^B\r\nC", 75), "A B  C");
+        assert_eq!(text("  Acme  ", 3), "Acm");
+    }
+
+    #[test]
+    fn statement_is_header_batch_then_each_challan_with_its_deductions() {
+        let challans = vec![
+            challan(vec![
+                deduction("AAACB1234C", "94C", "50000", "1000"),
+                deduction(NO_PAN, "4JB", "30000", "6000"),
+            ]),
+            challan(vec![deduction("ABCPD1234E", "94A", "12000", "1200")]),
+        ];
+        let text = build(&deductor(), 2025, 1, &challans, day(2025, 7, 20));
+
+        assert!(text.ends_with("\r\n"));
+        let records = records(&text);
+        let kinds: Vec<&str> = records.iter().map(|r| r[1]).collect();
+        assert_eq!(kinds, ["FH", "BH", "CD", "DD", "DD", "CD", "DD"]);
+        for (idx, record) in records.iter().enumerate() {
+            assert_eq!(record[0], (idx + 1).to_string());
+        }
+    }
+
+    #[test]
+    fn records_keep_their_field_counts() {
+        let deductions = vec![deduction("AAACB1234C", "94C", "50000", "1000")];
+        let challans = [challan(deductions)];
+        let text = build(&deductor(), 2025, 1, &challans, day(2025, 7, 20));
+        let counts: Vec<usize> = records(&text).iter().map(Vec::len).collect();
+        assert_eq!(counts, [18, 70, 41, 33]);
+    }
+
+    #[test]
+    fn header_and_batch_carry_the_deductor_and_period() {
+        let deductions = vec![deduction("AAACB1234C", "94C", "50000", "1000")];
+        let challans = [challan(deductions)];
+        let text = build(&deductor(), 2025, 2, &challans, day(2025, 10, 15));
+        let records = records(&text);
+
+        let header = &records[0];
+        assert_eq!(
+            header[2..9],
+            ["NS1", "R", "15102025", "1", "D", "MUMA12345B", "1"]
+        );
+
+        let batch = &records[1];
+        assert_eq!(batch[3], "1");
+        assert_eq!(batch[4], "26Q");
+        assert_eq!(batch[12], "MUMA12345B");
+        assert_eq!(batch[14], "AAACA1234A");
+        assert_eq!(batch[15..18], ["202627", "202526", "Q2"]);
+        assert_eq!(batch[25], "19");
+        assert_eq!(batch[26], "400069");
+        // Deposit of the batch: TDS plus interest
+        assert!(batch.contains(&"1015.00"));
+        assert!(batch.contains(&"ABCPR1234K"));
+    }
+
+    #[test]
+    fn challan_totals_match_its_deductions() {
+        let challans = vec![challan(vec![
+            deduction("AAACB1234C", "94C", "50000", "1000"),
+            deduction(NO_PAN, "4JB", "30000", "6000"),
+        ])];
+        let text = build(&deductor(), 2025, 1, &challans, day(2025, 7, 20));
+        let cd = &records(&text)[2];
+
+        assert_eq!(cd[3], "1");
+        assert_eq!(cd[4], "2");
+        assert_eq!(cd[11], "00123");
+        assert_eq!(cd[15], "0510308");
+        assert_eq!(cd[17], "07062025");
+        assert_eq!(cd[21], "7000.00");
+        assert_eq!(cd[24], "15.00");
+        assert_eq!(cd[26], "7015.00");
+        assert_eq!(cd[28], "7000.00");
+        assert_eq!(cd[39], "200");
+    }
+
+    #[test]
+    fn deductions_flag_companies_and_missing_pans() {
+        let challans = vec![challan(vec![
+            deduction("AAACB1234C", "94C", "50000", "1000"),
+            deduction(NO_PAN, "4JB", "30000", "6000"),
+        ])];
+        let text = build(&deductor(), 2025, 1, &challans, day(2025, 7, 20));
+        let records = records(&text);
+        let (company, no_pan) = (&records[3], &records[4]);
+
+        assert_eq!(company[3..5], ["1", "1"]);
+        assert_eq!(company[7], "01");
+        assert_eq!(company[9], "AAACB1234C");
+        assert_eq!(company[12], "Payee Name");
+        assert_eq!(company[13], "1000.00");
+        assert_eq!(company[21], "50000.00");
+        assert_eq!(company[22..25], ["12052025", "12052025", "07062025"]);
+        assert_eq!(company[25], "10");
+        assert_eq!(company[29], "");
+        assert_eq!(company[30], "94C");
+
+        assert_eq!(no_pan[4], "2");
+        assert_eq!(no_pan[7], "02");
+        assert_eq!(no_pan[9], NO_PAN);
+        assert_eq!(no_pan[29], "C");
+        assert_eq!(no_pan[30], "4JB");
+    }
+}
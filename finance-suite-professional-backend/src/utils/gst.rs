@@ -0,0 +1,494 @@
+use rust_decimal::Decimal;
+use std::str::FromStr;
+
+use crate::error::ApiError;
+use crate::models::invoice::{ExportType, Invoice, InvoiceItem, CGST, IGST, SGST};
+use crate::models::money::Money;
+use crate::models::organisation::RoundingRule;
+
+/// GST state codes as used in GSTINs and place of supply
+pub const STATE_CODES: &[(&str, &str)] = &[
+    ("01", "Jammu and Kashmir"),
+    ("02", "Himachal Pradesh"),
+    ("03", "Punjab"),
+    ("04", "Chandigarh"),
+    ("05", "Uttarakhand"),
+    ("06", "Haryana"),
+    ("07", "Delhi"),
+    ("08", "Rajasthan"),
+    ("09", "Uttar Pradesh"),
+    ("10", "Bihar"),
+    ("11", "Sikkim"),
+    ("12", "Arunachal Pradesh"),
+    ("13", "Nagaland"),
+    ("14", "Manipur"),
+    ("15", "Mizoram"),
+    ("16", "Tripura"),
+    ("17", "Meghalaya"),
+    ("18", "Assam"),
+    ("19", "West Bengal"),
+    ("20", "Jharkhand"),
+    ("21", "Odisha"),
+    ("22", "Chhattisgarh"),
+    ("23", "Madhya Pradesh"),
+    ("24", "Gujarat"),
+    ("25", "Daman and Diu"),
+    ("26", "Dadra and Nagar Haveli and Daman and Diu"),
+    ("27", "Maharashtra"),
+    ("28", "Andhra Pradesh (Before Division)"),
+    ("29", "Karnataka"),
+    ("30", "Goa"),
+    ("31", "Lakshadweep"),
+    ("32", "Kerala"),
+    ("33", "Tamil Nadu"),
+    ("34", "Puducherry"),
+    ("35", "Andaman and Nicobar Islands"),
+    ("36", "Telangana"),
+    ("37", "Andhra Pradesh"),
+    ("38", "Ladakh"),
+    ("96", "Other Country"),
+    ("97", "Other Territory"),
+];
+
+/// Whether a supply is within one state (CGST + SGST) or across states (IGST)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SupplyType {
+    IntraState,
+    InterState,
+    /// Export under LUT: the IGST rate is recorded but no tax is charged
+    ZeroRated,
+}
+
+/// Look up the state name for a two-digit state code
+pub fn state_name(code: &str) -> Option<&'static str> {
+    STATE_CODES
+        .iter()
+        .find(|(c, _)| *c == code)
+        .map(|(_, name)| *name)
+}
+
+/// Resolve a place of supply to its state code.
+///
+/// Accepts "33", "33-Tamil Nadu", "Tamil Nadu" or "tamil nadu".
+pub fn resolve_state_code(place_of_supply: &str) -> Option<&'static str> {
+    let place = place_of_supply.trim();
+    if place.is_empty() {
+        return None;
+    }
+
+    let digits: String = place.chars().take_while(|c| c.is_ascii_digit()).collect();
+    if !digits.is_empty() {
+        let code = format!("{:0>2}", digits);
+        return STATE_CODES
+            .iter()
+            .find(|(c, _)| *c == code)
+            .map(|(c, _)| *c);
+    }
+
+    let wanted = normalise_state_name(place);
+    STATE_CODES
+        .iter()
+        .find(|(_, name)| normalise_state_name(name) == wanted)
+        .map(|(c, _)| *c)
+}
+
+fn normalise_state_name(name: &str) -> String {
+    name.to_lowercase()
+        .replace('&', "and")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Decide intra-state vs inter-state from the supplier state code and the
+/// invoice's place of supply. International invoices are always inter-state,
+/// and zero-rated when exported without payment of IGST.
+pub fn determine_supply_type(
+    supplier_state_code: Option<&str>,
+    invoice: &Invoice,
+) -> Result<SupplyType, ApiError> {
+    if invoice.export_type == Some(ExportType::WithoutPayment)
+        && invoice.invoice_type.eq_ignore_ascii_case("international")
+    {
+        return Ok(SupplyType::ZeroRated);
+    }
+    supply_type_for(
+        supplier_state_code,
+        &invoice.invoice_type,
+        &invoice.place_of_supply,
+    )
+}
+
+/// [`determine_supply_type`] for documents other than invoices (e.g. estimates)
+/// that carry the same `invoice_type` / place of supply pair
+pub fn supply_type_for(
+    supplier_state_code: Option<&str>,
+    invoice_type: &str,
+    place_of_supply: &str,
+) -> Result<SupplyType, ApiError> {
+    if invoice_type.eq_ignore_ascii_case("international") {
+        return Ok(SupplyType::InterState);
+    }
+
+    let supplier = supplier_state_code.ok_or_else(|| {
+        ApiError::ValidationError(
+            "Organisation GSTIN is required to determine the supply type".to_string(),
+        )
+    })?;
+
+    let place = resolve_state_code(place_of_supply).ok_or_else(|| {
+        ApiError::ValidationError(format!("Unknown place of supply '{}'", place_of_supply))
+    })?;
+
+    if supplier == place {
+        Ok(SupplyType::IntraState)
+    } else {
+        Ok(SupplyType::InterState)
+    }
+}
+
+/// Parse a numeric string field; blank values count as zero
+pub fn parse_decimal(field: &str, value: &str) -> Result<Decimal, ApiError> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Ok(Decimal::ZERO);
+    }
+
+    let parsed = Decimal::from_str(value)
+        .map_err(|_| ApiError::ValidationError(format!("{} must be a number", field)))?;
+
+    if parsed.is_sign_negative() {
+        return Err(ApiError::ValidationError(format!(
+            "{} cannot be negative",
+            field
+        )));
+    }
+
+    Ok(parsed)
+}
+
+/// GST rate of a taxed line: CGST% + SGST%, or IGST% for inter-state lines
+pub fn item_gst_rate(item: &InvoiceItem) -> Result<Decimal, ApiError> {
+    let split = parse_decimal("CGST percent", &item.cgst.cgst_percent)?
+        + parse_decimal("SGST percent", &item.sgst.sgst_percent)?;
+    if split.is_zero() {
+        parse_decimal("IGST percent", &item.igst.igst_percent)
+    } else {
+        Ok(split)
+    }
+}
+
+/// True when the client sent a non-zero value that differs from `computed`
+fn client_value_differs(client: Money, computed: Money) -> bool {
+    !client.is_zero() && client != computed
+}
+
+fn format_percent(percent: Decimal) -> String {
+    percent.normalize().to_string()
+}
+
+/// Header totals of a set of taxed lines
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TaxTotals {
+    pub sub_total: Money,
+    pub total_cgst: Money,
+    pub total_sgst: Money,
+    pub total_igst: Money,
+    pub total: Money,
+}
+
+/// Recompute every line tax amount and the invoice totals from hours, rate
+/// and tax percents, overwriting whatever the client sent.
+///
+/// The GST rate of a line is CGST% + SGST%, or IGST% when no split is given,
+/// and is re-split according to the supply type. Line amounts are kept to the
+/// paisa; the invoice-level tax totals follow the organisation rounding rule.
+pub fn compute_invoice_taxes(
+    invoice: &mut Invoice,
+    supply_type: SupplyType,
+    rounding: RoundingRule,
+) -> Result<(), ApiError> {
+    let totals = compute_item_taxes(&mut invoice.items, supply_type, rounding)?;
+
+    if client_value_differs(invoice.total, totals.total) {
+        log::warn!(
+            "Invoice total '{}' overwritten with '{}'",
+            invoice.total,
+            totals.total
+        );
+    }
+
+    invoice.sub_total = totals.sub_total;
+    invoice.totalcgst = totals.total_cgst;
+    invoice.totalsgst = totals.total_sgst;
+    invoice.totaligst = totals.total_igst;
+    invoice.total = totals.total;
+
+    Ok(())
+}
+
+/// Line-level half of [`compute_invoice_taxes`], shared with documents that
+/// reuse `InvoiceItem` (credit/debit notes).
+pub fn compute_item_taxes(
+    items: &mut [InvoiceItem],
+    supply_type: SupplyType,
+    rounding: RoundingRule,
+) -> Result<TaxTotals, ApiError> {
+    let two = Decimal::from(2);
+
+    let mut sub_total = Money::ZERO;
+    let mut total_cgst = Money::ZERO;
+    let mut total_sgst = Money::ZERO;
+    let mut total_igst = Money::ZERO;
+
+    for (idx, item) in items.iter_mut().enumerate() {
+        let line = idx + 1;
+        let hours = parse_decimal(&format!("Item {} hours", line), &item.hours)?;
+        let rate = item.rate;
+        if rate.is_negative() {
+            return Err(ApiError::ValidationError(format!(
+                "Item {} rate cannot be negative",
+                line
+            )));
+        }
+        let cgst_percent =
+            parse_decimal(&format!("Item {} CGST percent", line), &item.cgst.cgst_percent)?;
+        let sgst_percent =
+            parse_decimal(&format!("Item {} SGST percent", line), &item.sgst.sgst_percent)?;
+        let igst_percent =
+            parse_decimal(&format!("Item {} IGST percent", line), &item.igst.igst_percent)?;
+
+        if cgst_percent != sgst_percent {
+            return Err(ApiError::ValidationError(format!(
+                "Item {}: CGST and SGST percent must be equal",
+                line
+            )));
+        }
+
+        let split_rate = cgst_percent + sgst_percent;
+        if !split_rate.is_zero() && !igst_percent.is_zero() && split_rate != igst_percent {
+            return Err(ApiError::ValidationError(format!(
+                "Item {}: CGST + SGST percent does not match IGST percent",
+                line
+            )));
+        }
+
+        let gst_rate = if split_rate.is_zero() {
+            igst_percent
+        } else {
+            split_rate
+        };
+
+        let taxable = (rate * hours).round_paise();
+        if client_value_differs(item.item_total, taxable) {
+            log::warn!(
+                "Item {}: client item total '{}' overwritten with '{}'",
+                line,
+                item.item_total,
+                taxable
+            );
+        }
+        item.item_total = taxable;
+        sub_total += taxable;
+
+        match supply_type {
+            SupplyType::IntraState => {
+                let half_rate = gst_rate / two;
+                let half_tax = taxable.percent(half_rate);
+                item.cgst = CGST {
+                    cgst_percent: format_percent(half_rate),
+                    cgst_amount: half_tax,
+                };
+                item.sgst = SGST {
+                    sgst_percent: format_percent(half_rate),
+                    sgst_amount: half_tax,
+                };
+                item.igst = IGST::default();
+                total_cgst += half_tax;
+                total_sgst += half_tax;
+            }
+            SupplyType::InterState => {
+                let tax = taxable.percent(gst_rate);
+                item.cgst = CGST::default();
+                item.sgst = SGST::default();
+                item.igst = IGST {
+                    igst_percent: format_percent(gst_rate),
+                    igst_amount: tax,
+                };
+                total_igst += tax;
+            }
+            SupplyType::ZeroRated => {
+                item.cgst = CGST::default();
+                item.sgst = SGST::default();
+                item.igst = IGST {
+                    igst_percent: format_percent(gst_rate),
+                    igst_amount: Money::ZERO,
+                };
+            }
+        }
+    }
+
+    let total_cgst = total_cgst.round(rounding);
+    let total_sgst = total_sgst.round(rounding);
+    let total_igst = total_igst.round(rounding);
+
+    Ok(TaxTotals {
+        sub_total,
+        total_cgst,
+        total_sgst,
+        total_igst,
+        total: sub_total + total_cgst + total_sgst + total_igst,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn money(value: &str) -> Money {
+        value.parse().unwrap()
+    }
+
+    fn item(rate: &str, hours: &str, cgst: &str, sgst: &str, igst: &str) -> InvoiceItem {
+        InvoiceItem {
+            hours: hours.to_string(),
+            rate: money(rate),
+            cgst: CGST {
+                cgst_percent: cgst.to_string(),
+                ..CGST::default()
+            },
+            sgst: SGST {
+                sgst_percent: sgst.to_string(),
+                ..SGST::default()
+            },
+            igst: IGST {
+                igst_percent: igst.to_string(),
+                ..IGST::default()
+            },
+            ..InvoiceItem::default()
+        }
+    }
+
+    #[test]
+    fn intra_state_splits_into_cgst_and_sgst() {
+        let mut items = vec![item("1000", "2", "", "", "18")];
+        let totals =
+            compute_item_taxes(&mut items, SupplyType::IntraState, RoundingRule::None).unwrap();
+
+        assert_eq!(items[0].item_total, money("2000"));
+        assert_eq!(items[0].cgst.cgst_percent, "9");
+        assert_eq!(items[0].cgst.cgst_amount, money("180"));
+        assert_eq!(items[0].sgst.sgst_amount, money("180"));
+        assert!(items[0].igst.igst_amount.is_zero());
+        assert_eq!(totals.total_cgst, money("180"));
+        assert_eq!(totals.total_sgst, money("180"));
+        assert!(totals.total_igst.is_zero());
+        assert_eq!(totals.total, money("2360"));
+    }
+
+    #[test]
+    fn inter_state_charges_igst_at_the_combined_rate() {
+        let mut items = vec![item("1000", "1", "9", "9", "")];
+        let totals =
+            compute_item_taxes(&mut items, SupplyType::InterState, RoundingRule::None).unwrap();
+
+        assert_eq!(items[0].igst.igst_percent, "18");
+        assert_eq!(items[0].igst.igst_amount, money("180"));
+        assert!(items[0].cgst.cgst_percent.is_empty());
+        assert!(items[0].cgst.cgst_amount.is_zero());
+        assert!(items[0].sgst.sgst_amount.is_zero());
+        assert_eq!(totals.total_igst, money("180"));
+        assert_eq!(totals.total, money("1180"));
+    }
+
+    #[test]
+    fn zero_rated_keeps_the_rate_without_tax() {
+        let mut items = vec![item("500", "1", "", "", "18")];
+        let totals =
+            compute_item_taxes(&mut items, SupplyType::ZeroRated, RoundingRule::Nearest).unwrap();
+
+        assert_eq!(items[0].igst.igst_percent, "18");
+        assert!(items[0].igst.igst_amount.is_zero());
+        assert_eq!(totals.total, money("500"));
+    }
+
+    #[test]
+    fn line_taxes_round_to_paise_and_totals_follow_the_rule() {
+        // 9% of 100.50 is 9.045, kept as 9.05 on the line
+        let cases = [
+            (RoundingRule::None, "9.05"),
+            (RoundingRule::Nearest, "9"),
+            (RoundingRule::Up, "10"),
+            (RoundingRule::Down, "9"),
+        ];
+        for (rule, expected) in cases {
+            let mut items = vec![item("100.50", "1", "9", "9", "")];
+            let totals = compute_item_taxes(&mut items, SupplyType::IntraState, rule).unwrap();
+
+            assert_eq!(items[0].cgst.cgst_amount, money("9.05"));
+            assert_eq!(totals.total_cgst, money(expected), "{:?}", rule);
+            assert_eq!(totals.total_sgst, money(expected), "{:?}", rule);
+            assert_eq!(
+                totals.total,
+                money("100.50") + money(expected) + money(expected)
+            );
+        }
+    }
+
+    #[test]
+    fn totals_round_once_over_all_lines() {
+        // Two lines of 0.45 CGST each: 0.90 rounds to 1, not 0 + 0
+        let mut items = vec![item("5", "1", "9", "9", ""), item("5", "1", "9", "9", "")];
+        let totals =
+            compute_item_taxes(&mut items, SupplyType::IntraState, RoundingRule::Nearest).unwrap();
+
+        assert_eq!(totals.total_cgst, money("1"));
+        assert_eq!(totals.total, money("12"));
+    }
+
+    #[test]
+    fn rejects_unequal_or_mismatched_rates() {
+        let mut unequal = vec![item("100", "1", "9", "6", "")];
+        assert!(
+            compute_item_taxes(&mut unequal, SupplyType::IntraState, RoundingRule::None).is_err()
+        );
+
+        let mut mismatched = vec![item("100", "1", "9", "9", "12")];
+        assert!(
+            compute_item_taxes(&mut mismatched, SupplyType::InterState, RoundingRule::None)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn invoice_totals_overwrite_client_values() {
+        let mut invoice = Invoice {
+            items: vec![item("1000", "1", "", "", "18")],
+            total: money("1"),
+            ..Invoice::default()
+        };
+        compute_invoice_taxes(&mut invoice, SupplyType::IntraState, RoundingRule::Nearest).unwrap();
+
+        assert_eq!(invoice.sub_total, money("1000"));
+        assert_eq!(invoice.totalcgst, money("90"));
+        assert_eq!(invoice.totalsgst, money("90"));
+        assert!(invoice.totaligst.is_zero());
+        assert_eq!(invoice.total, money("1180"));
+    }
+
+    #[test]
+    fn supply_type_follows_place_of_supply() {
+        assert_eq!(
+            supply_type_for(Some("33"), "Domestic", "33-Tamil Nadu").unwrap(),
+            SupplyType::IntraState
+        );
+        assert_eq!(
+            supply_type_for(Some("33"), "Domestic", "Karnataka").unwrap(),
+            SupplyType::InterState
+        );
+        assert_eq!(
+            supply_type_for(None, "International", "").unwrap(),
+            SupplyType::InterState
+        );
+    }
+}
@@ -0,0 +1,645 @@
+use printpdf::{
+    BuiltinFont, IndirectFontRef, Line, Mm, PdfDocument, PdfDocumentReference,
+    PdfLayerReference, Point, Rect,
+};
+use qrcode::{Color, EcLevel, QrCode};
+use rust_decimal::prelude::ToPrimitive;
+
+use crate::error::ApiError;
+use crate::models::invoice::{ExportType, Invoice};
+use crate::models::money::Money;
+use crate::models::organisation::Organisation;
+use crate::utils::gst;
+
+// A4 portrait, all positions in millimetres from the bottom-left corner
+const PAGE_WIDTH: f32 = 210.0;
+const PAGE_HEIGHT: f32 = 297.0;
+const MARGIN: f32 = 15.0;
+const RIGHT: f32 = PAGE_WIDTH - MARGIN;
+const LINE_HEIGHT: f32 = 4.5;
+
+const PT_TO_MM: f32 = 0.352_778;
+
+/// Printed size of the e-invoice QR code
+const QR_SIZE: f32 = 32.0;
+
+/// Render a GST tax invoice for `invoice` issued by `org`.
+///
+/// Supplier details are taken from the invoice snapshot, falling back to the
+/// organisation profile; bank, UPI and payment instructions always come from
+/// the organisation. Only the built-in Helvetica fonts are used so the output
+/// does not depend on fonts installed on the server.
+pub fn render_invoice_pdf(invoice: &Invoice, org: &Organisation) -> Result<Vec<u8>, ApiError> {
+    let title = format!("Invoice {}", invoice.invoice_number);
+    let (doc, page, layer) =
+        PdfDocument::new(&title, Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), "Layer 1");
+    let doc = doc.with_document_id(
+        invoice
+            .id
+            .map(|id| id.to_hex())
+            .unwrap_or_else(|| invoice.invoice_number.clone()),
+    );
+
+    let regular = doc.add_builtin_font(BuiltinFont::Helvetica).map_err(pdf_error)?;
+    let bold = doc.add_builtin_font(BuiltinFont::HelveticaBold).map_err(pdf_error)?;
+    let layer = doc.get_page(page).get_layer(layer);
+
+    let mut writer = PdfWriter {
+        doc,
+        layer,
+        regular,
+        bold,
+        y: PAGE_HEIGHT - MARGIN,
+    };
+
+    draw_header(&mut writer, invoice, org);
+    draw_e_invoice(&mut writer, invoice);
+    draw_invoice_details(&mut writer, invoice);
+    draw_parties(&mut writer, invoice);
+    draw_items(&mut writer, invoice);
+    draw_totals(&mut writer, invoice);
+    draw_payment_details(&mut writer, invoice, org);
+    draw_footer(&mut writer, invoice, org);
+
+    writer.doc.save_to_bytes().map_err(pdf_error)
+}
+
+fn pdf_error(err: printpdf::Error) -> ApiError {
+    ApiError::InternalServerError(format!("Failed to render PDF: {}", err))
+}
+
+/// Thin cursor over printpdf that flows content down the page and starts a
+/// new page when it runs out of room.
+struct PdfWriter {
+    doc: PdfDocumentReference,
+    layer: PdfLayerReference,
+    regular: IndirectFontRef,
+    bold: IndirectFontRef,
+    y: f32,
+}
+
+impl PdfWriter {
+    fn font(&self, bold: bool) -> &IndirectFontRef {
+        if bold {
+            &self.bold
+        } else {
+            &self.regular
+        }
+    }
+
+    fn text(&self, text: &str, size: f32, x: f32, y: f32, bold: bool) {
+        self.layer
+            .use_text(text, size, Mm(x), Mm(y), self.font(bold));
+    }
+
+    fn text_right(&self, text: &str, size: f32, right: f32, y: f32, bold: bool) {
+        let width = text_width_mm(text, size, bold);
+        self.text(text, size, right - width, y, bold);
+    }
+
+    fn hline(&self, x1: f32, x2: f32, y: f32) {
+        self.layer.add_line(Line {
+            points: vec![
+                (Point::new(Mm(x1), Mm(y)), false),
+                (Point::new(Mm(x2), Mm(y)), false),
+            ],
+            is_closed: false,
+        });
+    }
+
+    /// Start a new page unless `height` millimetres still fit above the margin
+    fn ensure_space(&mut self, height: f32) {
+        if self.y - height >= MARGIN {
+            return;
+        }
+        let (page, layer) = self
+            .doc
+            .add_page(Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), "Layer 1");
+        self.layer = self.doc.get_page(page).get_layer(layer);
+        self.y = PAGE_HEIGHT - MARGIN;
+    }
+
+    /// Write wrapped text at `x` and move the cursor below it
+    fn paragraph(&mut self, text: &str, size: f32, x: f32, max_width: f32, bold: bool) {
+        for line in wrap_text(text, size, max_width, bold) {
+            self.ensure_space(LINE_HEIGHT);
+            self.text(&line, size, x, self.y, bold);
+            self.y -= LINE_HEIGHT;
+        }
+    }
+}
+
+/// Approximate Helvetica advance widths (in 1/1000 em), good enough for
+/// right-aligning numbers and wrapping text.
+fn char_width(c: char, bold: bool) -> f32 {
+    let width = match c {
+        '0'..='9' => 556.0,
+        '.' | ',' | ' ' | ':' | ';' | '|' | '!' | 'i' | 'j' | 'l' | '\'' => 278.0,
+        '-' | '(' | ')' | '/' | 'f' | 't' | 'r' => 333.0,
+        'm' | 'M' | 'W' | 'w' => 833.0,
+        'A'..='Z' => 667.0,
+        _ => 556.0,
+    };
+    if bold {
+        width * 1.05
+    } else {
+        width
+    }
+}
+
+fn text_width_mm(text: &str, size: f32, bold: bool) -> f32 {
+    let units: f32 = text.chars().map(|c| char_width(c, bold)).sum();
+    units / 1000.0 * size * PT_TO_MM
+}
+
+fn wrap_text(text: &str, size: f32, max_width: f32, bold: bool) -> Vec<String> {
+    let mut lines = Vec::new();
+    for raw_line in text.lines() {
+        let mut current = String::new();
+        for word in raw_line.split_whitespace() {
+            let candidate = if current.is_empty() {
+                word.to_string()
+            } else {
+                format!("{} {}", current, word)
+            };
+            if text_width_mm(&candidate, size, bold) > max_width && !current.is_empty() {
+                lines.push(std::mem::replace(&mut current, word.to_string()));
+            } else {
+                current = candidate;
+            }
+        }
+        if !current.is_empty() {
+            lines.push(current);
+        }
+    }
+    lines
+}
+
+fn first_non_empty<'a>(values: &[&'a str]) -> &'a str {
+    values
+        .iter()
+        .map(|v| v.trim())
+        .find(|v| !v.is_empty())
+        .unwrap_or("")
+}
+
+fn is_international(invoice: &Invoice) -> bool {
+    invoice.invoice_type.eq_ignore_ascii_case("international")
+}
+
+fn draw_header(w: &mut PdfWriter, invoice: &Invoice, org: &Organisation) {
+    let company = first_non_empty(&[
+        &invoice.company_name,
+        &org.company_name,
+        &org.organisation_name,
+    ]);
+    let org_address = org
+        .addresses
+        .first()
+        .map(|a| a.value.as_str())
+        .unwrap_or("");
+    let address = first_non_empty(&[&invoice.company_address, org_address]);
+    let gstin = first_non_empty(&[&invoice.gst_in, &org.gst_in]);
+    let phone = first_non_empty(&[&invoice.company_phone, &org.phone]);
+    let email = first_non_empty(&[&invoice.company_email, &org.email]);
+
+    let (title, subtitle) = if is_international(invoice) {
+        let subtitle = if invoice.effective_export_type() == ExportType::WithoutPayment {
+            "Supply meant for export under LUT without payment of IGST"
+        } else {
+            "Supply meant for export with payment of IGST"
+        };
+        ("EXPORT INVOICE", subtitle)
+    } else {
+        ("TAX INVOICE", "Original for Recipient")
+    };
+
+    let top = w.y;
+    w.text_right(title, 14.0, RIGHT, top, true);
+    w.text_right(subtitle, 7.5, RIGHT, top - 5.0, false);
+
+    w.text(company, 15.0, MARGIN, top, true);
+    w.y = top - 7.0;
+    w.paragraph(address, 9.0, MARGIN, 105.0, false);
+    if !gstin.is_empty() {
+        w.paragraph(&format!("GSTIN: {}", gstin), 9.0, MARGIN, 105.0, true);
+    }
+    let contact = [phone, email]
+        .iter()
+        .filter(|v| !v.is_empty())
+        .cloned()
+        .collect::<Vec<_>>()
+        .join("  |  ");
+    if !contact.is_empty() {
+        w.paragraph(&contact, 9.0, MARGIN, 105.0, false);
+    }
+
+    w.y -= 2.0;
+    w.hline(MARGIN, RIGHT, w.y);
+    w.y -= 6.0;
+}
+
+/// IRN, acknowledgement and the IRP's signed QR code of a registered invoice
+fn draw_e_invoice(w: &mut PdfWriter, invoice: &Invoice) {
+    let Some(e_invoice) = &invoice.e_invoice else {
+        return;
+    };
+
+    let top = w.y;
+    let mut y = top;
+    for (label, value) in [
+        ("IRN", e_invoice.irn.as_str()),
+        ("Ack No", e_invoice.ack_no.as_str()),
+        ("Ack Date", e_invoice.ack_date.as_str()),
+    ] {
+        w.text(&format!("{}:", label), 8.0, MARGIN, y, true);
+        w.text(value, 8.0, MARGIN + 16.0, y, false);
+        y -= LINE_HEIGHT;
+    }
+
+    match QrCode::with_error_correction_level(&e_invoice.signed_qr_code, EcLevel::M) {
+        Ok(qr) => {
+            let modules = qr.width();
+            let module = QR_SIZE / modules as f32;
+            let left = RIGHT - QR_SIZE;
+            for row in 0..modules {
+                for col in 0..modules {
+                    if qr[(col, row)] == Color::Dark {
+                        let x = left + col as f32 * module;
+                        let y = top + 3.0 - (row + 1) as f32 * module;
+                        w.layer
+                            .add_rect(Rect::new(Mm(x), Mm(y), Mm(x + module), Mm(y + module)));
+                    }
+                }
+            }
+            y = y.min(top + 3.0 - QR_SIZE);
+        }
+        Err(e) => log::warn!(
+            "Could not encode e-invoice QR for invoice {}: {}",
+            invoice.invoice_number,
+            e
+        ),
+    }
+
+    w.y = y - 2.0;
+    w.hline(MARGIN, RIGHT, w.y);
+    w.y -= 6.0;
+}
+
+fn draw_invoice_details(w: &mut PdfWriter, invoice: &Invoice) {
+    let place_of_supply = match gst::resolve_state_code(&invoice.place_of_supply) {
+        Some(code) => format!(
+            "{} - {}",
+            code,
+            gst::state_name(code).unwrap_or(invoice.place_of_supply.as_str())
+        ),
+        None => invoice.place_of_supply.clone(),
+    };
+
+    let mut left = vec![
+        ("Invoice No", invoice.invoice_number.clone()),
+        ("Invoice Date", invoice.invoice_date.clone()),
+        ("Due Date", invoice.invoice_due_date.clone()),
+        ("Terms", invoice.invoice_terms.clone()),
+    ];
+    let mut right = vec![
+        ("Place of Supply", place_of_supply),
+        ("PO Number", invoice.po_number.clone()),
+        ("PO Date", invoice.po_date.clone()),
+    ];
+    if let Some(e_way_bill) = &invoice.e_way_bill {
+        right.push(("E-way Bill", e_way_bill.ewb_no.clone()));
+    }
+    if is_international(invoice) {
+        left.push(("Currency", invoice.currency.clone()));
+        if !invoice.exchange_rate.is_empty() {
+            left.push(("Rate", format!("1 {} = INR {}", invoice.currency, invoice.exchange_rate)));
+        }
+        right.push(("LUT No", invoice.lut_no.clone()));
+        right.push(("IEC", invoice.iec_no.clone()));
+        right.push(("Shipping Bill", invoice.shipping_bill_no.clone()));
+        right.push(("SB Date", invoice.shipping_bill_date.clone()));
+        right.push(("Port Code", invoice.port_code.clone()));
+    }
+
+    let start = w.y;
+    let mut left_y = start;
+    for (label, value) in left.iter().filter(|(_, v)| !v.trim().is_empty()) {
+        w.text(&format!("{}:", label), 9.0, MARGIN, left_y, true);
+        w.text(value, 9.0, MARGIN + 28.0, left_y, false);
+        left_y -= LINE_HEIGHT;
+    }
+    let mut right_y = start;
+    for (label, value) in right.iter().filter(|(_, v)| !v.trim().is_empty()) {
+        w.text(&format!("{}:", label), 9.0, 110.0, right_y, true);
+        w.text(value, 9.0, 140.0, right_y, false);
+        right_y -= LINE_HEIGHT;
+    }
+
+    w.y = left_y.min(right_y) - 2.0;
+    w.hline(MARGIN, RIGHT, w.y);
+    w.y -= 6.0;
+}
+
+fn draw_party(w: &PdfWriter, heading: &str, name: &str, address: &str, gstin: &str, x: f32) -> f32 {
+    let mut y = w.y;
+    w.text(heading, 9.0, x, y, true);
+    y -= LINE_HEIGHT;
+    for line in wrap_text(name, 9.0, 85.0, true) {
+        w.text(&line, 9.0, x, y, true);
+        y -= LINE_HEIGHT;
+    }
+    for line in wrap_text(address, 9.0, 85.0, false) {
+        w.text(&line, 9.0, x, y, false);
+        y -= LINE_HEIGHT;
+    }
+    if !gstin.trim().is_empty() {
+        w.text(&format!("GSTIN: {}", gstin), 9.0, x, y, false);
+        y -= LINE_HEIGHT;
+    }
+    y
+}
+
+fn draw_parties(w: &mut PdfWriter, invoice: &Invoice) {
+    w.ensure_space(30.0);
+    let bill_y = draw_party(
+        w,
+        "Bill To",
+        &invoice.billcustomer_name,
+        &invoice.billcustomer_address,
+        &invoice.billcustomer_gstin,
+        MARGIN,
+    );
+    let ship_y = if invoice.shipcustomer_name.trim().is_empty() {
+        w.y
+    } else {
+        draw_party(
+            w,
+            "Ship To",
+            &invoice.shipcustomer_name,
+            &invoice.shipcustomer_address,
+            &invoice.shipcustomer_gstin,
+            110.0,
+        )
+    };
+    w.y = bill_y.min(ship_y) - 2.0;
+
+    if !invoice.subject.trim().is_empty() {
+        w.y -= 2.0;
+        w.paragraph(&format!("Subject: {}", invoice.subject), 9.0, MARGIN, RIGHT - MARGIN, false);
+    }
+    w.y -= 2.0;
+}
+
+/// Column layout of the items table: (heading, right edge in mm)
+fn item_columns(intra_state: bool) -> Vec<(&'static str, f32)> {
+    let mut columns = vec![("Hours", 108.0), ("Rate", 128.0), ("Taxable", 150.0)];
+    if intra_state {
+        columns.push(("CGST", 172.5));
+        columns.push(("SGST", RIGHT));
+    } else {
+        columns.push(("IGST", RIGHT));
+    }
+    columns
+}
+
+fn draw_item_header(w: &mut PdfWriter, intra_state: bool) {
+    w.ensure_space(12.0);
+    w.hline(MARGIN, RIGHT, w.y + 4.0);
+    w.text("#", 8.5, MARGIN, w.y, true);
+    w.text("Description", 8.5, MARGIN + 7.0, w.y, true);
+    for (heading, right) in item_columns(intra_state) {
+        w.text_right(heading, 8.5, right, w.y, true);
+    }
+    w.y -= 2.0;
+    w.hline(MARGIN, RIGHT, w.y);
+    w.y -= LINE_HEIGHT;
+}
+
+fn draw_items(w: &mut PdfWriter, invoice: &Invoice) {
+    let intra_state = !invoice.totalcgst.is_zero()
+        || !invoice.totalsgst.is_zero()
+        || invoice
+            .items
+            .iter()
+            .any(|i| !i.cgst.cgst_percent.trim().is_empty());
+
+    draw_item_header(w, intra_state);
+
+    for (idx, item) in invoice.items.iter().enumerate() {
+        let description = wrap_text(&item.description, 8.5, 72.0, false);
+        let rate_label = if intra_state {
+            &item.cgst.cgst_percent
+        } else {
+            &item.igst.igst_percent
+        };
+        let has_rate_line = !rate_label.trim().is_empty();
+        let rows = description.len().max(if has_rate_line { 2 } else { 1 }) as f32;
+        if w.y - rows * LINE_HEIGHT < MARGIN {
+            w.ensure_space(PAGE_HEIGHT);
+            draw_item_header(w, intra_state);
+        }
+
+        let row_y = w.y;
+        let rate_y = row_y - LINE_HEIGHT + 1.0;
+        let columns = item_columns(intra_state);
+        w.text(&(idx + 1).to_string(), 8.5, MARGIN, row_y, false);
+        w.text_right(&item.hours, 8.5, columns[0].1, row_y, false);
+        w.text_right(&item.rate.to_string(), 8.5, columns[1].1, row_y, false);
+        w.text_right(&item.item_total.to_string(), 8.5, columns[2].1, row_y, false);
+        if intra_state {
+            w.text_right(&item.cgst.cgst_amount.to_string(), 8.5, columns[3].1, row_y, false);
+            w.text_right(&item.sgst.sgst_amount.to_string(), 8.5, columns[4].1, row_y, false);
+            if has_rate_line {
+                w.text_right(&format!("@{}%", item.cgst.cgst_percent), 7.0, columns[3].1, rate_y, false);
+                w.text_right(&format!("@{}%", item.sgst.sgst_percent), 7.0, columns[4].1, rate_y, false);
+            }
+        } else {
+            w.text_right(&item.igst.igst_amount.to_string(), 8.5, columns[3].1, row_y, false);
+            if has_rate_line {
+                w.text_right(&format!("@{}%", item.igst.igst_percent), 7.0, columns[3].1, rate_y, false);
+            }
+        }
+
+        for (line_idx, line) in description.iter().enumerate() {
+            w.text(line, 8.5, MARGIN + 7.0, row_y - line_idx as f32 * LINE_HEIGHT, false);
+        }
+        w.y = row_y - rows * LINE_HEIGHT;
+    }
+
+    w.hline(MARGIN, RIGHT, w.y + 2.0);
+    w.y -= 3.0;
+}
+
+fn draw_totals(w: &mut PdfWriter, invoice: &Invoice) {
+    let currency = invoice.currency.as_str();
+    let mut rows = vec![("Sub Total", invoice.sub_total)];
+    if !invoice.totalcgst.is_zero() || !invoice.totalsgst.is_zero() {
+        rows.push(("CGST", invoice.totalcgst));
+        rows.push(("SGST", invoice.totalsgst));
+    }
+    if !invoice.totaligst.is_zero() {
+        rows.push(("IGST", invoice.totaligst));
+    }
+
+    w.ensure_space((rows.len() as f32 + 4.0) * LINE_HEIGHT);
+    for (label, amount) in rows {
+        w.text(label, 9.0, 130.0, w.y, false);
+        w.text_right(&format!("{} {}", currency, amount), 9.0, RIGHT, w.y, false);
+        w.y -= LINE_HEIGHT;
+    }
+    w.hline(130.0, RIGHT, w.y + 2.5);
+    w.y -= 1.0;
+    w.text("Total", 10.0, 130.0, w.y, true);
+    w.text_right(&format!("{} {}", currency, invoice.total), 10.0, RIGHT, w.y, true);
+    w.y -= LINE_HEIGHT + 2.0;
+
+    w.paragraph(
+        &format!("Amount in words: {}", amount_in_words(invoice.total, currency)),
+        8.5,
+        MARGIN,
+        RIGHT - MARGIN,
+        false,
+    );
+    w.y -= 3.0;
+}
+
+fn draw_payment_details(w: &mut PdfWriter, invoice: &Invoice, org: &Organisation) {
+    let bank_rows: Vec<(&str, &str)> = vec![
+        ("Account Holder", org.account_holder.as_str()),
+        ("Bank", org.bank_name.as_str()),
+        ("Account No", org.account_number.as_str()),
+        ("IFSC", org.ifsc_code.as_str()),
+        ("UPI ID", org.upi_id.as_str()),
+    ]
+    .into_iter()
+    .filter(|(_, v)| !v.trim().is_empty())
+    .collect();
+
+    if !bank_rows.is_empty() {
+        w.ensure_space((bank_rows.len() as f32 + 1.0) * LINE_HEIGHT);
+        w.text("Bank Details", 9.0, MARGIN, w.y, true);
+        w.y -= LINE_HEIGHT;
+        for (label, value) in bank_rows {
+            w.text(&format!("{}:", label), 8.5, MARGIN, w.y, false);
+            w.text(value, 8.5, MARGIN + 28.0, w.y, false);
+            w.y -= LINE_HEIGHT;
+        }
+        w.y -= 2.0;
+    }
+
+    for (heading, body) in [
+        ("Payment Instructions", org.payment_instructions.as_str()),
+        ("Notes", invoice.notes.as_str()),
+    ] {
+        if body.trim().is_empty() {
+            continue;
+        }
+        w.ensure_space(2.0 * LINE_HEIGHT);
+        w.text(heading, 9.0, MARGIN, w.y, true);
+        w.y -= LINE_HEIGHT;
+        w.paragraph(body, 8.5, MARGIN, RIGHT - MARGIN, false);
+        w.y -= 2.0;
+    }
+}
+
+fn draw_footer(w: &mut PdfWriter, invoice: &Invoice, org: &Organisation) {
+    let company = first_non_empty(&[
+        &invoice.company_name,
+        &org.company_name,
+        &org.organisation_name,
+    ]);
+
+    w.ensure_space(22.0);
+    w.y -= 4.0;
+    w.text_right(&format!("For {}", company), 9.0, RIGHT, w.y, true);
+    w.y -= 12.0;
+    w.text_right("Authorised Signatory", 8.5, RIGHT, w.y, false);
+    w.y -= LINE_HEIGHT + 2.0;
+
+    if !org.footer_note.trim().is_empty() {
+        w.hline(MARGIN, RIGHT, w.y + 2.0);
+        w.y -= 1.0;
+        w.paragraph(&org.footer_note, 8.0, MARGIN, RIGHT - MARGIN, false);
+    }
+}
+
+const ONES: [&str; 20] = [
+    "", "One", "Two", "Three", "Four", "Five", "Six", "Seven", "Eight", "Nine", "Ten",
+    "Eleven", "Twelve", "Thirteen", "Fourteen", "Fifteen", "Sixteen", "Seventeen",
+    "Eighteen", "Nineteen",
+];
+const TENS: [&str; 10] = [
+    "", "", "Twenty", "Thirty", "Forty", "Fifty", "Sixty", "Seventy", "Eighty", "Ninety",
+];
+
+fn below_hundred(n: u64) -> String {
+    if n < 20 {
+        ONES[n as usize].to_string()
+    } else if n.is_multiple_of(10) {
+        TENS[(n / 10) as usize].to_string()
+    } else {
+        format!("{} {}", TENS[(n / 10) as usize], ONES[(n % 10) as usize])
+    }
+}
+
+fn below_thousand(n: u64) -> String {
+    match (n / 100, n % 100) {
+        (0, rest) => below_hundred(rest),
+        (hundreds, 0) => format!("{} Hundred", ONES[hundreds as usize]),
+        (hundreds, rest) => format!("{} Hundred {}", ONES[hundreds as usize], below_hundred(rest)),
+    }
+}
+
+/// Spell out a whole number using the Indian system (thousand, lakh, crore)
+fn number_in_words(n: u64) -> String {
+    if n == 0 {
+        return "Zero".to_string();
+    }
+
+    let crore = n / 10_000_000;
+    let lakh = (n / 100_000) % 100;
+    let thousand = (n / 1_000) % 100;
+    let rest = n % 1_000;
+
+    let mut parts = Vec::new();
+    if crore > 0 {
+        parts.push(format!("{} Crore", number_in_words(crore)));
+    }
+    if lakh > 0 {
+        parts.push(format!("{} Lakh", below_hundred(lakh)));
+    }
+    if thousand > 0 {
+        parts.push(format!("{} Thousand", below_hundred(thousand)));
+    }
+    if rest > 0 {
+        parts.push(below_thousand(rest));
+    }
+    parts.join(" ")
+}
+
+fn amount_in_words(amount: Money, currency: &str) -> String {
+    let amount = amount.round_paise().amount().abs();
+    let whole = amount.trunc().to_u64().unwrap_or(0);
+    let fraction = ((amount - amount.trunc()) * rust_decimal::Decimal::ONE_HUNDRED)
+        .to_u64()
+        .unwrap_or(0);
+
+    let (major, minor) = if currency == "INR" {
+        ("Rupees", "Paise")
+    } else {
+        (currency, "Cents")
+    };
+
+    if fraction == 0 {
+        format!("{} {} Only", major, number_in_words(whole))
+    } else {
+        format!(
+            "{} {} and {} {} Only",
+            major,
+            number_in_words(whole),
+            below_hundred(fraction),
+            minor
+        )
+    }
+}
@@ -1 +1,7 @@
-pub mod validation;
\ No newline at end of file
+pub mod csv;
+pub mod date;
+pub mod form26as;
+pub mod form26q;
+pub mod gst;
+pub mod invoice_pdf;
+pub mod validation;
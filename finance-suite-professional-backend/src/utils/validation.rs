@@ -1,6 +1,193 @@
 use lazy_static::lazy_static;
 use regex::Regex;
 
+use super::gst;
+
 lazy_static! {
     pub static ref PHONE_REGEX: Regex = Regex::new(r"^\d{10,15}$").unwrap();
-}
\ No newline at end of file
+    /// Income-tax PAN: five letters, four digits, one letter
+    pub static ref PAN_REGEX: Regex = Regex::new(r"^[A-Z]{5}[0-9]{4}[A-Z]$").unwrap();
+    /// GSTIN: state code, PAN (or TAN / foreign id), entity number,
+    /// default "Z" (or registration kind) and check character
+    pub static ref GSTIN_REGEX: Regex =
+        Regex::new(r"^[0-9]{2}[A-Z0-9]{10}[1-9A-Z][A-Z0-9][0-9A-Z]$").unwrap();
+    /// Six-digit Indian PIN code inside a free-form address
+    pub static ref PINCODE_REGEX: Regex = Regex::new(r"\b[1-9][0-9]{5}\b").unwrap();
+    /// Tax deduction account number: four letters, five digits, one letter
+    pub static ref TAN_REGEX: Regex = Regex::new(r"^[A-Z]{4}[0-9]{5}[A-Z]$").unwrap();
+    /// Shipping bill number as reported in GSTR-1: up to seven digits
+    pub static ref SHIPPING_BILL_REGEX: Regex = Regex::new(r"^[0-9]{1,7}$").unwrap();
+    /// Customs port code, e.g. INMAA1
+    pub static ref PORT_CODE_REGEX: Regex = Regex::new(r"^[A-Z0-9]{6}$").unwrap();
+}
+
+/// Stored in place of a GSTIN for parties not registered under GST (B2C)
+pub const UNREGISTERED: &str = "UNREGISTERED";
+
+/// State code used by OIDAR and other centrally registered GSTINs
+const CENTRE_STATE_CODE: &str = "99";
+
+const GSTIN_CHARSET: &[u8] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+/// Upper-case with spaces removed
+pub fn normalize_gstin(value: &str) -> String {
+    value
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect::<String>()
+        .to_uppercase()
+}
+
+/// "UNREGISTERED" or the GSTN shorthand "URP" (unregistered person)
+pub fn is_unregistered(value: &str) -> bool {
+    let value = normalize_gstin(value);
+    value == UNREGISTERED || value == "URP"
+}
+
+/// Check character of the first 14 characters of a GSTIN (mod-36 Luhn)
+fn gstin_check_char(body: &[u8]) -> Option<char> {
+    let mut sum = 0;
+    for (idx, byte) in body.iter().enumerate() {
+        let value = GSTIN_CHARSET.iter().position(|c| c == byte)?;
+        let product = value * if idx % 2 == 0 { 1 } else { 2 };
+        sum += product / 36 + product % 36;
+    }
+    Some(GSTIN_CHARSET[(36 - sum % 36) % 36] as char)
+}
+
+/// Verify the format, the embedded state code and the check character of a
+/// GSTIN. Expects a normalised (upper-case, no spaces) value.
+pub fn validate_gstin(gstin: &str) -> Result<(), String> {
+    if gstin.len() != 15 || !GSTIN_REGEX.is_match(gstin) {
+        return Err(format!(
+            "GSTIN '{}' must be 15 characters: 2-digit state code, PAN, entity number, Z and check character",
+            gstin
+        ));
+    }
+
+    let state = &gstin[0..2];
+    let known_state = state == CENTRE_STATE_CODE
+        || gst::state_name(state).is_some_and(|_| state != "96");
+    if !known_state {
+        return Err(format!("GSTIN '{}' has unknown state code {}", gstin, state));
+    }
+
+    let expected = gstin_check_char(&gstin.as_bytes()[..14]);
+    let actual = gstin.chars().last();
+    if expected != actual {
+        return Err(format!(
+            "GSTIN '{}' has an invalid check character; please re-check it for typos",
+            gstin
+        ));
+    }
+    Ok(())
+}
+
+/// Normalise a required GSTIN (customer master): a valid GSTIN or the
+/// explicit unregistered marker
+pub fn clean_required_gstin(value: &str) -> Result<String, String> {
+    let gstin = normalize_gstin(value);
+    if gstin.is_empty() {
+        return Err(format!(
+            "GSTIN is required; use '{}' for customers not registered under GST",
+            UNREGISTERED
+        ));
+    }
+    if is_unregistered(&gstin) {
+        return Ok(UNREGISTERED.to_string());
+    }
+    validate_gstin(&gstin)?;
+    Ok(gstin)
+}
+
+/// Normalise an optional GSTIN (organisation, invoice); blank and the unregistered
+/// marker both become empty, meaning not registered
+pub fn clean_optional_gstin(value: &str) -> Result<String, String> {
+    let gstin = normalize_gstin(value);
+    if gstin.is_empty() || is_unregistered(&gstin) {
+        return Ok(String::new());
+    }
+    validate_gstin(&gstin)?;
+    Ok(gstin)
+}
+
+/// Normalise an optional TAN; blank is allowed
+pub fn clean_optional_tan(value: &str) -> Result<String, String> {
+    let tan = normalize_gstin(value);
+    if !tan.is_empty() && !TAN_REGEX.is_match(&tan) {
+        return Err(format!("Invalid TAN '{}'", tan));
+    }
+    Ok(tan)
+}
+
+/// HSN/SAC code with spaces and dots removed ("9983 13" → "998313")
+pub fn normalize_hsn_sac(value: &str) -> String {
+    value.chars().filter(|c| !c.is_whitespace() && *c != '.').collect()
+}
+
+/// Check an HSN/SAC code is 4, 6 or 8 digits and at least `min_digits` long
+pub fn validate_hsn_sac(code: &str, min_digits: usize) -> Result<(), String> {
+    if code.is_empty() {
+        return Err("HSN/SAC code is required".to_string());
+    }
+    if !code.chars().all(|c| c.is_ascii_digit()) {
+        return Err(format!("HSN/SAC code '{}' must contain digits only", code));
+    }
+    if ![4, 6, 8].contains(&code.len()) {
+        return Err(format!("HSN/SAC code '{}' must be 4, 6 or 8 digits", code));
+    }
+    if code.len() < min_digits {
+        return Err(format!(
+            "HSN/SAC code '{}' must have at least {} digits",
+            code, min_digits
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_gstins_with_the_right_check_character() {
+        for gstin in ["27AAPFU0939F1ZV", "29AAGCB7383J1Z4", "33AAACH7409R1Z8"] {
+            assert_eq!(validate_gstin(gstin), Ok(()), "{}", gstin);
+        }
+    }
+
+    #[test]
+    fn rejects_a_wrong_check_character() {
+        let err = validate_gstin("27AAPFU0939F1ZX").unwrap_err();
+        assert!(err.contains("invalid check character"), "{}", err);
+    }
+
+    #[test]
+    fn rejects_transposed_characters() {
+        // Same characters as 27AAPFU0939F1ZV with two digits swapped
+        assert!(validate_gstin("27AAPFU9039F1ZV").is_err());
+    }
+
+    #[test]
+    fn check_character_covers_the_centre_state_code() {
+        assert_eq!(gstin_check_char(b"99AAAAA1234A1Z"), Some('D'));
+        assert_eq!(validate_gstin("99AAAAA1234A1ZD"), Ok(()));
+    }
+
+    #[test]
+    fn rejects_unknown_state_codes_before_the_check_character() {
+        let err = validate_gstin("96AAPFU0939F1ZV").unwrap_err();
+        assert!(err.contains("unknown state code 96"), "{}", err);
+    }
+
+    #[test]
+    fn cleaning_normalises_before_checking() {
+        assert_eq!(
+            clean_required_gstin(" 27aapfu0939f1zv ").as_deref(),
+            Ok("27AAPFU0939F1ZV")
+        );
+        assert_eq!(clean_required_gstin("urp").as_deref(), Ok(UNREGISTERED));
+        assert_eq!(clean_optional_gstin("").as_deref(), Ok(""));
+        assert!(clean_optional_gstin("27AAPFU0939F1ZX").is_err());
+    }
+}